@@ -3,6 +3,7 @@ use anchor_lang::prelude::AccountMeta;
 use anyhow::Ok;
 use anyhow::Result;
 use luxor_swap::luxor_pool_state;
+use luxor_swap::states::{FeeTier, PoolState};
 use solana_sdk::{instruction::Instruction, pubkey::Pubkey, system_program};
 
 use luxor_swap::accounts as raydium_cp_accounts;
@@ -12,18 +13,21 @@ use luxor_swap::vault_and_lp_mint_auth;
 use std::rc::Rc;
 
 use crate::instructions::utils::get_admin_stake_info_address;
-use crate::instructions::utils::get_amm_config_address;
 use crate::instructions::utils::get_authority_address;
+use crate::instructions::utils::get_fee_tiers_address;
 use crate::instructions::utils::get_global_config_address;
+use crate::instructions::utils::get_isolated_stake_info_address;
 use crate::instructions::utils::get_luxor_reward_vault_address;
 use crate::instructions::utils::get_luxor_vault_address;
-use crate::instructions::utils::get_observation_state_address;
+use crate::instructions::utils::get_pending_emergency_address;
 use crate::instructions::utils::get_raydium_vault;
 use crate::instructions::utils::get_sol_treasury_address;
 use crate::instructions::utils::get_split_stake_pda_address;
 use crate::instructions::utils::get_stake_info_address;
 use crate::instructions::utils::get_stake_pda_address;
+use crate::instructions::utils::get_user_isolated_stake_address;
 use crate::instructions::utils::get_user_stake_info_address;
+use crate::instructions::utils::get_user_unstake_address;
 
 use super::super::{read_keypair_file, ClientConfig};
 
@@ -39,6 +43,8 @@ pub fn initialise_configs_instr(
     purchase_enabled: bool,
     redeem_enabled: bool,
     initial_lxr_allocation_vault: u64,
+    reward_mint: Pubkey,
+    emergency_delay: u64,
 ) -> Result<Vec<Instruction>> {
     let payer = read_keypair_file(&config.payer_path)?;
     let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
@@ -55,6 +61,7 @@ pub fn initialise_configs_instr(
             admin_stake_info: get_admin_stake_info_address(&program.id()),
             authority: get_authority_address(&program.id()),
             luxor_mint: luxor_swap::luxor_mint::id(),
+            reward_mint,
             luxor_vault: get_luxor_vault_address(&program.id()),
             luxor_reward_vault: get_luxor_reward_vault_address(&program.id()),
             stake_pda: get_stake_pda_address(&program.id()),
@@ -76,6 +83,7 @@ pub fn initialise_configs_instr(
             purchase_enabled,
             redeem_enabled,
             initial_lxr_allocation_vault,
+            emergency_delay,
         })
         .instructions()?;
     Ok(instructions)
@@ -111,6 +119,55 @@ pub fn update_config_instr(
     Ok(ixs)
 }
 
+pub fn set_pool_fee_override_instr(
+    config: &ClientConfig,
+    use_fee_override: bool,
+    trade_fee: u64,
+    protocol_fee: u64,
+    fund_fee: u64,
+    creator_fee: u64,
+) -> anyhow::Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.luxor_swap_program)?;
+
+    let ixs = program
+        .request()
+        .accounts(raydium_cp_accounts::SetPoolFeeOverride {
+            owner: program.payer(),
+            global_config: get_global_config_address(&program.id()),
+        })
+        .args(raydium_cp_instructions::SetPoolFeeOverride {
+            use_fee_override,
+            trade_fee,
+            protocol_fee,
+            fund_fee,
+            creator_fee,
+        })
+        .instructions()?; // build the instruction(s)
+
+    Ok(ixs)
+}
+
+pub fn accept_admin_instr(config: &ClientConfig) -> anyhow::Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.luxor_swap_program)?;
+
+    let ixs = program
+        .request()
+        .accounts(raydium_cp_accounts::AcceptAdmin {
+            pending_admin: program.payer(),
+            global_config: get_global_config_address(&program.id()),
+        })
+        .args(raydium_cp_instructions::AcceptAdmin {})
+        .instructions()?; // build the instruction(s)
+
+    Ok(ixs)
+}
+
 pub fn manual_purchase_instr(
     config: &ClientConfig,
     user: Pubkey,
@@ -149,10 +206,112 @@ pub fn manual_purchase_instr(
     Ok(ixs)
 }
 
+pub fn airdrop_lxr_instr(
+    config: &ClientConfig,
+    user: Pubkey,
+    amount: u64,
+) -> anyhow::Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.luxor_swap_program)?;
+
+    let ixs = program
+        .request()
+        .accounts(raydium_cp_accounts::AirdropLxr {
+            owner: program.payer(),
+            global_config: get_global_config_address(&program.id()),
+            user,
+            user_stake_info: get_user_stake_info_address(&user, &program.id()),
+            stake_info: get_stake_info_address(&program.id()),
+            authority: get_authority_address(&program.id()),
+            luxor_vault: get_luxor_vault_address(&program.id()),
+            luxor_mint: luxor_swap::luxor_mint::id(),
+            user_lxr_token: spl_associated_token_account::get_associated_token_address(
+                &user,
+                &luxor_swap::luxor_mint::id(),
+            ),
+            token_program: spl_token::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            system_program: system_program::id(),
+        })
+        .args(raydium_cp_instructions::AirdropLxr { amount })
+        .instructions()?; // build the instruction(s)
+
+    Ok(ixs)
+}
+
+pub fn deposit_to_treasury_instr(
+    config: &ClientConfig,
+    amount: u64,
+    bump_allocation: bool,
+) -> anyhow::Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.luxor_swap_program)?;
+
+    let ixs = program
+        .request()
+        .accounts(raydium_cp_accounts::DepositToTreasury {
+            owner: program.payer(),
+            global_config: get_global_config_address(&program.id()),
+            luxor_vault: get_luxor_vault_address(&program.id()),
+            luxor_mint: luxor_swap::luxor_mint::id(),
+            owner_lxr_token: spl_associated_token_account::get_associated_token_address(
+                &program.payer(),
+                &luxor_swap::luxor_mint::id(),
+            ),
+            token_program: spl_token::id(),
+        })
+        .args(raydium_cp_instructions::DepositToTreasury {
+            amount,
+            bump_allocation,
+        })
+        .instructions()?; // build the instruction(s)
+
+    Ok(ixs)
+}
+
+pub fn withdraw_treasury_fees_instr(
+    config: &ClientConfig,
+    fee_recipient: Pubkey,
+    amount: u64,
+) -> anyhow::Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.luxor_swap_program)?;
+
+    let ixs = program
+        .request()
+        .accounts(raydium_cp_accounts::WithdrawTreasuryFees {
+            owner: program.payer(),
+            global_config: get_global_config_address(&program.id()),
+            authority: get_authority_address(&program.id()),
+            stake_info: get_stake_info_address(&program.id()),
+            sol_treasury_vault: get_sol_treasury_address(&program.id()),
+            fee_recipient,
+            native_mint: spl_token::native_mint::id(),
+            fee_recipient_wsol_token: spl_associated_token_account::get_associated_token_address(
+                &fee_recipient,
+                &spl_token::native_mint::id(),
+            ),
+            token_program: spl_token::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            system_program: system_program::id(),
+        })
+        .args(raydium_cp_instructions::WithdrawTreasuryFees { amount })
+        .instructions()?; // build the instruction(s)
+
+    Ok(ixs)
+}
+
 pub fn purchase_instr(
     config: &ClientConfig,
     lxr_to_purchase: u64,
     max_sol_amount: u64,
+    deadline: i64,
     vote_account: Pubkey,
 ) -> anyhow::Result<Vec<Instruction>> {
     let payer = read_keypair_file(&config.payer_path)?;
@@ -190,13 +349,20 @@ pub fn purchase_instr(
         .args(raydium_cp_instructions::Purchase {
             lxr_to_purchase,
             max_sol_amount,
+            deadline,
         })
         .instructions()?; // build the instruction(s)
 
     Ok(ixs)
 }
 
-pub fn redeem_instr(config: &ClientConfig) -> anyhow::Result<Vec<Instruction>> {
+pub fn purchase_isolated_instr(
+    config: &ClientConfig,
+    lxr_to_purchase: u64,
+    max_sol_amount: u64,
+    deadline: i64,
+    vote_account: Pubkey,
+) -> anyhow::Result<Vec<Instruction>> {
     let payer = read_keypair_file(&config.payer_path)?;
     let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
     let client = Client::new(url, Rc::new(payer));
@@ -204,10 +370,9 @@ pub fn redeem_instr(config: &ClientConfig) -> anyhow::Result<Vec<Instruction>> {
 
     let ixs = program
         .request()
-        .accounts(raydium_cp_accounts::Redeem {
+        .accounts(raydium_cp_accounts::PurchaseIsolated {
             owner: program.payer(),
             global_config: get_global_config_address(&program.id()),
-            user_stake_info: get_user_stake_info_address(&program.payer(), &program.id()),
             stake_info: get_stake_info_address(&program.id()),
             authority: get_authority_address(&program.id()),
             luxor_mint: luxor_swap::luxor_mint::id(),
@@ -217,20 +382,34 @@ pub fn redeem_instr(config: &ClientConfig) -> anyhow::Result<Vec<Instruction>> {
                 &luxor_swap::luxor_mint::id(),
             ),
             system_program: system_program::id(),
-            associated_token_program: spl_associated_token_account::id(),
+            user_stake_pda: get_user_isolated_stake_address(&program.payer(), &program.id()),
+            isolated_stake_info: get_isolated_stake_info_address(&program.payer(), &program.id()),
+            vote_account,
+            stake_program: solana_sdk::stake::program::id(),
+            clock: solana_sdk::sysvar::clock::id(),
+            stake_history: solana_sdk::sysvar::stake_history::id(),
+            stake_config: solana_sdk::stake::config::id(),
+            rent: solana_sdk::sysvar::rent::id(),
+            pool_state: luxor_pool_state::id(),
             token_program: spl_token::id(),
-            luxor_reward_vault: get_luxor_reward_vault_address(&program.id()),
+            token_0_vault: get_raydium_vault(&raydium_cpmm::id(), &spl_token::native_mint::id()),
+            token_1_vault: get_raydium_vault(&raydium_cpmm::id(), &luxor_swap::luxor_mint::id()),
+            associated_token_program: spl_associated_token_account::id(),
+        })
+        .args(raydium_cp_instructions::PurchaseIsolated {
+            lxr_to_purchase,
+            max_sol_amount,
+            deadline,
         })
-        .args(raydium_cp_instructions::Redeem {})
         .instructions()?; // build the instruction(s)
 
     Ok(ixs)
 }
 
-pub fn buyback_instr(
+pub fn redeem_instr(
     config: &ClientConfig,
-    count: u64,
-    vote_account: Pubkey,
+    reward_mint: Pubkey,
+    amount: Option<u64>,
 ) -> anyhow::Result<Vec<Instruction>> {
     let payer = read_keypair_file(&config.payer_path)?;
     let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
@@ -239,52 +418,120 @@ pub fn buyback_instr(
 
     let ixs = program
         .request()
-        .accounts(raydium_cp_accounts::Buyback {
+        .accounts(raydium_cp_accounts::Redeem {
             owner: program.payer(),
             global_config: get_global_config_address(&program.id()),
-            luxor_reward_vault: get_luxor_reward_vault_address(&program.id()),
-            sol_treasury_vault: get_sol_treasury_address(&program.id()),
+            user_stake_info: get_user_stake_info_address(&program.payer(), &program.id()),
             stake_info: get_stake_info_address(&program.id()),
+            authority: get_authority_address(&program.id()),
+            luxor_mint: luxor_swap::luxor_mint::id(),
+            luxor_vault: get_luxor_vault_address(&program.id()),
+            reward_mint,
+            owner_reward_token: spl_associated_token_account::get_associated_token_address(
+                &program.payer(),
+                &reward_mint,
+            ),
+            owner_lxr_token: spl_associated_token_account::get_associated_token_address(
+                &program.payer(),
+                &luxor_swap::luxor_mint::id(),
+            ),
             system_program: system_program::id(),
-            stake_pda: get_stake_pda_address(&program.id()),
-            stake_split_pda: get_split_stake_pda_address(&program.id(), count),
-            pool_state: luxor_pool_state::id(),
-            token_program: spl_token::id(),
             associated_token_program: spl_associated_token_account::id(),
-            token_0_vault: get_raydium_vault(&raydium_cpmm::id(), &spl_token::native_mint::id()),
-            token_1_vault: get_raydium_vault(&raydium_cpmm::id(), &luxor_swap::luxor_mint::id()),
-            token_0_account: spl_associated_token_account::get_associated_token_address(
+            token_program: spl_token::id(),
+            luxor_reward_vault: get_luxor_reward_vault_address(&program.id()),
+        })
+        .args(raydium_cp_instructions::Redeem { amount })
+        .instructions()?; // build the instruction(s)
+
+    Ok(ixs)
+}
+
+pub fn claim_sol_rewards_instr(config: &ClientConfig) -> anyhow::Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.luxor_swap_program)?;
+
+    let ixs = program
+        .request()
+        .accounts(raydium_cp_accounts::ClaimSolRewards {
+            owner: program.payer(),
+            global_config: get_global_config_address(&program.id()),
+            user_stake_info: get_user_stake_info_address(&program.payer(), &program.id()),
+            authority: get_authority_address(&program.id()),
+            stake_info: get_stake_info_address(&program.id()),
+            sol_treasury_vault: get_sol_treasury_address(&program.id()),
+            native_mint: spl_token::native_mint::id(),
+            owner_wsol_token: spl_associated_token_account::get_associated_token_address(
                 &program.payer(),
                 &spl_token::native_mint::id(),
             ),
-            token_1_account: spl_associated_token_account::get_associated_token_address(
+            token_program: spl_token::id(),
+            associated_token_program: spl_associated_token_account::id(),
+            system_program: system_program::id(),
+        })
+        .args(raydium_cp_instructions::ClaimSolRewards {})
+        .instructions()?; // build the instruction(s)
+
+    Ok(ixs)
+}
+
+pub fn unstake_instr(config: &ClientConfig, amount: u64) -> anyhow::Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.luxor_swap_program)?;
+
+    let ixs = program
+        .request()
+        .accounts(raydium_cp_accounts::Unstake {
+            owner: program.payer(),
+            global_config: get_global_config_address(&program.id()),
+            user_stake_info: get_user_stake_info_address(&program.payer(), &program.id()),
+            stake_info: get_stake_info_address(&program.id()),
+            authority: get_authority_address(&program.id()),
+            stake_pda: get_stake_pda_address(&program.id()),
+            user_unstake_pda: get_user_unstake_address(&program.payer(), &program.id()),
+            luxor_vault: get_luxor_vault_address(&program.id()),
+            luxor_mint: luxor_swap::luxor_mint::id(),
+            owner_lxr_token: spl_associated_token_account::get_associated_token_address(
                 &program.payer(),
                 &luxor_swap::luxor_mint::id(),
             ),
-            vault_0_mint: spl_token::native_mint::id(),
-            vault_1_mint: luxor_swap::luxor_mint::id(),
-            raydium_authority: vault_and_lp_mint_auth::id(),
-            raydium_cpmm_program: raydium_cpmm::id(),
-            authority: get_authority_address(&program.id()),
+            token_program: spl_token::id(),
+            clock: solana_sdk::sysvar::clock::id(),
             stake_program: solana_sdk::stake::program::id(),
+            system_program: system_program::id(),
+        })
+        .args(raydium_cp_instructions::Unstake { amount })
+        .instructions()?; // build the instruction(s)
+
+    Ok(ixs)
+}
+
+pub fn withdraw_unstaked_instr(config: &ClientConfig) -> anyhow::Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.luxor_swap_program)?;
+
+    let ixs = program
+        .request()
+        .accounts(raydium_cp_accounts::WithdrawUnstaked {
+            owner: program.payer(),
+            authority: get_authority_address(&program.id()),
+            user_unstake_pda: get_user_unstake_address(&program.payer(), &program.id()),
             clock: solana_sdk::sysvar::clock::id(),
-            vote_account,
-            stake_config: solana_sdk::stake::config::id(),
             stake_history: solana_sdk::sysvar::stake_history::id(),
-            amm_config: get_amm_config_address(&raydium_cpmm::id(), 0),
-            observation_state: get_observation_state_address(&raydium_cpmm::id()),
+            stake_program: solana_sdk::stake::program::id(),
         })
-        .args(raydium_cp_instructions::Buyback {})
+        .args(raydium_cp_instructions::WithdrawUnstaked {})
         .instructions()?; // build the instruction(s)
 
     Ok(ixs)
 }
 
-pub fn emergency_withdraw_instr(
-    config: &ClientConfig,
-    param: u8,
-    value: u64,
-) -> anyhow::Result<Vec<Instruction>> {
+pub fn unstake_isolated_instr(config: &ClientConfig) -> anyhow::Result<Vec<Instruction>> {
     let payer = read_keypair_file(&config.payer_path)?;
     let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
     let client = Client::new(url, Rc::new(payer));
@@ -292,43 +539,30 @@ pub fn emergency_withdraw_instr(
 
     let ixs = program
         .request()
-        .accounts(raydium_cp_accounts::EmergencyWithdraw {
+        .accounts(raydium_cp_accounts::UnstakeIsolated {
             owner: program.payer(),
             global_config: get_global_config_address(&program.id()),
             authority: get_authority_address(&program.id()),
-            luxor_vault_any: get_luxor_vault_address(&program.id()),
-            luxor_reward_vault: get_luxor_reward_vault_address(&program.id()),
-            admin_stake_info: get_admin_stake_info_address(&program.id()),
-            stake_info: get_stake_info_address(&program.id()),
+            user_stake_pda: get_user_isolated_stake_address(&program.payer(), &program.id()),
+            isolated_stake_info: get_isolated_stake_info_address(&program.payer(), &program.id()),
+            luxor_vault: get_luxor_vault_address(&program.id()),
             luxor_mint: luxor_swap::luxor_mint::id(),
-            native_mint: spl_token::native_mint::id(),
-            sol_treasury_vault: get_sol_treasury_address(&program.id()),
             owner_lxr_token: spl_associated_token_account::get_associated_token_address(
                 &program.payer(),
                 &luxor_swap::luxor_mint::id(),
             ),
-            owner_wsol_token: spl_associated_token_account::get_associated_token_address(
-                &program.payer(),
-                &spl_token::native_mint::id(),
-            ),
-            stake_pda: get_stake_pda_address(&program.id()),
+            token_program: spl_token::id(),
             clock: solana_sdk::sysvar::clock::id(),
-            stake_program: solana_sdk::stake::program::id(),
             stake_history: solana_sdk::sysvar::stake_history::id(),
-            token_program: spl_token::id(),
-            system_program: system_program::id(),
-            associated_token_program: spl_associated_token_account::id(),
+            stake_program: solana_sdk::stake::program::id(),
         })
-        .args(raydium_cp_instructions::EmergencyWithdraw { param, value })
+        .args(raydium_cp_instructions::UnstakeIsolated {})
         .instructions()?; // build the instruction(s)
 
     Ok(ixs)
 }
 
-pub fn blacklist_user_instr(
-    config: &ClientConfig,
-    user: Pubkey,
-) -> anyhow::Result<Vec<Instruction>> {
+pub fn close_user_stake_info_instr(config: &ClientConfig) -> anyhow::Result<Vec<Instruction>> {
     let payer = read_keypair_file(&config.payer_path)?;
     let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
     let client = Client::new(url, Rc::new(payer));
@@ -336,16 +570,644 @@ pub fn blacklist_user_instr(
 
     let ixs = program
         .request()
-        .accounts(raydium_cp_accounts::Blacklist {
+        .accounts(raydium_cp_accounts::CloseUserStakeInfo {
             owner: program.payer(),
-            user,
-            user_stake_info: get_user_stake_info_address(&user, &program.id()),
+            global_config: get_global_config_address(&program.id()),
+            user_stake_info: get_user_stake_info_address(&program.payer(), &program.id()),
             stake_info: get_stake_info_address(&program.id()),
+        })
+        .args(raydium_cp_instructions::CloseUserStakeInfo {})
+        .instructions()?; // build the instruction(s)
+
+    Ok(ixs)
+}
+
+/// Accounts shared by `buyback`, `buyback_withdraw` and `buyback_settle`.
+fn buyback_accounts(
+    program_id: &Pubkey,
+    payer: Pubkey,
+    count: u64,
+    vote_account: Pubkey,
+    pool_id: Pubkey,
+    pool_state: &PoolState,
+) -> raydium_cp_accounts::Buyback {
+    // `PoolState` is packed; copy the fields out before borrowing them.
+    let reward_mint = pool_state.token_1_mint;
+
+    raydium_cp_accounts::Buyback {
+        owner: payer,
+        global_config: get_global_config_address(program_id),
+        luxor_reward_vault: get_luxor_reward_vault_address(program_id),
+        sol_treasury_vault: get_sol_treasury_address(program_id),
+        stake_info: get_stake_info_address(program_id),
+        system_program: system_program::id(),
+        stake_pda: get_stake_pda_address(program_id),
+        stake_split_pda: get_split_stake_pda_address(program_id, count),
+        pool_state: pool_id,
+        token_program: spl_token::id(),
+        associated_token_program: spl_associated_token_account::id(),
+        token_0_vault: pool_state.token_0_vault,
+        token_1_vault: pool_state.token_1_vault,
+        token_0_account: spl_associated_token_account::get_associated_token_address(
+            &payer,
+            &spl_token::native_mint::id(),
+        ),
+        token_1_account: spl_associated_token_account::get_associated_token_address(
+            &payer,
+            &reward_mint,
+        ),
+        vault_0_mint: spl_token::native_mint::id(),
+        vault_1_mint: reward_mint,
+        raydium_authority: vault_and_lp_mint_auth::id(),
+        raydium_cpmm_program: raydium_cpmm::id(),
+        authority: get_authority_address(program_id),
+        stake_program: solana_sdk::stake::program::id(),
+        clock: solana_sdk::sysvar::clock::id(),
+        vote_account,
+        stake_config: solana_sdk::stake::config::id(),
+        stake_history: solana_sdk::sysvar::stake_history::id(),
+        amm_config: pool_state.amm_config,
+        fee_tiers: get_fee_tiers_address(program_id),
+        observation_state: pool_state.observation_key,
+    }
+}
+
+pub fn buyback_instr(
+    config: &ClientConfig,
+    count: u64,
+    vote_account: Pubkey,
+    min_lxr_out: u64,
+    pool_id: Pubkey,
+    pool_state: &PoolState,
+) -> anyhow::Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.luxor_swap_program)?;
+
+    let ixs = program
+        .request()
+        .accounts(buyback_accounts(&program.id(), program.payer(), count, vote_account, pool_id, pool_state))
+        .args(raydium_cp_instructions::Buyback { min_lxr_out })
+        .instructions()?; // build the instruction(s)
+
+    Ok(ixs)
+}
+
+pub fn buyback_withdraw_instr(
+    config: &ClientConfig,
+    count: u64,
+    vote_account: Pubkey,
+    pool_id: Pubkey,
+    pool_state: &PoolState,
+) -> anyhow::Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.luxor_swap_program)?;
+
+    let ixs = program
+        .request()
+        .accounts(buyback_accounts(&program.id(), program.payer(), count, vote_account, pool_id, pool_state))
+        .args(raydium_cp_instructions::BuybackWithdraw {})
+        .instructions()?; // build the instruction(s)
+
+    Ok(ixs)
+}
+
+pub fn buyback_settle_instr(
+    config: &ClientConfig,
+    count: u64,
+    vote_account: Pubkey,
+    min_lxr_out: u64,
+    pool_id: Pubkey,
+    pool_state: &PoolState,
+) -> anyhow::Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.luxor_swap_program)?;
+
+    let ixs = program
+        .request()
+        .accounts(buyback_accounts(&program.id(), program.payer(), count, vote_account, pool_id, pool_state))
+        .args(raydium_cp_instructions::BuybackSettle { min_lxr_out })
+        .instructions()?; // build the instruction(s)
+
+    Ok(ixs)
+}
+
+pub fn emergency_withdraw_instr(
+    config: &ClientConfig,
+    param: u8,
+    value: u64,
+) -> anyhow::Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.luxor_swap_program)?;
+
+    let ixs = program
+        .request()
+        .accounts(raydium_cp_accounts::EmergencyWithdraw {
+            owner: program.payer(),
+            global_config: get_global_config_address(&program.id()),
+            authority: get_authority_address(&program.id()),
+            luxor_vault_any: get_luxor_vault_address(&program.id()),
+            luxor_reward_vault: get_luxor_reward_vault_address(&program.id()),
+            admin_stake_info: get_admin_stake_info_address(&program.id()),
+            stake_info: get_stake_info_address(&program.id()),
+            luxor_mint: luxor_swap::luxor_mint::id(),
+            native_mint: spl_token::native_mint::id(),
+            sol_treasury_vault: get_sol_treasury_address(&program.id()),
+            owner_lxr_token: spl_associated_token_account::get_associated_token_address(
+                &program.payer(),
+                &luxor_swap::luxor_mint::id(),
+            ),
+            owner_wsol_token: spl_associated_token_account::get_associated_token_address(
+                &program.payer(),
+                &spl_token::native_mint::id(),
+            ),
+            stake_pda: get_stake_pda_address(&program.id()),
+            pending_emergency: get_pending_emergency_address(&program.id()),
+            clock: solana_sdk::sysvar::clock::id(),
+            stake_program: solana_sdk::stake::program::id(),
+            stake_history: solana_sdk::sysvar::stake_history::id(),
+            token_program: spl_token::id(),
+            system_program: system_program::id(),
+            associated_token_program: spl_associated_token_account::id(),
+        })
+        .args(raydium_cp_instructions::EmergencyWithdraw { param, value })
+        .instructions()?; // build the instruction(s)
+
+    Ok(ixs)
+}
+
+pub fn propose_blacklist_instr(
+    config: &ClientConfig,
+    user: Pubkey,
+) -> anyhow::Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.luxor_swap_program)?;
+
+    let ixs = program
+        .request()
+        .accounts(raydium_cp_accounts::ProposeBlacklist {
+            owner: program.payer(),
+            global_config: get_global_config_address(&program.id()),
+            user,
+            user_stake_info: get_user_stake_info_address(&user, &program.id()),
+        })
+        .args(raydium_cp_instructions::ProposeBlacklist {})
+        .instructions()?; // build the instruction(s)
+
+    Ok(ixs)
+}
+
+pub fn cancel_blacklist_instr(
+    config: &ClientConfig,
+    user: Pubkey,
+) -> anyhow::Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.luxor_swap_program)?;
+
+    let ixs = program
+        .request()
+        .accounts(raydium_cp_accounts::CancelBlacklist {
+            owner: program.payer(),
+            global_config: get_global_config_address(&program.id()),
+            user,
+            user_stake_info: get_user_stake_info_address(&user, &program.id()),
+        })
+        .args(raydium_cp_instructions::CancelBlacklist {})
+        .instructions()?; // build the instruction(s)
+
+    Ok(ixs)
+}
+
+/// Builds `finalize_blacklist` (requires a prior `propose_blacklist` and the delay to have elapsed).
+pub fn blacklist_user_instr(
+    config: &ClientConfig,
+    user: Pubkey,
+) -> anyhow::Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.luxor_swap_program)?;
+
+    let ixs = program
+        .request()
+        .accounts(raydium_cp_accounts::FinalizeBlacklist {
+            owner: program.payer(),
+            user,
+            user_stake_info: get_user_stake_info_address(&user, &program.id()),
+            stake_info: get_stake_info_address(&program.id()),
+            stake_pda: get_stake_pda_address(&program.id()),
             admin_stake_info: get_admin_stake_info_address(&program.id()),
             global_config: get_global_config_address(&program.id()),
             system_program: system_program::id(),
         })
-        .args(raydium_cp_instructions::Blacklist {})
+        .args(raydium_cp_instructions::FinalizeBlacklist {})
+        .instructions()?; // build the instruction(s)
+
+    Ok(ixs)
+}
+
+pub fn un_blacklist_instr(
+    config: &ClientConfig,
+    user: Pubkey,
+) -> anyhow::Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.luxor_swap_program)?;
+
+    let ixs = program
+        .request()
+        .accounts(raydium_cp_accounts::UnBlacklist {
+            owner: program.payer(),
+            global_config: get_global_config_address(&program.id()),
+            user,
+            user_stake_info: get_user_stake_info_address(&user, &program.id()),
+            admin_stake_info: get_admin_stake_info_address(&program.id()),
+            stake_info: get_stake_info_address(&program.id()),
+            stake_pda: get_stake_pda_address(&program.id()),
+        })
+        .args(raydium_cp_instructions::UnBlacklist {})
+        .instructions()?; // build the instruction(s)
+
+    Ok(ixs)
+}
+
+pub fn rotate_stake_authority_instr(
+    config: &ClientConfig,
+    new_authority: Pubkey,
+) -> anyhow::Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.luxor_swap_program)?;
+
+    let ixs = program
+        .request()
+        .accounts(raydium_cp_accounts::RotateStakeAuthority {
+            owner: program.payer(),
+            global_config: get_global_config_address(&program.id()),
+            authority: get_authority_address(&program.id()),
+            stake_pda: get_stake_pda_address(&program.id()),
+            clock: solana_sdk::sysvar::clock::id(),
+            stake_program: solana_sdk::stake::program::id(),
+            pending_emergency: get_pending_emergency_address(&program.id()),
+        })
+        .args(raydium_cp_instructions::RotateStakeAuthority { new_authority })
+        .instructions()?; // build the instruction(s)
+
+    Ok(ixs)
+}
+
+pub fn migrate_reward_scale_instr(
+    config: &ClientConfig,
+    numerator: u64,
+    denominator: u64,
+) -> anyhow::Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.luxor_swap_program)?;
+
+    let ixs = program
+        .request()
+        .accounts(raydium_cp_accounts::MigrateRewardScale {
+            owner: program.payer(),
+            global_config: get_global_config_address(&program.id()),
+            stake_info: get_stake_info_address(&program.id()),
+        })
+        .args(raydium_cp_instructions::MigrateRewardScale {
+            numerator,
+            denominator,
+        })
+        .instructions()?; // build the instruction(s)
+
+    Ok(ixs)
+}
+
+pub fn migrate_accounts_instr(
+    config: &ClientConfig,
+    users: &[Pubkey],
+    admin_record: bool,
+) -> anyhow::Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.luxor_swap_program)?;
+
+    let mut ixs = program
+        .request()
+        .accounts(raydium_cp_accounts::MigrateAccounts {
+            owner: program.payer(),
+            global_config: get_global_config_address(&program.id()),
+            stake_info: get_stake_info_address(&program.id()),
+            system_program: system_program::id(),
+        })
+        .args(raydium_cp_instructions::MigrateAccounts {})
+        .instructions()?; // build the instruction(s)
+
+    for user in users {
+        ixs[0].accounts.push(AccountMeta::new(
+            get_user_stake_info_address(user, &program.id()),
+            false,
+        ));
+    }
+    if admin_record {
+        ixs[0].accounts.push(AccountMeta::new(
+            get_admin_stake_info_address(&program.id()),
+            false,
+        ));
+    }
+
+    Ok(ixs)
+}
+
+pub fn reconcile_buyback_accounting_instr(
+    config: &ClientConfig,
+    sol_used_for_buyback: u64,
+    sol_rewards_accrued: u64,
+) -> anyhow::Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.luxor_swap_program)?;
+
+    let ixs = program
+        .request()
+        .accounts(raydium_cp_accounts::ReconcileBuybackAccounting {
+            owner: program.payer(),
+            global_config: get_global_config_address(&program.id()),
+            stake_info: get_stake_info_address(&program.id()),
+        })
+        .args(raydium_cp_instructions::ReconcileBuybackAccounting {
+            sol_used_for_buyback,
+            sol_rewards_accrued,
+        })
+        .instructions()?; // build the instruction(s)
+
+    Ok(ixs)
+}
+
+pub fn finalize_stake_closure_instr(config: &ClientConfig) -> anyhow::Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.luxor_swap_program)?;
+
+    let ixs = program
+        .request()
+        .accounts(raydium_cp_accounts::FinalizeStakeClosure {
+            owner: program.payer(),
+            global_config: get_global_config_address(&program.id()),
+            stake_info: get_stake_info_address(&program.id()),
+            stake_pda: get_stake_pda_address(&program.id()),
+        })
+        .args(raydium_cp_instructions::FinalizeStakeClosure {})
+        .instructions()?; // build the instruction(s)
+
+    Ok(ixs)
+}
+
+pub fn redelegate_instr(
+    config: &ClientConfig,
+    new_vote_account: Pubkey,
+) -> anyhow::Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.luxor_swap_program)?;
+
+    let ixs = program
+        .request()
+        .accounts(raydium_cp_accounts::Redelegate {
+            owner: program.payer(),
+            global_config: get_global_config_address(&program.id()),
+            stake_info: get_stake_info_address(&program.id()),
+            authority: get_authority_address(&program.id()),
+            stake_pda: get_stake_pda_address(&program.id()),
+            new_vote_account,
+            clock: solana_sdk::sysvar::clock::id(),
+            stake_history: solana_sdk::sysvar::stake_history::id(),
+            stake_config: solana_sdk::stake::config::id(),
+            stake_program: solana_sdk::stake::program::id(),
+        })
+        .args(raydium_cp_instructions::Redelegate {})
+        .instructions()?; // build the instruction(s)
+
+    Ok(ixs)
+}
+
+pub fn get_vault_balances_instr(config: &ClientConfig) -> anyhow::Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.luxor_swap_program)?;
+
+    let ixs = program
+        .request()
+        .accounts(raydium_cp_accounts::GetVaultBalances {
+            global_config: get_global_config_address(&program.id()),
+            luxor_vault: get_luxor_vault_address(&program.id()),
+            luxor_reward_vault: get_luxor_reward_vault_address(&program.id()),
+            sol_treasury_vault: get_sol_treasury_address(&program.id()),
+        })
+        .args(raydium_cp_instructions::GetVaultBalances {})
+        .instructions()?; // build the instruction(s)
+
+    Ok(ixs)
+}
+
+pub fn get_user_stake_status_instr(
+    config: &ClientConfig,
+    user: Pubkey,
+) -> anyhow::Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.luxor_swap_program)?;
+
+    let ixs = program
+        .request()
+        .accounts(raydium_cp_accounts::GetUserStakeStatus {
+            user,
+            user_stake_info: get_user_stake_info_address(&user, &program.id()),
+        })
+        .args(raydium_cp_instructions::GetUserStakeStatus {})
+        .instructions()?; // build the instruction(s)
+
+    Ok(ixs)
+}
+
+pub fn get_blacklist_status_instr(
+    config: &ClientConfig,
+    user: Pubkey,
+) -> anyhow::Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.luxor_swap_program)?;
+
+    let ixs = program
+        .request()
+        .accounts(raydium_cp_accounts::GetBlacklistStatus {
+            user,
+            user_stake_info: get_user_stake_info_address(&user, &program.id()),
+        })
+        .args(raydium_cp_instructions::GetBlacklistStatus {})
+        .instructions()?; // build the instruction(s)
+
+    Ok(ixs)
+}
+
+pub fn get_accrued_but_unbought_rewards_instr(config: &ClientConfig) -> anyhow::Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.luxor_swap_program)?;
+
+    let ixs = program
+        .request()
+        .accounts(raydium_cp_accounts::GetAccruedButUnboughtRewards {
+            global_config: get_global_config_address(&program.id()),
+            stake_info: get_stake_info_address(&program.id()),
+            stake_pda: get_stake_pda_address(&program.id()),
+        })
+        .args(raydium_cp_instructions::GetAccruedButUnboughtRewards {})
+        .instructions()?; // build the instruction(s)
+
+    Ok(ixs)
+}
+
+pub fn set_anchor_price_instr(config: &ClientConfig, anchor_price_x32: u128) -> anyhow::Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.luxor_swap_program)?;
+
+    let ixs = program
+        .request()
+        .accounts(raydium_cp_accounts::SetAnchorPrice {
+            owner: program.payer(),
+            global_config: get_global_config_address(&program.id()),
+        })
+        .args(raydium_cp_instructions::SetAnchorPrice { anchor_price_x32 })
+        .instructions()?; // build the instruction(s)
+
+    Ok(ixs)
+}
+
+/// `user_lxr_token` is the user's LXR ATA, or `None` when it does not exist.
+pub fn get_claimable_instr(
+    config: &ClientConfig,
+    user: Pubkey,
+    user_lxr_token: Option<Pubkey>,
+) -> anyhow::Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.luxor_swap_program)?;
+
+    let ixs = program
+        .request()
+        .accounts(raydium_cp_accounts::GetClaimable {
+            user,
+            global_config: get_global_config_address(&program.id()),
+            stake_info: get_stake_info_address(&program.id()),
+            user_stake_info: get_user_stake_info_address(&user, &program.id()),
+            luxor_mint: luxor_swap::luxor_mint::id(),
+            user_lxr_token,
+            token_program: spl_token::id(),
+        })
+        .args(raydium_cp_instructions::GetClaimable {})
+        .instructions()?; // build the instruction(s)
+
+    Ok(ixs)
+}
+
+pub fn set_fee_tiers_instr(config: &ClientConfig, tiers: Vec<FeeTier>) -> anyhow::Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.luxor_swap_program)?;
+
+    let ixs = program
+        .request()
+        .accounts(raydium_cp_accounts::SetFeeTiers {
+            owner: program.payer(),
+            global_config: get_global_config_address(&program.id()),
+            fee_tiers: get_fee_tiers_address(&program.id()),
+            system_program: system_program::id(),
+        })
+        .args(raydium_cp_instructions::SetFeeTiers { tiers })
+        .instructions()?; // build the instruction(s)
+
+    Ok(ixs)
+}
+
+pub fn queue_emergency_instr(
+    config: &ClientConfig,
+    param: u8,
+    value: u64,
+    target: Option<Pubkey>,
+) -> anyhow::Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.luxor_swap_program)?;
+
+    let mut ixs = program
+        .request()
+        .accounts(raydium_cp_accounts::QueueEmergency {
+            owner: program.payer(),
+            global_config: get_global_config_address(&program.id()),
+            pending_emergency: get_pending_emergency_address(&program.id()),
+            system_program: system_program::id(),
+        })
+        .args(raydium_cp_instructions::QueueEmergency { param, value })
+        .instructions()?; // build the instruction(s)
+
+    if let Some(target) = target {
+        ixs[0]
+            .accounts
+            .push(AccountMeta::new_readonly(target, false));
+    }
+
+    Ok(ixs)
+}
+
+pub fn rebalance_vaults_instr(
+    config: &ClientConfig,
+    from: u8,
+    to: u8,
+    amount: u64,
+) -> anyhow::Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.luxor_swap_program)?;
+
+    let ixs = program
+        .request()
+        .accounts(raydium_cp_accounts::RebalanceVaults {
+            owner: program.payer(),
+            global_config: get_global_config_address(&program.id()),
+            authority: get_authority_address(&program.id()),
+            luxor_vault: get_luxor_vault_address(&program.id()),
+            luxor_reward_vault: get_luxor_reward_vault_address(&program.id()),
+            luxor_mint: luxor_swap::luxor_mint::id(),
+            token_program: spl_token::id(),
+        })
+        .args(raydium_cp_instructions::RebalanceVaults { from, to, amount })
         .instructions()?; // build the instruction(s)
 
     Ok(ixs)
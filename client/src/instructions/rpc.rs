@@ -1,15 +1,32 @@
 use anyhow::{anyhow, Result};
 use solana_client::{
+    client_error::{ClientError, ClientErrorKind},
     rpc_client::RpcClient,
     rpc_config::RpcSendTransactionConfig,
     rpc_request::RpcRequest,
     rpc_response::{RpcResult, RpcSimulateTransactionResult},
 };
 use solana_sdk::{
-    account::Account, commitment_config::CommitmentConfig, program_pack::Pack as TokenPack,
-    pubkey::Pubkey, signature::Signature, transaction::Transaction,
+    account::Account,
+    commitment_config::CommitmentConfig,
+    program_pack::Pack as TokenPack,
+    pubkey::Pubkey,
+    signature::Signature,
+    signer::signers::Signers,
+    transaction::{Transaction, TransactionError},
 };
 use std::convert::Into;
+use std::thread::sleep;
+use std::time::Duration;
+
+/// Resend policy for `send_txn_with_retry`.
+#[derive(Clone, Copy, Debug)]
+pub struct RetryConfig {
+    /// Resends after the first attempt.
+    pub max_retries: u32,
+    /// Delay before the first resend; doubled after every further attempt.
+    pub retry_delay: Duration,
+}
 
 pub fn simulate_transaction(
     client: &RpcClient,
@@ -41,6 +58,74 @@ pub fn send_txn(client: &RpcClient, txn: &Transaction, wait_confirm: bool) -> Re
     )?)
 }
 
+/// Errors after which the transaction may still land or be resent: an expired or
+/// unknown blockhash, a lagging node, a transport failure or a timed-out confirmation.
+fn is_recoverable(err: &ClientError) -> bool {
+    if err.get_transaction_error() == Some(TransactionError::BlockhashNotFound) {
+        return true;
+    }
+    if matches!(err.kind(), ClientErrorKind::Io(_) | ClientErrorKind::Reqwest(_)) {
+        return true;
+    }
+    let msg = err.to_string().to_lowercase();
+    msg.contains("blockhash not found")
+        || msg.contains("node is behind")
+        || msg.contains("unable to confirm transaction")
+}
+
+/// `send_txn`, resending up to `retry.max_retries` times on recoverable errors,
+/// after an exponentially growing delay. `retry = None` sends exactly once.
+///
+/// A failed send may still have landed, so before each resend the status of the
+/// original signature is checked first: a landed transaction is returned as is.
+/// Otherwise the same signed transaction is resent while its blockhash is valid,
+/// and `txn` is only re-signed with `signers` against a fresh blockhash once the old
+/// one has expired, when the earlier signature can no longer execute.
+pub fn send_txn_with_retry<T: Signers + ?Sized>(
+    client: &RpcClient,
+    mut txn: Transaction,
+    signers: &T,
+    wait_confirm: bool,
+    retry: Option<RetryConfig>,
+) -> Result<Signature> {
+    let Some(retry) = retry else {
+        return send_txn(client, &txn, wait_confirm);
+    };
+    let mut delay = retry.retry_delay;
+    let mut attempt = 0;
+    loop {
+        let err = match send_txn(client, &txn, wait_confirm) {
+            Ok(signature) => return Ok(signature),
+            Err(err) => err,
+        };
+        let recoverable = err
+            .downcast_ref::<ClientError>()
+            .is_some_and(is_recoverable);
+        if !recoverable || attempt >= retry.max_retries {
+            return Err(err);
+        }
+        attempt += 1;
+        eprintln!(
+            "send failed ({}), retry {}/{} in {:?}",
+            err, attempt, retry.max_retries, delay
+        );
+        sleep(delay);
+        delay = delay.saturating_mul(2);
+
+        let signature = txn.signatures[0];
+        match client.get_signature_status(&signature)? {
+            Some(Ok(())) => return Ok(signature),
+            Some(Err(txn_err)) => return Err(anyhow!("{} failed: {}", signature, txn_err)),
+            None => {}
+        }
+        if !client
+            .is_blockhash_valid(&txn.message.recent_blockhash, CommitmentConfig::processed())?
+        {
+            txn.try_sign(signers, client.get_latest_blockhash()?)?;
+        }
+    }
+}
+
 pub fn get_token_account<T: TokenPack>(client: &RpcClient, addr: &Pubkey) -> Result<T> {
     let account = client
         .get_account_with_commitment(addr, CommitmentConfig::processed())?
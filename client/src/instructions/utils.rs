@@ -1,16 +1,27 @@
-use anchor_lang::AccountDeserialize;
-use anyhow::Result;
+use crate::instructions::rpc::get_multiple_accounts;
+use anchor_lang::{AccountDeserialize, AnchorDeserialize, Event};
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
 use luxor_swap::{
+    curve::{CurveCalculator, CurveType, FEE_RATE_DENOMINATOR_VALUE},
     luxor_pool_state,
-    states::{ADMIN_STAKE_INFO_SEED, GLOBAL_CONFIG_SEED, STAKE_INFO_SEED, USER_STAKE_INFO_SEED},
-    STAKE_SPLIT_ACCOUNT_SEED,
+    pricing::{priced_lxr_amount, quote_sol_for_lxr},
+    states::{
+        AmmConfig, GlobalConfig, PoolState, StakeInfo, UserStakeInfo, ADMIN_STAKE_INFO_SEED,
+        FeeTiers, GLOBAL_CONFIG_SEED, STAKE_INFO_SEED, USER_STAKE_INFO_SEED,
+        FEE_TIERS_SEED, ISOLATED_STAKE_INFO_SEED, PENDING_EMERGENCY_SEED,
+    },
+    STAKE_ACCOUNT_SEED, STAKE_SPLIT_ACCOUNT_SEED, USER_UNSTAKE_ACCOUNT_SEED,
 };
 use solana_client::rpc_client::RpcClient;
-use solana_sdk::{account::Account, pubkey::Pubkey};
+use solana_sdk::{
+    account::Account, compute_budget::ComputeBudgetInstruction, instruction::Instruction,
+    pubkey::Pubkey,
+};
 use spl_token_2022::{
     extension::{
         transfer_fee::{TransferFeeConfig, MAX_FEE_BASIS_POINTS},
-        BaseState, BaseStateWithExtensions, StateWithExtensionsMut,
+        BaseState, BaseStateWithExtensions, StateWithExtensions, StateWithExtensionsMut,
     },
     state::Mint,
 };
@@ -54,12 +65,12 @@ pub fn get_pool_mints_inverse_fee(
         TransferFeeInfo {
             mint: token_mint_0,
             owner: mint0_account.owner,
-            transfer_fee: get_transfer_inverse_fee(&mint0_state, post_fee_amount_0, epoch),
+            transfer_fee: get_transfer_inverse_fee(&mint0_state, epoch, post_fee_amount_0),
         },
         TransferFeeInfo {
             mint: token_mint_1,
             owner: mint1_account.owner,
-            transfer_fee: get_transfer_inverse_fee(&mint1_state, post_fee_amount_1, epoch),
+            transfer_fee: get_transfer_inverse_fee(&mint1_state, epoch, post_fee_amount_1),
         },
     )
 }
@@ -82,12 +93,12 @@ pub fn get_pool_mints_transfer_fee(
         TransferFeeInfo {
             mint: token_mint_0,
             owner: mint0_account.owner,
-            transfer_fee: get_transfer_fee(&mint0_state, pre_fee_amount_0, epoch),
+            transfer_fee: get_transfer_fee(&mint0_state, epoch, pre_fee_amount_0),
         },
         TransferFeeInfo {
             mint: token_mint_1,
             owner: mint1_account.owner,
-            transfer_fee: get_transfer_fee(&mint1_state, pre_fee_amount_1, epoch),
+            transfer_fee: get_transfer_fee(&mint1_state, epoch, pre_fee_amount_1),
         },
     )
 }
@@ -220,3 +231,588 @@ pub fn get_split_stake_pda_address(program_id: &Pubkey, count: u64) -> Pubkey {
     );
     split_stake_pda
 }
+
+pub fn get_user_isolated_stake_address(user: &Pubkey, program_id: &Pubkey) -> Pubkey {
+    let (user_stake_pda, _bump) = Pubkey::find_program_address(
+        &[STAKE_ACCOUNT_SEED.as_bytes(), user.as_ref()],
+        &program_id,
+    );
+    user_stake_pda
+}
+
+pub fn get_isolated_stake_info_address(user: &Pubkey, program_id: &Pubkey) -> Pubkey {
+    let (isolated_stake_info, _bump) = Pubkey::find_program_address(
+        &[ISOLATED_STAKE_INFO_SEED.as_bytes(), user.as_ref()],
+        &program_id,
+    );
+    isolated_stake_info
+}
+
+pub fn get_user_unstake_address(user: &Pubkey, program_id: &Pubkey) -> Pubkey {
+    let (user_unstake_pda, _bump) = Pubkey::find_program_address(
+        &[USER_UNSTAKE_ACCOUNT_SEED.as_bytes(), user.as_ref()],
+        &program_id,
+    );
+    user_unstake_pda
+}
+
+pub fn get_fee_tiers_address(program_id: &Pubkey) -> Pubkey {
+    let (fee_tiers, _bump) = Pubkey::find_program_address(&[FEE_TIERS_SEED.as_bytes()], &program_id);
+    fee_tiers
+}
+
+pub fn get_pending_emergency_address(program_id: &Pubkey) -> Pubkey {
+    let (pending_emergency, _bump) =
+        Pubkey::find_program_address(&[PENDING_EMERGENCY_SEED.as_bytes()], &program_id);
+    pending_emergency
+}
+
+/// Protocol-wide state fetched in a single `get_multiple_accounts` round-trip.
+#[derive(Debug)]
+pub struct ProtocolSnapshot {
+    pub global_config: GlobalConfig,
+    pub stake_info: StakeInfo,
+    pub admin_stake_info: UserStakeInfo,
+    /// LXR held by the treasury vault (base units).
+    pub lxr_treasury_vault_amount: u64,
+    /// LXR held by the reward vault (base units).
+    pub lxr_reward_vault_amount: u64,
+    /// WSOL held by the SOL treasury vault (lamports).
+    pub sol_treasury_vault_amount: u64,
+}
+
+pub fn get_protocol_snapshot(rpc_client: &RpcClient, program_id: &Pubkey) -> Result<ProtocolSnapshot> {
+    let keys = [
+        get_global_config_address(program_id),
+        get_stake_info_address(program_id),
+        get_admin_stake_info_address(program_id),
+        get_luxor_vault_address(program_id),
+        get_luxor_reward_vault_address(program_id),
+        get_sol_treasury_address(program_id),
+    ];
+    let accounts = get_multiple_accounts(rpc_client, &keys)?;
+    let mut accounts = keys.iter().zip(accounts).map(|(key, account)| {
+        account.ok_or_else(|| anyhow!("Account not found: {}", key))
+    });
+    let mut next = || accounts.next().unwrap();
+    let token_amount = |account: Account| -> Result<u64> {
+        Ok(StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)?
+            .base
+            .amount)
+    };
+
+    Ok(ProtocolSnapshot {
+        global_config: deserialize_anchor_account::<GlobalConfig>(&next()?)?,
+        stake_info: deserialize_anchor_account::<StakeInfo>(&next()?)?,
+        admin_stake_info: deserialize_anchor_account::<UserStakeInfo>(&next()?)?,
+        lxr_treasury_vault_amount: token_amount(next()?)?,
+        lxr_reward_vault_amount: token_amount(next()?)?,
+        sol_treasury_vault_amount: token_amount(next()?)?,
+    })
+}
+
+/// Fetches and deserializes a single Anchor account.
+pub fn fetch_anchor_account<T: AccountDeserialize>(
+    rpc_client: &RpcClient,
+    address: &Pubkey,
+) -> Result<T> {
+    deserialize_anchor_account::<T>(&rpc_client.get_account(address)?)
+}
+
+/// `GlobalConfig` as JSON for scripting (`Pubkey`s as base58 strings).
+pub fn global_config_to_json(global_config: &GlobalConfig) -> serde_json::Value {
+    serde_json::json!({
+        "bump": global_config.bump,
+        "admin": global_config.admin.to_string(),
+        "lxr_treasury_vault": global_config.lxr_treasury_vault.to_string(),
+        "lxr_reward_vault": global_config.lxr_reward_vault.to_string(),
+        "sol_treasury_vault": global_config.sol_treasury_vault.to_string(),
+        "stake_account": global_config.stake_account.to_string(),
+        "vote_account": global_config.vote_account.to_string(),
+        "stake_info": global_config.stake_info.to_string(),
+        "bonus_rate": global_config.bonus_rate,
+        "max_stake_count_to_get_bonus": global_config.max_stake_count_to_get_bonus,
+        "min_swap_amount": global_config.min_swap_amount,
+        "max_swap_amount": global_config.max_swap_amount,
+        "fee_treasury_rate": global_config.fee_treasury_rate,
+        "purchase_enabled": global_config.purchase_enabled,
+        "redeem_enabled": global_config.redeem_enabled,
+        "initial_lxr_allocation_vault": global_config.initial_lxr_allocation_vault,
+        "max_sol_per_buyback": global_config.max_sol_per_buyback,
+        "luxor_decimals": global_config.luxor_decimals,
+        "blacklist_delay_secs": global_config.blacklist_delay_secs,
+        "stake_rent_exempt": global_config.stake_rent_exempt,
+        "buyback_out_tolerance_rate": global_config.buyback_out_tolerance_rate,
+        "min_treasury_for_purchase": global_config.min_treasury_for_purchase,
+        "min_pool_reserve": global_config.min_pool_reserve,
+        "curve_type": global_config.curve_type,
+        "curve_offset_sol": global_config.curve_offset_sol,
+        "curve_offset_lxr": global_config.curve_offset_lxr,
+        "buyback_fee_enabled": global_config.buyback_fee_enabled,
+        "forfeiture_grace_bps": global_config.forfeiture_grace_bps,
+        "pending_admin": global_config.pending_admin.to_string(),
+        "use_fee_override": global_config.use_fee_override,
+        "override_trade_fee_rate": global_config.override_trade_fee_rate,
+        "override_protocol_fee_rate": global_config.override_protocol_fee_rate,
+        "override_fund_fee_rate": global_config.override_fund_fee_rate,
+        "override_creator_fee_rate": global_config.override_creator_fee_rate,
+        "max_pool_fraction_bps": global_config.max_pool_fraction_bps,
+        "redistribute_forfeitures": global_config.redistribute_forfeitures,
+        "reward_vault_target": global_config.reward_vault_target,
+        "buyback_cooldown_secs": global_config.buyback_cooldown_secs,
+        "fee_recipient": global_config.fee_recipient.to_string(),
+        "min_hold_ratio_bps": global_config.min_hold_ratio_bps,
+        "reward_mint": global_config.reward_mint.to_string(),
+        "reward_pool_state": global_config.reward_pool_state().to_string(),
+        "dex_type": global_config.dex_type,
+        "max_stake_per_user": global_config.max_stake_per_user,
+        "max_total_staked_sol": global_config.max_total_staked_sol,
+        "anchor_price_x32": global_config.anchor_price_x32.to_string(),
+        "max_price_deviation_bps": global_config.max_price_deviation_bps,
+        "emergency_delay": global_config.emergency_delay,
+        "sol_reward_share_bps": global_config.sol_reward_share_bps,
+        "min_lxr_per_purchase": global_config.min_lxr_per_purchase,
+        "max_lxr_per_purchase": global_config.max_lxr_per_purchase,
+    })
+}
+
+/// `StakeInfo` as JSON for scripting (`u128` reward indices as decimal strings,
+/// since they can exceed the range of JSON numbers).
+pub fn stake_info_to_json(stake_info: &StakeInfo) -> serde_json::Value {
+    serde_json::json!({
+        "bump": stake_info.bump,
+        "total_staked_sol": stake_info.total_staked_sol,
+        "total_stake_count": stake_info.total_stake_count,
+        "total_sol_rewards_accrued": stake_info.total_sol_rewards_accrued,
+        "last_tracked_sol_balance": stake_info.last_tracked_sol_balance,
+        "reward_per_token_sol_stored": stake_info.reward_per_token_sol_stored.to_string(),
+        "total_luxor_rewards_accrued": stake_info.total_luxor_rewards_accrued,
+        "total_sol_used_for_buyback": stake_info.total_sol_used_for_buyback,
+        "last_update_timestamp": stake_info.last_update_timestamp,
+        "last_buyback_timestamp": stake_info.last_buyback_timestamp,
+        "reward_per_token_lxr_stored": stake_info.reward_per_token_lxr_stored.to_string(),
+        "total_lxr_claimed": stake_info.total_lxr_claimed,
+        "total_lxr_forfeited": stake_info.total_lxr_forfeited,
+        "buyback_count": stake_info.buyback_count,
+        "buyback_requested": stake_info.buyback_requested,
+        "reward_scale_version": stake_info.reward_scale_version,
+        "reward_scale_numerator": stake_info.reward_scale_numerator,
+        "reward_scale_denominator": stake_info.reward_scale_denominator,
+        "total_forfeiture_redistributed": stake_info.total_forfeiture_redistributed,
+        "stake_closed": stake_info.stake_closed,
+        "processing": stake_info.processing,
+        "buyback_wsol_pending": stake_info.buyback_wsol_pending,
+        "buyback_rent_refunded": stake_info.buyback_rent_refunded,
+        "total_sol_rewards_reserved": stake_info.total_sol_rewards_reserved,
+        "total_sol_reserve_funded": stake_info.total_sol_reserve_funded,
+        "total_sol_rewards_claimed": stake_info.total_sol_rewards_claimed,
+        "buyback_reserve_split": stake_info.buyback_reserve_split,
+        "lxr_rewards_undistributed": stake_info.lxr_rewards_undistributed,
+    })
+}
+
+/// What `redeem` would pay out right now, computed locally with the program's
+/// own reward helpers.
+#[derive(Debug)]
+pub struct RedeemPreview {
+    /// Current LXR balance of the user's ATA (base units).
+    pub lxr_holdings: u64,
+    /// Baseline holdings recorded at purchase time (base units).
+    pub base_lxr_holdings: u64,
+    /// LXR that would be transferred to the user, including carried-over pending rewards.
+    pub claimable: u64,
+    /// LXR that would be forfeited to treasury because holdings are below baseline.
+    pub forfeited: u64,
+    /// Whether holdings reach `min_hold_ratio_bps` of the baseline; `redeem` fails
+    /// with `NoRewardsToClaim` otherwise.
+    pub meets_min_hold: bool,
+}
+
+pub fn get_redeem_preview(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    user: &Pubkey,
+) -> Result<RedeemPreview> {
+    let keys = [
+        get_user_stake_info_address(user, program_id),
+        get_stake_info_address(program_id),
+        spl_associated_token_account::get_associated_token_address(
+            user,
+            &luxor_swap::luxor_mint::id(),
+        ),
+        get_global_config_address(program_id),
+    ];
+    let accounts = get_multiple_accounts(rpc_client, &keys)?;
+    let mut user_stake_info = deserialize_anchor_account::<UserStakeInfo>(
+        accounts[0]
+            .as_ref()
+            .ok_or_else(|| anyhow!("Account not found: {}", keys[0]))?,
+    )?;
+    let stake_info = deserialize_anchor_account::<StakeInfo>(
+        accounts[1]
+            .as_ref()
+            .ok_or_else(|| anyhow!("Account not found: {}", keys[1]))?,
+    )?;
+    // A missing ATA holds nothing, which forfeits everything not already pending.
+    let lxr_holdings = match &accounts[2] {
+        Some(account) => {
+            StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)?
+                .base
+                .amount
+        }
+        None => 0,
+    };
+    let global_config = deserialize_anchor_account::<GlobalConfig>(
+        accounts[3]
+            .as_ref()
+            .ok_or_else(|| anyhow!("Account not found: {}", keys[3]))?,
+    )?;
+
+    let full_rewards = user_stake_info.pending_lxr_rewards(&stake_info)?;
+    let (claimable, forfeited) = user_stake_info.apply_holdings_forfeiture(
+        full_rewards,
+        lxr_holdings,
+        global_config.forfeiture_grace_bps,
+    )?;
+
+    Ok(RedeemPreview {
+        lxr_holdings,
+        base_lxr_holdings: user_stake_info.base_lxr_holdings,
+        claimable: claimable
+            .checked_add(user_stake_info.lxr_rewards_pending)
+            .ok_or_else(|| anyhow!("claimable overflow"))?,
+        forfeited,
+        meets_min_hold: user_stake_info
+            .meets_min_hold(lxr_holdings, global_config.min_hold_ratio_bps)?,
+    })
+}
+
+/// `min_lxr_out` for a buyback execution: quotes the swap the program will price
+/// for the withdrawn split stake `count` against the current pool and applies
+/// `slippage`. Returns `0` when the split does not exist yet (request phase).
+pub fn get_buyback_min_lxr_out(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    count: u64,
+    slippage: f64,
+) -> Result<u64> {
+    let keys = [
+        get_global_config_address(program_id),
+        get_split_stake_pda_address(program_id, count),
+        get_fee_tiers_address(program_id),
+    ];
+    let accounts = get_multiple_accounts(rpc_client, &keys)?;
+    let global_config = deserialize_anchor_account::<GlobalConfig>(
+        accounts[0]
+            .as_ref()
+            .ok_or_else(|| anyhow!("Account not found: {}", keys[0]))?,
+    )?;
+    let split = match &accounts[1] {
+        Some(account) if account.owner == solana_sdk::stake::program::id() => account,
+        _ => return Ok(0),
+    };
+    let pool_state =
+        fetch_anchor_account::<PoolState>(rpc_client, &global_config.reward_pool_state())?;
+
+    let rent = match global_config.stake_rent_exempt {
+        0 => rpc_client.get_minimum_balance_for_rent_exemption(split.data.len())?,
+        cached => cached,
+    };
+    let sol_withdrawn = split.lamports.saturating_sub(rent);
+    // Same rate selection as the program: the matching tier once a schedule exists.
+    let fee_rate = match &accounts[2] {
+        _ if !global_config.buyback_fee_enabled => 0,
+        Some(account) => deserialize_anchor_account::<FeeTiers>(account)?.rate_for(sol_withdrawn),
+        None => global_config.fee_treasury_rate,
+    };
+    let fee_treasury =
+        (sol_withdrawn as u128 * fee_rate as u128 / FEE_RATE_DENOMINATOR_VALUE as u128) as u64;
+    let amount_in = sol_withdrawn.saturating_sub(fee_treasury);
+
+    let keys = [
+        pool_state.amm_config,
+        pool_state.token_0_vault,
+        pool_state.token_1_vault,
+    ];
+    let accounts = get_multiple_accounts(rpc_client, &keys)?;
+    let amm_config = match global_config.fee_override() {
+        Some(fee_override) => fee_override,
+        None => deserialize_anchor_account::<AmmConfig>(
+            accounts[0]
+                .as_ref()
+                .ok_or_else(|| anyhow!("Account not found: {}", keys[0]))?,
+        )?,
+    };
+    let vault_amount = |i: usize| -> Result<u64> {
+        let account = accounts[i]
+            .as_ref()
+            .ok_or_else(|| anyhow!("Account not found: {}", keys[i]))?;
+        Ok(StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)?
+            .base
+            .amount)
+    };
+
+    let swap_params = pool_state.get_swap_params(
+        pool_state.token_0_vault,
+        pool_state.token_1_vault,
+        vault_amount(1)?,
+        vault_amount(2)?,
+    )?;
+    let result = CurveCalculator::swap_base_input(
+        u128::from(amount_in),
+        u128::from(swap_params.total_input_token_amount),
+        u128::from(swap_params.total_output_token_amount),
+        amm_config.trade_fee_rate,
+        pool_state.adjust_creator_fee_rate(amm_config.creator_fee_rate),
+        amm_config.protocol_fee_rate,
+        amm_config.fund_fee_rate,
+        swap_params.is_creator_fee_on_input,
+        CurveType::ConstantProduct,
+    )
+    .ok_or_else(|| anyhow!("buyback quote failed"))?;
+
+    Ok(amount_with_slippage(
+        u64::try_from(result.output_amount)?,
+        slippage,
+        false,
+    ))
+}
+
+/// Off-chain preview of `purchase`, priced with the program's own `quote_sol_for_lxr`.
+#[derive(Debug)]
+pub struct PurchaseQuote {
+    /// Lamports `purchase` would currently charge (lower bound for `max_sol_amount`).
+    pub sol_needed: u64,
+    /// LXR amount actually priced on the curve after the bonus or inventory scaling.
+    pub priced_lxr_amount: u64,
+    /// Whether the early-bird bonus applies (otherwise inventory scaling does).
+    pub bonus_applied: bool,
+    /// `priced_lxr_amount / lxr_to_purchase`.
+    pub price_factor: f64,
+}
+
+pub fn get_purchase_quote(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    user: &Pubkey,
+    lxr_to_purchase: u64,
+) -> Result<PurchaseQuote> {
+    let keys = [
+        get_global_config_address(program_id),
+        get_stake_info_address(program_id),
+        get_user_stake_info_address(user, program_id),
+        luxor_pool_state::id(),
+    ];
+    let accounts = get_multiple_accounts(rpc_client, &keys)?;
+    let global_config = deserialize_anchor_account::<GlobalConfig>(
+        accounts[0]
+            .as_ref()
+            .ok_or_else(|| anyhow!("Account not found: {}", keys[0]))?,
+    )?;
+    let stake_info = deserialize_anchor_account::<StakeInfo>(
+        accounts[1]
+            .as_ref()
+            .ok_or_else(|| anyhow!("Account not found: {}", keys[1]))?,
+    )?;
+    // Airdrop-only records keep a default `owner` and still price as new stakers.
+    let is_new_staker = match accounts[2].as_ref() {
+        Some(account) => {
+            deserialize_anchor_account::<UserStakeInfo>(account)?.owner == Pubkey::default()
+        }
+        None => true,
+    };
+    let pool_state = deserialize_anchor_account::<PoolState>(
+        accounts[3]
+            .as_ref()
+            .ok_or_else(|| anyhow!("Account not found: {}", keys[3]))?,
+    )?;
+
+    let keys = [
+        pool_state.token_0_vault,
+        pool_state.token_1_vault,
+        global_config.lxr_treasury_vault,
+    ];
+    let accounts = get_multiple_accounts(rpc_client, &keys)?;
+    let token_amount = |i: usize| -> Result<u64> {
+        let account = accounts[i]
+            .as_ref()
+            .ok_or_else(|| anyhow!("Account not found: {}", keys[i]))?;
+        Ok(StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)?
+            .base
+            .amount)
+    };
+
+    let luxor_vault_amount = token_amount(2)?;
+    let sol_needed = quote_sol_for_lxr(
+        &pool_state,
+        token_amount(0)?,
+        token_amount(1)?,
+        lxr_to_purchase,
+        &stake_info,
+        &global_config,
+        is_new_staker,
+        luxor_vault_amount,
+    )?;
+    let priced_lxr_amount = priced_lxr_amount(
+        lxr_to_purchase,
+        &stake_info,
+        &global_config,
+        is_new_staker,
+        luxor_vault_amount,
+    )?;
+
+    Ok(PurchaseQuote {
+        sol_needed,
+        priced_lxr_amount,
+        bonus_applied: is_new_staker && global_config.bonus_applies(stake_info.total_stake_count),
+        price_factor: priced_lxr_amount as f64 / lxr_to_purchase as f64,
+    })
+}
+
+/// Unix timestamp `secs` seconds from now, for instruction `deadline` arguments.
+pub fn deadline_from_now(secs: i64) -> Result<i64> {
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?;
+    Ok(i64::try_from(now.as_secs())? + secs)
+}
+
+/// `SetComputeUnitLimit` and `SetComputeUnitPrice` instructions to prepend to a
+/// transaction (the price is skipped when `priority_fee` is `0`).
+pub fn compute_budget_instructions(cu_limit: u32, priority_fee: u64) -> Vec<Instruction> {
+    let mut instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(cu_limit)];
+    if priority_fee > 0 {
+        instructions.push(ComputeBudgetInstruction::set_compute_unit_price(priority_fee));
+    }
+    instructions
+}
+
+/// Decodes the first Anchor event of type `T` emitted in `logs`
+/// (`Program data: <base64>` lines), e.g. from a simulated transaction.
+pub fn decode_event<T: Event + AnchorDeserialize>(logs: &[String]) -> Option<T> {
+    logs.iter()
+        .filter_map(|log| log.strip_prefix("Program data: "))
+        .filter_map(|data| STANDARD.decode(data).ok())
+        .find_map(|data| {
+            data.strip_prefix(T::DISCRIMINATOR)
+                .and_then(|mut payload| T::deserialize(&mut payload).ok())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_account_decoder::{UiAccount, UiAccountEncoding};
+    use solana_client::rpc_request::RpcRequest;
+    use solana_sdk::{epoch_info::EpochInfo, program_pack::Pack};
+    use spl_token_2022::extension::{transfer_fee::TransferFee, ExtensionType};
+    use std::collections::HashMap;
+
+    const NEWER_FEE_EPOCH: u64 = 5;
+
+    fn transfer_fee(epoch: u64, basis_points: u16) -> TransferFee {
+        TransferFee {
+            epoch: epoch.into(),
+            maximum_fee: u64::MAX.into(),
+            transfer_fee_basis_points: basis_points.into(),
+        }
+    }
+
+    /// Token-2022 mint charging 100 bps before `NEWER_FEE_EPOCH` and 250 bps from it.
+    fn transfer_fee_mint() -> Account {
+        let space =
+            ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferFeeConfig])
+                .unwrap();
+        let mut data = vec![0; space];
+        let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+        let config = state.init_extension::<TransferFeeConfig>(true).unwrap();
+        config.older_transfer_fee = transfer_fee(0, 100);
+        config.newer_transfer_fee = transfer_fee(NEWER_FEE_EPOCH, 250);
+        state.base = Mint {
+            decimals: 6,
+            is_initialized: true,
+            ..Default::default()
+        };
+        state.pack_base();
+        state.init_account_type().unwrap();
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner: spl_token_2022::id(),
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    fn plain_mint() -> Account {
+        let mut data = vec![0; Mint::LEN];
+        Mint {
+            decimals: 9,
+            is_initialized: true,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut data);
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner: spl_token::id(),
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    /// RPC client answering `getMultipleAccounts` with `accounts` and
+    /// `getEpochInfo` with `epoch`.
+    fn mock_rpc_client(accounts: &[(Pubkey, Account)], epoch: u64) -> RpcClient {
+        let value: Vec<_> = accounts
+            .iter()
+            .map(|(key, account)| {
+                Some(UiAccount::encode(
+                    key,
+                    account,
+                    UiAccountEncoding::Base64,
+                    None,
+                    None,
+                ))
+            })
+            .collect();
+        let epoch_info = EpochInfo {
+            epoch,
+            slot_index: 0,
+            slots_in_epoch: 32,
+            absolute_slot: epoch * 32,
+            block_height: epoch * 32,
+            transaction_count: None,
+        };
+        let mocks = HashMap::from([
+            (
+                RpcRequest::GetMultipleAccounts,
+                serde_json::json!({ "context": { "slot": 1 }, "value": value }),
+            ),
+            (
+                RpcRequest::GetEpochInfo,
+                serde_json::to_value(epoch_info).unwrap(),
+            ),
+        ]);
+        RpcClient::new_mock_with_mocks("succeeds".to_string(), mocks)
+    }
+
+    /// The fee is the mint's rate for the current epoch applied to the amount
+    /// (rounded up); a mint without the extension charges nothing.
+    #[test]
+    fn pool_mints_transfer_fee_applies_the_current_epoch_rate() {
+        let fee_mint = Pubkey::new_unique();
+        let lxr_mint = Pubkey::new_unique();
+        let accounts = [(fee_mint, transfer_fee_mint()), (lxr_mint, plain_mint())];
+        let amount = 1_000_001;
+
+        for (epoch, expected) in [(NEWER_FEE_EPOCH - 1, 10_001), (NEWER_FEE_EPOCH, 25_001)] {
+            let rpc_client = mock_rpc_client(&accounts, epoch);
+            let (fee_0, fee_1) =
+                get_pool_mints_transfer_fee(&rpc_client, fee_mint, lxr_mint, amount, amount);
+            assert_eq!(fee_0.mint, fee_mint);
+            assert_eq!(fee_0.owner, spl_token_2022::id());
+            assert_eq!(fee_0.transfer_fee, expected, "epoch {epoch}");
+            assert_eq!(fee_1.owner, spl_token::id());
+            assert_eq!(fee_1.transfer_fee, 0);
+        }
+    }
+}
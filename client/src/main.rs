@@ -11,10 +11,22 @@ use solana_sdk::{
 };
 use std::rc::Rc;
 use std::str::FromStr;
+use std::time::Duration;
 
 mod instructions;
 use instructions::amm_instructions::*;
 use instructions::rpc::*;
+use instructions::utils::{
+    compute_budget_instructions, deadline_from_now, decode_event, fetch_anchor_account,
+    get_buyback_min_lxr_out, get_global_config_address, get_protocol_snapshot,
+    get_purchase_quote, get_redeem_preview, get_stake_info_address, global_config_to_json,
+    stake_info_to_json,
+};
+use luxor_swap::instructions::EmergencyOperation;
+use luxor_swap::states::{
+    AccruedButUnboughtRewards, BlacklistStatus, ClaimableRewards, FeeTier, GlobalConfig, PoolState, StakeInfo, UserStakeStatus, VaultBalances,
+};
+use solana_sdk::commitment_config::CommitmentConfig;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct ClientConfig {
@@ -65,10 +77,67 @@ fn read_keypair_file(s: &str) -> Result<Keypair> {
         .map_err(|_| format_err!("failed to read keypair from {}", s))
 }
 
+/// Parses a `<min_sol>:<rate>` pair for `set-fee-tiers`.
+fn parse_fee_tier(s: &str) -> Result<FeeTier> {
+    let (min_sol, rate) = s
+        .split_once(':')
+        .ok_or_else(|| format_err!("expected <min_sol>:<rate>, got {}", s))?;
+    Ok(FeeTier {
+        min_sol: min_sol.parse()?,
+        rate: rate.parse()?,
+    })
+}
+
+/// `emergency_withdraw` operation by name (`drain-lxr`, `drain-wsol`, `claim-admin-rewards`,
+/// `deactivate-stake`, `withdraw-stake`) or by its `u8` value.
+fn parse_emergency_operation(s: &str) -> Result<u8> {
+    let operation = match s {
+        "drain-lxr" => EmergencyOperation::DrainLxr,
+        "drain-wsol" => EmergencyOperation::DrainWsol,
+        "claim-admin-rewards" => EmergencyOperation::ClaimAdminRewards,
+        "deactivate-stake" => EmergencyOperation::DeactivateStake,
+        "withdraw-stake" => EmergencyOperation::WithdrawStake,
+        "rotate-stake-authority" => EmergencyOperation::RotateStakeAuthority,
+        _ => EmergencyOperation::try_from(s.parse::<u8>()?)
+            .map_err(|_| format_err!("unknown emergency operation {}", s))?,
+    };
+    Ok(operation as u8)
+}
+
 #[derive(Debug, Parser)]
 pub struct Opts {
     #[clap(subcommand)]
     pub command: RaydiumCpCommands,
+    /// Compute unit limit for sent transactions (per-command default when omitted).
+    #[arg(long, global = true)]
+    pub cu_limit: Option<u32>,
+    /// Priority fee for sent transactions, in micro-lamports per compute unit.
+    #[arg(long, global = true, default_value_t = 0)]
+    pub priority_fee: u64,
+    /// Resends on recoverable errors (expired blockhash, lagging node, transport),
+    /// unless the earlier attempt already landed.
+    #[arg(long, global = true, default_value_t = 3)]
+    pub max_retries: u32,
+    /// Delay before the first resend in milliseconds; doubled after every attempt.
+    #[arg(long, global = true, default_value_t = 500)]
+    pub retry_delay: u64,
+    /// Send every transaction exactly once.
+    #[arg(long, global = true)]
+    pub no_retry: bool,
+}
+
+impl RaydiumCpCommands {
+    /// Compute unit limit used when `--cu-limit` is omitted. The Raydium CPI and
+    /// stake CPIs of buyback and the purchase paths exceed the 200k default.
+    fn default_cu_limit(&self) -> u32 {
+        match self {
+            RaydiumCpCommands::Buyback { .. } => 600_000,
+            RaydiumCpCommands::Purchase { .. } | RaydiumCpCommands::PurchaseIsolated { .. } => {
+                400_000
+            }
+            _ => 200_000,
+        }
+    }
 }
 
 #[derive(Debug, Parser)]
@@ -94,6 +163,12 @@ pub enum RaydiumCpCommands {
         redeem_enabled: bool,
         #[arg(long)]
         initial_lxr_allocation_vault: u64,
+        /// Mint rewards are bought and paid in (defaults to the LUXOR mint).
+        #[arg(long)]
+        reward_mint: Option<Pubkey>,
+        /// Seconds an `emergency_withdraw` must be queued before it runs (0 = no timelock).
+        #[arg(long, default_value_t = 0)]
+        emergency_delay: u64,
     },
     UpdateConfig {
         #[arg(long)]
@@ -102,6 +177,26 @@ pub enum RaydiumCpCommands {
         value: u64,
         #[arg(long)]
         admin: Option<Pubkey>,
+        /// Fee recipient for param `23`.
+        #[arg(long)]
+        fee_recipient: Option<Pubkey>,
+        /// Buyback pool for param `25`.
+        #[arg(long)]
+        reward_pool: Option<Pubkey>,
+    },
+    /// Accepts a pending admin transfer (run with the nominated admin's keypair).
+    AcceptAdmin {},
+    SetPoolFeeOverride {
+        #[arg(long)]
+        use_fee_override: bool,
+        #[arg(long, default_value_t = 0)]
+        trade_fee: u64,
+        #[arg(long, default_value_t = 0)]
+        protocol_fee: u64,
+        #[arg(long, default_value_t = 0)]
+        fund_fee: u64,
+        #[arg(long, default_value_t = 0)]
+        creator_fee: u64,
     },
     ManualPurchase {
         #[arg(long)]
@@ -113,31 +208,216 @@ pub enum RaydiumCpCommands {
         #[arg(long)]
         vote_account: Pubkey,
     },
+    AirdropLxr {
+        #[arg(long)]
+        user: Pubkey,
+        #[arg(long)]
+        amount: u64,
+    },
+    /// Pays up to `amount` WSOL of buyback fees to the configured fee recipient.
+    WithdrawTreasuryFees {
+        #[arg(long)]
+        amount: u64,
+    },
+    DepositToTreasury {
+        #[arg(long)]
+        amount: u64,
+        /// Also add the deposit to `initial_lxr_allocation_vault`.
+        #[arg(long)]
+        bump_allocation: bool,
+    },
     Purchase {
         #[arg(long)]
         lxr_to_purchase: u64,
         #[arg(long)]
         max_sol_amount: u64,
+        /// Seconds from now after which the purchase reverts on-chain.
+        #[arg(long, default_value_t = 60)]
+        deadline_secs: i64,
         #[arg(long)]
         vote_account: Pubkey,
     },
-    Redeem {},
+    /// Prints the lamports `purchase` would currently charge the payer, plus the
+    /// applied bonus or inventory scaling factor.
+    Quote {
+        #[arg(long)]
+        lxr_to_purchase: u64,
+    },
+    Redeem {
+        /// Print the claimable/forfeited amounts and ask for confirmation before sending.
+        #[arg(long)]
+        preview: bool,
+        /// Claim at most this much LXR; the rest stays claimable (default: everything).
+        #[arg(long)]
+        amount: Option<u64>,
+    },
+    /// Claims the payer's pending SOL rewards as WSOL.
+    ClaimSolRewards {},
+    /// Splits `amount` lamports of the payer's stake into a deactivating unstake account.
+    Unstake {
+        #[arg(long)]
+        amount: u64,
+    },
+    /// Withdraws the payer's unstake account once its cooldown has passed.
+    WithdrawUnstaked {},
+    /// Buys LXR with SOL delegated from the payer's own isolated stake account.
+    PurchaseIsolated {
+        #[arg(long)]
+        lxr_to_purchase: u64,
+        #[arg(long)]
+        max_sol_amount: u64,
+        /// Seconds from now after which the purchase reverts on-chain.
+        #[arg(long, default_value_t = 60)]
+        deadline_secs: i64,
+        #[arg(long)]
+        vote_account: Pubkey,
+    },
+    /// Deactivates the payer's isolated stake, or withdraws it once inactive.
+    UnstakeIsolated {},
+    /// Closes the payer's fully settled `UserStakeInfo` and reclaims its rent.
+    CloseUserStakeInfo {},
+    /// Prints global config, stake info, admin stake info and vault balances.
+    Snapshot {},
+    /// Prints every `GlobalConfig` field.
+    FetchConfig {
+        /// Dump as JSON instead of the debug format.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Prints every `StakeInfo` field, including the reward indices.
+    FetchStakeInfo {
+        /// Dump as JSON instead of the debug format.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Simulates `get_vault_balances` and prints the emitted `VaultBalances`.
+    VaultBalances {},
+    /// Simulates `get_user_stake_status` and prints the emitted `UserStakeStatus`.
+    UserStakeStatus {
+        #[arg(long)]
+        user: Pubkey,
+    },
+    /// Simulates `get_blacklist_status` and prints the emitted `BlacklistStatus`.
+    BlacklistStatus {
+        #[arg(long)]
+        user: Pubkey,
+    },
+    /// Simulates `get_accrued_but_unbought_rewards` and prints the emitted
+    /// `AccruedButUnboughtRewards`.
+    UnboughtRewards {},
     Buyback {
         #[arg(long)]
         count: u64,
         #[arg(long)]
         vote_account: Pubkey,
+        /// Minimum LXR the swap must return; quoted from the pool when omitted.
+        #[arg(long)]
+        min_lxr_out: Option<u64>,
+        /// Slippage applied to the quote when `min_lxr_out` is omitted.
+        #[arg(long, default_value_t = 0.01)]
+        slippage: f64,
+        /// Execute in two transactions (`buyback_withdraw`, then `buyback_settle`) for
+        /// low compute limits; not atomic.
+        #[arg(long)]
+        split: bool,
     },
     EmergencyWithdraw {
-        #[arg(long)]
+        /// drain-lxr | drain-wsol | claim-admin-rewards | deactivate-stake | withdraw-stake (or 0-4)
+        #[arg(long, value_parser = parse_emergency_operation)]
         param: u8,
         #[arg(long)]
         value: u64,
     },
+    /// Starts the blacklist delay for a user; `blacklist` now maps here, since
+    /// blacklisting always begins with a proposal.
+    #[command(alias = "blacklist")]
+    ProposeBlacklist {
+        #[arg(long)]
+        user: Pubkey,
+    },
+    CancelBlacklist {
+        #[arg(long)]
+        user: Pubkey,
+    },
+    /// Finalizes a proposed blacklist once the configured delay has elapsed.
     BlacklistUser {
         #[arg(long)]
         user: Pubkey,
     },
+    UnBlacklist {
+        #[arg(long)]
+        user: Pubkey,
+    },
+    RotateStakeAuthority {
+        #[arg(long)]
+        new_authority: Pubkey,
+    },
+    MigrateRewardScale {
+        #[arg(long)]
+        numerator: u64,
+        #[arg(long)]
+        denominator: u64,
+    },
+    /// Grows first-release accounts to the current layouts (run right after an upgrade).
+    MigrateAccounts {
+        /// Wallet whose `UserStakeInfo` record to migrate, repeated per user
+        /// (at most `MAX_BATCH` records per call, admin record included).
+        #[arg(long = "user")]
+        users: Vec<Pubkey>,
+        /// Also migrate the admin stake record.
+        #[arg(long)]
+        admin_record: bool,
+    },
+    ReconcileBuybackAccounting {
+        #[arg(long)]
+        sol_used_for_buyback: u64,
+        #[arg(long)]
+        sol_rewards_accrued: u64,
+    },
+    FinalizeStakeClosure,
+    /// Moves the protocol stake to a new validator. Effective stake is only
+    /// deactivated; run again after the cooldown to delegate it.
+    Redelegate {
+        #[arg(long)]
+        vote_account: Pubkey,
+    },
+    /// Simulates `get_claimable` and prints the emitted `ClaimableRewards`.
+    Claimable {
+        #[arg(long)]
+        user: Pubkey,
+    },
+    /// Sets the purchase price-band anchor (Q32 lamports per LXR base unit, 0 = none).
+    SetAnchorPrice {
+        #[arg(long)]
+        anchor_price_x32: u128,
+    },
+    /// Replaces the buyback treasury fee schedule.
+    SetFeeTiers {
+        /// `<min_sol>:<rate>` (lamports, rate over the fee denominator), repeated per tier
+        /// in increasing order; the first must start at 0.
+        #[arg(long = "tier", required = true, value_parser = parse_fee_tier)]
+        tiers: Vec<FeeTier>,
+    },
+    /// Queue an `emergency-withdraw` behind the configured timelock.
+    QueueEmergency {
+        /// Same operations as `emergency-withdraw --param`, plus `rotate-stake-authority`.
+        #[arg(long, value_parser = parse_emergency_operation)]
+        param: u8,
+        #[arg(long)]
+        value: u64,
+        /// New stake authority (required by `rotate-stake-authority`).
+        #[arg(long)]
+        target: Option<Pubkey>,
+    },
+    /// Move LXR between the vaults (0 = treasury, 1 = reward).
+    RebalanceVaults {
+        #[arg(long)]
+        from: u8,
+        #[arg(long)]
+        to: u8,
+        #[arg(long)]
+        amount: u64,
+    },
 }
 
 fn main() -> Result<()> {
@@ -156,6 +436,14 @@ fn main() -> Result<()> {
     let program = anchor_client.program(pool_config.luxor_swap_program)?;
 
     let opts = Opts::parse();
+    let compute_budget_ixs = compute_budget_instructions(
+        opts.cu_limit.unwrap_or_else(|| opts.command.default_cu_limit()),
+        opts.priority_fee,
+    );
+    let retry = (!opts.no_retry).then(|| RetryConfig {
+        max_retries: opts.max_retries,
+        retry_delay: Duration::from_millis(opts.retry_delay),
+    });
     match opts.command {
         RaydiumCpCommands::InitialiseConfigs {
             admin,
@@ -168,8 +456,10 @@ fn main() -> Result<()> {
             purchase_enabled,
             redeem_enabled,
             initial_lxr_allocation_vault,
+            reward_mint,
+            emergency_delay,
         } => {
-            let mut instructions = Vec::new();
+            let mut instructions = compute_budget_ixs.clone();
             let initialise_ix = initialise_configs_instr(
                 &pool_config,
                 admin,
@@ -182,6 +472,8 @@ fn main() -> Result<()> {
                 purchase_enabled,
                 redeem_enabled,
                 initial_lxr_allocation_vault,
+                reward_mint.unwrap_or(luxor_swap::luxor_mint::id()),
+                emergency_delay,
             )?;
             instructions.extend(initialise_ix);
             let signers = vec![&payer];
@@ -192,16 +484,23 @@ fn main() -> Result<()> {
                 &signers,
                 recent_hash,
             );
-            let signature = send_txn(&rpc_client, &txn, true)?;
+            let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
             println!("{}", signature);
         }
         RaydiumCpCommands::UpdateConfig {
             param,
             value,
             admin,
+            fee_recipient,
+            reward_pool,
         } => {
-            let mut instructions = Vec::new();
-            let update_config_ix = update_config_instr(&pool_config, param, value, admin)?;
+            let mut instructions = compute_budget_ixs.clone();
+            let update_config_ix = update_config_instr(
+                &pool_config,
+                param,
+                value,
+                admin.or(fee_recipient).or(reward_pool),
+            )?;
             instructions.extend(update_config_ix);
             let signers = vec![&payer];
             let recent_hash = rpc_client.get_latest_blockhash()?;
@@ -211,7 +510,50 @@ fn main() -> Result<()> {
                 &signers,
                 recent_hash,
             );
-            let signature = send_txn(&rpc_client, &txn, true)?;
+            let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
+            println!("{}", signature);
+        }
+        RaydiumCpCommands::SetPoolFeeOverride {
+            use_fee_override,
+            trade_fee,
+            protocol_fee,
+            fund_fee,
+            creator_fee,
+        } => {
+            let mut instructions = compute_budget_ixs.clone();
+            let fee_override_ix = set_pool_fee_override_instr(
+                &pool_config,
+                use_fee_override,
+                trade_fee,
+                protocol_fee,
+                fund_fee,
+                creator_fee,
+            )?;
+            instructions.extend(fee_override_ix);
+            let signers = vec![&payer];
+            let recent_hash = rpc_client.get_latest_blockhash()?;
+            let txn = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&payer.pubkey()),
+                &signers,
+                recent_hash,
+            );
+            let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
+            println!("{}", signature);
+        }
+        RaydiumCpCommands::AcceptAdmin {} => {
+            let mut instructions = compute_budget_ixs.clone();
+            let accept_admin_ix = accept_admin_instr(&pool_config)?;
+            instructions.extend(accept_admin_ix);
+            let signers = vec![&payer];
+            let recent_hash = rpc_client.get_latest_blockhash()?;
+            let txn = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&payer.pubkey()),
+                &signers,
+                recent_hash,
+            );
+            let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
             println!("{}", signature);
         }
         RaydiumCpCommands::ManualPurchase {
@@ -220,7 +562,7 @@ fn main() -> Result<()> {
             sol_spent,
             vote_account,
         } => {
-            let mut instructions = Vec::new();
+            let mut instructions = compute_budget_ixs.clone();
             let manual_purchase_ix =
                 manual_purchase_instr(&pool_config, user, lxr_purchased, sol_spent, vote_account)?;
             instructions.extend(manual_purchase_ix);
@@ -232,17 +574,76 @@ fn main() -> Result<()> {
                 &signers,
                 recent_hash,
             );
-            let signature = send_txn(&rpc_client, &txn, true)?;
+            let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
+            println!("{}", signature);
+        }
+        RaydiumCpCommands::AirdropLxr { user, amount } => {
+            let mut instructions = compute_budget_ixs.clone();
+            let airdrop_lxr_ix = airdrop_lxr_instr(&pool_config, user, amount)?;
+            instructions.extend(airdrop_lxr_ix);
+            let signers = vec![&payer];
+            let recent_hash = rpc_client.get_latest_blockhash()?;
+            let txn = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&payer.pubkey()),
+                &signers,
+                recent_hash,
+            );
+            let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
+            println!("{}", signature);
+        }
+        RaydiumCpCommands::WithdrawTreasuryFees { amount } => {
+            let global_config = fetch_anchor_account::<GlobalConfig>(
+                &rpc_client,
+                &get_global_config_address(&pool_config.luxor_swap_program),
+            )?;
+            let mut instructions = compute_budget_ixs.clone();
+            let withdraw_ix =
+                withdraw_treasury_fees_instr(&pool_config, global_config.fee_recipient, amount)?;
+            instructions.extend(withdraw_ix);
+            let signers = vec![&payer];
+            let recent_hash = rpc_client.get_latest_blockhash()?;
+            let txn = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&payer.pubkey()),
+                &signers,
+                recent_hash,
+            );
+            let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
+            println!("{}", signature);
+        }
+        RaydiumCpCommands::DepositToTreasury {
+            amount,
+            bump_allocation,
+        } => {
+            let mut instructions = compute_budget_ixs.clone();
+            let deposit_ix = deposit_to_treasury_instr(&pool_config, amount, bump_allocation)?;
+            instructions.extend(deposit_ix);
+            let signers = vec![&payer];
+            let recent_hash = rpc_client.get_latest_blockhash()?;
+            let txn = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&payer.pubkey()),
+                &signers,
+                recent_hash,
+            );
+            let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
             println!("{}", signature);
         }
         RaydiumCpCommands::Purchase {
             lxr_to_purchase,
             max_sol_amount,
+            deadline_secs,
             vote_account,
         } => {
-            let mut instructions = Vec::new();
-            let purchase_ix =
-                purchase_instr(&pool_config, lxr_to_purchase, max_sol_amount, vote_account)?;
+            let mut instructions = compute_budget_ixs.clone();
+            let purchase_ix = purchase_instr(
+                &pool_config,
+                lxr_to_purchase,
+                max_sol_amount,
+                deadline_from_now(deadline_secs)?,
+                vote_account,
+            )?;
             instructions.extend(purchase_ix);
             let signers = vec![&payer];
             let recent_hash = rpc_client.get_latest_blockhash()?;
@@ -252,12 +653,151 @@ fn main() -> Result<()> {
                 &signers,
                 recent_hash,
             );
-            let signature = send_txn(&rpc_client, &txn, true)?;
+            let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
             println!("{}", signature);
         }
-        RaydiumCpCommands::Redeem {} => {
-            let mut instructions = Vec::new();
-            let redeem_ix = redeem_instr(&pool_config)?;
+        RaydiumCpCommands::Quote { lxr_to_purchase } => {
+            let purchase_quote = get_purchase_quote(
+                &rpc_client,
+                &pool_config.luxor_swap_program,
+                &payer.pubkey(),
+                lxr_to_purchase,
+            )?;
+            println!("{:#?}", purchase_quote);
+        }
+        RaydiumCpCommands::Snapshot {} => {
+            let snapshot = get_protocol_snapshot(&rpc_client, &pool_config.luxor_swap_program)?;
+            println!("{:#?}", snapshot);
+        }
+        RaydiumCpCommands::FetchConfig { json } => {
+            let global_config = fetch_anchor_account::<GlobalConfig>(
+                &rpc_client,
+                &get_global_config_address(&pool_config.luxor_swap_program),
+            )?;
+            if json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&global_config_to_json(&global_config))?
+                );
+            } else {
+                println!("{:#?}", global_config);
+            }
+        }
+        RaydiumCpCommands::FetchStakeInfo { json } => {
+            let stake_info = fetch_anchor_account::<StakeInfo>(
+                &rpc_client,
+                &get_stake_info_address(&pool_config.luxor_swap_program),
+            )?;
+            if json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&stake_info_to_json(&stake_info))?
+                );
+            } else {
+                println!("{:#?}", stake_info);
+            }
+        }
+        RaydiumCpCommands::VaultBalances {} => {
+            let instructions = get_vault_balances_instr(&pool_config)?;
+            let signers = vec![&payer];
+            let recent_hash = rpc_client.get_latest_blockhash()?;
+            let txn = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&payer.pubkey()),
+                &signers,
+                recent_hash,
+            );
+            let result =
+                simulate_transaction(&rpc_client, &txn, false, CommitmentConfig::confirmed())?;
+            let logs = result.value.logs.unwrap_or_default();
+            let balances = decode_event::<VaultBalances>(&logs)
+                .ok_or_else(|| format_err!("VaultBalances not found in logs: {:#?}", logs))?;
+            println!("{:#?}", balances);
+        }
+        RaydiumCpCommands::UserStakeStatus { user } => {
+            let instructions = get_user_stake_status_instr(&pool_config, user)?;
+            let signers = vec![&payer];
+            let recent_hash = rpc_client.get_latest_blockhash()?;
+            let txn = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&payer.pubkey()),
+                &signers,
+                recent_hash,
+            );
+            let result =
+                simulate_transaction(&rpc_client, &txn, false, CommitmentConfig::confirmed())?;
+            let logs = result.value.logs.unwrap_or_default();
+            let status = decode_event::<UserStakeStatus>(&logs)
+                .ok_or_else(|| format_err!("UserStakeStatus not found in logs: {:#?}", logs))?;
+            println!("{:#?}", status);
+        }
+        RaydiumCpCommands::BlacklistStatus { user } => {
+            let instructions = get_blacklist_status_instr(&pool_config, user)?;
+            let signers = vec![&payer];
+            let recent_hash = rpc_client.get_latest_blockhash()?;
+            let txn = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&payer.pubkey()),
+                &signers,
+                recent_hash,
+            );
+            let result =
+                simulate_transaction(&rpc_client, &txn, false, CommitmentConfig::confirmed())?;
+            let logs = result.value.logs.unwrap_or_default();
+            let status = decode_event::<BlacklistStatus>(&logs)
+                .ok_or_else(|| format_err!("BlacklistStatus not found in logs: {:#?}", logs))?;
+            println!("{:#?}", status);
+        }
+        RaydiumCpCommands::UnboughtRewards {} => {
+            let instructions = get_accrued_but_unbought_rewards_instr(&pool_config)?;
+            let signers = vec![&payer];
+            let recent_hash = rpc_client.get_latest_blockhash()?;
+            let txn = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&payer.pubkey()),
+                &signers,
+                recent_hash,
+            );
+            let result =
+                simulate_transaction(&rpc_client, &txn, false, CommitmentConfig::confirmed())?;
+            let logs = result.value.logs.unwrap_or_default();
+            let rewards = decode_event::<AccruedButUnboughtRewards>(&logs)
+                .ok_or_else(|| format_err!("AccruedButUnboughtRewards not found in logs: {:#?}", logs))?;
+            println!("{:#?}", rewards);
+        }
+        RaydiumCpCommands::Redeem { preview, amount } => {
+            if preview {
+                let redeem_preview = get_redeem_preview(
+                    &rpc_client,
+                    &pool_config.luxor_swap_program,
+                    &payer.pubkey(),
+                )?;
+                println!("{:#?}", redeem_preview);
+                if !redeem_preview.meets_min_hold {
+                    println!("Holdings are below the minimum hold ratio; redeem would fail");
+                    return Ok(());
+                }
+                if redeem_preview.forfeited > 0 {
+                    println!(
+                        "warning: {} LXR will be forfeited because holdings are below baseline",
+                        redeem_preview.forfeited
+                    );
+                }
+                print!("Send redeem transaction? [y/N] ");
+                std::io::Write::flush(&mut std::io::stdout())?;
+                let mut answer = String::new();
+                std::io::stdin().read_line(&mut answer)?;
+                if !answer.trim().eq_ignore_ascii_case("y") {
+                    println!("Aborted");
+                    return Ok(());
+                }
+            }
+            let global_config = fetch_anchor_account::<GlobalConfig>(
+                &rpc_client,
+                &get_global_config_address(&pool_config.luxor_swap_program),
+            )?;
+            let mut instructions = compute_budget_ixs.clone();
+            let redeem_ix = redeem_instr(&pool_config, global_config.reward_mint, amount)?;
             instructions.extend(redeem_ix);
             let signers = vec![&payer];
             let recent_hash = rpc_client.get_latest_blockhash()?;
@@ -267,13 +807,13 @@ fn main() -> Result<()> {
                 &signers,
                 recent_hash,
             );
-            let signature = send_txn(&rpc_client, &txn, true)?;
+            let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
             println!("{}", signature);
         }
-        RaydiumCpCommands::Buyback { count , vote_account } => {
-            let mut instructions = Vec::new();
-            let buyback_ix = buyback_instr(&pool_config, count , vote_account)?;
-            instructions.extend(buyback_ix);
+        RaydiumCpCommands::ClaimSolRewards {} => {
+            let mut instructions = compute_budget_ixs.clone();
+            let claim_sol_rewards_ix = claim_sol_rewards_instr(&pool_config)?;
+            instructions.extend(claim_sol_rewards_ix);
             let signers = vec![&payer];
             let recent_hash = rpc_client.get_latest_blockhash()?;
             let txn = Transaction::new_signed_with_payer(
@@ -282,11 +822,176 @@ fn main() -> Result<()> {
                 &signers,
                 recent_hash,
             );
-            let signature = send_txn(&rpc_client, &txn, true)?;
+            let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
             println!("{}", signature);
         }
+        RaydiumCpCommands::Unstake { amount } => {
+            let mut instructions = compute_budget_ixs.clone();
+            let unstake_ix = unstake_instr(&pool_config, amount)?;
+            instructions.extend(unstake_ix);
+            let signers = vec![&payer];
+            let recent_hash = rpc_client.get_latest_blockhash()?;
+            let txn = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&payer.pubkey()),
+                &signers,
+                recent_hash,
+            );
+            let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
+            println!("{}", signature);
+        }
+        RaydiumCpCommands::WithdrawUnstaked {} => {
+            let mut instructions = compute_budget_ixs.clone();
+            let withdraw_unstaked_ix = withdraw_unstaked_instr(&pool_config)?;
+            instructions.extend(withdraw_unstaked_ix);
+            let signers = vec![&payer];
+            let recent_hash = rpc_client.get_latest_blockhash()?;
+            let txn = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&payer.pubkey()),
+                &signers,
+                recent_hash,
+            );
+            let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
+            println!("{}", signature);
+        }
+        RaydiumCpCommands::PurchaseIsolated {
+            lxr_to_purchase,
+            max_sol_amount,
+            deadline_secs,
+            vote_account,
+        } => {
+            let mut instructions = compute_budget_ixs.clone();
+            let purchase_isolated_ix = purchase_isolated_instr(
+                &pool_config,
+                lxr_to_purchase,
+                max_sol_amount,
+                deadline_from_now(deadline_secs)?,
+                vote_account,
+            )?;
+            instructions.extend(purchase_isolated_ix);
+            let signers = vec![&payer];
+            let recent_hash = rpc_client.get_latest_blockhash()?;
+            let txn = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&payer.pubkey()),
+                &signers,
+                recent_hash,
+            );
+            let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
+            println!("{}", signature);
+        }
+        RaydiumCpCommands::UnstakeIsolated {} => {
+            let mut instructions = compute_budget_ixs.clone();
+            let unstake_isolated_ix = unstake_isolated_instr(&pool_config)?;
+            instructions.extend(unstake_isolated_ix);
+            let signers = vec![&payer];
+            let recent_hash = rpc_client.get_latest_blockhash()?;
+            let txn = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&payer.pubkey()),
+                &signers,
+                recent_hash,
+            );
+            let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
+            println!("{}", signature);
+        }
+        RaydiumCpCommands::CloseUserStakeInfo {} => {
+            let mut instructions = compute_budget_ixs.clone();
+            let close_ix = close_user_stake_info_instr(&pool_config)?;
+            instructions.extend(close_ix);
+            let signers = vec![&payer];
+            let recent_hash = rpc_client.get_latest_blockhash()?;
+            let txn = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&payer.pubkey()),
+                &signers,
+                recent_hash,
+            );
+            let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
+            println!("{}", signature);
+        }
+        RaydiumCpCommands::Buyback { count , vote_account, min_lxr_out, slippage, split } => {
+            let min_lxr_out = match min_lxr_out {
+                Some(min_lxr_out) => min_lxr_out,
+                None => get_buyback_min_lxr_out(
+                    &rpc_client,
+                    &pool_config.luxor_swap_program,
+                    count,
+                    slippage,
+                )?,
+            };
+            println!("min_lxr_out: {}", min_lxr_out);
+            let global_config = fetch_anchor_account::<GlobalConfig>(
+                &rpc_client,
+                &get_global_config_address(&pool_config.luxor_swap_program),
+            )?;
+            let pool_id = global_config.reward_pool_state();
+            let pool_state = fetch_anchor_account::<PoolState>(&rpc_client, &pool_id)?;
+            if split {
+                // Quoted above, before the split account is withdrawn and closed.
+                let mut instructions = compute_budget_ixs.clone();
+                instructions.extend(buyback_withdraw_instr(
+                    &pool_config,
+                    count,
+                    vote_account,
+                    pool_id,
+                    &pool_state,
+                )?);
+                let signers = vec![&payer];
+                let recent_hash = rpc_client.get_latest_blockhash()?;
+                let txn = Transaction::new_signed_with_payer(
+                    &instructions,
+                    Some(&payer.pubkey()),
+                    &signers,
+                    recent_hash,
+                );
+                let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
+                println!("{}", signature);
+
+                let mut instructions = compute_budget_ixs.clone();
+                instructions.extend(buyback_settle_instr(
+                    &pool_config,
+                    count,
+                    vote_account,
+                    min_lxr_out,
+                    pool_id,
+                    &pool_state,
+                )?);
+                let recent_hash = rpc_client.get_latest_blockhash()?;
+                let txn = Transaction::new_signed_with_payer(
+                    &instructions,
+                    Some(&payer.pubkey()),
+                    &signers,
+                    recent_hash,
+                );
+                let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
+                println!("{}", signature);
+            } else {
+                let mut instructions = compute_budget_ixs.clone();
+                let buyback_ix = buyback_instr(
+                    &pool_config,
+                    count,
+                    vote_account,
+                    min_lxr_out,
+                    pool_id,
+                    &pool_state,
+                )?;
+                instructions.extend(buyback_ix);
+                let signers = vec![&payer];
+                let recent_hash = rpc_client.get_latest_blockhash()?;
+                let txn = Transaction::new_signed_with_payer(
+                    &instructions,
+                    Some(&payer.pubkey()),
+                    &signers,
+                    recent_hash,
+                );
+                let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
+                println!("{}", signature);
+            }
+        }
         RaydiumCpCommands::EmergencyWithdraw { param, value } => {
-            let mut instructions = Vec::new();
+            let mut instructions = compute_budget_ixs.clone();
             let emergency_withdraw_ix = emergency_withdraw_instr(&pool_config, param, value)?;
             instructions.extend(emergency_withdraw_ix);
             let signers = vec![&payer];
@@ -297,11 +1002,41 @@ fn main() -> Result<()> {
                 &signers,
                 recent_hash,
             );
-            let signature = send_txn(&rpc_client, &txn, true)?;
+            let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
+            println!("{}", signature);
+        }
+        RaydiumCpCommands::ProposeBlacklist { user } => {
+            let mut instructions = compute_budget_ixs.clone();
+            let propose_blacklist_ix = propose_blacklist_instr(&pool_config, user)?;
+            instructions.extend(propose_blacklist_ix);
+            let signers = vec![&payer];
+            let recent_hash = rpc_client.get_latest_blockhash()?;
+            let txn = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&payer.pubkey()),
+                &signers,
+                recent_hash,
+            );
+            let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
+            println!("{}", signature);
+        }
+        RaydiumCpCommands::CancelBlacklist { user } => {
+            let mut instructions = compute_budget_ixs.clone();
+            let cancel_blacklist_ix = cancel_blacklist_instr(&pool_config, user)?;
+            instructions.extend(cancel_blacklist_ix);
+            let signers = vec![&payer];
+            let recent_hash = rpc_client.get_latest_blockhash()?;
+            let txn = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&payer.pubkey()),
+                &signers,
+                recent_hash,
+            );
+            let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
             println!("{}", signature);
         }
         RaydiumCpCommands::BlacklistUser { user } => {
-            let mut instructions = Vec::new();
+            let mut instructions = compute_budget_ixs.clone();
             let blacklist_user_ix = blacklist_user_instr(&pool_config, user)?;
             instructions.extend(blacklist_user_ix);
             let signers = vec![&payer];
@@ -312,7 +1047,217 @@ fn main() -> Result<()> {
                 &signers,
                 recent_hash,
             );
-            let signature = send_txn(&rpc_client, &txn, true)?;
+            let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
+            println!("{}", signature);
+        }
+        RaydiumCpCommands::UnBlacklist { user } => {
+            let mut instructions = compute_budget_ixs.clone();
+            let un_blacklist_ix = un_blacklist_instr(&pool_config, user)?;
+            instructions.extend(un_blacklist_ix);
+            let signers = vec![&payer];
+            let recent_hash = rpc_client.get_latest_blockhash()?;
+            let txn = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&payer.pubkey()),
+                &signers,
+                recent_hash,
+            );
+            let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
+            println!("{}", signature);
+        }
+        RaydiumCpCommands::RotateStakeAuthority { new_authority } => {
+            let mut instructions = compute_budget_ixs.clone();
+            let rotate_ix = rotate_stake_authority_instr(&pool_config, new_authority)?;
+            instructions.extend(rotate_ix);
+            let signers = vec![&payer];
+            let recent_hash = rpc_client.get_latest_blockhash()?;
+            let txn = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&payer.pubkey()),
+                &signers,
+                recent_hash,
+            );
+            let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
+            println!("{}", signature);
+        }
+        RaydiumCpCommands::MigrateRewardScale {
+            numerator,
+            denominator,
+        } => {
+            let mut instructions = compute_budget_ixs.clone();
+            let migrate_ix = migrate_reward_scale_instr(&pool_config, numerator, denominator)?;
+            instructions.extend(migrate_ix);
+            let signers = vec![&payer];
+            let recent_hash = rpc_client.get_latest_blockhash()?;
+            let txn = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&payer.pubkey()),
+                &signers,
+                recent_hash,
+            );
+            let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
+            println!("{}", signature);
+        }
+        RaydiumCpCommands::MigrateAccounts {
+            users,
+            admin_record,
+        } => {
+            if users.len() + usize::from(admin_record) > luxor_swap::MAX_BATCH {
+                panic!(
+                    "migrate at most {} records per call (got {})",
+                    luxor_swap::MAX_BATCH,
+                    users.len() + usize::from(admin_record)
+                );
+            }
+            let mut instructions = compute_budget_ixs.clone();
+            let migrate_ix = migrate_accounts_instr(&pool_config, &users, admin_record)?;
+            instructions.extend(migrate_ix);
+            let signers = vec![&payer];
+            let recent_hash = rpc_client.get_latest_blockhash()?;
+            let txn = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&payer.pubkey()),
+                &signers,
+                recent_hash,
+            );
+            let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
+            println!("{}", signature);
+        }
+        RaydiumCpCommands::ReconcileBuybackAccounting {
+            sol_used_for_buyback,
+            sol_rewards_accrued,
+        } => {
+            let mut instructions = compute_budget_ixs.clone();
+            let reconcile_ix = reconcile_buyback_accounting_instr(
+                &pool_config,
+                sol_used_for_buyback,
+                sol_rewards_accrued,
+            )?;
+            instructions.extend(reconcile_ix);
+            let signers = vec![&payer];
+            let recent_hash = rpc_client.get_latest_blockhash()?;
+            let txn = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&payer.pubkey()),
+                &signers,
+                recent_hash,
+            );
+            let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
+            println!("{}", signature);
+        }
+        RaydiumCpCommands::FinalizeStakeClosure => {
+            let mut instructions = compute_budget_ixs.clone();
+            let finalize_ix = finalize_stake_closure_instr(&pool_config)?;
+            instructions.extend(finalize_ix);
+            let signers = vec![&payer];
+            let recent_hash = rpc_client.get_latest_blockhash()?;
+            let txn = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&payer.pubkey()),
+                &signers,
+                recent_hash,
+            );
+            let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
+            println!("{}", signature);
+        }
+        RaydiumCpCommands::Redelegate { vote_account } => {
+            let mut instructions = compute_budget_ixs.clone();
+            let redelegate_ix = redelegate_instr(&pool_config, vote_account)?;
+            instructions.extend(redelegate_ix);
+            let signers = vec![&payer];
+            let recent_hash = rpc_client.get_latest_blockhash()?;
+            let txn = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&payer.pubkey()),
+                &signers,
+                recent_hash,
+            );
+            let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
+            println!("{}", signature);
+        }
+        RaydiumCpCommands::Claimable { user } => {
+            let ata = spl_associated_token_account::get_associated_token_address(
+                &user,
+                &luxor_swap::luxor_mint::id(),
+            );
+            let user_lxr_token = rpc_client
+                .get_account_with_commitment(&ata, CommitmentConfig::confirmed())?
+                .value
+                .map(|_| ata);
+            let instructions = get_claimable_instr(&pool_config, user, user_lxr_token)?;
+            let signers = vec![&payer];
+            let recent_hash = rpc_client.get_latest_blockhash()?;
+            let txn = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&payer.pubkey()),
+                &signers,
+                recent_hash,
+            );
+            let result =
+                simulate_transaction(&rpc_client, &txn, false, CommitmentConfig::confirmed())?;
+            let logs = result.value.logs.unwrap_or_default();
+            let claimable = decode_event::<ClaimableRewards>(&logs)
+                .ok_or_else(|| format_err!("ClaimableRewards not found in logs: {:#?}", logs))?;
+            println!("{:#?}", claimable);
+        }
+        RaydiumCpCommands::SetAnchorPrice { anchor_price_x32 } => {
+            let mut instructions = compute_budget_ixs.clone();
+            let anchor_price_ix = set_anchor_price_instr(&pool_config, anchor_price_x32)?;
+            instructions.extend(anchor_price_ix);
+            let signers = vec![&payer];
+            let recent_hash = rpc_client.get_latest_blockhash()?;
+            let txn = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&payer.pubkey()),
+                &signers,
+                recent_hash,
+            );
+            let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
+            println!("{}", signature);
+        }
+        RaydiumCpCommands::SetFeeTiers { tiers } => {
+            let mut instructions = compute_budget_ixs.clone();
+            let set_fee_tiers_ix = set_fee_tiers_instr(&pool_config, tiers)?;
+            instructions.extend(set_fee_tiers_ix);
+            let signers = vec![&payer];
+            let recent_hash = rpc_client.get_latest_blockhash()?;
+            let txn = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&payer.pubkey()),
+                &signers,
+                recent_hash,
+            );
+            let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
+            println!("{}", signature);
+        }
+        RaydiumCpCommands::QueueEmergency { param, value, target } => {
+            let mut instructions = compute_budget_ixs.clone();
+            let queue_emergency_ix = queue_emergency_instr(&pool_config, param, value, target)?;
+            instructions.extend(queue_emergency_ix);
+            let signers = vec![&payer];
+            let recent_hash = rpc_client.get_latest_blockhash()?;
+            let txn = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&payer.pubkey()),
+                &signers,
+                recent_hash,
+            );
+            let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
+            println!("{}", signature);
+        }
+        RaydiumCpCommands::RebalanceVaults { from, to, amount } => {
+            let mut instructions = compute_budget_ixs.clone();
+            let rebalance_vaults_ix = rebalance_vaults_instr(&pool_config, from, to, amount)?;
+            instructions.extend(rebalance_vaults_ix);
+            let signers = vec![&payer];
+            let recent_hash = rpc_client.get_latest_blockhash()?;
+            let txn = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&payer.pubkey()),
+                &signers,
+                recent_hash,
+            );
+            let signature = send_txn_with_retry(&rpc_client, txn, &signers, true, retry)?;
             println!("{}", signature);
         }
     }
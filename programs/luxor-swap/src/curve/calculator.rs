@@ -1,6 +1,6 @@
 //! Swap calculations
 
-use crate::curve::{constant_product::ConstantProductCurve, fees::Fees};
+use crate::curve::{constant_product::ConstantProductCurve, fees::Fees, offset_product::OffsetProductCurve};
 use anchor_lang::prelude::*;
 use {crate::error::ErrorCode, std::fmt::Debug};
 
@@ -45,6 +45,86 @@ impl TradeDirection {
     }
 }
 
+/// The invariant used to price a trade
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum CurveType {
+    /// Plain `x * y = k`, identical to the Raydium CPMM pool
+    #[default]
+    ConstantProduct,
+    /// `(x + input_offset) * (y + output_offset) = k`, with virtual reserves
+    /// added to the input and output vaults
+    OffsetProduct {
+        input_offset: u128,
+        output_offset: u128,
+    },
+}
+
+impl CurveType {
+    fn swap_base_input_without_fees(
+        &self,
+        input_amount: u128,
+        input_vault_amount: u128,
+        output_vault_amount: u128,
+    ) -> Option<u128> {
+        match *self {
+            CurveType::ConstantProduct => Some(ConstantProductCurve::swap_base_input_without_fees(
+                input_amount,
+                input_vault_amount,
+                output_vault_amount,
+            )),
+            CurveType::OffsetProduct {
+                input_offset,
+                output_offset,
+            } => OffsetProductCurve::swap_base_input_without_fees(
+                input_amount,
+                input_vault_amount,
+                output_vault_amount,
+                input_offset,
+                output_offset,
+            ),
+        }
+    }
+
+    fn swap_base_output_without_fees(
+        &self,
+        output_amount: u128,
+        input_vault_amount: u128,
+        output_vault_amount: u128,
+    ) -> Option<u128> {
+        match *self {
+            CurveType::ConstantProduct => Some(ConstantProductCurve::swap_base_output_without_fees(
+                output_amount,
+                input_vault_amount,
+                output_vault_amount,
+            )),
+            CurveType::OffsetProduct {
+                input_offset,
+                output_offset,
+            } => OffsetProductCurve::swap_base_output_without_fees(
+                output_amount,
+                input_vault_amount,
+                output_vault_amount,
+                input_offset,
+                output_offset,
+            ),
+        }
+    }
+
+    /// The invariant value `k` for the given (real) vault amounts, including
+    /// any virtual reserves of the curve
+    pub fn invariant(&self, input_vault_amount: u128, output_vault_amount: u128) -> Option<u128> {
+        match *self {
+            CurveType::ConstantProduct => input_vault_amount.checked_mul(output_vault_amount),
+            CurveType::OffsetProduct {
+                input_offset,
+                output_offset,
+            } => input_vault_amount
+                .checked_add(input_offset)?
+                .checked_mul(output_vault_amount.checked_add(output_offset)?),
+        }
+    }
+}
+
 /// Encodes results of depositing both sides at once
 #[derive(Debug, PartialEq)]
 pub struct TradingTokenResult {
@@ -101,6 +181,7 @@ impl CurveCalculator {
         protocol_fee_rate: u64,
         fund_fee_rate: u64,
         is_creator_fee_on_input: bool,
+        curve_type: CurveType,
     ) -> Option<SwapResult> {
         let mut creator_fee = 0;
 
@@ -116,11 +197,11 @@ impl CurveCalculator {
         let protocol_fee = Fees::protocol_fee(trade_fee, protocol_fee_rate)?;
         let fund_fee = Fees::fund_fee(trade_fee, fund_fee_rate)?;
 
-        let output_amount_swapped = ConstantProductCurve::swap_base_input_without_fees(
+        let output_amount_swapped = curve_type.swap_base_input_without_fees(
             input_amount_less_fees,
             input_vault_amount,
             output_vault_amount,
-        );
+        )?;
 
         let output_amount = if is_creator_fee_on_input {
             output_amount_swapped
@@ -150,6 +231,7 @@ impl CurveCalculator {
         protocol_fee_rate: u64,
         fund_fee_rate: u64,
         is_creator_fee_on_input: bool,
+        curve_type: CurveType,
     ) -> Option<SwapResult> {
         let trade_fee: u128;
         let mut creator_fee = 0;
@@ -163,11 +245,11 @@ impl CurveCalculator {
             out_amount_with_creator_fee
         };
 
-        let input_amount_swapped = ConstantProductCurve::swap_base_output_without_fees(
+        let input_amount_swapped = curve_type.swap_base_output_without_fees(
             actual_output_amount,
             input_vault_amount,
             output_vault_amount,
-        );
+        )?;
 
         let input_amount = if is_creator_fee_on_input {
             let input_amount_with_fee = Fees::calculate_pre_fee_amount(
@@ -3,7 +3,9 @@
 pub mod calculator;
 pub mod constant_product;
 pub mod fees;
+pub mod offset_product;
 
 pub use calculator::*;
 pub use constant_product::*;
 pub use fees::*;
+pub use offset_product::*;
@@ -0,0 +1,127 @@
+//! Constant product invariant with virtual (offset) reserves
+
+use crate::curve::constant_product::ConstantProductCurve;
+
+/// OffsetProductCurve struct implementing CurveCalculator
+///
+/// Prices trades as if each vault held `offset` extra tokens, i.e. keeps
+/// `(x + offset_x) * (y + offset_y)` constant. Only the real vault amounts can
+/// be paid out, so an output exceeding the real output vault fails downstream.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OffsetProductCurve;
+
+impl OffsetProductCurve {
+    /// Offset product swap ensures (x + offset_x) * (y + offset_y) = constant
+    ///
+    /// Returns `None` if a vault plus its offset overflows `u128`.
+    pub fn swap_base_input_without_fees(
+        input_amount: u128,
+        input_vault_amount: u128,
+        output_vault_amount: u128,
+        input_offset: u128,
+        output_offset: u128,
+    ) -> Option<u128> {
+        // (x + offset_x + delta_x) * (y + offset_y - delta_y) = (x + offset_x) * (y + offset_y)
+        // delta_y = (delta_x * (y + offset_y)) / (x + offset_x + delta_x)
+        Some(ConstantProductCurve::swap_base_input_without_fees(
+            input_amount,
+            input_vault_amount.checked_add(input_offset)?,
+            output_vault_amount.checked_add(output_offset)?,
+        ))
+    }
+
+    /// Returns `None` if a vault plus its offset overflows `u128`.
+    pub fn swap_base_output_without_fees(
+        output_amount: u128,
+        input_vault_amount: u128,
+        output_vault_amount: u128,
+        input_offset: u128,
+        output_offset: u128,
+    ) -> Option<u128> {
+        // (x + offset_x + delta_x) * (y + offset_y - delta_y) = (x + offset_x) * (y + offset_y)
+        // delta_x = ((x + offset_x) * delta_y) / (y + offset_y - delta_y)
+        Some(ConstantProductCurve::swap_base_output_without_fees(
+            output_amount,
+            input_vault_amount.checked_add(input_offset)?,
+            output_vault_amount.checked_add(output_offset)?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_offsets_match_constant_product() {
+        for (amount, x, y) in [
+            (1_000u128, 50_000u128, 80_000u128),
+            (7, 1_000_000, 3),
+            (123_456, 999, 1_000_000_000),
+        ] {
+            assert_eq!(
+                OffsetProductCurve::swap_base_input_without_fees(amount, x, y, 0, 0),
+                Some(ConstantProductCurve::swap_base_input_without_fees(
+                    amount, x, y
+                ))
+            );
+            assert_eq!(
+                OffsetProductCurve::swap_base_output_without_fees(1, x, y, 0, 0),
+                Some(ConstantProductCurve::swap_base_output_without_fees(1, x, y))
+            );
+        }
+    }
+
+    #[test]
+    fn offsets_price_against_virtual_reserves() {
+        // (100 + 900) * (100 + 900): 100 in → 1000 * 100 / (1000 + 100) = 90 out.
+        assert_eq!(
+            OffsetProductCurve::swap_base_input_without_fees(100, 100, 100, 900, 900),
+            Some(90)
+        );
+        // Without offsets the same trade moves the price much further: 100 * 100 / 200 = 50.
+        assert_eq!(
+            OffsetProductCurve::swap_base_input_without_fees(100, 100, 100, 0, 0),
+            Some(50)
+        );
+        // Exact output of 90 costs ceil(1000 * 90 / (1000 - 90)) = 99.
+        assert_eq!(
+            OffsetProductCurve::swap_base_output_without_fees(90, 100, 100, 900, 900),
+            Some(99)
+        );
+    }
+
+    #[test]
+    fn output_rounds_in_favour_of_the_pool() {
+        let (x, y, offset_x, offset_y) =
+            (1_000_000u128, 2_000_000u128, 3_000_000u128, 5_000_000u128);
+        let out =
+            OffsetProductCurve::swap_base_input_without_fees(12_345, x, y, offset_x, offset_y)
+                .unwrap();
+        let cost = OffsetProductCurve::swap_base_output_without_fees(out, x, y, offset_x, offset_y)
+            .unwrap();
+        assert!(cost <= 12_345);
+        let k = (x + offset_x) * (y + offset_y);
+        assert!((x + offset_x + 12_345) * (y + offset_y - out) >= k);
+    }
+
+    #[test]
+    fn offset_overflow_returns_none() {
+        assert_eq!(
+            OffsetProductCurve::swap_base_input_without_fees(1, u128::MAX, 10, 1, 0),
+            None
+        );
+        assert_eq!(
+            OffsetProductCurve::swap_base_input_without_fees(1, 10, u128::MAX, 0, 1),
+            None
+        );
+        assert_eq!(
+            OffsetProductCurve::swap_base_output_without_fees(1, u128::MAX, 10, 1, 0),
+            None
+        );
+        assert_eq!(
+            OffsetProductCurve::swap_base_output_without_fees(1, 10, u128::MAX, 0, 1),
+            None
+        );
+    }
+}
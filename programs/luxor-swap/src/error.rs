@@ -103,4 +103,133 @@ pub enum ErrorCode {
 
     #[msg("Invalid stake account data")]
     InvalidStakeAccountData,
+
+    #[msg("Split amount would dip into staked principal")]
+    WouldTouchPrincipal,
+
+    #[msg("LXR treasury vault does not hold enough LXR for this purchase")]
+    TreasuryNotFunded,
+
+    #[msg("LUXOR mint decimals do not match LUXOR_DECIMALS")]
+    MintDecimalsMismatch,
+
+    #[msg("A blacklist is already pending for this user")]
+    BlacklistAlreadyProposed,
+
+    #[msg("No blacklist has been proposed for this user")]
+    BlacklistNotProposed,
+
+    #[msg("Blacklist delay has not elapsed yet")]
+    BlacklistDelayNotElapsed,
+
+    #[msg("Pool reserves are too low to price this trade")]
+    InsufficientPoolLiquidity,
+
+    #[msg("Stake account is not delegated with an effective stake")]
+    StakeNotActive,
+
+    #[msg("Purchases are paused: LXR treasury is below the configured minimum")]
+    TreasuryBelowMinimum,
+
+    #[msg("Reward scale has already been migrated")]
+    RewardScaleAlreadyMigrated,
+
+    #[msg("SOL treasury vault does not hold enough WSOL to pay these rewards")]
+    InsufficientSolTreasury,
+
+    #[msg("Unstake amount exceeds the user's unstakeable SOL")]
+    UnstakeExceedsStake,
+
+    #[msg("A previous unstake has not been withdrawn yet")]
+    UnstakeAlreadyPending,
+
+    #[msg("No unstake is pending for this user")]
+    NoUnstakePending,
+
+    #[msg("Purchase amount is outside the configured min/max swap amount")]
+    SwapAmountOutOfBounds,
+
+    #[msg("Redeem functionality is currently disabled")]
+    RedeemDisabled,
+
+    #[msg("Signer is not the pending admin")]
+    NotPendingAdmin,
+
+    #[msg("User still has staked SOL or unclaimed rewards")]
+    OutstandingRewards,
+
+    #[msg("Purchase exceeds the allowed fraction of the pool's LXR reserve")]
+    PriceImpactTooHigh,
+
+    #[msg("User has no blacklisted SOL to restore")]
+    NotBlacklisted,
+
+    #[msg("User already has an open isolated stake")]
+    IsolatedStakeExists,
+
+    #[msg("User has no isolated stake")]
+    NoIsolatedStake,
+
+    #[msg("Transaction deadline has passed")]
+    DeadlineExceeded,
+
+    #[msg("Protocol stake account has been closed")]
+    StakeClosed,
+
+    #[msg("Protocol stake account still holds lamports")]
+    StakeNotDrained,
+
+    #[msg("Buyback cooldown has not elapsed")]
+    BuybackCooldown,
+
+    #[msg("Treasury fee recipient is not set")]
+    FeeRecipientNotSet,
+
+    #[msg("Stake is still activating and cannot be redelegated yet")]
+    StakeNotRedelegatable,
+
+    #[msg("Account does not match the configured reward mint")]
+    InvalidRewardMint,
+
+    #[msg("Swap venue is not supported yet")]
+    UnsupportedSwapVenue,
+
+    #[msg("Purchase would exceed the per-user stake cap")]
+    UserStakeCapExceeded,
+
+    #[msg("Global stake cap reached")]
+    GlobalStakeCapReached,
+
+    #[msg("Reward accounting exceeds the lamports held above principal")]
+    AccountingMismatch,
+
+    #[msg("A buyback is already being processed")]
+    BuybackInProgress,
+
+    #[msg("Pool price is outside the allowed band around the anchor price")]
+    PriceOutOfBand,
+
+    #[msg("Fee tiers must start at 0, increase strictly and not exceed the fee denominator")]
+    InvalidFeeTiers,
+
+    #[msg("No queued emergency action matches these parameters")]
+    EmergencyNotQueued,
+
+    #[msg("Queued emergency action is still timelocked")]
+    EmergencyTimelockActive,
+
+    #[msg("No withdrawn buyback SOL is waiting to be settled")]
+    BuybackNotWithdrawn,
+
+    #[msg("Account size matches neither the legacy nor the current layout")]
+    AccountLayoutMismatch,
+
+    #[msg("Account is not a UserStakeInfo record of this program")]
+    InvalidUserStakeInfo,
+
+    #[msg("User is blacklisted; un_blacklist must restore their SOL first")]
+    UserBlacklisted,
+
+    #[msg("Too many remaining accounts for one batch")]
+    BatchTooLarge,
 }
@@ -0,0 +1,46 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+/// Accounts context for `accept_admin`.
+///
+/// Second step of the admin transfer started by `update_config` param `0`:
+/// only the nominated `pending_admin` can complete it, so a mistyped key can
+/// never take over (or brick) admin control.
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    /// Nominated admin (must match `global_config.pending_admin`).
+    #[account(
+        constraint = global_config.pending_admin != Pubkey::default() @ ErrorCode::NotPendingAdmin,
+        constraint = pending_admin.key() == global_config.pending_admin @ ErrorCode::NotPendingAdmin,
+    )]
+    pub pending_admin: Signer<'info>,
+
+    /// Global protocol configuration.
+    #[account(
+        mut,
+        seeds = [GLOBAL_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
+
+/// Moves `pending_admin` into `admin`, clears `pending_admin` and emits
+/// `AdminTransferAccepted`.
+///
+/// # Fails
+/// - `NotPendingAdmin` if no transfer is pending or the signer is not the nominee.
+pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+    let old_admin = global_config.admin;
+
+    global_config.admin = global_config.pending_admin;
+    global_config.pending_admin = Pubkey::default();
+
+    emit!(AdminTransferAccepted {
+        old_admin,
+        new_admin: global_config.admin,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,138 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::transfer_from_pool_vault_to_user;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+/// Admin-only promotional path to credit LXR to a `user` without any SOL being
+/// staked. Unlike `manual_purchase`, no SOL moves and `total_staked_sol` is left
+/// untouched; the airdrop is only recorded in `base_lxr_holdings`, so the usual
+/// forfeiture rules on `redeem` still apply to it.
+///
+/// 1) Transfers `amount` LXR from the treasury vault to the user's ATA.
+/// 2) Adds `amount` to the user's `base_lxr_holdings` (lazily creating the record,
+///    without claiming it as a staker's).
+/// 3) Emits `LxrAirdropped` for off-chain consumers.
+#[derive(Accounts)]
+pub struct AirdropLxr<'info> {
+    /// Admin (authorized) signer; pays for any account creation. Must be either
+    /// the current protocol admin stored in `global_config.admin` or the hardcoded
+    /// program admin.
+    #[account(
+        mut,
+        constraint = (owner.key() == global_config.admin || owner.key() == crate::admin::id()) @ ErrorCode::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    /// Global configuration for the protocol.
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Recipient of the airdrop.
+    pub user: SystemAccount<'info>,
+
+    /// Per-user stake info for the target `user`. Lazily initialized if missing.
+    #[account(
+        init_if_needed,
+        seeds = [
+            USER_STAKE_INFO_SEED.as_bytes(),
+            user.key().as_ref()
+        ],
+        bump,
+        payer = owner,
+        space = UserStakeInfo::LEN
+    )]
+    pub user_stake_info: Account<'info, UserStakeInfo>,
+
+    /// Global stake meta; read to checkpoint a freshly created user record.
+    #[account(address = global_config.stake_info)]
+    pub stake_info: Account<'info, StakeInfo>,
+
+    /// Program authority PDA (treasury vault authority).
+    ///
+    /// CHECK: PDA derivation is enforced by seeds; used only as a signer PDA.
+    #[account(
+        seeds = [crate::AUTH_SEED.as_bytes()],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// Program treasury vault the airdropped LXR is taken from.
+    #[account(
+        mut,
+        address = global_config.lxr_treasury_vault,
+    )]
+    pub luxor_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Canonical LUXOR mint (decimals must match the value recorded at init).
+    #[account(
+        address = crate::luxor_mint::id() @ ErrorCode::InvalidLuxorMint,
+        constraint = crate::utils::luxor_decimals_match(luxor_mint.decimals) @ ErrorCode::MintDecimalsMismatch,
+    )]
+    pub luxor_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// User's LXR ATA; created if missing.
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = luxor_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_lxr_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL Token-2022 interface program.
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Associated Token Program (for ATA creation).
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// System Program (payer/rent).
+    pub system_program: Program<'info, System>,
+}
+
+/// Airdrops `amount` LXR from the treasury to `user` and records it as holdings.
+///
+/// # Fails
+/// - `InvalidOwner` if the signer is not an admin.
+/// - `TreasuryNotFunded` if `luxor_vault` holds less than `amount`.
+pub fn airdrop_lxr(ctx: Context<AirdropLxr>, amount: u64) -> Result<()> {
+    require_gt!(amount, 0);
+    require_gte!(ctx.accounts.luxor_vault.amount, amount, ErrorCode::TreasuryNotFunded);
+
+    // `owner` stays unset until the first stake, so `purchase` still treats the
+    // recipient as a new staker (bonus and `total_stake_count`).
+    let user_stake_info = &mut ctx.accounts.user_stake_info;
+    if user_stake_info.owner == Pubkey::default() {
+        user_stake_info.bump = ctx.bumps.user_stake_info;
+        user_stake_info.reset_reward_checkpoint(&ctx.accounts.stake_info);
+    }
+
+    // Holdings only: rewards accrue on `total_staked_sol`, which is unchanged.
+    user_stake_info.base_lxr_holdings = user_stake_info
+        .base_lxr_holdings
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.luxor_vault.to_account_info(),
+        ctx.accounts.user_lxr_token.to_account_info(),
+        ctx.accounts.luxor_mint.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        amount,
+        ctx.accounts.luxor_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]]],
+    )?;
+
+    emit!(LxrAirdropped {
+        user: ctx.accounts.user.key(),
+        amount,
+    });
+
+    Ok(())
+}
@@ -1,17 +1,15 @@
-use crate::curve::CurveCalculator;
+use crate::curve::{CurveCalculator, CurveType};
+use crate::instructions::load_stake_state;
 use crate::curve::FEE_RATE_DENOMINATOR_VALUE;
 use crate::error::ErrorCode;
 use crate::states::*;
+use crate::swap_venue::SwapVenue;
 use crate::utils::transfer_from_user_to_pool_vault;
 use crate::AUTH_SEED;
-use crate::PRECISION;
 use crate::STAKE_ACCOUNT_SEED;
 use crate::STAKE_SPLIT_ACCOUNT_SEED;
-use anchor_lang::prelude::borsh::BorshDeserialize;
-use anchor_lang::prelude::borsh::BorshSerialize;
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program;
-use anchor_lang::solana_program::instruction::Instruction;
 use anchor_lang::solana_program::program::invoke;
 use anchor_lang::solana_program::program::invoke_signed;
 use anchor_lang::solana_program::stake;
@@ -19,25 +17,16 @@ use anchor_lang::solana_program::stake::state::StakeStateV2;
 use anchor_lang::solana_program::system_instruction;
 use anchor_lang::solana_program::system_instruction::transfer;
 use anchor_lang::solana_program::sysvar;
+use anchor_lang::solana_program::sysvar::stake_history::StakeHistory;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::spl_token;
 use anchor_spl::token::spl_token::instruction::sync_native;
+use anchor_spl::token::{close_account, CloseAccount};
 use anchor_spl::token::Token;
 use anchor_spl::token_interface::{Mint, TokenAccount};
 use anchor_lang::solana_program::stake::instruction as stake_ix;
 use std::mem::size_of;
 
-/// Anchor-encoded parameters for Raydium's `swap_base_input` CPI call.
-/// Represents an exact-input trade where `amount_in` is spent to receive
-/// at least `minimum_amount_out` of the output token.
-#[derive(BorshSerialize, BorshDeserialize)]
-pub struct SwapBaseInput {
-    /// Exact amount of input tokens to spend.
-    amount_in: u64,
-    /// Minimum acceptable output (slippage guard).
-    minimum_amount_out: u64,
-}
-
 /// Accounts required to perform protocol **buyback** using SOL rewards accrued
 /// in the stake PDA. The flow:
 ///
@@ -45,11 +34,15 @@ pub struct SwapBaseInput {
 /// 2. Compute rewards available for buyback: `total_sol_rewards_accrued - total_sol_used_for_buyback`.
 /// 3. Transfer that SOL (WSOL via native account) to a temporary token account (`token_0_account`)
 ///    owned by the admin, then `sync_native`.
-/// 4. Deduct a treasury fee (`fee_treasury_rate`) from the available SOL to get `actual_amount_in`.
+/// 4. Deduct a treasury fee (`fee_treasury_rate`, or the matching `FeeTiers` tier) from the available SOL to get `actual_amount_in`.
 /// 5. Price an **exact-input** swap via `CurveCalculator::swap_base_input` and sanity-check invariants.
 /// 6. Execute Raydium CPMM `swap_base_input` CPI to buy LXR.
 /// 7. Send acquired LXR to `luxor_reward_vault` and the fee (in SOL/WSOL) to `sol_treasury_vault`.
 /// 8. Update reward indices and emit `BuybackExecuted`.
+/// 9. Sweep leftover WSOL dust to the treasury and close `token_0_account`, refunding its rent.
+///
+/// `buyback_withdraw` (steps 1–3) and `buyback_settle` (4–9) take the same accounts and
+/// run the execution phase in two transactions.
 #[derive(Accounts)]
 pub struct Buyback<'info> {
     /// Admin signer (must be current protocol admin or hardcoded program admin).
@@ -70,6 +63,7 @@ pub struct Buyback<'info> {
     #[account(
         mut,
         address = global_config.stake_info,
+        constraint = !stake_info.stake_closed @ ErrorCode::StakeClosed,
     )]
     pub stake_info: Account<'info, StakeInfo>,
 
@@ -116,7 +110,8 @@ pub struct Buyback<'info> {
     pub sol_treasury_vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// Admin's temporary token account to receive **input token** (token_0, typically WSOL).
-    /// Created if missing; later used as the input account for the Raydium swap.
+    /// Created if missing; later used as the input account for the Raydium swap and
+    /// closed at the end of the execution phase (rent refunded to `owner`).
     #[account(
         init_if_needed,
         payer = owner,
@@ -127,6 +122,8 @@ pub struct Buyback<'info> {
     pub token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// Admin's temporary token account to receive **output token** (token_1, expected to be LXR).
+    /// Created if missing; closed at the end of the execution phase (rent refunded to
+    /// `owner`) unless it already held LXR of the admin's before the swap.
     #[account(
         init_if_needed,
         payer = owner,
@@ -148,14 +145,17 @@ pub struct Buyback<'info> {
     #[account(address = token_0_vault.mint)]
     pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
 
-    /// Mint for token_1 vault (must match).
-    #[account(address = token_1_vault.mint)]
+    /// Mint for token_1 vault (must match, and must be `global_config.reward_mint`).
+    #[account(
+        address = token_1_vault.mint,
+        constraint = vault_1_mint.key() == global_config.reward_mint @ ErrorCode::InvalidRewardMint,
+    )]
     pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
 
     /// Raydium pool state (pricing & parameters source).
     ///
-    /// CHECK: Address pinned in code; deserialized ad-hoc.
-    #[account(mut,address = crate::luxor_pool_state::id())]
+    /// CHECK: Address pinned to `global_config.reward_pool_state()`; deserialized ad-hoc.
+    #[account(mut,address = global_config.reward_pool_state())]
     pub pool_state: UncheckedAccount<'info>,
 
     /// Raydium vault / LP mint authority PDA for the pool (fixed).
@@ -166,9 +166,20 @@ pub struct Buyback<'info> {
 
     /// Raydium AMM config account (fee/parameters).
     ///
-    /// CHECK: Passed through to Raydium CPI.
+    /// CHECK: Must be the pool's `amm_config`; deserialized ad-hoc for the fee
+    /// rates (unless `use_fee_override` is on) and passed through to Raydium CPI.
     pub amm_config: UncheckedAccount<'info>,
 
+    /// Buyback fee schedule (derived by FEE_TIERS_SEED).
+    ///
+    /// CHECK: PDA derivation enforced by seeds; read via `FeeTiers::load`, which
+    /// falls back to the flat `fee_treasury_rate` while the account does not exist.
+    #[account(
+        seeds = [FEE_TIERS_SEED.as_bytes()],
+        bump,
+    )]
+    pub fee_tiers: UncheckedAccount<'info>,
+
     /// Raydium observation state (TWAP / oracle buffers, etc.).
     ///
     /// CHECK: Passed through to Raydium CPI.
@@ -212,309 +223,174 @@ pub struct Buyback<'info> {
 /// ## Steps & Invariants
 /// - Accrual: Realizes any delta SOL in `stake_pda` into `stake_info` and updates
 ///   `reward_per_token_sol_stored` with `PRECISION / total_staked_sol`.
-/// - Budget: `reward_available_to_buyback = total_sol_rewards_accrued - total_sol_used_for_buyback`.
-/// - Movement: Transfers `reward_available_to_buyback` lamports from `stake_pda` to admin's
-///   `token_0_account` (native SOL → WSOL), then `sync_native`.
-/// - Fee: `fee_treasury = reward_available_to_buyback * fee_treasury_rate / FEE_RATE_DENOMINATOR_VALUE`.
+/// - Budget: `reward_available_to_buyback = total_sol_rewards_accrued - total_sol_used_for_buyback`,
+///   capped by `max_sol_per_buyback` when set and, with `reward_vault_target` set, by the
+///   SOL (treasury fee included) needed to refill the reward vault up to the target.
+///   The uncapped budget must not exceed what the stake PDA holds above
+///   `total_staked_sol + rent` (`AccountingMismatch` otherwise), the split must leave at
+///   least `total_staked_sol + rent` in the stake PDA (`WouldTouchPrincipal` otherwise), and
+///   the stake PDA must be delegated with a positive effective stake (`StakeNotActive`).
+/// - Movement: Withdraws the whole split account to the admin; its rewards (lamports above
+///   `min_rent`) go to `token_0_account` (native SOL → WSOL), then `sync_native`. The rent
+///   the admin paid for the split account in the request phase stays with the admin
+///   (`rent_refunded` in `BuybackExecuted`).
+/// - Fee: `fee_treasury = reward_available_to_buyback * rate / FEE_RATE_DENOMINATOR_VALUE`,
+///   or `0` (no fee transfer) when `buyback_fee_enabled` is false. `rate` is the `FeeTiers`
+///   tier matching the withdrawn amount once the schedule exists, `fee_treasury_rate` before.
 /// - Trade: For `actual_amount_in = reward_available_to_buyback - fee_treasury`, compute exact-input
-///   swap via `CurveCalculator::swap_base_input`. Check:
+///   swap via `CurveCalculator::swap_base_input` (both pool reserves must be at least
+///   `min_pool_reserve`, `InsufficientPoolLiquidity` otherwise). Check:
 ///     * `constant_after >= constant_before`
 ///     * `result.input_amount == actual_amount_in`
+/// - Cooldown: The execution phase fails with `BuybackCooldown` until
+///   `last_buyback_timestamp + buyback_cooldown_secs` has passed.
+/// - Slippage: `min_lxr_out` (ignored in the request phase) must not exceed the priced
+///   output (`ExceededSlippage`) and floors the CPI `minimum_amount_out`.
 /// - CPI: Call Raydium `swap_base_input` with a constructed discriminator+payload.
-/// - Settlement: Move LXR output to reward vault; move SOL fee to SOL treasury vault.
+/// - Settlement: Measure the LXR actually received by `token_1_account` across the CPI
+///   (`ZeroTradingTokens` if none); that amount, not the quote, is moved to the reward
+///   vault and credited to the index. Move SOL fee to SOL treasury vault.
+/// - Cleanup: Any WSOL dust this buyback left in `token_0_account` goes to the SOL
+///   treasury vault, then the account is closed so its rent (and any WSOL the caller
+///   held beforehand, unwrapped) returns to the caller. Stray LXR left in
+///   `token_1_account` goes to the reward vault, and the account is closed when it
+///   held no LXR before the swap.
+/// - Guard: `stake_info.processing` is set (and written to the account) on entry and cleared
+///   on return; entering while it is set fails with `BuybackInProgress`. This is not what
+///   stops re-entrancy: the runtime already rejects a CPI chain that calls back into this
+///   program (A → B → A), and a failed phase rolls the flag back with the rest of the
+///   transaction. The flag is a consistency check that only trips if a successful phase
+///   ever returned without clearing it.
+/// - Split: `buyback_withdraw` + `buyback_settle` run the execution phase in two
+///   transactions; while their WSOL is pending (`buyback_wsol_pending > 0`) this
+///   instruction fails with `BuybackInProgress`.
 /// - State: Update `total_luxor_rewards_accrued`, `total_sol_used_for_buyback`,
-///   `reward_per_token_lxr_stored`, timestamps; emit `BuybackExecuted`.
-pub fn buyback(ctx: Context<Buyback>) -> Result<()> {
+///   `reward_per_token_lxr_stored` (held in `lxr_rewards_undistributed` while
+///   `total_staked_sol == 0`, credited by the next buyback with stakers), timestamps;
+///   emit `BuybackExecuted`. The request phase emits `BuybackRequested`; both carry the
+///   same `buyback_count`.
+pub fn buyback(ctx: Context<Buyback>, min_lxr_out: u64) -> Result<()> {
     let stake_info = &mut ctx.accounts.stake_info;
     let stake_split_pda = &ctx.accounts.stake_split_pda;
+
+    // --- Processing flag: written through before the CPIs, cleared on return ---
+    require!(!stake_info.processing, ErrorCode::BuybackInProgress);
+    stake_info.processing = true;
+    stake_info.exit(&crate::ID)?;
+
     let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
     let space = size_of::<StakeStateV2>();
-    let min_rent = Rent::get()?.minimum_balance(space);
-    require!(min_rent > 0, ErrorCode::InsufficientRent);
+    let min_rent = ctx.accounts.global_config.stake_split_rent()?;
 
     // --- Accrue any newly observed SOL rewards on the stake PDA ---
-    if ctx.accounts.stake_pda.lamports() > stake_info.last_tracked_sol_balance {
-        let rewards_accured = ctx
-            .accounts
-            .stake_pda
-            .lamports()
-            .checked_sub(stake_info.last_tracked_sol_balance)
-            .unwrap();
-        stake_info.total_sol_rewards_accrued = stake_info
-            .total_sol_rewards_accrued
-            .checked_add(rewards_accured)
-            .unwrap();
-        stake_info.last_tracked_sol_balance = ctx.accounts.stake_pda.lamports();
-    }
+    stake_info.accrue_sol_rewards(ctx.accounts.stake_pda.lamports(), ctx.accounts.global_config.sol_reward_share_bps)?;
 
     let authority_ai = ctx.accounts.authority.to_account_info();
     let clock_ai = ctx.accounts.clock.to_account_info();
-    let stake_pda_ai = ctx.accounts.stake_pda.to_account_info();
-    let vote_ai = ctx.accounts.vote_account.to_account_info();
     let stake_history_ai = ctx.accounts.stake_history.to_account_info();
-    let stake_config_ai = ctx.accounts.stake_config.to_account_info();
     
     if stake_info.buyback_requested {
-        
-        // PDA seeds for authority (PDA acts as signer).
-        let auth_bump = ctx.bumps.authority;
-        let seeds: &[&[u8]] = &[crate::AUTH_SEED.as_bytes(), &[auth_bump]];
-       
-        {
-            let ix = stake_ix::delegate_stake(
-                &ctx.accounts.stake_pda.key(), 
-                &ctx.accounts.authority.key(), 
-                &ctx.accounts.vote_account.key()
-            );
-
-            let delegate_account_infos = Box::new(vec![
-                stake_pda_ai,
-                vote_ai,
-                clock_ai.clone(),
-                stake_history_ai.clone(),
-                stake_config_ai.clone(),
-                authority_ai.clone(),
-            ]);
-
-            invoke_signed(&ix, &*delegate_account_infos, &[seeds])?;
-        }
-
-        if *stake_split_pda.owner == ctx.accounts.stake_program.key() {
-             
-            let stake_account = ctx.accounts.stake_split_pda.to_account_info();
-            let recipient_ai = ctx.accounts.owner.to_account_info();
-            let system_program = ctx.accounts.system_program.to_account_info();
-            let token_program = ctx.accounts.token_program.to_account_info();
-            let owner_wsol = ctx.accounts.token_0_account.to_account_info();
-            
-            let sol_withdrawan = ctx.accounts.stake_split_pda.lamports().checked_sub(min_rent).unwrap();   
-
-            let ix = stake_ix::withdraw(
-                &stake_account.key(),
-                &ctx.accounts.authority.key(),
-                &ctx.accounts.owner.key(),
-                ctx.accounts.stake_split_pda.lamports(),   // u64, or ALL available
-                None,       // custodian optional
-            );
-
-            let withdraw_account_infos = Box::new(vec![
-                stake_account,
-                recipient_ai.clone(),
-                clock_ai,
-                stake_history_ai,
-                stake_config_ai,
-                authority_ai,
-            ]);
-
-
-            invoke_signed(&ix, &*withdraw_account_infos, &[seeds])?;
-
-            let ix = transfer(
-                &ctx.accounts.owner.key(),
-                &ctx.accounts.token_0_account.key(),
-                sol_withdrawan,
-            );
-
-            invoke(&ix, &[recipient_ai, owner_wsol.clone(), system_program])?;
-
-            // Convert the lamports just transferred into WSOL token balance.
-            let sync_ix = sync_native(&spl_token::id(), &ctx.accounts.token_0_account.key())?;
-            invoke(&sync_ix, &[owner_wsol, token_program.clone()])?;
-
-            // --- Treasury fee (in SOL/WSOL) ---
-            let fee_treasury = (sol_withdrawan as u128)
-                .checked_mul(ctx.accounts.global_config.fee_treasury_rate as u128)
-                .unwrap()
-                .checked_div(FEE_RATE_DENOMINATOR_VALUE as u128)
-                .unwrap() as u64;
-
-            // --- Exact-input amount sent to the pool after fee ---
-            let actual_amount_in = sol_withdrawan
-                .checked_sub(fee_treasury)
-                .unwrap();
-            require_gt!(actual_amount_in, 0);
-
-            // --- Read pool state + compute pricing invariants ---
-            let pool_state_info = &ctx.accounts.pool_state;
-            let pool_state = PoolState::try_deserialize(&mut &pool_state_info.data.borrow()[..])?;
-            let SwapParams {
-                trade_direction: _,
-                total_input_token_amount,
-                total_output_token_amount,
-                token_0_price_x64: _,
-                token_1_price_x64: _,
-                is_creator_fee_on_input,
-            } = pool_state.get_swap_params(
-                ctx.accounts.token_0_vault.key(),
-                ctx.accounts.token_1_vault.key(),
-                ctx.accounts.token_0_vault.amount,
-                ctx.accounts.token_1_vault.amount,
-            )?;
-
-            // let constant_before = u128::from(total_input_token_amount)
-            //     .checked_mul(u128::from(total_output_token_amount))
-            //     .unwrap();
-
-            let creator_fee_rate = pool_state.adjust_creator_fee_rate(500);
-
-            // Price the exact-input trade and validate invariants.
-            let result = CurveCalculator::swap_base_input(
-                u128::from(actual_amount_in),
-                u128::from(total_input_token_amount),
-                u128::from(total_output_token_amount),
-                2500, // base fee (example)
-                creator_fee_rate,
-                120000, // price impact limit (example)
-                40000,  // oracle/other adjustment (example)
-                is_creator_fee_on_input,
-            )
-            .ok_or(ErrorCode::ZeroTradingTokens)?;
-
-            // let constant_after = u128::from(result.new_input_vault_amount)
-            //     .checked_mul(u128::from(result.new_output_vault_amount))
-            //     .unwrap();
-
-            require_eq!(
-                u64::try_from(result.input_amount).unwrap(),
-                actual_amount_in
-            );
-            // require_gte!(constant_after, constant_before);
-
-            // Output LXR expected from the priced trade (will later be verified by Raydium CPI).
-            let lxr_bought = u64::try_from(result.output_amount).unwrap();
-
-            stake_info.total_luxor_rewards_accrued = stake_info
-                .total_luxor_rewards_accrued
-                .checked_add(lxr_bought)
-                .unwrap();
-            stake_info.total_sol_used_for_buyback = stake_info
-                .total_sol_used_for_buyback
-                .checked_add(sol_withdrawan)
-                .unwrap();
-        
-            stake_info.last_buyback_timestamp = block_timestamp;
-            stake_info.reward_per_token_lxr_stored = stake_info
-                .reward_per_token_lxr_stored
-                .checked_add(
-                    (lxr_bought as u128)
-                        .checked_mul(PRECISION)
-                        .unwrap()
-                        .checked_div(stake_info.total_staked_sol as u128)
-                        .unwrap()).unwrap();
-
-            // --- Build Raydium `swap_base_input` CPI payload (Anchor-style discriminator + params) ---
-            let params = SwapBaseInput {
-                amount_in: actual_amount_in,
-                minimum_amount_out: 0, // accept any positive amount; slippage bounded by invariant checks above
-            };
-
-            // Discriminator for `global:swap_base_input` (Raydium CPMM)
-            let discriminator =
-                anchor_lang::solana_program::hash::hash(b"global:swap_base_input").to_bytes()[..8].to_vec();
-            let mut data = discriminator;
-            data.extend(params.try_to_vec()?);
-
-            let accounts = vec![
-                AccountMeta::new(ctx.accounts.owner.key(), true),
-                AccountMeta::new_readonly(ctx.accounts.raydium_authority.key(), false),
-                AccountMeta::new_readonly(ctx.accounts.amm_config.key(), false),
-                AccountMeta::new(ctx.accounts.pool_state.key(), false),
-                AccountMeta::new(ctx.accounts.token_0_account.key(), false),
-                AccountMeta::new(ctx.accounts.token_1_account.key(), false),
-                AccountMeta::new(ctx.accounts.token_0_vault.key(), false),
-                AccountMeta::new(ctx.accounts.token_1_vault.key(), false),
-                AccountMeta::new_readonly(ctx.accounts.token_program.key(), false),
-                AccountMeta::new_readonly(ctx.accounts.token_program.key(), false),
-                AccountMeta::new_readonly(ctx.accounts.vault_0_mint.key(), false),
-                AccountMeta::new_readonly(ctx.accounts.vault_1_mint.key(), false),
-                AccountMeta::new(ctx.accounts.observation_state.key(), false),
-            ];
-
-            let ix = Instruction {
-                program_id: crate::raydium_cpmm::id(),
-                accounts,
-                data,
-            };
-
-            // Execute the Raydium CPMM swap.
-            let accounts = Box::new(vec![
-                ctx.accounts.owner.to_account_info(),
-                ctx.accounts.raydium_authority.to_account_info(),
-                ctx.accounts.amm_config.to_account_info(),
-                ctx.accounts.pool_state.to_account_info(),
-                ctx.accounts.token_0_account.to_account_info(),
-                ctx.accounts.token_1_account.to_account_info(),
-                ctx.accounts.token_0_vault.to_account_info(),
-                ctx.accounts.token_1_vault.to_account_info(),
-                ctx.accounts.token_program.to_account_info(),
-                ctx.accounts.token_program.to_account_info(),
-                ctx.accounts.vault_0_mint.to_account_info(),
-                ctx.accounts.vault_1_mint.to_account_info(),
-                ctx.accounts.observation_state.to_account_info(),
-            ]);
-
-            invoke(&ix, &*accounts)?;
-            // --- Settle post-swap balances ---
-
-            // Send acquired LXR (token_1) to the LXR reward vault.
-            transfer_from_user_to_pool_vault(
-                ctx.accounts.owner.to_account_info(),
-                ctx.accounts.token_1_account.to_account_info(),
-                ctx.accounts.luxor_reward_vault.to_account_info(),
-                ctx.accounts.vault_1_mint.to_account_info(),
-                ctx.accounts.token_program.to_account_info(),
-                lxr_bought,
-                ctx.accounts.vault_1_mint.decimals,
-            )?; 
-
-            // Send the treasury fee (token_0 / WSOL) to the SOL treasury vault.
-            transfer_from_user_to_pool_vault(
-                ctx.accounts.owner.to_account_info(),
-                ctx.accounts.token_0_account.to_account_info(),
-                ctx.accounts.sol_treasury_vault.to_account_info(),
-                ctx.accounts.vault_0_mint.to_account_info(),
-                ctx.accounts.token_program.to_account_info(),
-                fee_treasury,
-                ctx.accounts.vault_0_mint.decimals,
-            )?;
-
-            // --- Event for indexers / analytics ---
-            emit!(BuybackExecuted {
-                sol_amount: sol_withdrawan,
-                lxr_bought,
-                fee_to_treasury: fee_treasury,
-            });  
+        // A split execution (`buyback_withdraw`) holds the SOL until `buyback_settle`.
+        require_eq!(stake_info.buyback_wsol_pending, 0, ErrorCode::BuybackInProgress);
+        let venue = check_buyback_execution(&ctx.accounts.global_config, stake_info, block_timestamp)?;
 
+        if let Some(withdrawal) = withdraw_split_to_wsol(ctx.accounts, ctx.bumps.authority, min_rent)? {
+            swap_and_settle(ctx.accounts, venue, withdrawal, min_lxr_out, block_timestamp)?;
         }
-        
-        stake_info.buyback_requested = false;
-        stake_info.buyback_count = stake_info.buyback_count.checked_add(1).unwrap();
-
-             
-    
+        finish_buyback(&mut ctx.accounts.stake_info)?;
     } else {
         require_keys_eq!(*stake_split_pda.owner, ctx.accounts.system_program.key());
         require!(!stake_info.buyback_requested, ErrorCode::BuybackAlreadyRequested);
 
+        // --- Only genuinely reward-earning stake may be split: it must be delegated and effective ---
+        let effective_stake = match load_stake_state(&ctx.accounts.stake_pda.to_account_info())? {
+            StakeStateV2::Stake(_, stake, _) => {
+                let stake_history = StakeHistory::from_account_info(&stake_history_ai)?;
+                stake
+                    .delegation
+                    .stake_activating_and_deactivating(Clock::get()?.epoch, &stake_history, None)
+                    .effective
+            }
+            _ => 0,
+        };
+        require_gt!(effective_stake, 0, ErrorCode::StakeNotActive);
+
         let payer = ctx.accounts.owner.to_account_info();
         let stake_ai = ctx.accounts.stake_pda.to_account_info();
         let split_stake_pda_ai = ctx.accounts.stake_split_pda.to_account_info();
         let system_program_ai = ctx.accounts.system_program.to_account_info();
 
-        // --- Available rewards (SOL) to use for buyback ---
-        let reward_available_to_buyback = stake_info
-            .total_sol_rewards_accrued
-            .checked_sub(stake_info.total_sol_used_for_buyback).unwrap();
+        // --- Available rewards (SOL) to use for buyback; the SOL claim reserve is not ---
+        // With nothing staked the rewards stay on the stake account for a later buyback.
+        let reward_available_to_buyback = if stake_info.total_staked_sol > 0 {
+            stake_info.sol_available_to_buyback()?
+        } else {
+            0
+        };
+        let reserve_to_fund = stake_info.sol_reserve_unfunded()?;
 
         msg!("Available rewards for buyback: {}", reward_available_to_buyback);
 
+        // --- Solvency invariant: accounted rewards must actually sit above principal + rent ---
+        let principal_floor = stake_info
+            .total_staked_sol
+            .checked_add(min_rent)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let rewards_held = stake_ai.lamports().saturating_sub(principal_floor);
+        require_gte!(
+            rewards_held,
+            reward_available_to_buyback
+                .checked_add(reserve_to_fund)
+                .ok_or(ErrorCode::MathOverflow)?,
+            ErrorCode::AccountingMismatch
+        );
+
+        // --- Apply the per-request cap; any remainder stays available for the next buyback ---
+        let max_sol_per_buyback = ctx.accounts.global_config.max_sol_per_buyback;
+        let sol_to_split = if max_sol_per_buyback > 0 {
+            reward_available_to_buyback.min(max_sol_per_buyback)
+        } else {
+            reward_available_to_buyback
+        };
+
+        // --- Demand-driven sizing: only split what refills the reward vault to its target ---
+        let reward_vault_target = ctx.accounts.global_config.reward_vault_target;
+        let sol_to_split = if reward_vault_target > 0 {
+            let lxr_deficit = reward_vault_target.saturating_sub(ctx.accounts.luxor_reward_vault.amount);
+            let sol_to_refill = sol_needed_for_lxr(
+                &ctx.accounts.global_config,
+                &ctx.accounts.pool_state,
+                &ctx.accounts.amm_config,
+                &ctx.accounts.fee_tiers,
+                &ctx.accounts.token_0_vault,
+                &ctx.accounts.token_1_vault,
+                lxr_deficit,
+            )?;
+            sol_to_split.min(sol_to_refill)
+        } else {
+            sol_to_split
+        };
+        msg!("SOL to split for buyback: {}", sol_to_split);
+
+        // --- The reserved SOL rides along with the split, uncapped, to fund SOL claims ---
+        let sol_to_split = sol_to_split
+            .checked_add(reserve_to_fund)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // --- Solvency guard: the split must never reach into staked principal ---
+        let remaining_after_split = stake_ai
+            .lamports()
+            .checked_sub(sol_to_split)
+            .ok_or(ErrorCode::WouldTouchPrincipal)?;
+        require_gte!(remaining_after_split, principal_floor, ErrorCode::WouldTouchPrincipal);
+
         let bump  = ctx.bumps.authority;
         let stake_seeds: &[&[u8]] = &[AUTH_SEED.as_bytes(), &[bump]];
 
         let ix = stake_ix::deactivate_stake(&stake_ai.key(), &authority_ai.key());
         invoke_signed(&ix, &[stake_ai.clone(), clock_ai, authority_ai.clone()], &[stake_seeds])?;
 
-        if reward_available_to_buyback > 0 {
+        if sol_to_split > 0 {
             // Derive seeds for stake account PDA.
             let bump = ctx.bumps.stake_split_pda;
             let stake_seeds: &[&[u8]] = &[STAKE_SPLIT_ACCOUNT_SEED.as_bytes(), &stake_info.buyback_count.to_le_bytes(), &[bump]];
@@ -537,7 +413,7 @@ pub fn buyback(ctx: Context<Buyback>) -> Result<()> {
             let ix = &stake_ix::split(
                 &stake_ai.key(),            // source stake
                 &authority_ai.key(),            // stake authority PDA
-                reward_available_to_buyback,     // rewards you computed
+                sol_to_split,                    // capped rewards, never principal
                 &split_stake_pda_ai.key(),            // destination stake account (rent-exempt, stake-owned)
             )[2];
             let auth_bump = ctx.bumps.authority;
@@ -548,10 +424,625 @@ pub fn buyback(ctx: Context<Buyback>) -> Result<()> {
 
         stake_info.last_tracked_sol_balance = ctx.accounts.stake_pda.lamports();
         stake_info.buyback_requested = true;
+        stake_info.buyback_reserve_split = reserve_to_fund;
         stake_info.last_update_timestamp = block_timestamp;
 
+        emit!(BuybackRequested {
+            buyback_count: stake_info.buyback_count,
+            sol_to_buyback: sol_to_split - reserve_to_fund,
+            sol_to_reserve: reserve_to_fund,
+            split_pda: ctx.accounts.stake_split_pda.key(),
+        });
+
     }
 
-    
+    ctx.accounts.stake_info.processing = false;
     Ok(())
 }
+
+/// First half of a split buyback execution, for transactions too small in compute
+/// for `buyback`'s execution phase: re-delegates the stake PDA, withdraws the split
+/// account and wraps its rewards into `token_0_account`, recording them in
+/// `stake_info.buyback_wsol_pending`. `buyback_settle` then swaps and settles them.
+///
+/// Takes the same accounts as `buyback`. With no split account (nothing was
+/// available at request time) the buyback completes here, as `buyback` would.
+///
+/// # Atomicity
+/// The two halves land in separate transactions, so the pool can move in between
+/// and the WSOL sits in the admin's `token_0_account` until settled. While
+/// `buyback_wsol_pending > 0`, `buyback_requested` stays set (unstake, redelegate and
+/// new requests stay blocked), `buyback` and a second `buyback_withdraw` fail with
+/// `BuybackInProgress`, and `buyback_settle` refuses to run if that WSOL is gone
+/// (`AccountingMismatch`).
+///
+/// # Fails
+/// - `NoBuybackRequested` outside the execution phase.
+/// - `BuybackInProgress` if a withdrawal is already pending.
+/// - `BuybackCooldown` and `UnsupportedSwapVenue` as in `buyback`.
+pub fn buyback_withdraw(ctx: Context<Buyback>) -> Result<()> {
+    let stake_info = &mut ctx.accounts.stake_info;
+    require!(!stake_info.processing, ErrorCode::BuybackInProgress);
+    require!(stake_info.buyback_requested, ErrorCode::NoBuybackRequested);
+    require_eq!(stake_info.buyback_wsol_pending, 0, ErrorCode::BuybackInProgress);
+    stake_info.processing = true;
+    stake_info.exit(&crate::ID)?;
+
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let min_rent = ctx.accounts.global_config.stake_split_rent()?;
+    stake_info.accrue_sol_rewards(ctx.accounts.stake_pda.lamports(), ctx.accounts.global_config.sol_reward_share_bps)?;
+    check_buyback_execution(&ctx.accounts.global_config, stake_info, block_timestamp)?;
+
+    match withdraw_split_to_wsol(ctx.accounts, ctx.bumps.authority, min_rent)? {
+        Some(withdrawal) => {
+            let stake_info = &mut ctx.accounts.stake_info;
+            stake_info.buyback_wsol_pending = withdrawal.sol_withdrawn;
+            stake_info.buyback_rent_refunded = withdrawal.rent_refunded;
+            emit!(BuybackWithdrawn {
+                buyback_count: stake_info.buyback_count,
+                sol_amount: withdrawal.sol_withdrawn,
+                rent_refunded: withdrawal.rent_refunded,
+            });
+        }
+        None => finish_buyback(&mut ctx.accounts.stake_info)?,
+    }
+
+    ctx.accounts.stake_info.processing = false;
+    Ok(())
+}
+
+/// Second half of a split buyback execution: swaps the WSOL recorded by
+/// `buyback_withdraw` and settles it exactly like `buyback`'s execution phase
+/// (fee, slippage via `min_lxr_out`, reward index, cleanup, `BuybackExecuted`).
+///
+/// # Fails
+/// - `BuybackNotWithdrawn` unless `buyback_withdraw` ran first.
+/// - `AccountingMismatch` if `token_0_account` no longer holds the pending WSOL.
+/// - `UnsupportedSwapVenue`, `ExceededSlippage` and the pricing errors of `buyback`.
+pub fn buyback_settle(ctx: Context<Buyback>, min_lxr_out: u64) -> Result<()> {
+    let stake_info = &mut ctx.accounts.stake_info;
+    require!(!stake_info.processing, ErrorCode::BuybackInProgress);
+    let sol_withdrawn = stake_info.buyback_wsol_pending;
+    require_gt!(sol_withdrawn, 0, ErrorCode::BuybackNotWithdrawn);
+    stake_info.processing = true;
+    stake_info.exit(&crate::ID)?;
+
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let venue = SwapVenue::from_dex_type(ctx.accounts.global_config.dex_type)?;
+    require!(venue.is_supported(), ErrorCode::UnsupportedSwapVenue);
+
+    // Only the WSOL on top of what the caller held before the withdrawal is swept later.
+    let token_0_balance_before = ctx
+        .accounts
+        .token_0_account
+        .amount
+        .checked_sub(sol_withdrawn)
+        .ok_or(ErrorCode::AccountingMismatch)?;
+    let withdrawal = SplitWithdrawal {
+        sol_withdrawn,
+        rent_refunded: stake_info.buyback_rent_refunded,
+        token_0_balance_before,
+    };
+    swap_and_settle(ctx.accounts, venue, withdrawal, min_lxr_out, block_timestamp)?;
+
+    let stake_info = &mut ctx.accounts.stake_info;
+    stake_info.buyback_wsol_pending = 0;
+    stake_info.buyback_rent_refunded = 0;
+    finish_buyback(stake_info)?;
+    stake_info.processing = false;
+    Ok(())
+}
+
+/// Execution-phase gates: the cooldown since the last buyback must have passed and
+/// the configured swap venue must be supported. Returns the venue.
+fn check_buyback_execution(global_config: &GlobalConfig, stake_info: &StakeInfo, block_timestamp: u64) -> Result<SwapVenue> {
+    // --- Rate limit executions; requesting a split stays unrestricted ---
+    let next_buyback_at = stake_info
+        .last_buyback_timestamp
+        .checked_add(global_config.buyback_cooldown_secs)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require_gte!(block_timestamp, next_buyback_at, ErrorCode::BuybackCooldown);
+
+    // --- Resolve the swap venue before touching the stake ---
+    let venue = SwapVenue::from_dex_type(global_config.dex_type)?;
+    require!(venue.is_supported(), ErrorCode::UnsupportedSwapVenue);
+    Ok(venue)
+}
+
+/// Closes the execution phase: the next `buyback` requests a new split.
+fn finish_buyback(stake_info: &mut StakeInfo) -> Result<()> {
+    stake_info.buyback_requested = false;
+    stake_info.buyback_count = stake_info.buyback_count.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+    Ok(())
+}
+
+/// SOL taken out of the split stake account and wrapped for the swap.
+struct SplitWithdrawal {
+    /// Rewards wrapped into `token_0_account` (lamports).
+    sol_withdrawn: u64,
+    /// Split account rent left with the admin who paid it.
+    rent_refunded: u64,
+    /// `token_0_account` balance before the rewards were wrapped into it.
+    token_0_balance_before: u64,
+}
+
+/// Re-delegates the stake PDA and, when the request phase created a split account,
+/// withdraws it to `owner` and wraps its rewards (lamports above `min_rent`) into
+/// `token_0_account`. Returns `None` when there is no split account.
+fn withdraw_split_to_wsol<'info>(
+    accounts: &mut Buyback<'info>,
+    auth_bump: u8,
+    min_rent: u64,
+) -> Result<Option<SplitWithdrawal>> {
+    // PDA seeds for authority (PDA acts as signer).
+    let seeds: &[&[u8]] = &[crate::AUTH_SEED.as_bytes(), &[auth_bump]];
+    let authority_ai = accounts.authority.to_account_info();
+    let clock_ai = accounts.clock.to_account_info();
+    let stake_history_ai = accounts.stake_history.to_account_info();
+    let stake_config_ai = accounts.stake_config.to_account_info();
+
+    {
+        let ix = stake_ix::delegate_stake(
+            &accounts.stake_pda.key(),
+            &accounts.authority.key(),
+            &accounts.vote_account.key()
+        );
+
+        let delegate_account_infos = Box::new(vec![
+            accounts.stake_pda.to_account_info(),
+            accounts.vote_account.to_account_info(),
+            clock_ai.clone(),
+            stake_history_ai.clone(),
+            stake_config_ai.clone(),
+            authority_ai.clone(),
+        ]);
+
+        invoke_signed(&ix, &delegate_account_infos, &[seeds])?;
+    }
+
+    if *accounts.stake_split_pda.owner != accounts.stake_program.key() {
+        return Ok(None);
+    }
+
+    let stake_account = accounts.stake_split_pda.to_account_info();
+    let recipient_ai = accounts.owner.to_account_info();
+    let system_program = accounts.system_program.to_account_info();
+    let token_program = accounts.token_program.to_account_info();
+    let owner_wsol = accounts.token_0_account.to_account_info();
+
+    // The split account holds the rewards plus the rent the request-phase admin paid
+    // to create it. Everything is withdrawn to `owner`: the rewards are wrapped below,
+    // the rent stays with `owner` as the refund (both phases are admin-only).
+    let split_lamports = accounts.stake_split_pda.lamports();
+    let sol_withdrawn = split_lamports.checked_sub(min_rent).ok_or(ErrorCode::UnderflowError)?;
+    let rent_refunded = split_lamports.checked_sub(sol_withdrawn).ok_or(ErrorCode::UnderflowError)?;
+    // WSOL the caller already held; only dust left over by this buyback is swept.
+    let token_0_balance_before = accounts.token_0_account.amount;
+
+    let ix = stake_ix::withdraw(
+        &stake_account.key(),
+        &accounts.authority.key(),
+        &accounts.owner.key(),
+        split_lamports,   // rewards + rent; the account is closed
+        None,       // custodian optional
+    );
+
+    let withdraw_account_infos = Box::new(vec![
+        stake_account,
+        recipient_ai.clone(),
+        clock_ai,
+        stake_history_ai,
+        stake_config_ai,
+        authority_ai,
+    ]);
+
+    invoke_signed(&ix, &withdraw_account_infos, &[seeds])?;
+
+    let ix = transfer(
+        &accounts.owner.key(),
+        &accounts.token_0_account.key(),
+        sol_withdrawn,
+    );
+
+    invoke(&ix, &[recipient_ai, owner_wsol.clone(), system_program])?;
+
+    // Convert the lamports just transferred into WSOL token balance.
+    let sync_ix = sync_native(&spl_token::id(), &accounts.token_0_account.key())?;
+    invoke(&sync_ix, &[owner_wsol, token_program])?;
+
+    Ok(Some(SplitWithdrawal {
+        sol_withdrawn,
+        rent_refunded,
+        token_0_balance_before,
+    }))
+}
+
+/// Moves the reserved SOL rewards to the SOL treasury vault, swaps the rest (after the
+/// treasury fee) for the reward token, credits what the swap delivered to the reward
+/// index, routes LXR and fee to their vaults, cleans up the temporary token accounts
+/// and emits `BuybackExecuted`. With nothing left to swap, `min_lxr_out` is ignored.
+fn swap_and_settle<'info>(
+    accounts: &mut Buyback<'info>,
+    venue: SwapVenue,
+    withdrawal: SplitWithdrawal,
+    min_lxr_out: u64,
+    block_timestamp: u64,
+) -> Result<()> {
+    let SplitWithdrawal {
+        sol_withdrawn,
+        rent_refunded,
+        token_0_balance_before,
+    } = withdrawal;
+
+    // --- Reserved SOL rewards back `claim_sol_rewards`; only the rest is swapped ---
+    let sol_reserved = accounts.stake_info.buyback_reserve_split;
+    let sol_to_swap = sol_withdrawn
+        .checked_sub(sol_reserved)
+        .ok_or(ErrorCode::AccountingMismatch)?;
+    if sol_reserved > 0 {
+        transfer_from_user_to_pool_vault(
+            accounts.owner.to_account_info(),
+            accounts.token_0_account.to_account_info(),
+            accounts.sol_treasury_vault.to_account_info(),
+            accounts.vault_0_mint.to_account_info(),
+            accounts.token_program.to_account_info(),
+            sol_reserved,
+            accounts.vault_0_mint.decimals,
+        )?;
+    }
+
+    let lxr_before = accounts.token_1_account.amount;
+    let (lxr_bought, fee_treasury) = if sol_to_swap > 0 {
+        swap_rewards(accounts, venue, sol_to_swap, min_lxr_out)?
+    } else {
+        (0, 0)
+    };
+
+    let stake_info = &mut accounts.stake_info;
+    stake_info.total_luxor_rewards_accrued = stake_info
+        .total_luxor_rewards_accrued
+        .checked_add(lxr_bought)
+        .ok_or(ErrorCode::MathOverflow)?;
+    stake_info.total_sol_used_for_buyback = stake_info
+        .total_sol_used_for_buyback
+        .checked_add(sol_to_swap)
+        .ok_or(ErrorCode::MathOverflow)?;
+    stake_info.total_sol_reserve_funded = stake_info
+        .total_sol_reserve_funded
+        .checked_add(sol_reserved)
+        .ok_or(ErrorCode::MathOverflow)?;
+    stake_info.buyback_reserve_split = 0;
+
+    stake_info.last_buyback_timestamp = block_timestamp;
+    // Held in `lxr_rewards_undistributed` while nothing is staked.
+    stake_info.credit_lxr_rewards(lxr_bought)?;
+
+    // --- Settle post-swap balances ---
+
+    // Send acquired LXR (token_1) to the LXR reward vault.
+    if lxr_bought > 0 {
+        transfer_from_user_to_pool_vault(
+            accounts.owner.to_account_info(),
+            accounts.token_1_account.to_account_info(),
+            accounts.luxor_reward_vault.to_account_info(),
+            accounts.vault_1_mint.to_account_info(),
+            accounts.token_program.to_account_info(),
+            lxr_bought,
+            accounts.vault_1_mint.decimals,
+        )?;
+    }
+
+    // --- Sweep WSOL dust to the treasury and close the temp account (rent → caller) ---
+    accounts.token_0_account.reload()?;
+    let wsol_dust = accounts
+        .token_0_account
+        .amount
+        .saturating_sub(token_0_balance_before);
+    if wsol_dust > 0 {
+        transfer_from_user_to_pool_vault(
+            accounts.owner.to_account_info(),
+            accounts.token_0_account.to_account_info(),
+            accounts.sol_treasury_vault.to_account_info(),
+            accounts.vault_0_mint.to_account_info(),
+            accounts.token_program.to_account_info(),
+            wsol_dust,
+            accounts.vault_0_mint.decimals,
+        )?;
+    }
+    close_account(CpiContext::new(
+        accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: accounts.token_0_account.to_account_info(),
+            destination: accounts.owner.to_account_info(),
+            authority: accounts.owner.to_account_info(),
+        },
+    ))?;
+
+    // --- Sweep stray LXR to the reward vault; close the temp account if it held none of the caller's ---
+    accounts.token_1_account.reload()?;
+    let lxr_residual = accounts
+        .token_1_account
+        .amount
+        .saturating_sub(lxr_before);
+    if lxr_residual > 0 {
+        transfer_from_user_to_pool_vault(
+            accounts.owner.to_account_info(),
+            accounts.token_1_account.to_account_info(),
+            accounts.luxor_reward_vault.to_account_info(),
+            accounts.vault_1_mint.to_account_info(),
+            accounts.token_program.to_account_info(),
+            lxr_residual,
+            accounts.vault_1_mint.decimals,
+        )?;
+    }
+    // LXR the caller already held stays put, so only an account that started
+    // empty (created for this buyback) can be closed.
+    if lxr_before == 0 {
+        close_account(CpiContext::new(
+            accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: accounts.token_1_account.to_account_info(),
+                destination: accounts.owner.to_account_info(),
+                authority: accounts.owner.to_account_info(),
+            },
+        ))?;
+    }
+
+    // --- Event for indexers / analytics ---
+    emit!(BuybackExecuted {
+        sol_amount: sol_to_swap,
+        lxr_bought,
+        fee_to_treasury: fee_treasury,
+        rent_refunded,
+        buyback_count: accounts.stake_info.buyback_count,
+        sol_reserved,
+    });
+
+    Ok(())
+}
+
+/// Takes the treasury fee from `sol_to_swap` WSOL in `token_0_account`, swaps the rest
+/// for the reward token on `venue` and sends the fee to the SOL treasury vault.
+/// Returns `(lxr_bought, fee_treasury)`, where `lxr_bought` is what the swap actually
+/// delivered to `token_1_account`.
+fn swap_rewards<'info>(
+    accounts: &mut Buyback<'info>,
+    venue: SwapVenue,
+    sol_to_swap: u64,
+    min_lxr_out: u64,
+) -> Result<(u64, u64)> {
+    // --- Treasury fee (in SOL/WSOL); skipped entirely when disabled ---
+    let fee_rate = buyback_fee_rate(
+        &accounts.global_config,
+        &accounts.fee_tiers,
+        Some(sol_to_swap),
+    )?;
+    let fee_treasury = u64::try_from(
+        (sol_to_swap as u128)
+            .checked_mul(fee_rate as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(FEE_RATE_DENOMINATOR_VALUE as u128)
+            .ok_or(ErrorCode::MathOverflow)?,
+    )
+    .map_err(|_| ErrorCode::MathOverflow)?;
+
+    // --- Exact-input amount sent to the pool after fee ---
+    let actual_amount_in = sol_to_swap
+        .checked_sub(fee_treasury)
+        .ok_or(ErrorCode::UnderflowError)?;
+    require_gt!(actual_amount_in, 0);
+
+    // --- Read pool state + compute pricing invariants ---
+    let pool_state_info = &accounts.pool_state;
+    let pool_state = PoolState::try_deserialize(&mut &pool_state_info.data.borrow()[..])?;
+    // Price only against the pool's own vaults (SOL in, LXR out).
+    require_keys_eq!(pool_state.token_0_vault, accounts.token_0_vault.key());
+    require_keys_eq!(pool_state.token_1_vault, accounts.token_1_vault.key());
+    let SwapParams {
+        trade_direction: _,
+        total_input_token_amount,
+        total_output_token_amount,
+        token_0_price_x64: _,
+        token_1_price_x64: _,
+        is_creator_fee_on_input,
+    } = pool_state.get_swap_params(
+        accounts.token_0_vault.key(),
+        accounts.token_1_vault.key(),
+        accounts.token_0_vault.amount,
+        accounts.token_1_vault.amount,
+    )?;
+    accounts.global_config.check_pool_reserves(total_input_token_amount, total_output_token_amount)?;
+
+    // let constant_before = u128::from(total_input_token_amount)
+    //     .checked_mul(u128::from(total_output_token_amount))
+    //     .unwrap();
+
+    // Price with the same fee rates the Raydium CPI will apply, unless the
+    // admin fee override is on.
+    let amm_config = pool_state.load_amm_config(
+        &accounts.amm_config,
+        accounts.global_config.fee_override(),
+    )?;
+
+    let creator_fee_rate = pool_state.adjust_creator_fee_rate(amm_config.creator_fee_rate);
+    // A rate above the denominator means a malformed pool state; fee math would underflow.
+    require!(creator_fee_rate <= FEE_RATE_DENOMINATOR_VALUE, ErrorCode::InvalidFeeModel);
+
+    // Price the exact-input trade and validate invariants.
+    let result = CurveCalculator::swap_base_input(
+        u128::from(actual_amount_in),
+        u128::from(total_input_token_amount),
+        u128::from(total_output_token_amount),
+        amm_config.trade_fee_rate,
+        creator_fee_rate,
+        amm_config.protocol_fee_rate,
+        amm_config.fund_fee_rate,
+        is_creator_fee_on_input,
+        // Raydium executes the trade on its own x * y = k pool.
+        CurveType::ConstantProduct,
+    )
+    .ok_or(ErrorCode::ZeroTradingTokens)?;
+
+    // let constant_after = u128::from(result.new_input_vault_amount)
+    //     .checked_mul(u128::from(result.new_output_vault_amount))
+    //     .unwrap();
+
+    require_eq!(
+        u64::try_from(result.input_amount).map_err(|_| ErrorCode::MathOverflow)?,
+        actual_amount_in
+    );
+    // require_gte!(constant_after, constant_before);
+
+    // Output LXR expected from the priced trade (the CPI fill is measured below).
+    let lxr_quoted = u64::try_from(result.output_amount).map_err(|_| ErrorCode::MathOverflow)?;
+    // On-chain pricing reads reserves an attacker may already have moved;
+    // the caller's off-chain quote is the real sandwich protection.
+    require_gte!(lxr_quoted, min_lxr_out, ErrorCode::ExceededSlippage);
+
+    // --- Build Raydium `swap_base_input` CPI payload (Anchor-style discriminator + params) ---
+    // Raydium itself rejects a fill materially below the program-priced output
+    // (defense-in-depth against pool changes by other instructions in the tx).
+    let minimum_amount_out = lxr_quoted
+        .checked_sub(
+            u64::try_from(
+                (lxr_quoted as u128)
+                    .checked_mul(accounts.global_config.buyback_out_tolerance_rate as u128)
+                    .ok_or(ErrorCode::MathOverflow)?
+                    .checked_div(FEE_RATE_DENOMINATOR_VALUE as u128)
+                    .ok_or(ErrorCode::MathOverflow)?,
+            )
+            .map_err(|_| ErrorCode::MathOverflow)?,
+        )
+        .ok_or(ErrorCode::MathOverflow)?
+        .max(min_lxr_out);
+    // Execute the swap on the configured venue (Raydium CPMM account order).
+    let swap_accounts = Box::new(vec![
+        accounts.owner.to_account_info(),
+        accounts.raydium_authority.to_account_info(),
+        accounts.amm_config.to_account_info(),
+        accounts.pool_state.to_account_info(),
+        accounts.token_0_account.to_account_info(),
+        accounts.token_1_account.to_account_info(),
+        accounts.token_0_vault.to_account_info(),
+        accounts.token_1_vault.to_account_info(),
+        accounts.token_program.to_account_info(),
+        accounts.token_program.to_account_info(),
+        accounts.vault_0_mint.to_account_info(),
+        accounts.vault_1_mint.to_account_info(),
+        accounts.observation_state.to_account_info(),
+    ]);
+
+    let lxr_before = accounts.token_1_account.amount;
+    venue.swap_base_input(&swap_accounts, actual_amount_in, minimum_amount_out)?;
+
+    // --- Credit what the swap actually delivered, not the quote ---
+    // Rounding, fee or transfer-fee differences would otherwise over-credit
+    // the reward index or fail the transfer below.
+    accounts.token_1_account.reload()?;
+    let lxr_bought = accounts
+        .token_1_account
+        .amount
+        .checked_sub(lxr_before)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require_gt!(lxr_bought, 0, ErrorCode::ZeroTradingTokens);
+
+    // Send the treasury fee (token_0 / WSOL) to the SOL treasury vault.
+    if fee_treasury > 0 {
+        transfer_from_user_to_pool_vault(
+            accounts.owner.to_account_info(),
+            accounts.token_0_account.to_account_info(),
+            accounts.sol_treasury_vault.to_account_info(),
+            accounts.vault_0_mint.to_account_info(),
+            accounts.token_program.to_account_info(),
+            fee_treasury,
+            accounts.vault_0_mint.decimals,
+        )?;
+    }
+
+    Ok((lxr_bought, fee_treasury))
+}
+
+/// Gross SOL (treasury fee included) a buyback must split to receive `lxr_amount`
+/// LXR at current pool prices. Returns `u64::MAX` when the pool cannot deliver that
+/// much, so the caller falls back to its other caps. The vaults must be the pool's own,
+/// as in the execution phase. With a fee schedule the gross-up uses its highest rate,
+/// since the tier depends on the gross amount; that can only over-size the split.
+#[allow(clippy::too_many_arguments)]
+fn sol_needed_for_lxr(
+    global_config: &GlobalConfig,
+    pool_state_ai: &AccountInfo,
+    amm_config_ai: &AccountInfo,
+    fee_tiers_ai: &AccountInfo,
+    token_0_vault: &InterfaceAccount<TokenAccount>,
+    token_1_vault: &InterfaceAccount<TokenAccount>,
+    lxr_amount: u64,
+) -> Result<u64> {
+    if lxr_amount == 0 {
+        return Ok(0);
+    }
+    let pool_state = PoolState::try_deserialize(&mut &pool_state_ai.data.borrow()[..])?;
+    require_keys_eq!(pool_state.token_0_vault, token_0_vault.key());
+    require_keys_eq!(pool_state.token_1_vault, token_1_vault.key());
+    let SwapParams {
+        trade_direction: _,
+        total_input_token_amount,
+        total_output_token_amount,
+        token_0_price_x64: _,
+        token_1_price_x64: _,
+        is_creator_fee_on_input,
+    } = pool_state.get_swap_params(
+        token_0_vault.key(),
+        token_1_vault.key(),
+        token_0_vault.amount,
+        token_1_vault.amount,
+    )?;
+    let amm_config = pool_state.load_amm_config(amm_config_ai, global_config.fee_override())?;
+    let creator_fee_rate = pool_state.adjust_creator_fee_rate(amm_config.creator_fee_rate);
+    require!(creator_fee_rate <= FEE_RATE_DENOMINATOR_VALUE, ErrorCode::InvalidFeeModel);
+
+    let Some(result) = CurveCalculator::swap_base_output(
+        u128::from(lxr_amount),
+        u128::from(total_input_token_amount),
+        u128::from(total_output_token_amount),
+        amm_config.trade_fee_rate,
+        creator_fee_rate,
+        amm_config.protocol_fee_rate,
+        amm_config.fund_fee_rate,
+        is_creator_fee_on_input,
+        CurveType::ConstantProduct,
+    ) else {
+        return Ok(u64::MAX);
+    };
+
+    // Gross up for the treasury fee taken before the swap (rounded up).
+    let fee_rate = buyback_fee_rate(global_config, fee_tiers_ai, None)?;
+    let net_rate = FEE_RATE_DENOMINATOR_VALUE
+        .checked_sub(fee_rate)
+        .ok_or(ErrorCode::UnderflowError)?;
+    if net_rate == 0 {
+        return Ok(u64::MAX);
+    }
+    let gross = result
+        .input_amount
+        .checked_mul(FEE_RATE_DENOMINATOR_VALUE as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_add(net_rate as u128 - 1)
+        .ok_or(ErrorCode::MathOverflow)?
+        / net_rate as u128;
+    Ok(u64::try_from(gross).unwrap_or(u64::MAX))
+}
+
+/// Treasury fee rate for a buyback withdrawing `sol_amount` lamports: `0` while
+/// `buyback_fee_enabled` is off, otherwise the matching `FeeTiers` tier once the schedule
+/// exists and the flat `fee_treasury_rate` before. `None` asks for the highest rate the
+/// schedule can charge.
+fn buyback_fee_rate(global_config: &GlobalConfig, fee_tiers_ai: &AccountInfo, sol_amount: Option<u64>) -> Result<u64> {
+    if !global_config.buyback_fee_enabled {
+        return Ok(0);
+    }
+    Ok(match (FeeTiers::load(fee_tiers_ai)?, sol_amount) {
+        (Some(fee_tiers), Some(sol_amount)) => fee_tiers.rate_for(sol_amount),
+        (Some(fee_tiers), None) => fee_tiers.max_rate(),
+        (None, _) => global_config.fee_treasury_rate,
+    })
+}
@@ -0,0 +1,65 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+
+//
+// ──────────────────────────────────────────────────────────────────────────────
+// Cancel Blacklist Instruction
+// ──────────────────────────────────────────────────────────────────────────────
+//
+
+/// Cancel a pending blacklist proposal before it is finalized.
+///
+/// Clears `blacklist_pending_timestamp` on the user's `UserStakeInfo`; a new
+/// `propose_blacklist` restarts the full delay.
+#[derive(Accounts)]
+pub struct CancelBlacklist<'info> {
+    /// Admin (authorized) signer.  
+    /// Must be either the current protocol admin stored in `global_config.admin`
+    /// or the hardcoded program admin.
+    #[account(
+        constraint = (owner.key() == global_config.admin || owner.key() == crate::admin::id()) @ ErrorCode::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    /// Global protocol configuration (holds admin).
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// The user whose blacklist proposal is cancelled.  
+    /// Identity key for deriving `user_stake_info`.
+    pub user: SystemAccount<'info>,
+
+    /// Per-user stake info for `user`; its pending timestamp is cleared.
+    #[account(
+        mut,
+        seeds = [
+            USER_STAKE_INFO_SEED.as_bytes(),
+            user.key().as_ref()
+        ],
+        bump,
+    )]
+    pub user_stake_info: Account<'info, UserStakeInfo>,
+}
+
+/// Instruction: Withdraw a pending blacklist proposal.
+///
+/// # Fails
+/// - `BlacklistNotProposed` if no proposal is pending for the user.
+pub fn cancel_blacklist(ctx: Context<CancelBlacklist>) -> Result<()> {
+    let user_stake_info = &mut ctx.accounts.user_stake_info;
+    require!(
+        user_stake_info.blacklist_pending_timestamp != 0,
+        ErrorCode::BlacklistNotProposed
+    );
+    user_stake_info.blacklist_pending_timestamp = 0;
+
+    emit!(BlacklistCancelled {
+        user: ctx.accounts.user.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,153 @@
+use anchor_lang::prelude::*;
+use crate::{states::{GlobalConfig, SolRewardsCollected, StakeInfo, UserStakeInfo, GLOBAL_CONFIG_SEED, USER_STAKE_INFO_SEED}, utils::transfer_from_pool_vault_to_user};
+use anchor_spl::{associated_token::AssociatedToken, token::spl_token, token_interface::{Mint, TokenAccount, TokenInterface}};
+use crate::error::ErrorCode;
+
+/// Claim pending SOL rewards accrued from staking SOL.
+///
+/// Reward math overview:
+/// - Global index: `stake_info.reward_per_token_sol_stored` accumulates
+///   lamports-per-staked-SOL, scaled by `PRECISION`, whenever `purchase` or
+///   `buyback` observes new rewards on the stake PDA.
+/// - Per-user checkpoint: `user_stake_info.sol_reward_per_token_completed`
+///   stores the index at the user's last claim or stake change.
+/// - Pending = `(user.total_staked_sol * (global_idx - user_idx)) / PRECISION`,
+///   plus any `sol_rewards_pending` carried over from earlier checkpoints.
+///
+/// Funds movement:
+/// - WSOL moves from `sol_treasury_vault` → user's WSOL ATA, limited to the SOL
+///   reward reserve that buybacks have already moved into the vault.
+#[derive(Accounts)]
+pub struct ClaimSolRewards<'info> {
+    /// User claiming rewards (payer for ATA creation if needed).
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Global protocol configuration.
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Per-user staking record (derived by USER_STAKE_INFO_SEED + owner).
+    #[account(
+        mut,
+        seeds = [
+            USER_STAKE_INFO_SEED.as_bytes(),
+            owner.key().as_ref()
+        ],
+        bump,
+    )]
+    pub user_stake_info: Account<'info, UserStakeInfo>,
+
+    /// Program authority PDA (acts as token authority for vault transfers).
+    ///
+    /// CHECK: PDA derivation enforced by seeds; used only as a signer.
+    #[account(
+        seeds = [crate::AUTH_SEED.as_bytes()],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// Global staking aggregates, reward indices and the SOL reward reserve.
+    #[account(
+        mut,
+        address = global_config.stake_info,
+        constraint = !stake_info.stake_closed @ ErrorCode::StakeClosed,
+    )]
+    pub stake_info: Account<'info, StakeInfo>,
+
+    /// Protocol SOL treasury vault (WSOL; pays out SOL rewards).
+    #[account(mut, address = global_config.sol_treasury_vault)]
+    pub sol_treasury_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL Native mint (WSOL). Used to create the user's WSOL ATA if needed.
+    #[account(address = spl_token::native_mint::id() @ ErrorCode::InvalidLuxorMint)]
+    pub native_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// User's WSOL ATA; created on demand to receive rewards.
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = native_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+    )]
+    pub owner_wsol_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL Token-2022 interface program.
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Associated Token Program (for ATA init).
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// System Program (for rent/ATA).
+    pub system_program: Program<'info, System>,
+}
+
+/// Pays the caller's pending SOL rewards as WSOL.
+///
+/// Steps:
+/// 1) Compute rewards since the last checkpoint and add `sol_rewards_pending`.
+/// 2) Advance the checkpoint (also when the payout is `0`, so the checkpoint never
+///    lags the global index).
+/// 3) Pay up to the funded reserve (`stake_info.sol_reserve_in_treasury()`) from
+///    `sol_treasury_vault` to the user's WSOL ATA; the rest stays in
+///    `sol_rewards_pending` until the next buyback funds the reserve.
+/// 4) Emit `SolRewardsCollected`.
+///
+/// # Fails
+/// - `BlacklistAlreadyProposed` if a blacklist is pending for the user.
+/// - `InsufficientSolTreasury` if the SOL treasury vault holds less than the payout.
+pub fn claim_sol_rewards(ctx: Context<ClaimSolRewards>) -> Result<()> {
+    let user_stake_info = &mut ctx.accounts.user_stake_info;
+    let stake_info = &mut ctx.accounts.stake_info;
+
+    require!(
+        user_stake_info.blacklist_pending_timestamp == 0,
+        ErrorCode::BlacklistAlreadyProposed
+    );
+
+    // --- 1) Base rewards = stake * delta_index, scaled down by PRECISION, plus carryover ---
+    let sol_rewards_to_claim = user_stake_info
+        .pending_sol_rewards(stake_info)?
+        .checked_add(user_stake_info.sol_rewards_pending)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    // --- 2) Advance the checkpoint regardless of the payout ---
+    user_stake_info.sol_reward_per_token_completed = stake_info.reward_per_token_sol_stored;
+
+    // --- 3) Pay what the reserve covers from SOL treasury vault → user; defer the rest ---
+    let sol_collected = sol_rewards_to_claim.min(stake_info.sol_reserve_in_treasury()?);
+    let sol_deferred = sol_rewards_to_claim - sol_collected;
+    user_stake_info.sol_rewards_pending = sol_deferred;
+    stake_info.total_sol_rewards_claimed = stake_info
+        .total_sol_rewards_claimed
+        .checked_add(sol_collected)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require_gte!(
+        ctx.accounts.sol_treasury_vault.amount,
+        sol_collected,
+        ErrorCode::InsufficientSolTreasury
+    );
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.sol_treasury_vault.to_account_info(),
+        ctx.accounts.owner_wsol_token.to_account_info(),
+        ctx.accounts.native_mint.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        sol_collected,
+        ctx.accounts.native_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]]],
+    )?;
+
+    // --- 4) Event for indexers/UX ---
+    emit!(SolRewardsCollected {
+        collector: ctx.accounts.owner.key(),
+        sol_collected,
+        sol_deferred,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+/// Accounts context for `close_user_stake_info`.
+///
+/// Lets a user who has fully unstaked and claimed everything reclaim the rent
+/// held by their `UserStakeInfo` PDA. A later purchase recreates the record
+/// from scratch (and treats the user as a new staker).
+#[derive(Accounts)]
+pub struct CloseUserStakeInfo<'info> {
+    /// Record owner; receives the reclaimed rent.
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Global protocol configuration.
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Per-user staking record being closed (derived by USER_STAKE_INFO_SEED + owner).
+    #[account(
+        mut,
+        close = owner,
+        seeds = [
+            USER_STAKE_INFO_SEED.as_bytes(),
+            owner.key().as_ref()
+        ],
+        bump,
+    )]
+    pub user_stake_info: Account<'info, UserStakeInfo>,
+
+    /// Global staking aggregates and reward indices.
+    #[account(address = global_config.stake_info)]
+    pub stake_info: Account<'info, StakeInfo>,
+}
+
+/// Closes the caller's `UserStakeInfo` and emits `UserStakeInfoClosed`.
+///
+/// With nothing staked no rewards accrue, so "nothing unclaimed" means both
+/// carried-over balances (`lxr_rewards_pending`, `sol_rewards_pending`) are
+/// zero and nothing is earned since the checkpoints.
+///
+/// # Fails
+/// - `OutstandingRewards` if SOL is still staked or any LXR/SOL rewards are unclaimed.
+/// - `BlacklistAlreadyProposed` while a blacklist proposal is pending for the user.
+/// - `UserBlacklisted` while a finalized blacklist holds SOL that `un_blacklist`
+///   would restore into this record.
+pub fn close_user_stake_info(ctx: Context<CloseUserStakeInfo>) -> Result<()> {
+    let user_stake_info = &mut ctx.accounts.user_stake_info;
+    let stake_info = &ctx.accounts.stake_info;
+
+    require!(
+        user_stake_info.blacklist_pending_timestamp == 0,
+        ErrorCode::BlacklistAlreadyProposed
+    );
+    require!(
+        !user_stake_info.is_blacklisted() && user_stake_info.blacklisted_sol == 0,
+        ErrorCode::UserBlacklisted
+    );
+    require!(
+        user_stake_info.total_staked_sol == 0
+            && user_stake_info.lxr_rewards_pending == 0
+            && user_stake_info.sol_rewards_pending == 0
+            && user_stake_info.pending_lxr_rewards(stake_info)? == 0
+            && user_stake_info.pending_sol_rewards(stake_info)? == 0,
+        ErrorCode::OutstandingRewards
+    );
+
+    emit!(UserStakeInfoClosed {
+        user: ctx.accounts.owner.key(),
+        total_lxr_claimed: user_stake_info.total_lxr_claimed,
+        total_lxr_forfeited: user_stake_info.total_lxr_forfeited,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::transfer_from_user_to_pool_vault;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+/// Admin-only top-up of the LXR treasury vault after `initialise_configs`.
+///
+/// 1) Transfers `amount` LXR from the admin's ATA into `luxor_vault`.
+/// 2) Optionally adds `amount` to `initial_lxr_allocation_vault`, so purchase
+///    pricing treats the deposit as new inventory rather than a surplus.
+/// 3) Emits `TreasuryFunded` for off-chain consumers.
+#[derive(Accounts)]
+pub struct DepositToTreasury<'info> {
+    /// Admin (authorized) signer and owner of the source ATA. Must be either the
+    /// current protocol admin stored in `global_config.admin` or the hardcoded
+    /// program admin.
+    #[account(
+        constraint = (owner.key() == global_config.admin || owner.key() == crate::admin::id()) @ ErrorCode::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    /// Global configuration; `initial_lxr_allocation_vault` may be bumped.
+    #[account(
+        mut,
+        seeds = [GLOBAL_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Program treasury vault receiving the LXR.
+    #[account(
+        mut,
+        address = global_config.lxr_treasury_vault,
+    )]
+    pub luxor_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Canonical LUXOR mint (decimals must match the value recorded at init).
+    #[account(
+        address = crate::luxor_mint::id() @ ErrorCode::InvalidLuxorMint,
+        constraint = crate::utils::luxor_decimals_match(luxor_mint.decimals) @ ErrorCode::MintDecimalsMismatch,
+    )]
+    pub luxor_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Admin's LXR ATA the deposit is taken from.
+    #[account(
+        mut,
+        associated_token::mint = luxor_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+    )]
+    pub owner_lxr_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL Token-2022 interface program.
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Deposits `amount` LXR into the treasury vault, bumping
+/// `initial_lxr_allocation_vault` by the same amount when `bump_allocation` is set.
+///
+/// # Fails
+/// - `InvalidOwner` if the signer is not an admin.
+/// - Token program error if the admin's ATA holds less than `amount`.
+pub fn deposit_to_treasury(
+    ctx: Context<DepositToTreasury>,
+    amount: u64,
+    bump_allocation: bool,
+) -> Result<()> {
+    require_gt!(amount, 0);
+
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.owner.to_account_info(),
+        ctx.accounts.owner_lxr_token.to_account_info(),
+        ctx.accounts.luxor_vault.to_account_info(),
+        ctx.accounts.luxor_mint.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        amount,
+        ctx.accounts.luxor_mint.decimals,
+    )?;
+
+    let global_config = &mut ctx.accounts.global_config;
+    if bump_allocation {
+        global_config.initial_lxr_allocation_vault = global_config
+            .initial_lxr_allocation_vault
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+
+    emit!(TreasuryFunded {
+        amount,
+        new_allocation: global_config.initial_lxr_allocation_vault,
+    });
+
+    Ok(())
+}
@@ -1,20 +1,60 @@
 use anchor_lang::{prelude::*, solana_program::{program::invoke_signed, stake, sysvar}};
-use crate::{error::ErrorCode, states::{GlobalConfig, StakeInfo, UserStakeInfo, ADMIN_STAKE_INFO_SEED, GLOBAL_CONFIG_SEED}, utils::transfer_from_pool_vault_to_user, PRECISION};
+use crate::{error::ErrorCode, states::{EmergencyAction, EmergencyExecuted, GlobalConfig, PendingEmergency, StakeInfo, UserStakeInfo, ADMIN_STAKE_INFO_SEED, GLOBAL_CONFIG_SEED, PENDING_EMERGENCY_SEED}, utils::transfer_from_pool_vault_to_user};
 use anchor_spl::{associated_token::AssociatedToken, token::spl_token, token_interface::{Mint, TokenAccount, TokenInterface}};
 use anchor_lang::solana_program::stake::instruction as stake_ix;
 
+/// Operation selected by `emergency_withdraw`'s `param` (its `u8` value).
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EmergencyOperation {
+    /// `0`: withdraw all LXR from `luxor_vault_any` to the admin's LXR ATA.
+    DrainLxr = 0,
+    /// `1`: withdraw all WSOL from the SOL treasury vault to the admin's WSOL ATA.
+    DrainWsol = 1,
+    /// `2`: move the admin's pending LXR rewards from the reward vault to the treasury vault.
+    ClaimAdminRewards = 2,
+    /// `3`: deactivate the protocol stake PDA.
+    DeactivateStake = 3,
+    /// `4`: withdraw `value` lamports from the deactivated stake PDA to the admin.
+    WithdrawStake = 4,
+    /// `5`: hand the stake PDA's authorities to the queued target. Executed through
+    /// `rotate_stake_authority`, never `emergency_withdraw`.
+    RotateStakeAuthority = 5,
+}
+
+impl TryFrom<u8> for EmergencyOperation {
+    type Error = anchor_lang::error::Error;
+
+    /// `InvalidParam` for anything outside `0..=5`.
+    fn try_from(param: u8) -> Result<Self> {
+        match param {
+            0 => Ok(EmergencyOperation::DrainLxr),
+            1 => Ok(EmergencyOperation::DrainWsol),
+            2 => Ok(EmergencyOperation::ClaimAdminRewards),
+            3 => Ok(EmergencyOperation::DeactivateStake),
+            4 => Ok(EmergencyOperation::WithdrawStake),
+            5 => Ok(EmergencyOperation::RotateStakeAuthority),
+            _ => err!(ErrorCode::InvalidParam),
+        }
+    }
+}
+
 /// Emergency controls for protocol administrators.
 ///
-/// This instruction supports **four** emergency operations, selected by `param`:
-/// - `0` → Withdraw **all LXR** from a specified vault (treasury or reward) to admin’s ATA.
-/// - `1` → Withdraw **all WSOL** from the SOL treasury vault to admin’s WSOL ATA.
-/// - `2` → **Deactivate stake** for the protocol stake PDA (begins cooldown).
-/// - `3` → **Withdraw staked SOL** (post-cooldown) from the stake PDA to the admin’s system account.
+/// This instruction supports **five** emergency operations, selected by `param`
+/// (see `EmergencyOperation`; `5` belongs to `rotate_stake_authority`):
+/// - `0` (`DrainLxr`) → Withdraw **all LXR** from a specified vault (treasury or reward) to admin’s ATA.
+/// - `1` (`DrainWsol`) → Withdraw **all WSOL** from the SOL treasury vault to admin’s WSOL ATA.
+/// - `2` (`ClaimAdminRewards`) → **Claim admin LXR rewards** from the reward vault into the treasury vault.
+/// - `3` (`DeactivateStake`) → **Deactivate stake** for the protocol stake PDA (begins cooldown).
+/// - `4` (`WithdrawStake`) → **Withdraw staked SOL** (post-cooldown) from the stake PDA to the admin’s system account.
 ///
 /// Security model:
 /// - Only the protocol `admin` or hardcoded program admin may call this (checked on `owner`).
 /// - All token movements require the program `authority` PDA to sign via seeds.
 /// - Stake CPIs (deactivate/withdraw) also use the `authority` PDA as the stake authority.
+/// - While `global_config.emergency_delay > 0`, the call must match a matured
+///   `queue_emergency` entry.
 #[derive(Accounts)]
 pub struct EmergencyWithdraw<'info> {
     /// Admin (must match `global_config.admin` or program admin).
@@ -42,7 +82,9 @@ pub struct EmergencyWithdraw<'info> {
 
     /// LXR vault to drain (either treasury or reward vault).
     ///
-    /// When `param == 0`, this account is the **source** of LXR withdrawn to the admin.
+    /// For `DrainLxr`, this account is the **source** of LXR withdrawn to the admin.
+    /// For `ClaimAdminRewards`, it is the **destination** of the admin reward claim and must be
+    /// the treasury vault (it may not alias `luxor_reward_vault`).
     /// Guarded to ensure it matches **either** `global_config.lxr_treasury_vault` **or**
     /// `global_config.lxr_reward_vault`.
     #[account(
@@ -54,7 +96,7 @@ pub struct EmergencyWithdraw<'info> {
     #[account(mut,address = global_config.lxr_reward_vault)]
     pub luxor_reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    /// SOL treasury vault (WSOL). Used when `param == 1`.
+    /// SOL treasury vault (WSOL). Used by `DrainWsol`.
     #[account(mut,address = global_config.sol_treasury_vault)]
     pub sol_treasury_vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
@@ -70,15 +112,18 @@ pub struct EmergencyWithdraw<'info> {
     #[account(address = global_config.stake_info)]
     pub stake_info: Account<'info, StakeInfo>,
 
-    /// Canonical LXR mint.
-    #[account(address = crate::luxor_mint::id() @ ErrorCode::InvalidLuxorMint)]
+    /// Canonical LXR mint (decimals must match the value recorded at init).
+    #[account(
+        address = crate::luxor_mint::id() @ ErrorCode::InvalidLuxorMint,
+        constraint = crate::utils::luxor_decimals_match(luxor_mint.decimals) @ ErrorCode::MintDecimalsMismatch,
+    )]
     pub luxor_mint: Box<InterfaceAccount<'info, Mint>>,
 
     /// SPL Native mint (WSOL). Used to create admin WSOL ATA if needed.
     #[account(address = spl_token::native_mint::id() @ ErrorCode::InvalidLuxorMint)]
     pub native_mint: Box<InterfaceAccount<'info, Mint>>,
 
-    /// Admin’s LXR ATA (receiver for param `0`). Created on demand.
+    /// Admin’s LXR ATA (receiver for `DrainLxr`). Created on demand.
     #[account(
         init_if_needed,
         payer = owner,
@@ -88,7 +133,7 @@ pub struct EmergencyWithdraw<'info> {
     )]
     pub owner_lxr_token: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    /// Admin’s WSOL ATA (receiver for param `1`). Created on demand.
+    /// Admin’s WSOL ATA (receiver for `DrainWsol`). Created on demand.
     #[account(
         init_if_needed,
         payer = owner,
@@ -104,6 +149,16 @@ pub struct EmergencyWithdraw<'info> {
     #[account(mut,address = global_config.stake_account)]
     pub stake_pda: UncheckedAccount<'info>,
 
+    /// Queued emergency action; only read while `global_config.emergency_delay > 0`.
+    ///
+    /// CHECK: PDA derivation enforced by seeds; may not exist when no timelock is set.
+    #[account(
+        mut,
+        seeds = [PENDING_EMERGENCY_SEED.as_bytes()],
+        bump,
+    )]
+    pub pending_emergency: UncheckedAccount<'info>,
+
     /// Token program interface (Token-2022).
     pub token_program: Interface<'info, TokenInterface>,
 
@@ -129,22 +184,57 @@ pub struct EmergencyWithdraw<'info> {
 /// Perform one of the emergency operations selected by `param`.
 ///
 /// # Parameters
-/// - `param`:
-///     - `0` → Withdraw **all LXR** from `luxor_vault_any` → `owner_lxr_token`.
-///     - `1` → Withdraw **all WSOL** from `sol_treasury_vault` → `owner_wsol_token`.
-///     - `2` → Deactivate stake for `stake_pda` (requires later epoch to withdraw).
-///     - `3` → Withdraw `value` lamports from `stake_pda` → `owner` (post-deactivation).
-/// - `value`: Used only when `param == 3` (amount to withdraw).
+/// - `param`: an `EmergencyOperation` value (`InvalidParam` otherwise):
+///   - `DrainLxr` (`0`) → Withdraw **all LXR** from `luxor_vault_any` → `owner_lxr_token`.
+///   - `DrainWsol` (`1`) → Withdraw **all WSOL** from `sol_treasury_vault` → `owner_wsol_token`.
+///   - `ClaimAdminRewards` (`2`) → Move the admin's pending LXR rewards from
+///     `luxor_reward_vault` → `luxor_vault_any` (treasury); fails with `InvalidVault`
+///     if both accounts are the reward vault.
+///   - `DeactivateStake` (`3`) → Deactivate stake for `stake_pda` (requires later epoch to withdraw).
+///   - `WithdrawStake` (`4`) → Withdraw `value` lamports from `stake_pda` → `owner` (post-deactivation).
+///   - `RotateStakeAuthority` (`5`) is rejected with `InvalidParam`; it only runs
+///     through `rotate_stake_authority`.
+///
+///   `DrainLxr` and `ClaimAdminRewards` move LXR only: they fail with `InvalidRewardMint`
+///   on a reward vault holding another mint (`global_config.reward_mint`).
+/// - `value`: Used only by `WithdrawStake` (amount to withdraw).
+///
+/// Every operation emits `EmergencyAction` with the amount actually moved and the
+/// account it came from.
+///
+/// # Timelock
+/// With `global_config.emergency_delay > 0`, `(param, value)` must have been queued
+/// through `queue_emergency` at least that long ago. The entry is consumed and
+/// `EmergencyExecuted` is emitted; otherwise the call fails with `EmergencyNotQueued`
+/// or `EmergencyTimelockActive`.
 ///
 /// # Notes
 /// - Token withdrawals use `transfer_from_pool_vault_to_user` with `authority` PDA signer seeds.
 /// - Stake actions use Stake Program CPIs with `authority` as stake authority signer.
-/// - For `param == 3`, make sure the stake is fully or partially deactivated
+/// - For `WithdrawStake`, make sure the stake is fully or partially deactivated
 ///   and the requested `value` is available to withdraw.
 pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>, param: u8 , value: u64) -> Result<()> {
-    match param {
-        0 => {
+    let operation = EmergencyOperation::try_from(param)?;
+    let executed_after = if ctx.accounts.global_config.emergency_delay > 0 {
+        Some(PendingEmergency::consume(
+            &ctx.accounts.pending_emergency,
+            param,
+            value,
+            Pubkey::default(),
+            Clock::get()?.unix_timestamp as u64,
+        )?)
+    } else {
+        None
+    };
+
+    let (amount, source) = match operation {
+        EmergencyOperation::DrainLxr => {
             // (0) Withdraw all LXR from selected vault (treasury or reward) to admin ATA.
+            require_keys_eq!(
+                ctx.accounts.luxor_vault_any.mint,
+                ctx.accounts.luxor_mint.key(),
+                ErrorCode::InvalidRewardMint
+            );
             transfer_from_pool_vault_to_user(
                 ctx.accounts.authority.to_account_info(),
                 ctx.accounts.luxor_vault_any.to_account_info(),
@@ -155,9 +245,9 @@ pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>, param: u8 , value: u6
                 ctx.accounts.luxor_mint.decimals,
                 &[&[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]]],
             )?;
-
+            (ctx.accounts.luxor_vault_any.amount, ctx.accounts.luxor_vault_any.key())
         }
-        1 => {
+        EmergencyOperation::DrainWsol => {
             // (1) Withdraw all WSOL from SOL treasury vault to admin WSOL ATA.
             transfer_from_pool_vault_to_user(
                 ctx.accounts.authority.to_account_info(),
@@ -169,21 +259,23 @@ pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>, param: u8 , value: u6
                 ctx.accounts.native_mint.decimals,
                 &[&[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]]],
             )?;
+            (ctx.accounts.sol_treasury_vault.amount, ctx.accounts.sol_treasury_vault.key())
         }
-        2 => {
+        EmergencyOperation::ClaimAdminRewards => {
+            // (2) Claim admin rewards from the reward vault into the treasury vault.
+            // Source and destination must differ, otherwise the transfer is a no-op
+            // while the admin checkpoint below still advances.
+            require_keys_neq!(
+                ctx.accounts.luxor_vault_any.key(),
+                ctx.accounts.luxor_reward_vault.key(),
+                ErrorCode::InvalidVault
+            );
+            require!(ctx.accounts.global_config.rewards_in_lxr(), ErrorCode::InvalidRewardMint);
+
             let admin_stake_info = &mut ctx.accounts.admin_stake_info;
             let stake_info = &ctx.accounts.stake_info;
              
-            let reward_per_token_lxr_pending_admin = stake_info.reward_per_token_lxr_stored
-            .checked_sub(admin_stake_info.lxr_reward_per_token_completed).unwrap();
-    
-            let lxr_rewards_to_claim_admin = (admin_stake_info.total_staked_sol as u128)
-            .checked_mul(reward_per_token_lxr_pending_admin).unwrap()
-            .checked_div(PRECISION).unwrap() as u64;
-    
-            admin_stake_info.lxr_rewards_pending = admin_stake_info.lxr_rewards_pending
-            .checked_add(lxr_rewards_to_claim_admin).unwrap();
-            admin_stake_info.lxr_reward_per_token_completed = stake_info.reward_per_token_lxr_stored;
+            admin_stake_info.checkpoint_lxr_rewards(stake_info)?;
 
             transfer_from_pool_vault_to_user(
                 ctx.accounts.authority.to_account_info(),
@@ -195,10 +287,12 @@ pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>, param: u8 , value: u6
                 ctx.accounts.luxor_mint.decimals,
                 &[&[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]]],
             )?;
+            let claimed = admin_stake_info.lxr_rewards_pending;
             admin_stake_info.lxr_rewards_pending = 0;
+            (claimed, ctx.accounts.luxor_reward_vault.key())
         }
-        3 => {
-            // (2) Deactivate the protocol stake PDA (begin cooldown).
+        EmergencyOperation::DeactivateStake => {
+            // (3) Deactivate the protocol stake PDA (begin cooldown).
             let auth_bump = ctx.bumps.authority;
             let seeds: &[&[u8]] = &[crate::AUTH_SEED.as_bytes(), &[auth_bump]];
             let ix = stake_ix::deactivate_stake(&ctx.accounts.stake_pda.key(), &ctx.accounts.authority.key());
@@ -206,9 +300,11 @@ pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>, param: u8 , value: u6
             let staker_ai = ctx.accounts.authority.to_account_info();
             let clock_ai = ctx.accounts.clock.to_account_info();
             invoke_signed(&ix, &[stake_account_ai, staker_ai, clock_ai], &[seeds])?;
+            // Nothing moves yet; report the balance entering cooldown.
+            (ctx.accounts.stake_pda.lamports(), ctx.accounts.stake_pda.key())
         }
-        4 => {
-            // (3) Withdraw lamports from stake PDA to admin system account (post-deactivation).
+        EmergencyOperation::WithdrawStake => {
+            // (4) Withdraw lamports from stake PDA to admin system account (post-deactivation).
             let ix = stake_ix::withdraw(
                     &ctx.accounts.stake_pda.key(),
                     &ctx.accounts.authority.key(),
@@ -224,8 +320,25 @@ pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>, param: u8 , value: u6
             let clock_ai = ctx.accounts.clock.to_account_info();
             let stake_history_ai = ctx.accounts.stake_history.to_account_info(); 
             invoke_signed(&ix, &[stake_account_ai, withdrawer_ai, destination_ai, clock_ai, stake_history_ai], &[seeds])?;
+            (value, ctx.accounts.stake_pda.key())
         }
-        _ => return Err(ErrorCode::InvalidParam.into()),
+        EmergencyOperation::RotateStakeAuthority => return err!(ErrorCode::InvalidParam),
+    };
+
+    emit!(EmergencyAction {
+        param,
+        value,
+        amount,
+        source,
+        executor: ctx.accounts.owner.key(),
+    });
+    if let Some(execute_after) = executed_after {
+        emit!(EmergencyExecuted {
+            param,
+            value,
+            execute_after,
+        });
     }
+
     Ok(())
 }
\ No newline at end of file
@@ -1,29 +1,36 @@
-use crate::{states::*, PRECISION};
+use crate::states::*;
 use anchor_lang::{prelude::*};
 use crate::error::ErrorCode;
 
 //
 // ──────────────────────────────────────────────────────────────────────────────
-// Blacklist Instruction
+// Finalize Blacklist Instruction
 // ──────────────────────────────────────────────────────────────────────────────
 //
 
-/// Blacklist a user from the protocol.
+/// Finalize a previously proposed blacklist of a user.
 ///
-/// This instruction forcibly removes a user’s staked SOL from active participation,
-/// forfeits their pending rewards, and transfers their stake accounting into
-/// the admin’s `UserStakeInfo`.  
+/// Second step of the two-step blacklist: callable only once `propose_blacklist`
+/// has been recorded for the user and `global_config.blacklist_delay_secs` have
+/// elapsed since. This instruction forcibly removes a user’s staked SOL from active participation
+/// and transfers their stake accounting and pending rewards into the admin’s
+/// `UserStakeInfo`.  
 ///
 /// Effects:
-/// - User’s rewards are calculated up to the current reward index and then
-///   marked as forfeited.
+/// - SOL rewards that landed on the stake PDA since the last observation are
+///   realized into the SOL index first, so the user's final SOL entitlement is
+///   complete. LXR rewards only move on buyback, so the LXR index is already
+///   current and needs no accrual here.
+/// - User’s rewards are calculated up to the current reward index.
 /// - User’s total staked SOL is reset to `0`, but the same amount is added
 ///   to the admin’s stake record.
 /// - User’s pending rewards are transferred to the admin’s pending rewards.
+///   This is their only destination: they are not also counted in the user's
+///   `total_lxr_forfeited`, so the admin gain equals the user's entitlement.
 /// - User’s base LXR holdings are reset to `0`.
 /// - An event `UserBlacklisted` is emitted.
 #[derive(Accounts)]
-pub struct Blacklist<'info> {
+pub struct FinalizeBlacklist<'info> {
     /// Admin (authorized) signer.  
     /// Must be either the current protocol admin stored in `global_config.admin`
     /// or the hardcoded program admin.
@@ -69,77 +76,87 @@ pub struct Blacklist<'info> {
 
     /// Global stake info account.  
     /// Used to compute reward-per-token deltas for both user and admin.
-    #[account(address = global_config.stake_info)]
+    #[account(mut, address = global_config.stake_info)]
     pub stake_info: Account<'info, StakeInfo>,
 
+    /// Protocol stake account, read to realize newly accrued SOL rewards.
+    ///
+    /// CHECK: Address enforced via `global_config.stake_account`; only lamports are read.
+    #[account(address = global_config.stake_account)]
+    pub stake_pda: UncheckedAccount<'info>,
+
     /// System Program (required by Anchor).  
     /// Not directly used in this instruction.
     pub system_program: Program<'info, System>,
 }
 
-/// Instruction: Blacklist a user and reassign their stake to the admin.
+/// Instruction: Finalize a user's blacklist and reassign their stake to the admin.
+///
+/// # Fails
+/// - `BlacklistNotProposed` if no blacklist is pending for the user.
+/// - `BlacklistDelayNotElapsed` if the dispute window has not passed yet.
 ///
 /// # Steps
-/// 1. Compute user’s pending rewards since their last checkpoint:
-///    - Add to their `lxr_rewards_pending`.
-///    - Then mark all pending rewards as forfeited (`total_lxr_forfeited`).
+/// 1. Realize newly accrued SOL rewards on the stake PDA, then compute the user’s
+///    pending rewards since their last checkpoint and add them to their
+///    `lxr_rewards_pending` / `sol_rewards_pending`.
 /// 2. Mark user’s total staked SOL as blacklisted (`blacklisted_sol`) and reset `total_staked_sol = 0`.
 /// 3. Compute admin’s pending rewards since their last checkpoint and update.
-/// 4. Add user’s stake and pending rewards into the admin’s record.
+/// 4. Add user’s stake and pending LXR/SOL rewards into the admin’s record.
 /// 5. Reset user’s pending rewards and base LXR holdings to `0`.
-/// 6. Emit a `UserBlacklisted` event.
-pub fn blacklist(ctx: Context<Blacklist>) -> Result<()> {
+/// 6. Clear the pending blacklist and emit a `UserBlacklisted` event.
+pub fn finalize_blacklist(ctx: Context<FinalizeBlacklist>) -> Result<()> {
     let user_stake_info = &mut ctx.accounts.user_stake_info;
     let admin_stake_info = &mut ctx.accounts.admin_stake_info;
-    let stake_info = &ctx.accounts.stake_info;
+    let stake_info = &mut ctx.accounts.stake_info;
+
+    // --- 0. Enforce the dispute window opened by `propose_blacklist` ---
+    require!(
+        user_stake_info.blacklist_pending_timestamp != 0,
+        ErrorCode::BlacklistNotProposed
+    );
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let finalize_after = user_stake_info
+        .blacklist_pending_timestamp
+        .checked_add(ctx.accounts.global_config.blacklist_delay_secs)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require_gte!(block_timestamp, finalize_after, ErrorCode::BlacklistDelayNotElapsed);
+    user_stake_info.blacklist_pending_timestamp = 0;
 
     if admin_stake_info.owner == Pubkey::default() {
        admin_stake_info.owner = ctx.accounts.owner.key();
        admin_stake_info.bump = ctx.bumps.admin_stake_info;
-       admin_stake_info.lxr_reward_per_token_completed = stake_info.reward_per_token_lxr_stored;
+       admin_stake_info.reset_reward_checkpoint(stake_info);
     }
 
-    // --- 1. Compute user's pending rewards and mark as forfeited ---
-    let reward_per_token_lxr_pending_user = stake_info.reward_per_token_lxr_stored
-        .checked_sub(user_stake_info.lxr_reward_per_token_completed)
-        .unwrap();
-    
-    let lxr_rewards_to_claim_user = (user_stake_info.total_staked_sol as u128)
-        .checked_mul(reward_per_token_lxr_pending_user).unwrap()
-        .checked_div(PRECISION).unwrap() as u64;
-
-    user_stake_info.lxr_rewards_pending = user_stake_info.lxr_rewards_pending
-        .checked_add(lxr_rewards_to_claim_user).unwrap();
-    user_stake_info.total_lxr_forfeited = user_stake_info.total_lxr_forfeited
-        .checked_add(user_stake_info.lxr_rewards_pending).unwrap();
+    // --- 1. Realize new SOL rewards, then compute user's pending rewards and mark as forfeited ---
+    stake_info.accrue_sol_rewards(ctx.accounts.stake_pda.lamports(), ctx.accounts.global_config.sol_reward_share_bps)?;
+    user_stake_info.checkpoint_lxr_rewards(stake_info)?;
+    user_stake_info.checkpoint_sol_rewards(stake_info)?;
 
     // Mark SOL as blacklisted
     let sol_blacklisted = user_stake_info.total_staked_sol;
     user_stake_info.blacklisted_sol = user_stake_info.blacklisted_sol
-        .checked_add(user_stake_info.total_staked_sol).unwrap();
-    user_stake_info.lxr_reward_per_token_completed = stake_info.reward_per_token_lxr_stored;
+        .checked_add(user_stake_info.total_staked_sol).ok_or(ErrorCode::MathOverflow)?;
 
     // --- 2. Compute admin's pending rewards and add user’s stake ---
-    let reward_per_token_lxr_pending_admin = stake_info.reward_per_token_lxr_stored
-        .checked_sub(admin_stake_info.lxr_reward_per_token_completed)
-        .unwrap();
-    let lxr_rewards_to_claim_admin = (admin_stake_info.total_staked_sol as u128)
-        .checked_mul(reward_per_token_lxr_pending_admin).unwrap()
-        .checked_div(PRECISION).unwrap() as u64;
-    
-    admin_stake_info.lxr_rewards_pending = admin_stake_info.lxr_rewards_pending
-        .checked_add(lxr_rewards_to_claim_admin).unwrap();
-    admin_stake_info.lxr_reward_per_token_completed = stake_info.reward_per_token_lxr_stored;
+    admin_stake_info.checkpoint_lxr_rewards(stake_info)?;
+    admin_stake_info.checkpoint_sol_rewards(stake_info)?;
 
     // Transfer SOL stake ownership from user → admin
     admin_stake_info.total_staked_sol = admin_stake_info.total_staked_sol
-        .checked_add(user_stake_info.total_staked_sol).unwrap();
+        .checked_add(user_stake_info.total_staked_sol).ok_or(ErrorCode::MathOverflow)?;
     user_stake_info.total_staked_sol = 0;
 
-    // Transfer pending rewards from user → admin
+    // Transfer pending rewards from user → admin (their single destination;
+    // they are not additionally recorded as forfeited by the user)
+    let lxr_reassigned = user_stake_info.lxr_rewards_pending;
     admin_stake_info.lxr_rewards_pending = admin_stake_info.lxr_rewards_pending
-        .checked_add(user_stake_info.lxr_rewards_pending).unwrap();
+        .checked_add(user_stake_info.lxr_rewards_pending).ok_or(ErrorCode::MathOverflow)?;
     user_stake_info.lxr_rewards_pending = 0;
+    admin_stake_info.sol_rewards_pending = admin_stake_info.sol_rewards_pending
+        .checked_add(user_stake_info.sol_rewards_pending).ok_or(ErrorCode::MathOverflow)?;
+    user_stake_info.sol_rewards_pending = 0;
 
     // Reset base holdings for blacklisted user
     user_stake_info.base_lxr_holdings = 0;
@@ -147,7 +164,8 @@ pub fn blacklist(ctx: Context<Blacklist>) -> Result<()> {
     // --- 3. Emit blacklist event ---
     emit!(UserBlacklisted {
         user: ctx.accounts.user.key(),
-        sol_blacklisted: sol_blacklisted,
+        sol_blacklisted,
+        lxr_reassigned,
     });
 
     Ok(())
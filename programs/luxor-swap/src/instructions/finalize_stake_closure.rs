@@ -0,0 +1,68 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+/// Accounts context for `finalize_stake_closure`.
+///
+/// Last step of a wind-down (or migration): after the protocol stake PDA has been
+/// deactivated and fully withdrawn through `emergency_withdraw` (params `3` and `4`),
+/// the admin records the closure in `StakeInfo`. From then on every instruction
+/// that stakes into, splits from or pays out of the stake PDA refuses to run.
+#[derive(Accounts)]
+pub struct FinalizeStakeClosure<'info> {
+    /// Admin (must match `global_config.admin` or program admin).
+    #[account(
+        constraint = (owner.key() == global_config.admin || owner.key() == crate::admin::id()) @ ErrorCode::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    /// Global protocol configuration.
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Global staking state; receives the closure flag.
+    #[account(
+        mut,
+        address = global_config.stake_info,
+        constraint = !stake_info.stake_closed @ ErrorCode::StakeClosed,
+    )]
+    pub stake_info: Account<'info, StakeInfo>,
+
+    /// Protocol stake account (PDA), expected to be drained.
+    ///
+    /// CHECK: Address enforced via `global_config.stake_account`; only its lamports are read.
+    #[account(address = global_config.stake_account)]
+    pub stake_pda: UncheckedAccount<'info>,
+}
+
+/// Records that the protocol stake PDA has been closed and emits `StakeClosureFinalized`.
+///
+/// The stake program only lets a stake account be emptied once it is fully
+/// inactive, and the runtime deletes an account left with zero lamports, so a
+/// zero balance proves both conditions.
+///
+/// # Fails
+/// - `StakeClosed` if the closure was already recorded.
+/// - `BuybackAlreadyRequested` while a buyback split is outstanding.
+/// - `StakeNotDrained` if the stake PDA still holds lamports.
+pub fn finalize_stake_closure(ctx: Context<FinalizeStakeClosure>) -> Result<()> {
+    let stake_info = &mut ctx.accounts.stake_info;
+
+    require!(!stake_info.buyback_requested, ErrorCode::BuybackAlreadyRequested);
+    require_eq!(ctx.accounts.stake_pda.lamports(), 0, ErrorCode::StakeNotDrained);
+
+    let timestamp = Clock::get()?.unix_timestamp as u64;
+    stake_info.stake_closed = true;
+    stake_info.last_tracked_sol_balance = 0;
+    stake_info.last_update_timestamp = timestamp;
+
+    emit!(StakeClosureFinalized {
+        total_staked_sol: stake_info.total_staked_sol,
+        timestamp,
+    });
+
+    Ok(())
+}
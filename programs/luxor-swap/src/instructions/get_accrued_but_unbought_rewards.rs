@@ -0,0 +1,57 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+/// Accounts context for `get_accrued_but_unbought_rewards`.
+///
+/// Read-only view comparing the SOL rewards the accounting still owes to buyback
+/// with what the stake PDA actually holds above principal, so drift in the
+/// accrual tracking shows up before a buyback trips over it.
+#[derive(Accounts)]
+pub struct GetAccruedButUnboughtRewards<'info> {
+    /// Global protocol configuration.
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Global staking aggregates (reward and principal accounting).
+    #[account(address = global_config.stake_info)]
+    pub stake_info: Account<'info, StakeInfo>,
+
+    /// Protocol stake account (PDA); only its lamport balance is read.
+    ///
+    /// CHECK: Address enforced via `global_config.stake_account`.
+    #[account(address = global_config.stake_account)]
+    pub stake_pda: UncheckedAccount<'info>,
+}
+
+/// Emits `AccruedButUnboughtRewards`. Does not modify any state.
+///
+/// - `accounted_rewards = total_sol_rewards_accrued - total_sol_used_for_buyback
+///   - total_sol_reserve_funded` (accrued rewards still on the stake account)
+/// - `stake_rewards = stake_pda.lamports() - total_staked_sol - rent`
+///
+/// `stake_rewards` also covers rewards not yet realized by `accrue_sol_rewards`
+/// (reported separately as `unaccrued_rewards`), so after the next accrual the
+/// two figures should agree; a lasting gap means the accounting has drifted
+/// (see `reconcile_buyback_accounting`). Both figures floor at zero.
+pub fn get_accrued_but_unbought_rewards(ctx: Context<GetAccruedButUnboughtRewards>) -> Result<()> {
+    let stake_info = &ctx.accounts.stake_info;
+    let stake_lamports = ctx.accounts.stake_pda.lamports();
+    let min_rent = ctx.accounts.global_config.stake_split_rent()?;
+
+    emit!(AccruedButUnboughtRewards {
+        accounted_rewards: stake_info
+            .total_sol_rewards_accrued
+            .saturating_sub(stake_info.total_sol_used_for_buyback)
+            .saturating_sub(stake_info.total_sol_reserve_funded),
+        stake_rewards: stake_lamports
+            .saturating_sub(stake_info.total_staked_sol)
+            .saturating_sub(min_rent),
+        unaccrued_rewards: stake_lamports.saturating_sub(stake_info.last_tracked_sol_balance),
+        stake_lamports,
+    });
+
+    Ok(())
+}
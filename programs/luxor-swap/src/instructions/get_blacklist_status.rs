@@ -0,0 +1,41 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+/// Accounts context for `get_blacklist_status`.
+///
+/// Read-only view answering whether a user is currently blacklisted, so
+/// frontends do not have to infer it from the raw `UserStakeInfo` fields.
+#[derive(Accounts)]
+pub struct GetBlacklistStatus<'info> {
+    /// User whose blacklist state is reported.
+    ///
+    /// CHECK: Only used as a PDA seed.
+    pub user: UncheckedAccount<'info>,
+
+    /// Per-user staking record (derived by USER_STAKE_INFO_SEED + user).
+    #[account(
+        seeds = [
+            USER_STAKE_INFO_SEED.as_bytes(),
+            user.key().as_ref()
+        ],
+        bump,
+    )]
+    pub user_stake_info: Account<'info, UserStakeInfo>,
+}
+
+/// Emits `BlacklistStatus` for `user`. Does not modify any state.
+///
+/// A user is blacklisted while `blacklisted_sol > 0`, i.e. after
+/// `finalize_blacklist` and until `un_blacklist`. A merely proposed blacklist
+/// does not count (see `get_user_stake_status` for the pending timestamp).
+pub fn get_blacklist_status(ctx: Context<GetBlacklistStatus>) -> Result<()> {
+    let user_stake_info = &ctx.accounts.user_stake_info;
+
+    emit!(BlacklistStatus {
+        user: ctx.accounts.user.key(),
+        is_blacklisted: user_stake_info.is_blacklisted(),
+        blacklisted_sol: user_stake_info.blacklisted_sol,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+/// Accounts context for `get_claimable`.
+///
+/// Read-only view of what `redeem` would pay `user` right now, for showing
+/// pending rewards without sending a state-changing `redeem` (which also needs
+/// the reward ATA). Must be run through `simulate_transaction`; the result is
+/// read from the emitted `ClaimableRewards`.
+#[derive(Accounts)]
+pub struct GetClaimable<'info> {
+    /// User whose rewards are reported.
+    ///
+    /// CHECK: Only used as a PDA seed and ATA authority.
+    pub user: UncheckedAccount<'info>,
+
+    /// Global protocol configuration (forfeiture grace and minimum hold ratio).
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Global staking aggregates and reward indices.
+    #[account(address = global_config.stake_info)]
+    pub stake_info: Account<'info, StakeInfo>,
+
+    /// Per-user staking record (derived by USER_STAKE_INFO_SEED + user).
+    #[account(
+        seeds = [
+            USER_STAKE_INFO_SEED.as_bytes(),
+            user.key().as_ref()
+        ],
+        bump,
+    )]
+    pub user_stake_info: Account<'info, UserStakeInfo>,
+
+    /// Canonical LXR mint.
+    #[account(address = crate::luxor_mint::id() @ ErrorCode::InvalidLuxorMint)]
+    pub luxor_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// User's LXR ATA (holdings for the forfeiture rule); omit it when the ATA
+    /// does not exist, which counts as zero holdings.
+    #[account(
+        associated_token::mint = luxor_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_lxr_token: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// SPL Token-2022 interface program.
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Emits `ClaimableRewards` for `user`. Does not modify any state.
+///
+/// Applies the same math as `redeem` to a copy of the user's record:
+/// `total_staked_sol * (reward_per_token_lxr_stored - lxr_reward_per_token_completed) / PRECISION`
+/// is split by `apply_holdings_forfeiture` against the current LXR holdings, and
+/// the carried-over `lxr_rewards_pending` is added to the claimable side.
+/// `meets_min_hold = false` means `redeem` would currently fail with `NoRewardsToClaim`.
+///
+/// # Fails
+/// - `UnderflowError` if the user's checkpoint is ahead of the global index.
+pub fn get_claimable(ctx: Context<GetClaimable>) -> Result<()> {
+    let global_config = &ctx.accounts.global_config;
+    let lxr_holdings = ctx.accounts.user_lxr_token.as_ref().map_or(0, |ata| ata.amount);
+    // `pending_lxr_rewards` may rescale the checkpoint, so work on a copy.
+    let mut user_stake_info = (*ctx.accounts.user_stake_info).clone();
+
+    let full_rewards = user_stake_info.pending_lxr_rewards(&ctx.accounts.stake_info)?;
+    let (claimable, forfeitable) =
+        user_stake_info.apply_holdings_forfeiture(full_rewards, lxr_holdings, global_config.forfeiture_grace_bps)?;
+
+    emit!(ClaimableRewards {
+        user: ctx.accounts.user.key(),
+        claimable: claimable
+            .checked_add(user_stake_info.lxr_rewards_pending)
+            .ok_or(ErrorCode::MathOverflow)?,
+        forfeitable,
+        lxr_holdings,
+        meets_min_hold: user_stake_info.meets_min_hold(lxr_holdings, global_config.min_hold_ratio_bps)?,
+    });
+
+    Ok(())
+}
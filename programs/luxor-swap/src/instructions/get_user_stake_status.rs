@@ -0,0 +1,38 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+/// Accounts context for `get_user_stake_status`.
+///
+/// Read-only view for transparency around blacklisting: reports the stake that
+/// still earns rewards next to the SOL that was reassigned by `finalize_blacklist`.
+#[derive(Accounts)]
+pub struct GetUserStakeStatus<'info> {
+    /// User whose record is reported.
+    ///
+    /// CHECK: Only used as a PDA seed.
+    pub user: UncheckedAccount<'info>,
+
+    /// Per-user staking record (derived by USER_STAKE_INFO_SEED + user).
+    #[account(
+        seeds = [
+            USER_STAKE_INFO_SEED.as_bytes(),
+            user.key().as_ref()
+        ],
+        bump,
+    )]
+    pub user_stake_info: Account<'info, UserStakeInfo>,
+}
+
+/// Emits `UserStakeStatus` for `user`. Does not modify any state.
+pub fn get_user_stake_status(ctx: Context<GetUserStakeStatus>) -> Result<()> {
+    let user_stake_info = &ctx.accounts.user_stake_info;
+
+    emit!(UserStakeStatus {
+        user: ctx.accounts.user.key(),
+        effective_stake: user_stake_info.total_staked_sol,
+        blacklisted_sol: user_stake_info.blacklisted_sol,
+        blacklist_pending_timestamp: user_stake_info.blacklist_pending_timestamp,
+    });
+
+    Ok(())
+}
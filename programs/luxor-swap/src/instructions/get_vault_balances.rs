@@ -0,0 +1,54 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::{token::spl_token, token_interface::TokenAccount};
+
+/// Accounts context for `get_vault_balances`.
+///
+/// Read-only view for monitoring. Every vault is pinned to the address stored in
+/// `global_config` (and to its expected mint), so the emitted balances always
+/// come from the canonical vaults.
+#[derive(Accounts)]
+pub struct GetVaultBalances<'info> {
+    /// Global protocol configuration.
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Protocol LXR treasury vault.
+    #[account(
+        address = global_config.lxr_treasury_vault,
+        constraint = luxor_vault.mint == crate::luxor_mint::id() @ ErrorCode::InvalidLuxorMint,
+    )]
+    pub luxor_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Rewards vault (holds `global_config.reward_mint`).
+    #[account(
+        address = global_config.lxr_reward_vault,
+        constraint = luxor_reward_vault.mint == global_config.reward_mint @ ErrorCode::InvalidRewardMint,
+    )]
+    pub luxor_reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Protocol SOL treasury vault (WSOL).
+    #[account(
+        address = global_config.sol_treasury_vault,
+        constraint = sol_treasury_vault.mint == spl_token::native_mint::id() @ ErrorCode::InvalidLuxorMint,
+    )]
+    pub sol_treasury_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+}
+
+/// Emits `VaultBalances` with the live amounts of the three protocol vaults.
+/// Does not modify any state.
+pub fn get_vault_balances(ctx: Context<GetVaultBalances>) -> Result<()> {
+    emit!(VaultBalances {
+        treasury_lxr: ctx.accounts.luxor_vault.amount,
+        reward_lxr: ctx.accounts.luxor_reward_vault.amount,
+        treasury_wsol: ctx.accounts.sol_treasury_vault.amount,
+        lxr_mint: ctx.accounts.luxor_vault.mint,
+        wsol_mint: ctx.accounts.sol_treasury_vault.mint,
+    });
+
+    Ok(())
+}
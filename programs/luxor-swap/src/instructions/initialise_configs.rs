@@ -61,10 +61,18 @@ pub struct InitialiseConfigs<'info> {
 
     /// LUXOR mint address (fixed, canonical program mint).
     #[account(
-        address = crate::luxor_mint::id() @ ErrorCode::InvalidLuxorMint
+        address = crate::luxor_mint::id() @ ErrorCode::InvalidLuxorMint,
+        constraint = crate::utils::luxor_decimals_match(luxor_mint.decimals) @ ErrorCode::MintDecimalsMismatch,
     )]
     pub luxor_mint: Box<InterfaceAccount<'info, Mint>>,
 
+    /// Mint rewards are bought and paid in; pass the LUXOR mint for LXR rewards.
+    /// Immutable after init, since the reward vault is created for it.
+    #[account(
+        mint::token_program = token_program,
+    )]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
     #[account(
         address = spl_token::native_mint::id() @ ErrorCode::InvalidLuxorMint
     )]
@@ -93,13 +101,13 @@ pub struct InitialiseConfigs<'info> {
     )]
     pub luxor_vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    /// Vault for distributing rewards (also holds LUXOR).
+    /// Vault for distributing rewards (holds `reward_mint`).
     #[account(
         init,
         seeds =[LUXOR_REWARD_VAULT_SEED.as_bytes()],
         bump,
         payer = owner,
-        token::mint = luxor_mint,
+        token::mint = reward_mint,
         token::authority = authority,
         token::token_program = token_program,
     )]
@@ -162,6 +170,7 @@ pub fn initialise_configs(
     purchase_enabled: bool,
     redeem_enabled: bool,
     initial_lxr_allocation_vault: u64,
+    emergency_delay: u64,
 ) -> Result<()> {
     // ---------------------------
     // 1) Write global config
@@ -183,6 +192,13 @@ pub fn initialise_configs(
     global_config.purchase_enabled = purchase_enabled;
     global_config.redeem_enabled = redeem_enabled;
     global_config.initial_lxr_allocation_vault = initial_lxr_allocation_vault;
+    global_config.luxor_decimals = crate::LUXOR_DECIMALS;
+    global_config.reward_mint = ctx.accounts.reward_mint.key();
+    // StakeStateV2 has a fixed size, so its rent-exempt minimum is cached once here.
+    global_config.stake_rent_exempt = Rent::get()?.minimum_balance(size_of::<StakeStateV2>());
+    global_config.buyback_out_tolerance_rate = DEFAULT_BUYBACK_OUT_TOLERANCE_RATE;
+    global_config.buyback_fee_enabled = true;
+    global_config.emergency_delay = emergency_delay;
     msg!("Global Config initialized");
 
     // Write bump seed for stake_info metadata
@@ -212,9 +228,9 @@ pub fn initialise_configs(
         ErrorCode::InvalidStakePdaOwner
     );
 
-    // Compute rent-exempt minimum lamports for StakeStateV2.
+    // Rent-exempt minimum lamports for StakeStateV2 (cached above).
     let space = size_of::<StakeStateV2>();
-    let min_rent = Rent::get()?.minimum_balance(space);
+    let min_rent = ctx.accounts.global_config.stake_rent_exempt;
     require!(min_rent > 0, ErrorCode::InsufficientRent);
 
     // Derive seeds for stake account PDA.
@@ -278,6 +294,8 @@ pub fn initialise_configs(
         purchase_enabled,
         redeem_enabled,
         initial_lxr_allocation_vault,
+        reward_mint: ctx.accounts.reward_mint.key(),
+        emergency_delay,
     });
     Ok(())
 }
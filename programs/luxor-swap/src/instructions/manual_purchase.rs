@@ -1,5 +1,5 @@
 use crate::instructions::load_stake_state;
-use crate::{states::*, PRECISION};
+use crate::states::*;
 use anchor_lang::solana_program::stake::state::StakeStateV2;
 use anchor_lang::{prelude::*, solana_program};
 use anchor_lang::solana_program::program::{invoke, invoke_signed};
@@ -60,6 +60,7 @@ pub struct ManualPurchase<'info> {
     #[account(
         mut,
         address = global_config.stake_info,
+        constraint = !stake_info.stake_closed @ ErrorCode::StakeClosed,
     )]
     pub stake_info: Account<'info, StakeInfo>,
 
@@ -127,14 +128,23 @@ pub struct ManualPurchase<'info> {
 ///   and the user’s aggregates (`total_staked_sol`, `base_lxr_holdings`).
 /// - Emits `ManualLxrPurchased { purchaser, sol_amount, lxr_amount }`.
 ///
+/// # Fails
+/// - `SwapAmountOutOfBounds` if `sol_spent` is outside `[min_swap_amount, max_swap_amount]`
+///   or `lxr_purchased` is outside `[min_lxr_per_purchase, max_lxr_per_purchase]`.
+/// - `UserStakeCapExceeded` if the user's stake would exceed `max_stake_per_user`.
+/// - `GlobalStakeCapReached` if `total_staked_sol` would exceed `max_total_staked_sol`.
+///
 /// # Notes
 /// - No pricing is computed here—caller must ensure `lxr_purchased` and `sol_spent`
 ///   reflect an externally agreed settlement.
 /// - Assumes `stake_pda` is already initialized as a Stake account with `authority` set.
 pub fn manual_purchase(ctx: Context<ManualPurchase>, lxr_purchased: u64, sol_spent: u64) -> Result<()> {
-    
+    ctx.accounts.global_config.check_purchase_bounds(lxr_purchased, sol_spent)?;
+
     let stake_info = &mut ctx.accounts.stake_info;
     let user_stake_info = &mut ctx.accounts.user_stake_info;
+    ctx.accounts.global_config.check_user_stake_cap(user_stake_info.total_staked_sol, sol_spent)?;
+    ctx.accounts.global_config.check_global_stake_cap(stake_info.total_staked_sol, sol_spent)?;
 
     let stake_pda_ai = ctx.accounts.stake_pda.to_account_info();
     let stake_pda_state = load_stake_state(&stake_pda_ai)?;
@@ -158,13 +168,9 @@ pub fn manual_purchase(ctx: Context<ManualPurchase>, lxr_purchased: u64, sol_spe
 
 
     // --- Accrue any newly observed SOL rewards on the stake PDA ---
-    if ctx.accounts.stake_pda.lamports() > stake_info.last_tracked_sol_balance {
-        let rewards_accured = ctx.accounts.stake_pda.lamports()
-            .checked_sub(stake_info.last_tracked_sol_balance).unwrap();
-        stake_info.total_sol_rewards_accrued = stake_info.total_sol_rewards_accrued
-            .checked_add(rewards_accured).unwrap();
-        stake_info.last_tracked_sol_balance = ctx.accounts.stake_pda.lamports();
-    }
+    // Same realization as `purchase`, including the `reward_per_token_sol_stored` bump,
+    // so rewards observed here are distributable.
+    stake_info.accrue_sol_rewards(ctx.accounts.stake_pda.lamports(), ctx.accounts.global_config.sol_reward_share_bps)?;
 
     // --- Transfer SOL from admin to the stake PDA (fund new stake) ---
     let ix = transfer(&ctx.accounts.owner.key(), &ctx.accounts.stake_pda.key(), sol_spent);
@@ -205,7 +211,7 @@ pub fn manual_purchase(ctx: Context<ManualPurchase>, lxr_purchased: u64, sol_spe
 
     // --- Global stake info updates ---
     stake_info.total_staked_sol = stake_info.total_staked_sol
-        .checked_add(sol_spent).unwrap();
+        .checked_add(sol_spent).ok_or(ErrorCode::MathOverflow)?;
     stake_info.last_tracked_sol_balance = ctx.accounts.stake_pda.lamports();
     let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
     stake_info.last_update_timestamp = block_timestamp;
@@ -214,25 +220,18 @@ pub fn manual_purchase(ctx: Context<ManualPurchase>, lxr_purchased: u64, sol_spe
     if user_stake_info.owner == Pubkey::default() {
         user_stake_info.owner = ctx.accounts.user.key();
         user_stake_info.bump = ctx.bumps.user_stake_info;
-        user_stake_info.lxr_reward_per_token_completed = stake_info.reward_per_token_lxr_stored;
+        user_stake_info.reset_reward_checkpoint(stake_info);
+        stake_info.total_stake_count = stake_info.total_stake_count
+        .checked_add(1).ok_or(ErrorCode::MathOverflow)?;
     } else {
-        let reward_per_token_lxr_pending_user = stake_info.reward_per_token_lxr_stored
-        .checked_sub(user_stake_info.lxr_reward_per_token_completed)
-        .unwrap();
-
-        let lxr_rewards_to_claim_user = (user_stake_info.total_staked_sol as u128)
-        .checked_mul(reward_per_token_lxr_pending_user).unwrap()
-        .checked_div(PRECISION).unwrap() as u64;
-
-        user_stake_info.lxr_rewards_pending = user_stake_info.lxr_rewards_pending
-        .checked_add(lxr_rewards_to_claim_user).unwrap();
-        user_stake_info.lxr_reward_per_token_completed = stake_info.reward_per_token_lxr_stored;
+        user_stake_info.checkpoint_lxr_rewards(stake_info)?;
+        user_stake_info.checkpoint_sol_rewards(stake_info)?;
     }
     
     user_stake_info.total_staked_sol = user_stake_info.total_staked_sol
-        .checked_add(sol_spent).unwrap();
+        .checked_add(sol_spent).ok_or(ErrorCode::MathOverflow)?;
     user_stake_info.base_lxr_holdings = user_stake_info.base_lxr_holdings
-        .checked_add(lxr_purchased).unwrap();
+        .checked_add(lxr_purchased).ok_or(ErrorCode::MathOverflow)?;
     
     // --- Emit event for indexers/UX ---
     emit!(ManualLxrPurchased{
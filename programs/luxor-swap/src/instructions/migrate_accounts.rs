@@ -0,0 +1,196 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::stake::state::StakeStateV2;
+use anchor_lang::system_program::{self, Transfer};
+use std::mem::size_of;
+
+/// Accounts context for `migrate_accounts`.
+///
+/// Grows `GlobalConfig`, `StakeInfo` and `UserStakeInfo` accounts created by the
+/// first release (`LEGACY_LEN` bytes) to the current `LEN`. Until this has run
+/// for an account, every instruction that loads it fails to deserialize, so it
+/// must follow the program upgrade immediately. Already migrated accounts are
+/// skipped, so the instruction can be re-run safely.
+///
+/// Remaining accounts: up to `MAX_BATCH` writable `UserStakeInfo` records (user or
+/// admin) to grow in the same transaction. Each record is grown and written on its
+/// own, so larger sets are migrated over several calls.
+#[derive(Accounts)]
+pub struct MigrateAccounts<'info> {
+    /// Admin (must match `global_config.admin` or program admin, checked once
+    /// the config is readable). Pays the rent for the added bytes.
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Global protocol configuration, possibly still in the legacy layout.
+    ///
+    /// CHECK: PDA enforced by seeds and owner; deserialized after it is grown.
+    #[account(
+        mut,
+        seeds = [GLOBAL_CONFIG_SEED.as_bytes()],
+        bump,
+        owner = crate::ID,
+    )]
+    pub global_config: UncheckedAccount<'info>,
+
+    /// Global staking state, possibly still in the legacy layout.
+    ///
+    /// CHECK: PDA enforced by seeds and owner; deserialized after it is grown.
+    #[account(
+        mut,
+        seeds = [STAKE_INFO_SEED.as_bytes()],
+        bump,
+        owner = crate::ID,
+    )]
+    pub stake_info: UncheckedAccount<'info>,
+
+    /// System program (rent top-ups).
+    pub system_program: Program<'info, System>,
+}
+
+/// Grows legacy accounts to the current layouts and initializes the appended
+/// fields whose zero value would be wrong.
+///
+/// # Fails
+/// - `AccountLayoutMismatch` if an account is neither `LEGACY_LEN` nor `LEN` bytes.
+/// - `InvalidOwner` if the signer is not the admin.
+/// - `InvalidUserStakeInfo` if a remaining account is not a `UserStakeInfo` PDA.
+/// - `BatchTooLarge` if more than `MAX_BATCH` remaining accounts are passed.
+///
+/// # Steps
+/// 1. Grow `global_config`; on a legacy config, set `luxor_decimals`, the cached
+///    stake rent, the buyback tolerance/fee flag and `reward_mint`.
+/// 2. Check the signer against the now readable config.
+/// 3. Grow `stake_info` (every appended field is valid zero-filled).
+/// 4. Grow each remaining `UserStakeInfo` and checkpoint its SOL rewards at the
+///    current index.
+pub fn migrate_accounts<'info>(
+    ctx: Context<'_, '_, 'info, 'info, MigrateAccounts<'info>>,
+) -> Result<()> {
+    require!(
+        ctx.remaining_accounts.len() <= crate::MAX_BATCH,
+        ErrorCode::BatchTooLarge
+    );
+    let owner = ctx.accounts.owner.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+
+    // --- 1. GlobalConfig ---
+    let config_info = ctx.accounts.global_config.to_account_info();
+    let global_config_migrated = grow_account(
+        &config_info,
+        GlobalConfig::LEGACY_LEN,
+        GlobalConfig::LEN,
+        &owner,
+        &system_program,
+    )?;
+    let mut global_config = GlobalConfig::try_deserialize(&mut &config_info.try_borrow_data()?[..])?;
+    if global_config_migrated {
+        let stake_rent_exempt = Rent::get()?.minimum_balance(size_of::<StakeStateV2>());
+        global_config.init_appended_fields(stake_rent_exempt);
+        global_config.try_serialize(&mut &mut config_info.try_borrow_mut_data()?[..])?;
+    }
+
+    // --- 2. Admin check ---
+    require!(
+        owner.key() == global_config.admin || owner.key() == crate::admin::id(),
+        ErrorCode::InvalidOwner
+    );
+
+    // --- 3. StakeInfo ---
+    let stake_info_info = ctx.accounts.stake_info.to_account_info();
+    let stake_info_migrated = grow_account(
+        &stake_info_info,
+        StakeInfo::LEGACY_LEN,
+        StakeInfo::LEN,
+        &owner,
+        &system_program,
+    )?;
+    let stake_info = StakeInfo::try_deserialize(&mut &stake_info_info.try_borrow_data()?[..])?;
+
+    // --- 4. UserStakeInfo records ---
+    let (admin_stake_info, _) =
+        Pubkey::find_program_address(&[ADMIN_STAKE_INFO_SEED.as_bytes()], &crate::ID);
+    let mut user_records_migrated: u64 = 0;
+    for record in ctx.remaining_accounts.iter() {
+        require_keys_eq!(*record.owner, crate::ID, ErrorCode::InvalidUserStakeInfo);
+        {
+            let data = record.try_borrow_data()?;
+            require!(
+                data.len() >= 8 && data[..8] == *UserStakeInfo::DISCRIMINATOR,
+                ErrorCode::InvalidUserStakeInfo
+            );
+        }
+        if record.key() != admin_stake_info {
+            // Legacy and current layouts share the leading `bump` and `owner` fields.
+            let (bump, user) = {
+                let data = record.try_borrow_data()?;
+                require_gte!(data.len(), 8 + 1 + 32, ErrorCode::InvalidUserStakeInfo);
+                (data[8], Pubkey::try_from(&data[9..41]).unwrap())
+            };
+            let expected = Pubkey::create_program_address(
+                &[USER_STAKE_INFO_SEED.as_bytes(), user.as_ref(), &[bump]],
+                &crate::ID,
+            )
+            .map_err(|_| ErrorCode::InvalidUserStakeInfo)?;
+            require_keys_eq!(record.key(), expected, ErrorCode::InvalidUserStakeInfo);
+        }
+
+        if !grow_account(
+            record,
+            UserStakeInfo::LEGACY_LEN,
+            UserStakeInfo::LEN,
+            &owner,
+            &system_program,
+        )? {
+            continue;
+        }
+        let mut user_stake_info = UserStakeInfo::try_deserialize(&mut &record.try_borrow_data()?[..])?;
+        user_stake_info.init_appended_fields(&stake_info)?;
+        user_stake_info.try_serialize(&mut &mut record.try_borrow_mut_data()?[..])?;
+        user_records_migrated += 1;
+    }
+
+    emit!(AccountsMigrated {
+        global_config_migrated,
+        stake_info_migrated,
+        user_records_migrated,
+    });
+
+    Ok(())
+}
+
+/// Resizes a program account from `legacy_len` to `len` bytes (zero-filled),
+/// first topping its balance up to the rent-exempt minimum from `payer`.
+/// Returns `false` without touching the account if it is already `len` bytes.
+fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    legacy_len: usize,
+    len: usize,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<bool> {
+    match account.data_len() {
+        current if current == len => return Ok(false),
+        current if current == legacy_len => {}
+        _ => return err!(ErrorCode::AccountLayoutMismatch),
+    }
+
+    let rent_due = Rent::get()?
+        .minimum_balance(len)
+        .saturating_sub(account.lamports());
+    if rent_due > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            rent_due,
+        )?;
+    }
+    account.resize(len)?;
+    Ok(true)
+}
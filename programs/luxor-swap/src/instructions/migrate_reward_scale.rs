@@ -0,0 +1,77 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+/// Accounts context for `migrate_reward_scale`.
+///
+/// One-time admin migration of the reward indices to a new scale (e.g. after a
+/// change of `PRECISION`). Rewriting every `UserStakeInfo` on-chain is not
+/// feasible, so only the global indices are rescaled here; each user checkpoint
+/// is rescaled by the stored factor the next time it is touched
+/// (`UserStakeInfo::sync_reward_scale`).
+#[derive(Accounts)]
+pub struct MigrateRewardScale<'info> {
+    /// Admin (must match `global_config.admin` or program admin).
+    #[account(
+        constraint = (owner.key() == global_config.admin || owner.key() == crate::admin::id()) @ ErrorCode::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    /// Global protocol configuration.
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Global staking state whose reward indices are rescaled.
+    #[account(
+        mut,
+        address = global_config.stake_info,
+    )]
+    pub stake_info: Account<'info, StakeInfo>,
+}
+
+/// Multiplies both global reward indices by `numerator / denominator` and records
+/// the factor so user checkpoints can follow lazily.
+///
+/// # Fails
+/// - `RewardScaleAlreadyMigrated` if a migration was already applied; the lazy
+///   per-user correction only tracks a single factor.
+/// - `InvalidParam` if either side of the factor is zero.
+pub fn migrate_reward_scale(
+    ctx: Context<MigrateRewardScale>,
+    numerator: u64,
+    denominator: u64,
+) -> Result<()> {
+    let stake_info = &mut ctx.accounts.stake_info;
+    require!(
+        stake_info.reward_scale_version == 0,
+        ErrorCode::RewardScaleAlreadyMigrated
+    );
+    require!(numerator > 0 && denominator > 0, ErrorCode::InvalidParam);
+
+    stake_info.reward_per_token_lxr_stored = stake_info
+        .reward_per_token_lxr_stored
+        .checked_mul(numerator as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(denominator as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+    stake_info.reward_per_token_sol_stored = stake_info
+        .reward_per_token_sol_stored
+        .checked_mul(numerator as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(denominator as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+    stake_info.reward_scale_numerator = numerator;
+    stake_info.reward_scale_denominator = denominator;
+    stake_info.reward_scale_version = 1;
+
+    emit!(RewardScaleMigrated {
+        numerator,
+        denominator,
+        reward_scale_version: stake_info.reward_scale_version,
+    });
+
+    Ok(())
+}
@@ -10,6 +10,15 @@ pub use buyback::*;
 pub mod redeem;
 pub use redeem::*;
 
+pub mod claim_sol_rewards;
+pub use claim_sol_rewards::*;
+
+pub mod unstake;
+pub use unstake::*;
+
+pub mod withdraw_unstaked;
+pub use withdraw_unstaked::*;
+
 pub mod update_configs;
 pub use update_configs::*;
 
@@ -19,5 +28,83 @@ pub use emergency_withdraw::*;
 pub mod manual_purchase;
 pub use manual_purchase::*;
 
-pub mod blacklist;
-pub use blacklist::*;
+pub mod airdrop_lxr;
+pub use airdrop_lxr::*;
+
+pub mod propose_blacklist;
+pub use propose_blacklist::*;
+
+pub mod cancel_blacklist;
+pub use cancel_blacklist::*;
+
+pub mod finalize_blacklist;
+pub use finalize_blacklist::*;
+
+pub mod rotate_stake_authority;
+pub use rotate_stake_authority::*;
+
+pub mod migrate_reward_scale;
+pub use migrate_reward_scale::*;
+
+pub mod reconcile_buyback_accounting;
+pub use reconcile_buyback_accounting::*;
+
+pub mod get_vault_balances;
+pub use get_vault_balances::*;
+
+pub mod accept_admin;
+pub use accept_admin::*;
+
+pub mod close_user_stake_info;
+pub use close_user_stake_info::*;
+
+pub mod set_pool_fee_override;
+pub use set_pool_fee_override::*;
+
+pub mod get_user_stake_status;
+pub use get_user_stake_status::*;
+
+pub mod un_blacklist;
+pub use un_blacklist::*;
+
+pub mod purchase_isolated;
+pub use purchase_isolated::*;
+
+pub mod unstake_isolated;
+pub use unstake_isolated::*;
+
+pub mod finalize_stake_closure;
+pub use finalize_stake_closure::*;
+
+pub mod deposit_to_treasury;
+pub use deposit_to_treasury::*;
+
+pub mod get_blacklist_status;
+pub use get_blacklist_status::*;
+
+pub mod withdraw_treasury_fees;
+pub use withdraw_treasury_fees::*;
+
+pub mod redelegate;
+pub use redelegate::*;
+
+pub mod get_accrued_but_unbought_rewards;
+pub use get_accrued_but_unbought_rewards::*;
+
+pub mod set_anchor_price;
+pub use set_anchor_price::*;
+
+pub mod get_claimable;
+pub use get_claimable::*;
+
+pub mod set_fee_tiers;
+pub use set_fee_tiers::*;
+
+pub mod queue_emergency;
+pub use queue_emergency::*;
+
+pub mod rebalance_vaults;
+pub use rebalance_vaults::*;
+
+pub mod migrate_accounts;
+pub use migrate_accounts::*;
@@ -0,0 +1,76 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+
+//
+// ──────────────────────────────────────────────────────────────────────────────
+// Propose Blacklist Instruction
+// ──────────────────────────────────────────────────────────────────────────────
+//
+
+/// Propose blacklisting a user (first step of the two-step blacklist).
+///
+/// Records `blacklist_pending_timestamp` on the user's `UserStakeInfo`. The
+/// stake reassignment itself only happens in `finalize_blacklist`, once
+/// `global_config.blacklist_delay_secs` have elapsed, which gives the user a
+/// dispute window. The admin can withdraw the proposal with `cancel_blacklist`.
+#[derive(Accounts)]
+pub struct ProposeBlacklist<'info> {
+    /// Admin (authorized) signer.  
+    /// Must be either the current protocol admin stored in `global_config.admin`
+    /// or the hardcoded program admin.
+    #[account(
+        constraint = (owner.key() == global_config.admin || owner.key() == crate::admin::id()) @ ErrorCode::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    /// Global protocol configuration (holds admin and the blacklist delay).
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// The user proposed for blacklisting.  
+    /// Identity key for deriving `user_stake_info`.
+    pub user: SystemAccount<'info>,
+
+    /// Per-user stake info for `user`; receives the pending timestamp.
+    #[account(
+        mut,
+        seeds = [
+            USER_STAKE_INFO_SEED.as_bytes(),
+            user.key().as_ref()
+        ],
+        bump,
+    )]
+    pub user_stake_info: Account<'info, UserStakeInfo>,
+}
+
+/// Instruction: Open the blacklist dispute window for a user.
+///
+/// # Fails
+/// - `BlacklistAlreadyProposed` if a proposal is already pending for the user.
+///
+/// # Effects
+/// - Sets `user_stake_info.blacklist_pending_timestamp` to the current time.
+/// - Emits `BlacklistProposed` with the earliest finalization time.
+pub fn propose_blacklist(ctx: Context<ProposeBlacklist>) -> Result<()> {
+    let user_stake_info = &mut ctx.accounts.user_stake_info;
+    require!(
+        user_stake_info.blacklist_pending_timestamp == 0,
+        ErrorCode::BlacklistAlreadyProposed
+    );
+
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    user_stake_info.blacklist_pending_timestamp = block_timestamp;
+
+    emit!(BlacklistProposed {
+        user: ctx.accounts.user.key(),
+        finalize_after: block_timestamp
+            .checked_add(ctx.accounts.global_config.blacklist_delay_secs)
+            .ok_or(ErrorCode::MathOverflow)?,
+    });
+
+    Ok(())
+}
@@ -1,10 +1,10 @@
-use crate::curve::{CurveCalculator, FEE_RATE_DENOMINATOR_VALUE};
 use crate::error::ErrorCode;
+use crate::pricing::quote_sol_for_lxr;
 use crate::utils::transfer_from_pool_vault_to_user;
-use crate::{states::*, PRECISION};
+use crate::states::*;
 use anchor_lang::solana_program::stake::state::StakeStateV2;
 use anchor_lang::{prelude::*, solana_program};
-use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_lang::solana_program::program::{invoke, invoke_signed, set_return_data};
 use anchor_lang::solana_program::stake::instruction as stake_ix;
 use anchor_lang::solana_program::system_instruction::transfer;
 use anchor_lang::solana_program::{stake};
@@ -60,6 +60,7 @@ pub struct Purchase<'info> {
     #[account(
         mut,
         address = global_config.stake_info,
+        constraint = !stake_info.stake_closed @ ErrorCode::StakeClosed,
     )]
     pub stake_info: Account<'info, StakeInfo>,
 
@@ -72,8 +73,11 @@ pub struct Purchase<'info> {
     )]
     pub authority: UncheckedAccount<'info>,
 
-    /// Canonical LUXOR mint.
-    #[account(address = crate::luxor_mint::id())]
+    /// Canonical LUXOR mint (decimals must match the value recorded at init).
+    #[account(
+        address = crate::luxor_mint::id(),
+        constraint = crate::utils::luxor_decimals_match(luxor_mint.decimals) @ ErrorCode::MintDecimalsMismatch,
+    )]
     pub luxor_mint: Box<InterfaceAccount<'info, Mint>>,
 
     /// Payer's LXR ATA; created if missing so they can receive purchased LXR.
@@ -152,12 +156,18 @@ pub struct Purchase<'info> {
 /// # Parameters
 /// - `lxr_to_purchase`: Exact LXR amount desired by the user (base units).
 /// - `max_sol_amount`: Max SOL the user is willing to pay for the purchase (slippage cap).
+/// - `deadline`: Unix timestamp (seconds) after which the purchase reverts.
 ///
 /// # Pricing / Mechanics
+/// - Priced by `pricing::quote_sol_for_lxr`, which clients can call to quote identically.
 /// - Uses pool state (`pool_state`) to compute the required SOL input for the exact LXR output
-///   via `CurveCalculator::swap_base_output(...)`.
+///   via `CurveCalculator::swap_base_output(...)`, on the curve selected by
+///   `global_config.curve_type` (plain constant product or offset/virtual reserves).
+/// - Fee rates come from `global_config.fee_override()` when `use_fee_override` is on,
+///   otherwise from the built-in baseline.
 /// - Applies an early-bird bonus discount to the SOL needed if `total_stake_count + 1` is within
-///   `max_stake_count_to_get_bonus`; otherwise scales price with treasury inventory.
+///   `max_stake_count_to_get_bonus`; otherwise scales price with treasury inventory. A threshold
+///   of `0` disables the bonus, so inventory scaling applies from the first purchase.
 /// - Ensures constant product is non-decreasing and the exact output matches `lxr_to_purchase`.
 ///
 /// # Rewards Accrual
@@ -169,14 +179,53 @@ pub struct Purchase<'info> {
 /// - Transfers `total_sol_needed` SOL from user to stake PDA, delegates to `vote_account`.
 /// - Sends `lxr_to_purchase` LXR from treasury vault to the user's ATA.
 /// - Updates global and per-user staking aggregates; emits `LxrPurchased`.
+/// - Sets the return data to the borsh-encoded `(total_sol_needed, lxr_to_purchase)`
+///   (`u64`, `u64`), so CPI callers read the lamports charged without parsing logs.
 ///
 /// # Fails
+/// - `DeadlineExceeded` if the cluster time is past `deadline`.
 /// - `PurchaseDisabled` if purchases are globally disabled.
+/// - `SwapAmountOutOfBounds` if the SOL price is outside `[min_swap_amount, max_swap_amount]`
+///   or `lxr_to_purchase` is outside `[min_lxr_per_purchase, max_lxr_per_purchase]`.
+/// - `TreasuryBelowMinimum` if `luxor_vault` is below `min_treasury_for_purchase`.
+/// - `TreasuryNotFunded` if `luxor_vault` holds less than `lxr_to_purchase`.
+/// - `InsufficientPoolLiquidity` if either pool reserve is below `min_pool_reserve`, or the
+///   pool's LXR reserve is not above `POOL_LIQUIDITY_SAFETY_FACTOR` times the priced amount.
+/// - `InvalidParam` if inventory scaling applies but `initial_lxr_allocation_vault` is `0`.
+/// - `PriceImpactTooHigh` if `lxr_to_purchase` exceeds `max_pool_fraction_bps` of the pool's
+///   LXR reserve.
 /// - `ZeroTradingTokens` or arithmetic errors if pricing fails.
+/// - `PriceOutOfBand` if the pool-implied LXR price deviates from `anchor_price_x32` by more
+///   than `max_price_deviation_bps`.
+/// - `UserStakeCapExceeded` if the user's stake would exceed `max_stake_per_user`.
+/// - `GlobalStakeCapReached` if `total_staked_sol` would exceed `max_total_staked_sol`.
 /// - `require_*` guards for invariants, slippage (`max_sol_amount`), and pool addresses.
-pub fn purchase(ctx: Context<Purchase>, lxr_to_purchase: u64, max_sol_amount: u64) -> Result<()> {
+pub fn purchase(
+    ctx: Context<Purchase>,
+    lxr_to_purchase: u64,
+    max_sol_amount: u64,
+    deadline: i64,
+) -> Result<()> {
+    // A transaction that sat in the mempool must not execute at a changed inventory ratio.
+    require!(Clock::get()?.unix_timestamp <= deadline, ErrorCode::DeadlineExceeded);
     require_gt!(lxr_to_purchase, 0);
 
+    // Pause purchases while the treasury sits below the configured floor
+    // (the error logs both amounts).
+    require_gte!(
+        ctx.accounts.luxor_vault.amount,
+        ctx.accounts.global_config.min_treasury_for_purchase,
+        ErrorCode::TreasuryBelowMinimum
+    );
+
+    // Fail fast (before any SOL is staked) if the treasury can't deliver the LXR,
+    // e.g. purchases were enabled before `luxor_vault` was funded.
+    require_gte!(
+        ctx.accounts.luxor_vault.amount,
+        lxr_to_purchase,
+        ErrorCode::TreasuryNotFunded
+    );
+
     let stake_pda_ai = ctx.accounts.stake_pda.to_account_info();
     let stake_pda_state = load_stake_state(&stake_pda_ai)?;
     let clock = &*ctx.accounts.clock;               
@@ -204,114 +253,33 @@ pub fn purchase(ctx: Context<Purchase>, lxr_to_purchase: u64, max_sol_amount: u6
     require_keys_eq!(pool_state.token_0_vault, ctx.accounts.token_0_vault.key());
     require_keys_eq!(pool_state.token_1_vault, ctx.accounts.token_1_vault.key());
 
-    // Exact tokens user wants to receive (post any transfer fee logic, if applicable).
-    let mut amount_out_with_transfer_fee = lxr_to_purchase;
-
     let stake_info = &mut ctx.accounts.stake_info;
     let user_stake_info = &mut ctx.accounts.user_stake_info;
     let global_config = &ctx.accounts.global_config;
 
-
-    // new added code   
-    if user_stake_info.owner == Pubkey::default() && stake_info.total_stake_count + 1  <= global_config.max_stake_count_to_get_bonus {
-       amount_out_with_transfer_fee = amount_out_with_transfer_fee.checked_sub(
-        amount_out_with_transfer_fee.checked_mul(global_config.bonus_rate).unwrap()
-        .checked_div(FEE_RATE_DENOMINATOR_VALUE).unwrap()
-       ).unwrap();
-    } else {
-        amount_out_with_transfer_fee = u128::from(amount_out_with_transfer_fee)
-        .checked_mul(global_config.initial_lxr_allocation_vault as u128).unwrap()
-        .checked_div(ctx.accounts.luxor_vault.amount as u128).unwrap() as u64; 
-    }
-    msg!("amount_out_with_transfer_fee (post-bonus/scaling): {}", amount_out_with_transfer_fee);
-
-    // Compute swap parameters from pool state/current vault balances.
-    let SwapParams {
-        trade_direction : _,
-        total_input_token_amount,
-        total_output_token_amount,
-        token_0_price_x64: _,
-        token_1_price_x64: _,
-        is_creator_fee_on_input,
-    } = pool_state.get_swap_params(
-        ctx.accounts.token_0_vault.key(),
-        ctx.accounts.token_1_vault.key(),
+    // Bonus / inventory scaling, pool guards and curve pricing (shared with clients).
+    let total_sol_needed = quote_sol_for_lxr(
+        &pool_state,
         ctx.accounts.token_0_vault.amount,
         ctx.accounts.token_1_vault.amount,
+        lxr_to_purchase,
+        stake_info,
+        global_config,
+        user_stake_info.owner == Pubkey::default(),
+        ctx.accounts.luxor_vault.amount,
     )?;
-
-    // Constant-product before swap (sanity/invariant check).
-    let constant_before = u128::from(total_input_token_amount)
-        .checked_mul(u128::from(total_output_token_amount))
-        .unwrap();
-
-    // Compute creator fee rate (example uses 500 as a baseline).
-    let creator_fee_rate =
-        pool_state.adjust_creator_fee_rate(500);
-
-    // Price the exact-output trade (how much SOL is needed).
-    let result = CurveCalculator::swap_base_output(
-        u128::from(amount_out_with_transfer_fee),
-        u128::from(total_input_token_amount),
-        u128::from(total_output_token_amount),
-        2500,          // trade fee
-        creator_fee_rate,
-        120000,        // protocol fee
-        40000,         // fund fee
-        is_creator_fee_on_input,
-    )
-    .ok_or(ErrorCode::ZeroTradingTokens)?;
-
-    // Constant-product after swap must be ≥ before (no reversal of invariant).
-    let constant_after = u128::from(result.new_input_vault_amount)
-        .checked_mul(u128::from(result.new_output_vault_amount))
-        .unwrap();
-    
-    // Must receive exactly what was requested.
-    require_eq!(
-        u64::try_from(result.output_amount).unwrap(),
-        amount_out_with_transfer_fee
-    );
-    
-    require_gte!(constant_after, constant_before);
-
-    // Raw SOL needed from pricing path.
-    let total_sol_needed = u64::try_from(result.input_amount).unwrap();
-    
-    
-
-    msg!("total_sol_needed (raydium output): {}", total_sol_needed);
-
-    // --- Bonus / post-bonus pricing adjustments ---
-    // if user_stake_info.owner == Pubkey::default() && stake_info.total_stake_count + 1  <= global_config.max_stake_count_to_get_bonus {
-    //    total_sol_needed = total_sol_needed
-    //     .checked_sub(
-    //         total_sol_needed
-    //     .checked_mul(global_config.bonus_rate).unwrap()
-    //     .checked_div(FEE_RATE_DENOMINATOR_VALUE).unwrap()
-    //    ).unwrap();
-    // } else {
-    //     // After bonus phase, scale price against inventory depth.
-    //     total_sol_needed = u128::from(total_sol_needed)
-    //     .checked_mul(global_config.initial_lxr_allocation_vault as u128).unwrap()
-    //     .checked_div(ctx.accounts.luxor_vault.amount as u128).unwrap() as u64; 
-    // }
-    
-    msg!("total_sol_needed (post-bonus/scaling): {}", total_sol_needed);
+    msg!("total_sol_needed: {}", total_sol_needed);
 
     // Slippage/limit check from the payer.
     require_gte!(max_sol_amount, total_sol_needed);
-    require_gte!(total_sol_needed,global_config.min_swap_amount);
-    require_gte!(max_sol_amount,total_sol_needed);
+    global_config.check_purchase_bounds(lxr_to_purchase, total_sol_needed)?;
+    global_config.check_user_stake_cap(user_stake_info.total_staked_sol, total_sol_needed)?;
+    global_config.check_global_stake_cap(stake_info.total_staked_sol, total_sol_needed)?;
 
     // --- Realize newly accrued SOL rewards on stake PDA (if any) ---
-    if ctx.accounts.stake_pda.lamports() > stake_info.last_tracked_sol_balance {
-        let rewards_accured = ctx.accounts.stake_pda.lamports()
-            .checked_sub(stake_info.last_tracked_sol_balance).unwrap();
-        stake_info.total_sol_rewards_accrued = stake_info.total_sol_rewards_accrued
-            .checked_add(rewards_accured).unwrap();
-        stake_info.last_tracked_sol_balance = ctx.accounts.stake_pda.lamports();
-    }
+    // Epoch rewards compound into the delegation and are reflected in the account's
+    // lamports, so the balance delta is the reward (see `StakeInfo::last_tracked_sol_balance`).
+    stake_info.accrue_sol_rewards(ctx.accounts.stake_pda.lamports(), ctx.accounts.global_config.sol_reward_share_bps)?;
 
     // --- Transfer SOL from user to stake PDA (fund stake) ---
     let ix = transfer(&ctx.accounts.owner.key(), &ctx.accounts.stake_pda.key(), total_sol_needed);
@@ -351,7 +319,7 @@ pub fn purchase(ctx: Context<Purchase>, lxr_to_purchase: u64, max_sol_amount: u6
 
     // --- Global stake info updates ---
     stake_info.total_staked_sol = stake_info.total_staked_sol
-        .checked_add(total_sol_needed).unwrap();
+        .checked_add(total_sol_needed).ok_or(ErrorCode::MathOverflow)?;
     stake_info.last_tracked_sol_balance = ctx.accounts.stake_pda.lamports();
     let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
     stake_info.last_update_timestamp = block_timestamp;
@@ -360,27 +328,17 @@ pub fn purchase(ctx: Context<Purchase>, lxr_to_purchase: u64, max_sol_amount: u6
     if user_stake_info.owner == Pubkey::default() {
         user_stake_info.owner = ctx.accounts.owner.key();
         user_stake_info.bump = ctx.bumps.user_stake_info;
-        user_stake_info.lxr_reward_per_token_completed = stake_info.reward_per_token_lxr_stored;
+        user_stake_info.reset_reward_checkpoint(stake_info);
         stake_info.total_stake_count = stake_info.total_stake_count
-        .checked_add(1).unwrap();
+        .checked_add(1).ok_or(ErrorCode::MathOverflow)?;
     } else {
-        let reward_per_token_lxr_pending_user = stake_info.reward_per_token_lxr_stored
-        .checked_sub(user_stake_info.lxr_reward_per_token_completed)
-        .unwrap();
-
-        let lxr_rewards_to_claim_user = (user_stake_info.total_staked_sol as u128)
-        .checked_mul(reward_per_token_lxr_pending_user).unwrap()
-        .checked_div(PRECISION).unwrap() as u64;
-
-        user_stake_info.lxr_rewards_pending = user_stake_info.lxr_rewards_pending
-        .checked_add(lxr_rewards_to_claim_user).unwrap();
-        user_stake_info.lxr_reward_per_token_completed = stake_info.reward_per_token_lxr_stored;
-
+        user_stake_info.checkpoint_lxr_rewards(stake_info)?;
+        user_stake_info.checkpoint_sol_rewards(stake_info)?;
     }
     user_stake_info.total_staked_sol = user_stake_info.total_staked_sol
-        .checked_add(total_sol_needed).unwrap();
+        .checked_add(total_sol_needed).ok_or(ErrorCode::MathOverflow)?;
     user_stake_info.base_lxr_holdings = user_stake_info.base_lxr_holdings
-        .checked_add(lxr_to_purchase).unwrap();
+        .checked_add(lxr_to_purchase).ok_or(ErrorCode::MathOverflow)?;
 
     // --- Transfer purchased LXR from treasury vault to user ATA ---
     transfer_from_pool_vault_to_user(
@@ -399,8 +357,14 @@ pub fn purchase(ctx: Context<Purchase>, lxr_to_purchase: u64, max_sol_amount: u6
         purchaser: ctx.accounts.owner.key(),
         sol_amount: total_sol_needed,
         lxr_amount: lxr_to_purchase,
+        remaining_stake_capacity: ctx
+            .accounts
+            .global_config
+            .remaining_stake_capacity(ctx.accounts.stake_info.total_staked_sol),
     });
 
+    set_return_data(&(total_sol_needed, lxr_to_purchase).try_to_vec()?);
+
     Ok(())
 }
 
@@ -0,0 +1,301 @@
+use crate::error::ErrorCode;
+use crate::pricing::quote_sol_for_lxr;
+use crate::states::*;
+use crate::utils::{create_pda_account, transfer_from_pool_vault_to_user};
+use crate::{AUTH_SEED, STAKE_ACCOUNT_SEED};
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_lang::solana_program::stake;
+use anchor_lang::solana_program::stake::instruction as stake_ix;
+use anchor_lang::solana_program::stake::state::{Authorized, Lockup, StakeStateV2};
+use anchor_lang::{prelude::*, solana_program};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use std::mem::size_of;
+
+/// Accounts required for purchasing LXR into an **isolated** stake account.
+///
+/// Opt-in alternative to `purchase`: instead of funding the shared `stake_pda`,
+/// the SOL is delegated from a per-user stake account derived from
+/// `STAKE_ACCOUNT_SEED + owner`. The stake is kept out of the shared accounting
+/// (`total_staked_sol`, reward indices, `UserStakeInfo`): its epoch rewards
+/// compound in the account itself and are returned with the principal by
+/// `unstake_isolated`, which first takes the purchased LXR back (recorded in
+/// `isolated_stake_info`). Only one isolated stake can be open per user at a time.
+#[derive(Accounts)]
+pub struct PurchaseIsolated<'info> {
+    /// User paying SOL (stake + rent) and receiving LXR.
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Global configuration; purchase must be enabled.
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED.as_bytes()],
+        bump,
+        constraint = global_config.purchase_enabled @ ErrorCode::PurchaseDisabled,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Program treasury vault that holds LUXOR to be sold to users.
+    #[account(
+        mut,
+        address = global_config.lxr_treasury_vault,
+    )]
+    pub luxor_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Global staking aggregates (read for pricing only).
+    #[account(
+        address = global_config.stake_info,
+        constraint = !stake_info.stake_closed @ ErrorCode::StakeClosed,
+    )]
+    pub stake_info: Account<'info, StakeInfo>,
+
+    /// Program authority PDA (staker & withdrawer of the isolated stake account).
+    ///
+    /// CHECK: PDA derivation is enforced by seeds; used as a signing PDA.
+    #[account(
+        seeds = [AUTH_SEED.as_bytes()],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// Canonical LUXOR mint (decimals must match the value recorded at init).
+    #[account(
+        address = crate::luxor_mint::id(),
+        constraint = crate::utils::luxor_decimals_match(luxor_mint.decimals) @ ErrorCode::MintDecimalsMismatch,
+    )]
+    pub luxor_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Payer's LXR ATA; created if missing so they can receive purchased LXR.
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = luxor_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+    )]
+    pub owner_lxr_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The user's isolated stake account (created, initialized and delegated here).
+    ///
+    /// CHECK: PDA seeds ensure derivation; must still be system-owned (no isolated stake open).
+    #[account(
+        mut,
+        seeds = [
+            STAKE_ACCOUNT_SEED.as_bytes(),
+            owner.key().as_ref()
+        ],
+        bump
+    )]
+    pub user_stake_pda: UncheckedAccount<'info>,
+
+    /// Record of the isolated stake and the LXR sold against it.
+    #[account(
+        init,
+        seeds = [
+            ISOLATED_STAKE_INFO_SEED.as_bytes(),
+            owner.key().as_ref()
+        ],
+        bump,
+        payer = owner,
+        space = IsolatedStakeInfo::LEN
+    )]
+    pub isolated_stake_info: Account<'info, IsolatedStakeInfo>,
+
+    /// Target validator vote account to which stake is delegated.
+    ///
+    /// CHECK: Externally provided, validated by CPI to Stake program.
+    #[account(address = global_config.vote_account)]
+    pub vote_account: UncheckedAccount<'info>,
+
+    /// Stake program (CPI target).
+    ///
+    /// CHECK: Program ID only.
+    #[account(address = stake::program::ID)]
+    pub stake_program: UncheckedAccount<'info>,
+
+    /// Clock sysvar required by `delegate_stake`.
+    pub clock: Sysvar<'info, Clock>,
+
+    /// Stake history sysvar required by `delegate_stake`.
+    pub stake_history: Sysvar<'info, StakeHistory>,
+
+    /// Stake config account required by `delegate_stake` (fixed program address).
+    ///
+    /// CHECK: Program ID only.
+    #[account(address = solana_program::stake::config::ID)]
+    pub stake_config: UncheckedAccount<'info>,
+
+    /// Rent sysvar (required by Stake::Initialize).
+    pub rent: Sysvar<'info, Rent>,
+
+    /// Raydium pool state used to compute swap price for LXR in SOL terms.
+    ///
+    /// CHECK: Address pinned via `luxor_pool_state::id()`.
+    #[account(
+        address = crate::luxor_pool_state::id()
+    )]
+    pub pool_state: UncheckedAccount<'info>,
+
+    /// Pool vault for token_0 (pricing input).
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Pool vault for token_1 (pricing output).
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL Token-2022 interface program.
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Associated Token Program (for ATA creation).
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// System Program (for creating the isolated stake account).
+    pub system_program: Program<'info, System>,
+}
+
+/// Purchase LXR with SOL staked from the caller's own isolated stake account.
+///
+/// # Steps
+/// 1. Same bounds, treasury and pricing checks as `purchase` (via `quote_sol_for_lxr`).
+///    Isolated stakes never count as new stakers, so the early-bird bonus does not apply.
+/// 2. Create `user_stake_pda` funded with `total_sol_needed + rent` (paid by the user),
+///    initialize it with the authority PDA as staker/withdrawer and delegate it to
+///    `vote_account`.
+/// 3. Record the stake and `lxr_to_purchase` in `isolated_stake_info`, transfer
+///    the LXR from the treasury vault and emit `IsolatedStakePurchased`.
+///
+/// No shared aggregate is touched: the stake earns SOL rewards in place and no
+/// LXR rewards. The LXR is only held while the stake is: `unstake_isolated`
+/// returns it to the treasury.
+///
+/// # Fails
+/// - `DeadlineExceeded` if the cluster time is past `deadline`.
+/// - `PurchaseDisabled`, `SwapAmountOutOfBounds`, `TreasuryBelowMinimum`, `TreasuryNotFunded`
+///   and the pricing errors of `purchase`.
+/// - `IsolatedStakeExists` if the user already has an open isolated stake.
+/// - `require_*` guards for slippage (`max_sol_amount`) and pool addresses.
+pub fn purchase_isolated(
+    ctx: Context<PurchaseIsolated>,
+    lxr_to_purchase: u64,
+    max_sol_amount: u64,
+    deadline: i64,
+) -> Result<()> {
+    require!(Clock::get()?.unix_timestamp <= deadline, ErrorCode::DeadlineExceeded);
+    require_gt!(lxr_to_purchase, 0);
+
+    // Pause purchases while the treasury sits below the configured floor
+    // (the error logs both amounts).
+    require_gte!(
+        ctx.accounts.luxor_vault.amount,
+        ctx.accounts.global_config.min_treasury_for_purchase,
+        ErrorCode::TreasuryBelowMinimum
+    );
+    require_gte!(
+        ctx.accounts.luxor_vault.amount,
+        lxr_to_purchase,
+        ErrorCode::TreasuryNotFunded
+    );
+
+    require_keys_eq!(
+        *ctx.accounts.user_stake_pda.owner,
+        ctx.accounts.system_program.key(),
+        ErrorCode::IsolatedStakeExists
+    );
+
+    // --- 1. Pricing ---
+    let pool_state = PoolState::try_deserialize(&mut &ctx.accounts.pool_state.data.borrow()[..])?;
+    require_keys_eq!(pool_state.token_0_vault, ctx.accounts.token_0_vault.key());
+    require_keys_eq!(pool_state.token_1_vault, ctx.accounts.token_1_vault.key());
+
+    let total_sol_needed = quote_sol_for_lxr(
+        &pool_state,
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.amount,
+        lxr_to_purchase,
+        &ctx.accounts.stake_info,
+        &ctx.accounts.global_config,
+        false,
+        ctx.accounts.luxor_vault.amount,
+    )?;
+    msg!("total_sol_needed: {}", total_sol_needed);
+
+    // Slippage/limit check from the payer (rent is charged on top).
+    require_gte!(max_sol_amount, total_sol_needed);
+    ctx.accounts.global_config.check_purchase_bounds(lxr_to_purchase, total_sol_needed)?;
+
+    // --- 2. Create, initialize and delegate the isolated stake account ---
+    let space = size_of::<StakeStateV2>();
+    let min_rent = ctx.accounts.global_config.stake_split_rent()?;
+
+    let owner_key = ctx.accounts.owner.key();
+    let stake_bump = ctx.bumps.user_stake_pda;
+    let stake_seeds: &[&[u8]] = &[STAKE_ACCOUNT_SEED.as_bytes(), owner_key.as_ref(), &[stake_bump]];
+
+    create_pda_account(
+        &ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.user_stake_pda.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        min_rent.checked_add(total_sol_needed).ok_or(ErrorCode::MathOverflow)?,
+        space as u64,
+        &stake::program::ID,
+        &[stake_seeds],
+    )?;
+
+    let authorized = Authorized {
+        staker: ctx.accounts.authority.key(),
+        withdrawer: ctx.accounts.authority.key(),
+    };
+    let init_ix = stake_ix::initialize(&ctx.accounts.user_stake_pda.key(), &authorized, &Lockup::default());
+    invoke(
+        &init_ix,
+        &[
+            ctx.accounts.user_stake_pda.to_account_info(),
+            ctx.accounts.rent.to_account_info(),
+        ],
+    )?;
+
+    let auth_seeds: &[&[u8]] = &[AUTH_SEED.as_bytes(), &[ctx.bumps.authority]];
+    let delegate_ix = stake_ix::delegate_stake(
+        &ctx.accounts.user_stake_pda.key(),
+        &ctx.accounts.authority.key(),
+        &ctx.accounts.vote_account.key(),
+    );
+    invoke_signed(
+        &delegate_ix,
+        &[
+            ctx.accounts.user_stake_pda.to_account_info(),
+            ctx.accounts.vote_account.to_account_info(),
+            ctx.accounts.clock.to_account_info(),
+            ctx.accounts.stake_history.to_account_info(),
+            ctx.accounts.stake_config.to_account_info(),
+            ctx.accounts.authority.to_account_info(),
+        ],
+        &[auth_seeds],
+    )?;
+
+    // --- 3. Record the stake, then transfer purchased LXR from treasury vault to user ATA ---
+    let isolated_stake_info = &mut ctx.accounts.isolated_stake_info;
+    isolated_stake_info.bump = ctx.bumps.isolated_stake_info;
+    isolated_stake_info.owner = owner_key;
+    isolated_stake_info.sol_amount = total_sol_needed;
+    isolated_stake_info.lxr_amount = lxr_to_purchase;
+
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.luxor_vault.to_account_info(),
+        ctx.accounts.owner_lxr_token.to_account_info(),
+        ctx.accounts.luxor_mint.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        lxr_to_purchase,
+        ctx.accounts.luxor_mint.decimals,
+        &[auth_seeds],
+    )?;
+
+    emit!(IsolatedStakePurchased {
+        purchaser: owner_key,
+        stake_account: ctx.accounts.user_stake_pda.key(),
+        sol_amount: total_sol_needed,
+        lxr_amount: lxr_to_purchase,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use crate::error::ErrorCode;
+use crate::instructions::EmergencyOperation;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+/// Accounts context for `queue_emergency`.
+///
+/// Admin-only. Records the `emergency_withdraw` call the admin intends to make,
+/// which becomes executable after `global_config.emergency_delay` seconds.
+#[derive(Accounts)]
+pub struct QueueEmergency<'info> {
+    /// Admin (must match `global_config.admin` or program admin); pays rent on first use.
+    #[account(
+        mut,
+        constraint = (owner.key() == global_config.admin || owner.key() == crate::admin::id()) @ ErrorCode::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    /// Global protocol configuration (holds `emergency_delay`).
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Queued emergency action (derived by PENDING_EMERGENCY_SEED). Created if missing.
+    #[account(
+        init_if_needed,
+        seeds = [PENDING_EMERGENCY_SEED.as_bytes()],
+        bump,
+        payer = owner,
+        space = PendingEmergency::LEN
+    )]
+    pub pending_emergency: Account<'info, PendingEmergency>,
+
+    /// System Program (payer/rent).
+    pub system_program: Program<'info, System>,
+}
+
+/// Queues `emergency_withdraw(param, value)` for execution after
+/// `global_config.emergency_delay` seconds and emits `EmergencyQueued`.
+///
+/// For `RotateStakeAuthority` the new authority is read from `remaining_accounts[0]`,
+/// `value` must be `0`, and `rotate_stake_authority` must later be called with that
+/// same key.
+///
+/// Replaces any entry queued earlier, so a re-queue restarts the delay.
+///
+/// # Fails
+/// - `InvalidOwner` if the signer is not an admin.
+/// - `InvalidParam` if `param` is not an `EmergencyOperation`, or on a non-zero
+///   `value` for `RotateStakeAuthority`.
+/// - `MissingRemainingAccount` if `RotateStakeAuthority` is queued without its target.
+pub fn queue_emergency(ctx: Context<QueueEmergency>, param: u8, value: u64) -> Result<()> {
+    let target = match EmergencyOperation::try_from(param)? {
+        EmergencyOperation::RotateStakeAuthority => {
+            require!(value == 0, ErrorCode::InvalidParam);
+            let target = *ctx
+                .remaining_accounts
+                .iter()
+                .next()
+                .ok_or(error!(ErrorCode::MissingRemainingAccount))?
+                .key;
+            require_keys_neq!(target, Pubkey::default());
+            target
+        }
+        _ => Pubkey::default(),
+    };
+
+    let execute_after = (Clock::get()?.unix_timestamp as u64)
+        .checked_add(ctx.accounts.global_config.emergency_delay)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let pending_emergency = &mut ctx.accounts.pending_emergency;
+    pending_emergency.bump = ctx.bumps.pending_emergency;
+    pending_emergency.param = param;
+    pending_emergency.value = value;
+    pending_emergency.execute_after = execute_after;
+    pending_emergency.target = target;
+
+    emit!(EmergencyQueued {
+        param,
+        value,
+        target,
+        execute_after,
+        admin: ctx.accounts.owner.key(),
+    });
+
+    Ok(())
+}
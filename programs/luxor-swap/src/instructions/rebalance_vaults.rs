@@ -0,0 +1,106 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::transfer_from_pool_vault_to_user;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+/// `rebalance_vaults` selector for the LXR treasury vault (`global_config.lxr_treasury_vault`).
+pub const REBALANCE_VAULT_TREASURY: u8 = 0;
+
+/// `rebalance_vaults` selector for the reward vault (`global_config.lxr_reward_vault`).
+pub const REBALANCE_VAULT_REWARD: u8 = 1;
+
+/// Accounts context for `rebalance_vaults`.
+///
+/// Moves a chosen amount of LXR between the treasury and reward vaults, as a
+/// targeted alternative to draining a vault with `emergency_withdraw` param `0`.
+#[derive(Accounts)]
+pub struct RebalanceVaults<'info> {
+    /// Admin (must match `global_config.admin` or program admin).
+    #[account(
+        constraint = (owner.key() == global_config.admin || owner.key() == crate::admin::id()) @ ErrorCode::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    /// Global protocol configuration; rewards must be paid in LXR.
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED.as_bytes()],
+        bump,
+        constraint = global_config.rewards_in_lxr() @ ErrorCode::InvalidRewardMint,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Program authority PDA (authority of both vaults).
+    ///
+    /// CHECK: PDA derivation enforced by seeds; used only as signer for CPIs.
+    #[account(
+        seeds = [crate::AUTH_SEED.as_bytes()],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// LXR treasury vault.
+    #[account(mut, address = global_config.lxr_treasury_vault)]
+    pub luxor_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// LXR reward vault.
+    #[account(mut, address = global_config.lxr_reward_vault)]
+    pub luxor_reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Canonical LXR mint (decimals must match the value recorded at init).
+    #[account(
+        address = crate::luxor_mint::id() @ ErrorCode::InvalidLuxorMint,
+        constraint = crate::utils::luxor_decimals_match(luxor_mint.decimals) @ ErrorCode::MintDecimalsMismatch,
+    )]
+    pub luxor_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Token program interface.
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Transfers `amount` LXR from vault `from` to vault `to`
+/// (`REBALANCE_VAULT_TREASURY` / `REBALANCE_VAULT_REWARD`) and emits `VaultsRebalanced`.
+///
+/// Unredeemed rewards are paid from the reward vault; moving LXR out of it can
+/// leave `redeem` short until a buyback or `airdrop_lxr` refills it.
+///
+/// # Fails
+/// - `InvalidOwner` if the signer is not an admin.
+/// - `InvalidRewardMint` if the reward vault does not hold LXR.
+/// - `InvalidParam` if `from` or `to` is not a vault selector.
+/// - `InvalidVault` if `from == to`.
+/// - `InsufficientVault` if the source vault holds less than `amount`.
+pub fn rebalance_vaults(ctx: Context<RebalanceVaults>, from: u8, to: u8, amount: u64) -> Result<()> {
+    require_gt!(amount, 0);
+    require!(
+        from <= REBALANCE_VAULT_REWARD && to <= REBALANCE_VAULT_REWARD,
+        ErrorCode::InvalidParam
+    );
+    require_neq!(from, to, ErrorCode::InvalidVault);
+
+    let (source, destination) = match from {
+        REBALANCE_VAULT_TREASURY => (&ctx.accounts.luxor_vault, &ctx.accounts.luxor_reward_vault),
+        _ => (&ctx.accounts.luxor_reward_vault, &ctx.accounts.luxor_vault),
+    };
+    require_gte!(source.amount, amount, ErrorCode::InsufficientVault);
+
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        source.to_account_info(),
+        destination.to_account_info(),
+        ctx.accounts.luxor_mint.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        amount,
+        ctx.accounts.luxor_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]]],
+    )?;
+
+    emit!(VaultsRebalanced {
+        from,
+        to,
+        amount,
+        source_balance: source.amount - amount,
+    });
+
+    Ok(())
+}
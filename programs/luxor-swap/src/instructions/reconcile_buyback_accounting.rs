@@ -0,0 +1,69 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+/// Accounts context for `reconcile_buyback_accounting`.
+///
+/// Admin safety valve for when the SOL reward accounting has drifted from the
+/// stake PDA (e.g. after an emergency withdrawal), leaving
+/// `total_sol_rewards_accrued - total_sol_used_for_buyback` wrong for future
+/// buybacks.
+#[derive(Accounts)]
+pub struct ReconcileBuybackAccounting<'info> {
+    /// Admin (must match `global_config.admin` or program admin).
+    #[account(
+        constraint = (owner.key() == global_config.admin || owner.key() == crate::admin::id()) @ ErrorCode::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    /// Global protocol configuration.
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Global staking state holding the buyback totals.
+    #[account(
+        mut,
+        address = global_config.stake_info,
+    )]
+    pub stake_info: Account<'info, StakeInfo>,
+}
+
+/// Overwrites `total_sol_used_for_buyback` and `total_sol_rewards_accrued` with
+/// corrected values and emits `BuybackAccountingReconciled`.
+///
+/// Reward indices and `total_sol_rewards_reserved` are left untouched; only the
+/// buyback budget is affected.
+///
+/// # Fails
+/// - `InvalidParam` if `sol_used_for_buyback + total_sol_rewards_reserved` exceeds
+///   `sol_rewards_accrued`, which would leave `sol_available_to_buyback` underflowing.
+pub fn reconcile_buyback_accounting(
+    ctx: Context<ReconcileBuybackAccounting>,
+    sol_used_for_buyback: u64,
+    sol_rewards_accrued: u64,
+) -> Result<()> {
+    let stake_info = &mut ctx.accounts.stake_info;
+    let committed = sol_used_for_buyback
+        .checked_add(stake_info.total_sol_rewards_reserved)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require_gte!(sol_rewards_accrued, committed, ErrorCode::InvalidParam);
+
+    let old_used = stake_info.total_sol_used_for_buyback;
+    let old_accrued = stake_info.total_sol_rewards_accrued;
+
+    stake_info.total_sol_used_for_buyback = sol_used_for_buyback;
+    stake_info.total_sol_rewards_accrued = sol_rewards_accrued;
+    stake_info.last_update_timestamp = Clock::get()?.unix_timestamp as u64;
+
+    emit!(BuybackAccountingReconciled {
+        old_used,
+        new_used: sol_used_for_buyback,
+        old_accrued,
+        new_accrued: sol_rewards_accrued,
+    });
+
+    Ok(())
+}
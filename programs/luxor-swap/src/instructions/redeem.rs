@@ -1,26 +1,41 @@
 use anchor_lang::{prelude::*};
-use crate::{states::{GlobalConfig, RewardsCollected, StakeInfo, UserStakeInfo, GLOBAL_CONFIG_SEED, USER_STAKE_INFO_SEED}, utils::transfer_from_pool_vault_to_user, PRECISION};
+use crate::{states::{ForfeitureRedistributed, GlobalConfig, RewardsCollected, StakeInfo, UserStakeInfo, GLOBAL_CONFIG_SEED, USER_STAKE_INFO_SEED}, utils::transfer_from_pool_vault_to_user};
 use anchor_spl::{associated_token::AssociatedToken, token_interface::{Mint, TokenAccount, TokenInterface}};
 use crate::error::ErrorCode;
+use crate::PRECISION;
 
 /// Redeem pending LXR rewards accrued from staking SOL.
 ///
 /// Reward math overview:
 /// - Global index: `stake_info.reward_per_token_lxr_stored` accumulates LXR-per-staked-SOL,
-///   scaled by `PRECISION * PRECISION`.
+///   scaled by `PRECISION`.
 /// - Per-user checkpoint: `user_stake_info.lxr_reward_per_token_completed`
-///   stores the index at the user's last claim.
-/// - Pending = `(user.total_staked_sol * (global_idx - user_idx)) / PRECISION / PRECISION`.
+///   stores the index at the user's last claim (rescaled first if a
+///   `migrate_reward_scale` happened since).
+/// - Pending = `(user.total_staked_sol * (global_idx - user_idx)) / PRECISION`.
 ///
 /// Forfeiture (anti-dilution) rule:
 /// - If the user's current LXR balance (`owner_lxr_token.amount`) is **below**
 ///   their recorded base holdings (`user.base_lxr_holdings`), their claimable rewards
 ///   are **pro-rated** by the ratio `current / base`, and the difference is **forfeited**.
-/// - Forfeited rewards are transferred to treasury (`luxor_vault`).
+/// - Balances within `global_config.forfeiture_grace_bps` of the base count as full.
+/// - Forfeited rewards are transferred to treasury (`luxor_vault`), or redistributed to
+///   the other stakers when `global_config.redistribute_forfeitures` is set. When rewards
+///   are paid in a mint other than LUXOR the treasury cannot hold them, so they are always
+///   redistributed (or, with nobody else staked, left in the reward vault).
+///
+/// Minimum hold rule (stricter, checked first):
+/// - With `global_config.min_hold_ratio_bps` set, a balance below that share of
+///   `base_lxr_holdings` fails the redeem with `NoRewardsToClaim`; nothing is forfeited
+///   and the rewards stay claimable once holdings recover. Above it, the pro-rata rule
+///   applies as usual.
 ///
 /// Funds movement:
-/// - Claimable LXR moves from `luxor_reward_vault` → user ATA.
+/// - Claimable rewards (`global_config.reward_mint`) move from `luxor_reward_vault` → user ATA.
 /// - Forfeited LXR (if any) moves from `luxor_reward_vault` → `luxor_vault` (treasury).
+///
+/// Holdings are always read from the user's LUXOR ATA, which must exist when rewards
+/// are paid in another mint.
 #[derive(Accounts)]
 pub struct Redeem<'info> {
     /// User claiming rewards (payer for ATA creation if needed).
@@ -31,7 +46,7 @@ pub struct Redeem<'info> {
     #[account(
         seeds = [GLOBAL_CONFIG_SEED.as_bytes()],
         bump,
-        constraint = global_config.redeem_enabled @ ErrorCode::PurchaseDisabled,
+        constraint = global_config.redeem_enabled @ ErrorCode::RedeemDisabled,
     )]
     pub global_config: Account<'info, GlobalConfig>,
 
@@ -70,14 +85,30 @@ pub struct Redeem<'info> {
     #[account(mut,address = global_config.lxr_reward_vault)]
     pub luxor_reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    /// Canonical LXR mint.
-    #[account(address = crate::luxor_mint::id() @ ErrorCode::InvalidLuxorMint)]
+    /// Canonical LXR mint (decimals must match the value recorded at init).
+    #[account(
+        address = crate::luxor_mint::id() @ ErrorCode::InvalidLuxorMint,
+        constraint = crate::utils::luxor_decimals_match(luxor_mint.decimals) @ ErrorCode::MintDecimalsMismatch,
+    )]
     pub luxor_mint: Box<InterfaceAccount<'info, Mint>>,
 
-    /// User's LXR ATA; created on demand to receive rewards.
+    /// Reward mint (`global_config.reward_mint`).
+    #[account(address = global_config.reward_mint @ ErrorCode::InvalidRewardMint)]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// User's reward ATA; created on demand to receive rewards.
     #[account(
         init_if_needed,
         payer = owner,
+        associated_token::mint = reward_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program, 
+    )]
+    pub owner_reward_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// User's LXR ATA (holdings for the forfeiture rule). Same account as
+    /// `owner_reward_token` when rewards are paid in LUXOR.
+    #[account(
         associated_token::mint = luxor_mint,
         associated_token::authority = owner,
         associated_token::token_program = token_program, 
@@ -101,76 +132,160 @@ pub struct Redeem<'info> {
 /// 1) Compute pending index delta: `reward_per_token_lxr_pending`.
 /// 2) Calculate `lxr_rewards_to_claim` using user's `total_staked_sol`.
 /// 3) If user's current LXR < base holdings, pro-rate rewards; track `forfieted_lxr`.
-/// 4) Add any `lxr_rewards_pending` already owed to the user.
-/// 5) Update user & global tallies and indices.
+/// 4) Add any `lxr_rewards_pending` already owed to the user. With `amount = Some(a)`,
+///    only `min(a, claimable)` is paid; the rest stays in `lxr_rewards_pending`.
+/// 5) Update user & global tallies and indices. With `redistribute_forfeitures` on (and
+///    anyone else staked), the forfeited LXR bumps `reward_per_token_lxr_stored` over the
+///    other stakers instead, is counted in `total_forfeiture_redistributed` and
+///    `ForfeitureRedistributed` is emitted. A forfeiture that can go neither to the other
+///    stakers nor to the treasury (a non-LXR `reward_mint` with no one else staked) is
+///    held in `lxr_rewards_undistributed` for the next buyback to credit.
 /// 6) Transfer claimable LXR from rewards vault to user.
-/// 7) Transfer forfeited LXR (if any) from rewards vault to treasury.
+/// 7) Transfer forfeited LXR (if any, and not redistributed) from rewards vault to treasury.
 /// 8) Emit `RewardsCollected`.
-pub fn redeem(ctx: Context<Redeem>) -> Result<()> {
+///
+/// A partial claim still settles the whole fresh entitlement: the checkpoint moves to the
+/// current index and forfeiture is applied to all of it, so the unpaid remainder is carried
+/// over already net of forfeiture and is not pro-rated again later. Partial claims
+/// therefore add up to exactly what a single full claim would have paid.
+///
+/// Zero stake: a user whose `total_staked_sol` dropped to zero (typically after
+/// unstaking everything, which checkpoints into `lxr_rewards_pending`) earns no fresh
+/// rewards, but the carryover stays claimable here. It is paid in full without
+/// pro-rating, and only the minimum hold gate still applies. `finalize_blacklist`
+/// moves the carryover to the admin record, so a blacklisted user has none left.
+///
+/// # Fails
+/// - `BlacklistAlreadyProposed` if a blacklist is pending for the user, so the rewards
+///   `finalize_blacklist` would move cannot be drained during the dispute window.
+/// - `UnderflowError` if the user's checkpoint is ahead of the global index.
+/// - `NoRewardsToClaim` if the index has not moved since the checkpoint and
+///   nothing is carried over, or if `amount` is `Some(0)`.
+pub fn redeem(ctx: Context<Redeem>, amount: Option<u64>) -> Result<()> {
     let user_stake_info = &mut ctx.accounts.user_stake_info;
     let stake_info = &mut ctx.accounts.stake_info;
 
-    // --- 1) Pending index delta (must be positive) ---
-    let reward_per_token_lxr_pending = stake_info.reward_per_token_lxr_stored
-        .checked_sub(user_stake_info.lxr_reward_per_token_completed)
-        .unwrap();
-
-    // --- 2) Base rewards = stake * delta_index, scaled down by PRECISION^2 ---
-    let mut lxr_rewards_to_claim = (user_stake_info.total_staked_sol as u128)
-        .checked_mul(reward_per_token_lxr_pending).unwrap()
-        .checked_div(PRECISION).unwrap() as u64;
+    require!(
+        user_stake_info.blacklist_pending_timestamp == 0,
+        ErrorCode::BlacklistAlreadyProposed
+    );
 
-    // --- 3) Forfeiture if current holdings < base holdings ---
-    let mut forfieted_lxr = 0;
-    if ctx.accounts.owner_lxr_token.amount < user_stake_info.base_lxr_holdings {
-        let lxr_holdings = ctx.accounts.owner_lxr_token.amount;
-        let full_rewards = lxr_rewards_to_claim;
+    // --- 1-2) Base rewards = stake * delta_index, scaled down by PRECISION ---
+    // Zero with no stake; the carryover alone is then enough to redeem.
+    let full_rewards = user_stake_info.pending_lxr_rewards(stake_info)?;
+    require!(
+        full_rewards > 0 || user_stake_info.lxr_rewards_pending > 0,
+        ErrorCode::NoRewardsToClaim
+    );
 
-        // Pro-rate rewards by current/base ratio
-        lxr_rewards_to_claim = (lxr_holdings as u128)
-            .checked_mul(lxr_rewards_to_claim as u128).unwrap()
-            .checked_div(user_stake_info.base_lxr_holdings as u128).unwrap() as u64;
-
-        forfieted_lxr = full_rewards.checked_sub(lxr_rewards_to_claim).unwrap(); 
-    }
+    // --- 3) Minimum hold gate, then forfeiture if current holdings < base holdings (pro-rated by current/base) ---
+    require!(
+        user_stake_info.meets_min_hold(
+            ctx.accounts.owner_lxr_token.amount,
+            ctx.accounts.global_config.min_hold_ratio_bps,
+        )?,
+        ErrorCode::NoRewardsToClaim
+    );
+    let (mut lxr_rewards_to_claim, forfieted_lxr) = user_stake_info
+        .apply_holdings_forfeiture(
+            full_rewards,
+            ctx.accounts.owner_lxr_token.amount,
+            ctx.accounts.global_config.forfeiture_grace_bps,
+        )?;
+    // Truncation may only shift dust to the forfeited side; no LXR is created or lost.
+    require_eq!(
+        lxr_rewards_to_claim.checked_add(forfieted_lxr).ok_or(ErrorCode::MathOverflow)?,
+        full_rewards
+    );
 
-    // --- 4) Include any pending carryover ---
-    lxr_rewards_to_claim = lxr_rewards_to_claim.checked_add(user_stake_info.lxr_rewards_pending).unwrap();
+    // --- 4) Include any pending carryover, then cap to the requested amount ---
+    let full_claimable = lxr_rewards_to_claim.checked_add(user_stake_info.lxr_rewards_pending).ok_or(ErrorCode::MathOverflow)?;
+    lxr_rewards_to_claim = amount.map_or(full_claimable, |amount| amount.min(full_claimable));
+    let lxr_rewards_remaining = full_claimable.checked_sub(lxr_rewards_to_claim).ok_or(ErrorCode::UnderflowError)?;
 
     require!(lxr_rewards_to_claim > 0, ErrorCode::NoRewardsToClaim);
 
     // --- 5) Update user & global tallies and indices ---
     // User updates
-    user_stake_info.total_lxr_claimed = user_stake_info.total_lxr_claimed.checked_add(lxr_rewards_to_claim).unwrap();
-    user_stake_info.total_lxr_forfeited = user_stake_info.total_lxr_forfeited.checked_add(forfieted_lxr).unwrap();
+    user_stake_info.total_lxr_claimed = user_stake_info.total_lxr_claimed.checked_add(lxr_rewards_to_claim).ok_or(ErrorCode::MathOverflow)?;
+    user_stake_info.total_lxr_forfeited = user_stake_info.total_lxr_forfeited.checked_add(forfieted_lxr).ok_or(ErrorCode::MathOverflow)?;
     user_stake_info.lxr_reward_per_token_completed = stake_info.reward_per_token_lxr_stored;
-    user_stake_info.lxr_rewards_pending = 0;
+    user_stake_info.lxr_rewards_pending = lxr_rewards_remaining;
+
+    // Redistribution mode: forfeited LXR stays in the reward vault and is credited to
+    // everyone else's stake; the redeemer must not share in their own forfeiture.
+    let other_staked_sol = stake_info.total_staked_sol.saturating_sub(user_stake_info.total_staked_sol);
+    let redistribute = ctx.accounts.global_config.redistribute_forfeitures || !ctx.accounts.global_config.rewards_in_lxr();
+    let redistributed_lxr = if redistribute && other_staked_sol > 0 {
+        forfieted_lxr
+    } else {
+        0
+    };
+    let lxr_to_treasury = if ctx.accounts.global_config.rewards_in_lxr() {
+        forfieted_lxr.checked_sub(redistributed_lxr).ok_or(ErrorCode::UnderflowError)?
+    } else {
+        0
+    };
+    // Other reward mints cannot go to the treasury; with no one else to credit they
+    // stay in the reward vault and the next buyback adds them to the index.
+    let lxr_held = forfieted_lxr
+        .checked_sub(redistributed_lxr)
+        .ok_or(ErrorCode::UnderflowError)?
+        .checked_sub(lxr_to_treasury)
+        .ok_or(ErrorCode::UnderflowError)?;
+    if lxr_held > 0 {
+        stake_info.lxr_rewards_undistributed = stake_info
+            .lxr_rewards_undistributed
+            .checked_add(lxr_held)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+    if redistributed_lxr > 0 {
+        stake_info.reward_per_token_lxr_stored = stake_info
+            .reward_per_token_lxr_stored
+            .checked_add(
+                (redistributed_lxr as u128)
+                    .checked_mul(PRECISION)
+                    .ok_or(ErrorCode::MathOverflow)?
+                    .checked_div(other_staked_sol as u128)
+                    .ok_or(ErrorCode::MathOverflow)?,
+            )
+            .ok_or(ErrorCode::MathOverflow)?;
+        user_stake_info.lxr_reward_per_token_completed = stake_info.reward_per_token_lxr_stored;
+        stake_info.total_forfeiture_redistributed = stake_info
+            .total_forfeiture_redistributed
+            .checked_add(redistributed_lxr)
+            .ok_or(ErrorCode::MathOverflow)?;
+        emit!(ForfeitureRedistributed {
+            amount: redistributed_lxr,
+            new_index: stake_info.reward_per_token_lxr_stored,
+        });
+    }
 
     // Global updates
-    stake_info.total_lxr_claimed = stake_info.total_lxr_claimed.checked_add(lxr_rewards_to_claim).unwrap();
-    stake_info.total_lxr_forfeited = stake_info.total_lxr_forfeited.checked_add(forfieted_lxr).unwrap();
+    stake_info.total_lxr_claimed = stake_info.total_lxr_claimed.checked_add(lxr_rewards_to_claim).ok_or(ErrorCode::MathOverflow)?;
+    stake_info.total_lxr_forfeited = stake_info.total_lxr_forfeited.checked_add(lxr_to_treasury).ok_or(ErrorCode::MathOverflow)?;
 
     // --- 6) Pay claimable rewards from reward vault → user ---
     transfer_from_pool_vault_to_user(
         ctx.accounts.authority.to_account_info(),
         ctx.accounts.luxor_reward_vault.to_account_info(),
-        ctx.accounts.owner_lxr_token.to_account_info(),
-        ctx.accounts.luxor_mint.to_account_info(),
+        ctx.accounts.owner_reward_token.to_account_info(),
+        ctx.accounts.reward_mint.to_account_info(),
         ctx.accounts.token_program.to_account_info(),
         lxr_rewards_to_claim,
-        ctx.accounts.luxor_mint.decimals,
+        ctx.accounts.reward_mint.decimals,
         &[&[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]]],
     )?;
 
-    // --- 7) Send forfeited rewards (if any) from reward vault → treasury ---
-    if forfieted_lxr > 0 {
+    // --- 7) Send forfeited rewards (if any, and not redistributed) from reward vault → treasury ---
+    if lxr_to_treasury > 0 {
         transfer_from_pool_vault_to_user(
         ctx.accounts.authority.to_account_info(),
         ctx.accounts.luxor_reward_vault.to_account_info(),
         ctx.accounts.luxor_vault.to_account_info(),
         ctx.accounts.luxor_mint.to_account_info(),
         ctx.accounts.token_program.to_account_info(),
-        forfieted_lxr,
+        lxr_to_treasury,
         ctx.accounts.luxor_mint.decimals,
         &[&[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]]],
         )?;
@@ -181,6 +296,7 @@ pub fn redeem(ctx: Context<Redeem>) -> Result<()> {
         collector: ctx.accounts.owner.key(),
         lxr_collected: lxr_rewards_to_claim,
         lxr_forfeited: forfieted_lxr,
+        lxr_remaining: lxr_rewards_remaining,
     });
 
     Ok(())
@@ -0,0 +1,165 @@
+use crate::error::ErrorCode;
+use crate::instructions::load_stake_state;
+use crate::states::*;
+use crate::AUTH_SEED;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::stake::instruction as stake_ix;
+use anchor_lang::solana_program::stake::state::StakeStateV2;
+use anchor_lang::{prelude::*, solana_program};
+
+/// Accounts context for `redelegate`.
+///
+/// Moves the protocol stake PDA to a new validator. The stake program refuses to
+/// re-point an effective delegation, so the switch takes two calls: the first
+/// deactivates the stake and records the new vote account; once the cooldown has
+/// passed, a second call delegates the now inactive stake to it. A `purchase`
+/// landing after the cooldown delegates to `global_config.vote_account` as well,
+/// so it completes the switch just the same.
+#[derive(Accounts)]
+pub struct Redelegate<'info> {
+    /// Admin (must match `global_config.admin` or program admin).
+    #[account(
+        constraint = (owner.key() == global_config.admin || owner.key() == crate::admin::id()) @ ErrorCode::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    /// Global protocol configuration; `vote_account` is updated here.
+    #[account(
+        mut,
+        seeds = [GLOBAL_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Global staking state (read for the buyback and closure guards).
+    #[account(
+        address = global_config.stake_info,
+        constraint = !stake_info.stake_closed @ ErrorCode::StakeClosed,
+    )]
+    pub stake_info: Account<'info, StakeInfo>,
+
+    /// Program authority PDA (staker of `stake_pda`).
+    ///
+    /// CHECK: PDA derivation enforced by seeds; used only as signer for CPIs.
+    #[account(
+        seeds = [AUTH_SEED.as_bytes()],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// Protocol stake account (PDA).
+    ///
+    /// CHECK: Address enforced via `global_config.stake_account`; state parsed in the handler.
+    #[account(
+        mut,
+        address = global_config.stake_account,
+    )]
+    pub stake_pda: UncheckedAccount<'info>,
+
+    /// Vote account of the validator to delegate to.
+    ///
+    /// CHECK: Must be owned by the Vote program; further validated by the Stake program CPI.
+    #[account(owner = solana_program::vote::program::ID @ ErrorCode::InvalidParam)]
+    pub new_vote_account: UncheckedAccount<'info>,
+
+    /// Clock sysvar required by `deactivate_stake` and `delegate_stake`.
+    pub clock: Sysvar<'info, Clock>,
+
+    /// Stake history sysvar required by `delegate_stake`.
+    pub stake_history: Sysvar<'info, StakeHistory>,
+
+    /// Stake config account required by `delegate_stake` (fixed program address).
+    ///
+    /// CHECK: Program ID only.
+    #[account(address = solana_program::stake::config::ID)]
+    pub stake_config: UncheckedAccount<'info>,
+
+    /// Stake program (CPI target).
+    ///
+    /// CHECK: Program ID only.
+    #[account(address = solana_program::stake::program::ID @ ErrorCode::InvalidStakeProgram)]
+    pub stake_program: UncheckedAccount<'info>,
+}
+
+/// Points the protocol stake at `new_vote_account` and emits `ValidatorRedelegated`.
+///
+/// - Effective and not deactivating: deactivate it; `delegated = false`.
+/// - Inactive (or never delegated): delegate it to the new vote account; `delegated = true`.
+/// - Deactivating: only the vote account is recorded; `delegated = false`.
+///
+/// `global_config.vote_account` is updated in every case, so purchases and
+/// buybacks target the new validator from now on. The stake earns no rewards
+/// between the deactivation and the delegation.
+///
+/// # Fails
+/// - `StakeNotRedelegatable` while the stake is still activating.
+/// - `BuybackAlreadyRequested` while a buyback split is outstanding.
+/// - `StakeClosed` once the stake PDA has been closed.
+pub fn redelegate(ctx: Context<Redelegate>) -> Result<()> {
+    require!(!ctx.accounts.stake_info.buyback_requested, ErrorCode::BuybackAlreadyRequested);
+
+    let auth_seeds: &[&[u8]] = &[AUTH_SEED.as_bytes(), &[ctx.bumps.authority]];
+    let new_vote_account = ctx.accounts.new_vote_account.key();
+    let old_vote_account = ctx.accounts.global_config.vote_account;
+
+    let (to_deactivate, to_delegate) = match load_stake_state(&ctx.accounts.stake_pda.to_account_info())? {
+        StakeStateV2::Stake(_, stake, _) => {
+            let status = stake.delegation.stake_activating_and_deactivating(
+                ctx.accounts.clock.epoch,
+                &*ctx.accounts.stake_history,
+                None,
+            );
+            require_eq!(status.activating, 0, ErrorCode::StakeNotRedelegatable);
+            let deactivating = stake.delegation.deactivation_epoch != u64::MAX;
+            (status.effective > 0 && !deactivating, status.effective == 0)
+        }
+        StakeStateV2::Initialized(_) => (false, true),
+        _ => return err!(ErrorCode::InvalidStakeAccountData),
+    };
+
+    if to_deactivate {
+        let deactivate_ix = stake_ix::deactivate_stake(
+            &ctx.accounts.stake_pda.key(),
+            &ctx.accounts.authority.key(),
+        );
+        invoke_signed(
+            &deactivate_ix,
+            &[
+                ctx.accounts.stake_pda.to_account_info(),
+                ctx.accounts.clock.to_account_info(),
+                ctx.accounts.authority.to_account_info(),
+            ],
+            &[auth_seeds],
+        )?;
+    }
+
+    if to_delegate {
+        let delegate_ix = stake_ix::delegate_stake(
+            &ctx.accounts.stake_pda.key(),
+            &ctx.accounts.authority.key(),
+            &new_vote_account,
+        );
+        invoke_signed(
+            &delegate_ix,
+            &[
+                ctx.accounts.stake_pda.to_account_info(),
+                ctx.accounts.new_vote_account.to_account_info(),
+                ctx.accounts.clock.to_account_info(),
+                ctx.accounts.stake_history.to_account_info(),
+                ctx.accounts.stake_config.to_account_info(),
+                ctx.accounts.authority.to_account_info(),
+            ],
+            &[auth_seeds],
+        )?;
+    }
+
+    ctx.accounts.global_config.vote_account = new_vote_account;
+
+    emit!(ValidatorRedelegated {
+        old_vote_account,
+        new_vote_account,
+        delegated: to_delegate,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use crate::error::ErrorCode;
+use crate::instructions::EmergencyOperation;
+use crate::states::{
+    EmergencyExecuted, GlobalConfig, PendingEmergency, StakeAuthorityRotated, GLOBAL_CONFIG_SEED,
+    PENDING_EMERGENCY_SEED,
+};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::stake;
+use anchor_lang::solana_program::stake::instruction as stake_ix;
+use anchor_lang::solana_program::stake::state::StakeAuthorize;
+use anchor_lang::solana_program::sysvar;
+
+/// Accounts context for `rotate_stake_authority`.
+///
+/// Recovery path for the protocol stake PDA: re-assigns both the staker and the
+/// withdrawer authority of `stake_pda` away from the current `authority` PDA.
+/// Only needed if the authority PDA derivation (`AUTH_SEED`) ever has to change
+/// after an upgrade; under normal operation the stored authorities already equal
+/// the `authority` PDA and this instruction is never called.
+///
+/// While `global_config.emergency_delay > 0` it is timelocked like
+/// `emergency_withdraw`: the rotation must be queued first.
+#[derive(Accounts)]
+pub struct RotateStakeAuthority<'info> {
+    /// Admin (must match `global_config.admin` or program admin).
+    #[account(
+        constraint = (owner.key() == global_config.admin || owner.key() == crate::admin::id()) @ ErrorCode::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    /// Global protocol configuration.
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Current program authority PDA (staker & withdrawer of `stake_pda`).
+    ///
+    /// CHECK: PDA derivation enforced by seeds; used only as signer for CPIs.
+    #[account(
+        seeds = [crate::AUTH_SEED.as_bytes()],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// Protocol stake account whose authorities are rotated.
+    ///
+    /// CHECK: Address enforced via `global_config.stake_account`.
+    #[account(mut, address = global_config.stake_account)]
+    pub stake_pda: UncheckedAccount<'info>,
+
+    /// CHECK: Clock sysvar (required by Stake::Authorize).
+    #[account(address = sysvar::clock::ID)]
+    pub clock: UncheckedAccount<'info>,
+
+    /// CHECK: Stake program ID (CPI target).
+    #[account(address = stake::program::ID @ ErrorCode::InvalidStakeProgram)]
+    pub stake_program: UncheckedAccount<'info>,
+
+    /// Queued emergency action; only read while `global_config.emergency_delay > 0`.
+    ///
+    /// CHECK: PDA derivation enforced by seeds; may not exist when no timelock is set.
+    #[account(
+        mut,
+        seeds = [PENDING_EMERGENCY_SEED.as_bytes()],
+        bump,
+    )]
+    pub pending_emergency: UncheckedAccount<'info>,
+}
+
+/// Sets `new_authority` as both staker and withdrawer of the protocol stake PDA.
+///
+/// # Notes
+/// - Both CPIs are signed by the current `authority` PDA.
+/// - After rotation the program can no longer delegate, split or withdraw the
+///   stake unless `new_authority` is a PDA this program can sign for; pass the
+///   new PDA of an upgraded program, never an arbitrary wallet.
+///
+/// # Timelock
+/// With `global_config.emergency_delay > 0`, `RotateStakeAuthority` must have been
+/// queued through `queue_emergency` with `new_authority` as its target at least that
+/// long ago. The entry is consumed and `EmergencyExecuted` is emitted; otherwise the
+/// call fails with `EmergencyNotQueued` or `EmergencyTimelockActive`.
+pub fn rotate_stake_authority(
+    ctx: Context<RotateStakeAuthority>,
+    new_authority: Pubkey,
+) -> Result<()> {
+    require_keys_neq!(new_authority, Pubkey::default());
+
+    let param = EmergencyOperation::RotateStakeAuthority as u8;
+    let executed_after = if ctx.accounts.global_config.emergency_delay > 0 {
+        Some(PendingEmergency::consume(
+            &ctx.accounts.pending_emergency,
+            param,
+            0,
+            new_authority,
+            Clock::get()?.unix_timestamp as u64,
+        )?)
+    } else {
+        None
+    };
+
+    let auth_bump = ctx.bumps.authority;
+    let seeds: &[&[u8]] = &[crate::AUTH_SEED.as_bytes(), &[auth_bump]];
+
+    for stake_authorize in [StakeAuthorize::Staker, StakeAuthorize::Withdrawer] {
+        let ix = stake_ix::authorize(
+            &ctx.accounts.stake_pda.key(),
+            &ctx.accounts.authority.key(),
+            &new_authority,
+            stake_authorize,
+            None,
+        );
+        invoke_signed(
+            &ix,
+            &[
+                ctx.accounts.stake_pda.to_account_info(),
+                ctx.accounts.clock.to_account_info(),
+                ctx.accounts.authority.to_account_info(),
+            ],
+            &[seeds],
+        )?;
+    }
+
+    emit!(StakeAuthorityRotated {
+        stake_account: ctx.accounts.stake_pda.key(),
+        old_authority: ctx.accounts.authority.key(),
+        new_authority,
+    });
+    if let Some(execute_after) = executed_after {
+        emit!(EmergencyExecuted {
+            param,
+            value: 0,
+            execute_after,
+        });
+    }
+
+    Ok(())
+}
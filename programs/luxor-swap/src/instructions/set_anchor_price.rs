@@ -0,0 +1,41 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+/// Accounts context for `set_anchor_price`.
+///
+/// Admin-set reference price for the purchase price band: while
+/// `max_price_deviation_bps` is non-zero, purchases are refused once the pool
+/// price drifts too far from it.
+#[derive(Accounts)]
+pub struct SetAnchorPrice<'info> {
+    /// Admin (must match `global_config.admin` or program admin).
+    #[account(
+        constraint = (owner.key() == global_config.admin || owner.key() == crate::admin::id()) @ ErrorCode::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    /// Global protocol configuration.
+    #[account(
+        mut,
+        seeds = [GLOBAL_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
+
+/// Stores `anchor_price_x32` (lamports per LXR base unit, Q32 as returned by
+/// `PoolState::token_price_x32`) and emits `AnchorPriceSet`. `0` clears the
+/// anchor, which disables the band regardless of `max_price_deviation_bps`.
+pub fn set_anchor_price(ctx: Context<SetAnchorPrice>, anchor_price_x32: u128) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+    let old_anchor_price_x32 = global_config.anchor_price_x32;
+    global_config.anchor_price_x32 = anchor_price_x32;
+
+    emit!(AnchorPriceSet {
+        old_anchor_price_x32,
+        anchor_price_x32,
+    });
+
+    Ok(())
+}
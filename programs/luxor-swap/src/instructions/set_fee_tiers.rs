@@ -0,0 +1,57 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+/// Accounts context for `set_fee_tiers`.
+///
+/// Admin-only. Creates or replaces the `FeeTiers` schedule that sizes the buyback
+/// treasury fee by the amount bought back.
+#[derive(Accounts)]
+pub struct SetFeeTiers<'info> {
+    /// Admin (must match `global_config.admin` or program admin); pays rent on first use.
+    #[account(
+        mut,
+        constraint = (owner.key() == global_config.admin || owner.key() == crate::admin::id()) @ ErrorCode::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    /// Global protocol configuration.
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Fee schedule (derived by FEE_TIERS_SEED). Created if missing.
+    #[account(
+        init_if_needed,
+        seeds = [FEE_TIERS_SEED.as_bytes()],
+        bump,
+        payer = owner,
+        space = FeeTiers::LEN
+    )]
+    pub fee_tiers: Account<'info, FeeTiers>,
+
+    /// System Program (payer/rent).
+    pub system_program: Program<'info, System>,
+}
+
+/// Replaces the buyback fee schedule and emits `FeeTiersSet`. A single tier at
+/// `min_sol = 0` charges a flat rate, like `fee_treasury_rate` does without a schedule.
+///
+/// # Fails
+/// - `InvalidOwner` if the signer is not an admin.
+/// - `InvalidFeeTiers` if the list is empty, longer than `MAX_FEE_TIERS`, does not
+///   start at `min_sol = 0`, has non-increasing thresholds or a rate above
+///   `FEE_RATE_DENOMINATOR_VALUE`.
+pub fn set_fee_tiers(ctx: Context<SetFeeTiers>, tiers: Vec<FeeTier>) -> Result<()> {
+    FeeTiers::validate(&tiers)?;
+
+    let fee_tiers = &mut ctx.accounts.fee_tiers;
+    fee_tiers.bump = ctx.bumps.fee_tiers;
+    fee_tiers.tiers = tiers.clone();
+
+    emit!(FeeTiersSet { tiers });
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use crate::curve::FEE_RATE_DENOMINATOR_VALUE;
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+/// Accounts context for `set_pool_fee_override`.
+///
+/// Admin-set fee rates that `purchase` and `buyback` price with instead of the
+/// Raydium `amm_config` while `use_fee_override` is on, for environments
+/// (tests, devnet) where that account cannot be relied upon.
+#[derive(Accounts)]
+pub struct SetPoolFeeOverride<'info> {
+    /// Admin (must match `global_config.admin` or program admin).
+    #[account(
+        constraint = (owner.key() == global_config.admin || owner.key() == crate::admin::id()) @ ErrorCode::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    /// Global protocol configuration.
+    #[account(
+        mut,
+        seeds = [GLOBAL_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
+
+/// Stores the override flag and rates (all over `FEE_RATE_DENOMINATOR_VALUE`)
+/// and emits `PoolFeeOverrideSet`.
+///
+/// Buyback still swaps through Raydium, so an override that differs from the
+/// pool's real rates only shifts the quote; Raydium's own fees apply to the fill.
+///
+/// # Fails
+/// - `InvalidFeeModel` if any rate exceeds `FEE_RATE_DENOMINATOR_VALUE`.
+pub fn set_pool_fee_override(
+    ctx: Context<SetPoolFeeOverride>,
+    use_fee_override: bool,
+    trade_fee: u64,
+    protocol_fee: u64,
+    fund_fee: u64,
+    creator_fee: u64,
+) -> Result<()> {
+    for rate in [trade_fee, protocol_fee, fund_fee, creator_fee] {
+        require_gte!(FEE_RATE_DENOMINATOR_VALUE, rate, ErrorCode::InvalidFeeModel);
+    }
+
+    let global_config = &mut ctx.accounts.global_config;
+    global_config.use_fee_override = use_fee_override;
+    global_config.override_trade_fee_rate = trade_fee;
+    global_config.override_protocol_fee_rate = protocol_fee;
+    global_config.override_fund_fee_rate = fund_fee;
+    global_config.override_creator_fee_rate = creator_fee;
+
+    emit!(PoolFeeOverrideSet {
+        use_fee_override,
+        trade_fee,
+        protocol_fee,
+        fund_fee,
+        creator_fee,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,103 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+/// Accounts context for `un_blacklist`.
+///
+/// Reverses `finalize_blacklist` for a user blacklisted by mistake: the SOL
+/// recorded in `blacklisted_sol` moves from the admin record back to the user.
+#[derive(Accounts)]
+pub struct UnBlacklist<'info> {
+    /// Admin (must match `global_config.admin` or program admin).
+    #[account(
+        constraint = (owner.key() == global_config.admin || owner.key() == crate::admin::id()) @ ErrorCode::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    /// Global protocol configuration.
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// The user being restored; identity key for deriving `user_stake_info`.
+    pub user: SystemAccount<'info>,
+
+    /// Per-user stake info for `user`.
+    #[account(
+        mut,
+        seeds = [
+            USER_STAKE_INFO_SEED.as_bytes(),
+            user.key().as_ref()
+        ],
+        bump,
+    )]
+    pub user_stake_info: Account<'info, UserStakeInfo>,
+
+    /// Admin stake info account currently holding the blacklisted stake.
+    #[account(
+        mut,
+        seeds = [
+            ADMIN_STAKE_INFO_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub admin_stake_info: Account<'info, UserStakeInfo>,
+
+    /// Global stake info account (reward indices).
+    #[account(mut, address = global_config.stake_info)]
+    pub stake_info: Account<'info, StakeInfo>,
+
+    /// Protocol stake account, read to realize newly accrued SOL rewards.
+    ///
+    /// CHECK: Address enforced via `global_config.stake_account`; only lamports are read.
+    #[account(address = global_config.stake_account)]
+    pub stake_pda: UncheckedAccount<'info>,
+}
+
+/// Moves `blacklisted_sol` from the admin record back into the user's
+/// `total_staked_sol` and emits `UserUnblacklisted`.
+///
+/// Both records are checkpointed first, so rewards earned on the stake while
+/// the admin held it stay with the admin, and the user's restored stake only
+/// earns from the current indices on (no retroactive claim). Rewards that
+/// `finalize_blacklist` reassigned to the admin are not returned, and
+/// `base_lxr_holdings` stays at `0`.
+///
+/// # Fails
+/// - `NotBlacklisted` if the user has no `blacklisted_sol`.
+/// - `UnderflowError` if the admin record no longer holds that much stake.
+pub fn un_blacklist(ctx: Context<UnBlacklist>) -> Result<()> {
+    let user_stake_info = &mut ctx.accounts.user_stake_info;
+    let admin_stake_info = &mut ctx.accounts.admin_stake_info;
+    let stake_info = &mut ctx.accounts.stake_info;
+
+    require!(user_stake_info.is_blacklisted(), ErrorCode::NotBlacklisted);
+    let sol_restored = user_stake_info.blacklisted_sol;
+
+    // --- 1. Settle both records against the current indices ---
+    stake_info.accrue_sol_rewards(ctx.accounts.stake_pda.lamports(), ctx.accounts.global_config.sol_reward_share_bps)?;
+    admin_stake_info.checkpoint_lxr_rewards(stake_info)?;
+    admin_stake_info.checkpoint_sol_rewards(stake_info)?;
+    user_stake_info.checkpoint_lxr_rewards(stake_info)?;
+    user_stake_info.checkpoint_sol_rewards(stake_info)?;
+
+    // --- 2. Move the stake back from admin → user ---
+    admin_stake_info.total_staked_sol = admin_stake_info
+        .total_staked_sol
+        .checked_sub(sol_restored)
+        .ok_or(ErrorCode::UnderflowError)?;
+    user_stake_info.total_staked_sol = user_stake_info
+        .total_staked_sol
+        .checked_add(sol_restored)
+        .ok_or(ErrorCode::MathOverflow)?;
+    user_stake_info.blacklisted_sol = 0;
+
+    emit!(UserUnblacklisted {
+        user: ctx.accounts.user.key(),
+        sol_restored,
+    });
+
+    Ok(())
+}
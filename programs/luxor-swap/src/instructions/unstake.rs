@@ -0,0 +1,259 @@
+use crate::error::ErrorCode;
+use crate::instructions::load_stake_state;
+use crate::states::{GlobalConfig, StakeInfo, Unstaked, UserStakeInfo, GLOBAL_CONFIG_SEED, USER_STAKE_INFO_SEED};
+use crate::utils::{create_pda_account, transfer_from_user_to_pool_vault};
+use crate::{AUTH_SEED, STAKE_ACCOUNT_SEED, USER_UNSTAKE_ACCOUNT_SEED};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::stake;
+use anchor_lang::solana_program::stake::instruction as stake_ix;
+use anchor_lang::solana_program::stake::state::StakeStateV2;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use std::mem::size_of;
+
+/// Accounts context for `unstake`.
+///
+/// Splits part of the user's staked SOL off the protocol `stake_pda` into a
+/// per-user stake account (`user_unstake_pda`) and deactivates it. Once the
+/// cooldown has passed, `withdraw_unstaked` returns the lamports to the user.
+/// Only one unstake can be pending per user at a time.
+///
+/// The LXR bought with the unstaked SOL goes back to the treasury, so a purchase
+/// cannot be turned into free LXR by unstaking the SOL that paid for it.
+#[derive(Accounts)]
+pub struct Unstake<'info> {
+    /// User unstaking (pays rent for the destination stake account).
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Global protocol configuration.
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Per-user staking record (derived by USER_STAKE_INFO_SEED + owner).
+    #[account(
+        mut,
+        seeds = [
+            USER_STAKE_INFO_SEED.as_bytes(),
+            owner.key().as_ref()
+        ],
+        bump,
+    )]
+    pub user_stake_info: Account<'info, UserStakeInfo>,
+
+    /// Global staking aggregates and reward indices.
+    #[account(
+        mut,
+        address = global_config.stake_info,
+        constraint = !stake_info.stake_closed @ ErrorCode::StakeClosed,
+    )]
+    pub stake_info: Account<'info, StakeInfo>,
+
+    /// Program authority PDA (staker & withdrawer of both stake accounts).
+    ///
+    /// CHECK: PDA derivation enforced by seeds; used only as signer for CPIs.
+    #[account(
+        seeds = [AUTH_SEED.as_bytes()],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// PDA stake account holding staked SOL (split source).
+    ///
+    /// CHECK: PDA seeds ensure derivation; expected to be owned by Stake program.
+    #[account(
+        mut,
+        seeds = [STAKE_ACCOUNT_SEED.as_bytes()],
+        bump
+    )]
+    pub stake_pda: UncheckedAccount<'info>,
+
+    /// Destination stake account for the unstaked SOL (created here).
+    ///
+    /// CHECK: PDA seeds ensure derivation; must still be system-owned (no unstake pending).
+    #[account(
+        mut,
+        seeds = [
+            USER_UNSTAKE_ACCOUNT_SEED.as_bytes(),
+            owner.key().as_ref()
+        ],
+        bump
+    )]
+    pub user_unstake_pda: UncheckedAccount<'info>,
+
+    /// Protocol LXR treasury vault (receives the returned LXR).
+    #[account(mut, address = global_config.lxr_treasury_vault)]
+    pub luxor_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Canonical LXR mint.
+    #[account(
+        address = crate::luxor_mint::id() @ ErrorCode::InvalidLuxorMint,
+        constraint = crate::utils::luxor_decimals_match(luxor_mint.decimals) @ ErrorCode::MintDecimalsMismatch,
+    )]
+    pub luxor_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// User's LXR ATA the returned LXR is taken from.
+    #[account(
+        mut,
+        associated_token::mint = luxor_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+    )]
+    pub owner_lxr_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token program interface.
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// CHECK: Clock sysvar (required by Stake::Deactivate).
+    #[account(address = sysvar::clock::ID)]
+    pub clock: UncheckedAccount<'info>,
+
+    /// CHECK: Stake program ID (CPI target).
+    #[account(address = stake::program::ID @ ErrorCode::InvalidStakeProgram)]
+    pub stake_program: UncheckedAccount<'info>,
+
+    /// System Program (for creating the destination stake account).
+    pub system_program: Program<'info, System>,
+}
+
+/// Splits `amount` lamports of the caller's stake into `user_unstake_pda` and
+/// starts its deactivation.
+///
+/// # Steps
+/// 1. Realize pending SOL rewards on `stake_pda` and checkpoint the user's LXR and
+///    SOL rewards, so nothing accrued on the old stake amount is lost.
+/// 2. Transfer `amount / total_staked_sol` of the user's `base_lxr_holdings`
+///    (rounded up) from their LXR ATA to the treasury and lower the base by it.
+/// 3. Create `user_unstake_pda` (rent paid by the user), split `amount` into it and
+///    deactivate it if it carries a delegation.
+/// 4. Decrement `stake_info.total_staked_sol` and the user's `total_staked_sol`,
+///    refresh `last_tracked_sol_balance` and emit `Unstaked`.
+///
+/// # Fails
+/// - `UnstakeExceedsStake` if `amount` exceeds the user's `total_staked_sol`.
+/// - Token program error if the user's LXR ATA holds less than the LXR to return.
+/// - `UnstakeAlreadyPending` if a previous unstake has not been withdrawn yet.
+/// - `BlacklistAlreadyProposed` if a blacklist is pending for the user.
+/// - `BuybackAlreadyRequested` while a buyback split is outstanding.
+pub fn unstake(ctx: Context<Unstake>, amount: u64) -> Result<()> {
+    require_gt!(amount, 0);
+
+    let stake_info = &mut ctx.accounts.stake_info;
+    let user_stake_info = &mut ctx.accounts.user_stake_info;
+
+    require!(!stake_info.buyback_requested, ErrorCode::BuybackAlreadyRequested);
+    require!(
+        user_stake_info.blacklist_pending_timestamp == 0,
+        ErrorCode::BlacklistAlreadyProposed
+    );
+    require_keys_eq!(
+        *ctx.accounts.user_unstake_pda.owner,
+        ctx.accounts.system_program.key(),
+        ErrorCode::UnstakeAlreadyPending
+    );
+
+    // Blacklisted SOL was already moved out of `total_staked_sol`.
+    require_gte!(user_stake_info.total_staked_sol, amount, ErrorCode::UnstakeExceedsStake);
+
+    // --- 1. Settle rewards against the current stake amount ---
+    stake_info.accrue_sol_rewards(ctx.accounts.stake_pda.lamports(), ctx.accounts.global_config.sol_reward_share_bps)?;
+    user_stake_info.checkpoint_lxr_rewards(stake_info)?;
+    user_stake_info.checkpoint_sol_rewards(stake_info)?;
+
+    // --- 2. Hand the LXR bought with this SOL back to the treasury ---
+    let lxr_returned = user_stake_info.lxr_to_return_for_unstake(amount)?;
+    if lxr_returned > 0 {
+        transfer_from_user_to_pool_vault(
+            ctx.accounts.owner.to_account_info(),
+            ctx.accounts.owner_lxr_token.to_account_info(),
+            ctx.accounts.luxor_vault.to_account_info(),
+            ctx.accounts.luxor_mint.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            lxr_returned,
+            ctx.accounts.luxor_mint.decimals,
+        )?;
+        user_stake_info.base_lxr_holdings = user_stake_info
+            .base_lxr_holdings
+            .checked_sub(lxr_returned)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+
+    // --- 3. Create the destination stake account and split into it ---
+    let space = size_of::<StakeStateV2>();
+    let min_rent = ctx.accounts.global_config.stake_split_rent()?;
+
+    let owner_key = ctx.accounts.owner.key();
+    let unstake_bump = ctx.bumps.user_unstake_pda;
+    let unstake_seeds: &[&[u8]] = &[USER_UNSTAKE_ACCOUNT_SEED.as_bytes(), owner_key.as_ref(), &[unstake_bump]];
+
+    create_pda_account(
+        &ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.user_unstake_pda.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        min_rent,
+        space as u64,
+        &stake::program::ID,
+        &[unstake_seeds],
+    )?;
+
+    let auth_bump = ctx.bumps.authority;
+    let auth_seeds: &[&[u8]] = &[AUTH_SEED.as_bytes(), &[auth_bump]];
+
+    let split_ix = &stake_ix::split(
+        &ctx.accounts.stake_pda.key(),
+        &ctx.accounts.authority.key(),
+        amount,
+        &ctx.accounts.user_unstake_pda.key(),
+    )[2];
+    invoke_signed(
+        split_ix,
+        &[
+            ctx.accounts.stake_pda.to_account_info(),
+            ctx.accounts.user_unstake_pda.to_account_info(),
+            ctx.accounts.authority.to_account_info(),
+        ],
+        &[auth_seeds],
+    )?;
+
+    // An undelegated (Initialized) split is withdrawable right away.
+    if let StakeStateV2::Stake(..) = load_stake_state(&ctx.accounts.user_unstake_pda.to_account_info())? {
+        let deactivate_ix = stake_ix::deactivate_stake(
+            &ctx.accounts.user_unstake_pda.key(),
+            &ctx.accounts.authority.key(),
+        );
+        invoke_signed(
+            &deactivate_ix,
+            &[
+                ctx.accounts.user_unstake_pda.to_account_info(),
+                ctx.accounts.clock.to_account_info(),
+                ctx.accounts.authority.to_account_info(),
+            ],
+            &[auth_seeds],
+        )?;
+    }
+
+    // --- 4. Global and user aggregates ---
+    stake_info.total_staked_sol = stake_info
+        .total_staked_sol
+        .checked_sub(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    stake_info.last_tracked_sol_balance = ctx.accounts.stake_pda.lamports();
+    stake_info.last_update_timestamp = Clock::get()?.unix_timestamp as u64;
+    user_stake_info.total_staked_sol = user_stake_info
+        .total_staked_sol
+        .checked_sub(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(Unstaked {
+        user: owner_key,
+        amount,
+        destination: ctx.accounts.user_unstake_pda.key(),
+        lxr_returned,
+    });
+
+    Ok(())
+}
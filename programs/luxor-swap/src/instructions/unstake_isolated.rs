@@ -0,0 +1,190 @@
+use crate::error::ErrorCode;
+use crate::instructions::load_stake_state;
+use crate::states::{GlobalConfig, IsolatedStakeInfo, UnstakeWithdrawn, Unstaked, GLOBAL_CONFIG_SEED, ISOLATED_STAKE_INFO_SEED};
+use crate::utils::transfer_from_user_to_pool_vault;
+use crate::{AUTH_SEED, STAKE_ACCOUNT_SEED};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::stake;
+use anchor_lang::solana_program::stake::instruction as stake_ix;
+use anchor_lang::solana_program::stake::state::StakeStateV2;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+/// Accounts context for `unstake_isolated`.
+///
+/// Exit path for a stake opened with `purchase_isolated`. The first call returns
+/// the LXR bought with the stake to the treasury and deactivates the delegation;
+/// once the cooldown has passed, a second call withdraws every lamport
+/// (principal, accrued rewards and rent) to the user and closes
+/// `isolated_stake_info`, so a new isolated stake can be opened.
+#[derive(Accounts)]
+pub struct UnstakeIsolated<'info> {
+    /// Owner of the isolated stake, receiving the withdrawn SOL.
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Global protocol configuration.
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Program authority PDA (staker & withdrawer of `user_stake_pda`).
+    ///
+    /// CHECK: PDA derivation enforced by seeds; used only as signer for CPIs.
+    #[account(
+        seeds = [AUTH_SEED.as_bytes()],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The user's isolated stake account.
+    ///
+    /// CHECK: PDA seeds ensure derivation; must be owned by the Stake program.
+    #[account(
+        mut,
+        seeds = [
+            STAKE_ACCOUNT_SEED.as_bytes(),
+            owner.key().as_ref()
+        ],
+        bump,
+        owner = stake::program::ID @ ErrorCode::NoIsolatedStake,
+    )]
+    pub user_stake_pda: UncheckedAccount<'info>,
+
+    /// Record of the isolated stake; closed to `owner` on withdrawal.
+    #[account(
+        mut,
+        seeds = [
+            ISOLATED_STAKE_INFO_SEED.as_bytes(),
+            owner.key().as_ref()
+        ],
+        bump = isolated_stake_info.bump,
+    )]
+    pub isolated_stake_info: Account<'info, IsolatedStakeInfo>,
+
+    /// Protocol LXR treasury vault (receives the returned LXR).
+    #[account(mut, address = global_config.lxr_treasury_vault)]
+    pub luxor_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Canonical LXR mint.
+    #[account(
+        address = crate::luxor_mint::id() @ ErrorCode::InvalidLuxorMint,
+        constraint = crate::utils::luxor_decimals_match(luxor_mint.decimals) @ ErrorCode::MintDecimalsMismatch,
+    )]
+    pub luxor_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// User's LXR ATA the returned LXR is taken from.
+    #[account(
+        mut,
+        associated_token::mint = luxor_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+    )]
+    pub owner_lxr_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token program interface.
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// CHECK: Clock sysvar (required by Stake::Deactivate and Stake::Withdraw).
+    #[account(address = sysvar::clock::ID)]
+    pub clock: UncheckedAccount<'info>,
+
+    /// CHECK: Stake history sysvar (required by Stake::Withdraw).
+    #[account(address = sysvar::stake_history::ID)]
+    pub stake_history: UncheckedAccount<'info>,
+
+    /// CHECK: Stake program ID (CPI target).
+    #[account(address = stake::program::ID @ ErrorCode::InvalidStakeProgram)]
+    pub stake_program: UncheckedAccount<'info>,
+}
+
+/// Deactivates the caller's isolated stake, or withdraws it once inactive.
+///
+/// - Delegated and not yet deactivating: return the recorded LXR to the treasury,
+///   deactivate and emit `Unstaked`.
+/// - Otherwise: return any LXR still recorded, withdraw all lamports to the user,
+///   close `isolated_stake_info` and emit `UnstakeWithdrawn`.
+///
+/// # Fails
+/// - `NoIsolatedStake` if the user has no isolated stake account.
+/// - Token program error if the user no longer holds the purchased LXR.
+/// - Stake program error while the stake is still cooling down.
+pub fn unstake_isolated(ctx: Context<UnstakeIsolated>) -> Result<()> {
+    let owner_key = ctx.accounts.owner.key();
+    let auth_seeds: &[&[u8]] = &[AUTH_SEED.as_bytes(), &[ctx.bumps.authority]];
+
+    // The SOL only leaves once the LXR bought with it is back in the treasury.
+    let lxr_returned = ctx.accounts.isolated_stake_info.lxr_amount;
+    if lxr_returned > 0 {
+        transfer_from_user_to_pool_vault(
+            ctx.accounts.owner.to_account_info(),
+            ctx.accounts.owner_lxr_token.to_account_info(),
+            ctx.accounts.luxor_vault.to_account_info(),
+            ctx.accounts.luxor_mint.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            lxr_returned,
+            ctx.accounts.luxor_mint.decimals,
+        )?;
+        ctx.accounts.isolated_stake_info.lxr_amount = 0;
+    }
+
+    if let StakeStateV2::Stake(_, stake, _) = load_stake_state(&ctx.accounts.user_stake_pda.to_account_info())? {
+        if stake.delegation.deactivation_epoch == u64::MAX {
+            let deactivate_ix = stake_ix::deactivate_stake(
+                &ctx.accounts.user_stake_pda.key(),
+                &ctx.accounts.authority.key(),
+            );
+            invoke_signed(
+                &deactivate_ix,
+                &[
+                    ctx.accounts.user_stake_pda.to_account_info(),
+                    ctx.accounts.clock.to_account_info(),
+                    ctx.accounts.authority.to_account_info(),
+                ],
+                &[auth_seeds],
+            )?;
+
+            emit!(Unstaked {
+                user: owner_key,
+                amount: stake.delegation.stake,
+                destination: ctx.accounts.user_stake_pda.key(),
+                lxr_returned,
+            });
+            return Ok(());
+        }
+    }
+
+    let amount = ctx.accounts.user_stake_pda.lamports();
+    let withdraw_ix = stake_ix::withdraw(
+        &ctx.accounts.user_stake_pda.key(),
+        &ctx.accounts.authority.key(),
+        &owner_key,
+        amount,
+        None,
+    );
+    invoke_signed(
+        &withdraw_ix,
+        &[
+            ctx.accounts.user_stake_pda.to_account_info(),
+            ctx.accounts.owner.to_account_info(),
+            ctx.accounts.clock.to_account_info(),
+            ctx.accounts.stake_history.to_account_info(),
+            ctx.accounts.authority.to_account_info(),
+        ],
+        &[auth_seeds],
+    )?;
+
+    ctx.accounts
+        .isolated_stake_info
+        .close(ctx.accounts.owner.to_account_info())?;
+
+    emit!(UnstakeWithdrawn {
+        user: owner_key,
+        amount,
+    });
+
+    Ok(())
+}
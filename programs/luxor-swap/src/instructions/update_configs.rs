@@ -1,6 +1,11 @@
+use crate::curve::FEE_RATE_DENOMINATOR_VALUE;
 use crate::error::ErrorCode;
-use crate::states::{ConfigUpdated, GlobalConfig, GLOBAL_CONFIG_SEED};
+use crate::swap_venue::SwapVenue;
+use crate::states::{AdminTransferInitiated, ConfigUpdated, GlobalConfig, PoolState, BPS_DENOMINATOR, CURVE_TYPE_CONSTANT_PRODUCT, CURVE_TYPE_OFFSET_PRODUCT, GLOBAL_CONFIG_SEED};
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::stake::state::StakeStateV2;
+use anchor_spl::token::spl_token;
+use std::mem::size_of;
 
 /// Accounts context for the `update_config` instruction.
 ///
@@ -40,18 +45,65 @@ pub struct UpdateConfig<'info> {
 /// - `value`: The new value to assign (interpreted differently depending on `param`).
 ///
 /// # Param Mapping
-/// - `0`: **Admin change** → Expects a new admin Pubkey passed via `remaining_accounts[0]`.
-/// - `1`: **min_swap_amount** → Sets minimum swap amount (u64).
-/// - `2`: **max_swap_amount** → Sets maximum swap amount (u64).
-/// - `3`: **fee_treasury_rate** → Updates the treasury fee rate (u64).
+/// - `0`: **Admin transfer** → Nominates the Pubkey passed via `remaining_accounts[0]` as
+///   `pending_admin`; it becomes admin only after signing `accept_admin`.
+/// - `1`: **min_swap_amount** → Sets minimum SOL per purchase (u64, lamports).
+/// - `2`: **max_swap_amount** → Sets maximum SOL per purchase (u64, lamports, `0` = no cap).
+/// - `3`: **fee_treasury_rate** → Updates the treasury fee rate (u64, over
+///   `FEE_RATE_DENOMINATOR_VALUE`, must not exceed it).
 /// - `4`: **purchase_enabled** → Toggles purchase (bool, from nonzero value).
 /// - `5`: **redeem_enabled** → Toggles redeem (bool, from nonzero value).
+/// - `6`: **max_stake_count_to_get_bonus** → Sets the early-bird bonus threshold (u64; `0` disables the bonus).
+/// - `7`: **initial_lxr_allocation_vault** → Sets the inventory pricing reference (u64).
+/// - `8`: **max_sol_per_buyback** → Caps lamports split per buyback request (u64, `0` = no cap).
+/// - `9`: **blacklist_delay_secs** → Sets the propose → finalize blacklist delay (seconds).
+/// - `10`: **stake_rent_exempt** → Re-reads the live Rent sysvar into the cached value (`value` ignored).
+/// - `11`: **buyback_out_tolerance_rate** → Sets the Raydium `minimum_amount_out` tolerance
+///   for buyback (u64, over `FEE_RATE_DENOMINATOR_VALUE`, must not exceed it).
+/// - `12`: **min_treasury_for_purchase** → Sets the treasury LXR floor below which purchases pause (u64).
+/// - `13`: **min_pool_reserve** → Sets the minimum pool reserve for purchase/buyback pricing (u64, `0` = disabled).
+/// - `14`: **curve_type** → Selects the purchase pricing curve (`0` = constant product, `1` = offset product).
+/// - `15`: **curve_offset_sol** → Sets the offset curve's virtual SOL reserve (u64).
+/// - `16`: **curve_offset_lxr** → Sets the offset curve's virtual LXR reserve (u64).
+/// - `17`: **buyback_fee_enabled** → Toggles the buyback treasury fee (bool, from nonzero value).
+/// - `18`: **forfeiture_grace_bps** → Sets the redeem holdings grace (u64, bps, must not exceed `BPS_DENOMINATOR`).
+/// - `19`: **max_pool_fraction_bps** → Caps a purchase to this share of the pool's LXR reserve
+///   (u64, bps, `0` = no cap, must not exceed `BPS_DENOMINATOR`).
+/// - `20`: **redistribute_forfeitures** → Toggles redistributing redeem forfeitures to stakers
+///   instead of the treasury (bool, from nonzero value).
+/// - `21`: **reward_vault_target** → Sets the reward vault LXR level buyback refills toward
+///   (u64, `0` = disabled).
+/// - `22`: **buyback_cooldown_secs** → Sets the minimum seconds between executed buybacks
+///   (u64, `0` = no cooldown).
+/// - `23`: **fee_recipient** → Sets the Pubkey passed via `remaining_accounts[0]` as the
+///   recipient of `withdraw_treasury_fees` (`value` ignored).
+/// - `24`: **min_hold_ratio_bps** → Sets the share of base holdings required to redeem
+///   (u64, bps, `0` = off, must not exceed `BPS_DENOMINATOR`).
+/// - `25`: **reward_pool_state** → Sets the Raydium pool passed via `remaining_accounts[0]`
+///   as the buyback pool; it must pair WSOL (token_0) with `reward_mint` (token_1)
+///   (`value` ignored).
+/// - `26`: **dex_type** → Selects the buyback swap venue (`DEX_TYPE_*` in `crate::swap_venue`;
+///   only venues with a CPI builder are accepted, `UnsupportedSwapVenue` otherwise).
+/// - `27`: **max_stake_per_user** → Caps the SOL one user may stake through purchases
+///   (u64, lamports, `0` = no cap).
+/// - `28`: **max_total_staked_sol** → Caps the total SOL accepted into the shared stake
+///   (u64, lamports, `0` = unlimited).
+/// - `29`: **max_price_deviation_bps** → Widest deviation of the pool price from `anchor_price_x32`
+///   purchases accept (u64, bps, `0` = band disabled, must not exceed `BPS_DENOMINATOR`).
+/// - `30`: **bonus_rate** → Sets the early-bird discount on the priced LXR amount
+///   (u64, over `FEE_RATE_DENOMINATOR_VALUE`, must not exceed it).
+/// - `31`: **sol_reward_share_bps** → Sets the share of accrued stake rewards reserved for
+///   `claim_sol_rewards` instead of buybacks (u64, bps, `0` = LXR rewards only,
+///   must not exceed `BPS_DENOMINATOR`).
+/// - `32`: **min_lxr_per_purchase** → Sets minimum LXR per purchase (u64, `0` = no minimum).
+/// - `33`: **max_lxr_per_purchase** → Sets maximum LXR per purchase (u64, `0` = no cap).
 ///
 /// Any other `param` value returns `ErrorCode::InvalidParam`.
 ///
 /// # Errors
 /// - `InvalidOwner`: If the caller is not an authorized admin.
-/// - `MissingRemainingAccount`: If updating admin but no Pubkey is provided.
+/// - `MissingRemainingAccount`: If updating admin, fee recipient or reward pool but no account is provided.
+/// - `InvalidRewardMint`: If the reward pool does not pair WSOL with `reward_mint`.
 /// - `InvalidParam`: If `param` is outside the valid range.
 ///
 /// # Example
@@ -65,7 +117,7 @@ pub struct UpdateConfig<'info> {
 pub fn update_config(ctx: Context<UpdateConfig>, param: u8, value: u64) -> Result<()> {
     let global_config = &mut ctx.accounts.global_config;
     match param {
-        // Update admin (requires new admin key from remaining_accounts[0])
+        // Nominate a new admin (requires new admin key from remaining_accounts[0])
         0 => {
             let new_admin = *ctx
                 .remaining_accounts
@@ -74,7 +126,11 @@ pub fn update_config(ctx: Context<UpdateConfig>, param: u8, value: u64) -> Resul
                 .ok_or(error!(ErrorCode::MissingRemainingAccount))?
                 .key;
             require_keys_neq!(new_admin, Pubkey::default());
-            global_config.admin = new_admin;
+            global_config.pending_admin = new_admin;
+            emit!(AdminTransferInitiated {
+                admin: global_config.admin,
+                pending_admin: new_admin,
+            });
         }
         // Update minimum swap amount
         1 => {
@@ -86,6 +142,7 @@ pub fn update_config(ctx: Context<UpdateConfig>, param: u8, value: u64) -> Resul
         }
         // Update treasury fee rate
         3 => {
+            require_gte!(FEE_RATE_DENOMINATOR_VALUE, value, ErrorCode::InvalidParam);
             global_config.fee_treasury_rate = value;
         }
         // Toggle purchase_enabled flag
@@ -102,17 +159,183 @@ pub fn update_config(ctx: Context<UpdateConfig>, param: u8, value: u64) -> Resul
         7 => {
             global_config.initial_lxr_allocation_vault = value;
         }
+        // Update per-request buyback split cap (0 disables the cap)
+        8 => {
+            global_config.max_sol_per_buyback = value;
+        }
+        // Update the blacklist dispute window
+        9 => {
+            global_config.blacklist_delay_secs = value;
+        }
+        // Re-sync the cached stake rent-exempt minimum with the live Rent sysvar
+        10 => {
+            let min_rent = Rent::get()?.minimum_balance(size_of::<StakeStateV2>());
+            require!(min_rent > 0, ErrorCode::InsufficientRent);
+            global_config.stake_rent_exempt = min_rent;
+        }
+        // Update the buyback output tolerance
+        11 => {
+            require_gte!(FEE_RATE_DENOMINATOR_VALUE, value, ErrorCode::InvalidParam);
+            global_config.buyback_out_tolerance_rate = value;
+        }
+        // Update the low-treasury purchase pause threshold
+        12 => {
+            global_config.min_treasury_for_purchase = value;
+        }
+        // Update the minimum pool reserve used for pricing
+        13 => {
+            global_config.min_pool_reserve = value;
+        }
+        // Select the purchase pricing curve
+        14 => {
+            require!(
+                value == CURVE_TYPE_CONSTANT_PRODUCT as u64 || value == CURVE_TYPE_OFFSET_PRODUCT as u64,
+                ErrorCode::InvalidParam
+            );
+            global_config.curve_type = value as u8;
+        }
+        // Update the offset curve's virtual SOL reserve
+        15 => {
+            global_config.curve_offset_sol = value;
+        }
+        // Update the offset curve's virtual LXR reserve
+        16 => {
+            global_config.curve_offset_lxr = value;
+        }
+        // Toggle buyback_fee_enabled flag
+        17 => {
+            global_config.buyback_fee_enabled = value != 0;
+        }
+        // Update the redeem forfeiture grace
+        18 => {
+            require_gte!(BPS_DENOMINATOR, value, ErrorCode::InvalidParam);
+            global_config.forfeiture_grace_bps = value;
+        }
+        // Update the per-purchase pool fraction cap
+        19 => {
+            require_gte!(BPS_DENOMINATOR, value, ErrorCode::InvalidParam);
+            global_config.max_pool_fraction_bps = value;
+        }
+        // Toggle redistribute_forfeitures flag
+        20 => {
+            global_config.redistribute_forfeitures = value != 0;
+        }
+        // Update the reward vault refill target
+        21 => {
+            global_config.reward_vault_target = value;
+        }
+        // Update the buyback cooldown
+        22 => {
+            global_config.buyback_cooldown_secs = value;
+        }
+        // Set the treasury fee recipient (requires its key from remaining_accounts[0])
+        23 => {
+            let fee_recipient = *ctx
+                .remaining_accounts
+                .iter()
+                .next()
+                .ok_or(error!(ErrorCode::MissingRemainingAccount))?
+                .key;
+            require_keys_neq!(fee_recipient, Pubkey::default());
+            global_config.fee_recipient = fee_recipient;
+        }
+        // Update the minimum hold ratio for redeem
+        24 => {
+            require_gte!(BPS_DENOMINATOR, value, ErrorCode::InvalidParam);
+            global_config.min_hold_ratio_bps = value;
+        }
+        // Set the buyback pool (requires the pool account in remaining_accounts[0])
+        25 => {
+            let pool_info = ctx
+                .remaining_accounts
+                .iter()
+                .next()
+                .ok_or(error!(ErrorCode::MissingRemainingAccount))?;
+            require_keys_eq!(*pool_info.owner, crate::raydium_cpmm::id());
+            let pool_state = PoolState::try_deserialize(&mut &pool_info.data.borrow()[..])?;
+            require_keys_eq!(pool_state.token_0_mint, spl_token::native_mint::id(), ErrorCode::InvalidRewardMint);
+            require_keys_eq!(pool_state.token_1_mint, global_config.reward_mint, ErrorCode::InvalidRewardMint);
+            global_config.reward_pool_state = pool_info.key();
+        }
+        // Select the buyback swap venue
+        26 => {
+            let dex_type = u8::try_from(value).map_err(|_| ErrorCode::InvalidParam)?;
+            require!(SwapVenue::from_dex_type(dex_type)?.is_supported(), ErrorCode::UnsupportedSwapVenue);
+            global_config.dex_type = dex_type;
+        }
+        // Update the per-user stake cap
+        27 => {
+            global_config.max_stake_per_user = value;
+        }
+        // Update the global stake cap
+        28 => {
+            global_config.max_total_staked_sol = value;
+        }
+        // Update the purchase price band width
+        29 => {
+            require_gte!(BPS_DENOMINATOR, value, ErrorCode::InvalidParam);
+            global_config.max_price_deviation_bps = value;
+        }
+        // Update the early-bird bonus rate
+        30 => {
+            require_gte!(FEE_RATE_DENOMINATOR_VALUE, value, ErrorCode::InvalidParam);
+            global_config.bonus_rate = value;
+        }
+        // Update the SOL reward share of accrued stake rewards
+        31 => {
+            require_gte!(BPS_DENOMINATOR, value, ErrorCode::InvalidParam);
+            global_config.sol_reward_share_bps = value;
+        }
+        // Update the per-purchase LXR minimum
+        32 => {
+            global_config.min_lxr_per_purchase = value;
+        }
+        // Update the per-purchase LXR cap
+        33 => {
+            global_config.max_lxr_per_purchase = value;
+        }
         // Invalid parameter selector
         _ => return Err(error!(ErrorCode::InvalidParam)),
     }
 
     emit!(ConfigUpdated {
+        param,
         admin: global_config.admin,
+        bonus_rate: global_config.bonus_rate,
+        max_stake_count_to_get_bonus: global_config.max_stake_count_to_get_bonus,
         min_swap_amount: global_config.min_swap_amount,
         max_swap_amount: global_config.max_swap_amount,
         fee_treasury_rate: global_config.fee_treasury_rate,
         purchase_enabled: global_config.purchase_enabled,
         redeem_enabled: global_config.redeem_enabled,
+        initial_lxr_allocation_vault: global_config.initial_lxr_allocation_vault,
+        max_sol_per_buyback: global_config.max_sol_per_buyback,
+        blacklist_delay_secs: global_config.blacklist_delay_secs,
+        stake_rent_exempt: global_config.stake_rent_exempt,
+        buyback_out_tolerance_rate: global_config.buyback_out_tolerance_rate,
+        min_treasury_for_purchase: global_config.min_treasury_for_purchase,
+        min_pool_reserve: global_config.min_pool_reserve,
+        curve_type: global_config.curve_type,
+        curve_offset_sol: global_config.curve_offset_sol,
+        curve_offset_lxr: global_config.curve_offset_lxr,
+        buyback_fee_enabled: global_config.buyback_fee_enabled,
+        forfeiture_grace_bps: global_config.forfeiture_grace_bps,
+        pending_admin: global_config.pending_admin,
+        max_pool_fraction_bps: global_config.max_pool_fraction_bps,
+        redistribute_forfeitures: global_config.redistribute_forfeitures,
+        reward_vault_target: global_config.reward_vault_target,
+        buyback_cooldown_secs: global_config.buyback_cooldown_secs,
+        fee_recipient: global_config.fee_recipient,
+        min_hold_ratio_bps: global_config.min_hold_ratio_bps,
+        reward_pool_state: global_config.reward_pool_state(),
+        dex_type: global_config.dex_type,
+        max_stake_per_user: global_config.max_stake_per_user,
+        max_total_staked_sol: global_config.max_total_staked_sol,
+        anchor_price_x32: global_config.anchor_price_x32,
+        max_price_deviation_bps: global_config.max_price_deviation_bps,
+        sol_reward_share_bps: global_config.sol_reward_share_bps,
+        min_lxr_per_purchase: global_config.min_lxr_per_purchase,
+        max_lxr_per_purchase: global_config.max_lxr_per_purchase,
     });
     Ok(())
 }
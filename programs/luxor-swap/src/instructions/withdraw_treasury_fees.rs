@@ -0,0 +1,114 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::transfer_from_pool_vault_to_user;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::spl_token;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+/// Accounts context for `withdraw_treasury_fees`.
+///
+/// Pays buyback treasury fees (WSOL accumulated in `sol_treasury_vault`) out to
+/// the configured `global_config.fee_recipient`, as a targeted alternative to
+/// draining the whole vault to the admin with `emergency_withdraw` param `1`.
+#[derive(Accounts)]
+pub struct WithdrawTreasuryFees<'info> {
+    /// Admin (must match `global_config.admin` or program admin); pays for the
+    /// recipient's WSOL ATA if it does not exist yet.
+    #[account(
+        mut,
+        constraint = (owner.key() == global_config.admin || owner.key() == crate::admin::id()) @ ErrorCode::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    /// Global protocol configuration; a fee recipient must be set.
+    #[account(
+        seeds = [GLOBAL_CONFIG_SEED.as_bytes()],
+        bump,
+        constraint = global_config.fee_recipient != Pubkey::default() @ ErrorCode::FeeRecipientNotSet,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Program authority PDA (SOL treasury vault authority).
+    ///
+    /// CHECK: PDA derivation enforced by seeds; used only as signer for CPIs.
+    #[account(
+        seeds = [crate::AUTH_SEED.as_bytes()],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// Global staking state; its funded SOL reward reserve is excluded from
+    /// the withdrawable balance.
+    #[account(address = global_config.stake_info)]
+    pub stake_info: Account<'info, StakeInfo>,
+
+    /// SOL treasury vault (WSOL) the fees are paid from.
+    #[account(mut, address = global_config.sol_treasury_vault)]
+    pub sol_treasury_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Configured fee recipient.
+    ///
+    /// CHECK: Address enforced via `global_config.fee_recipient`; only used as ATA authority.
+    #[account(address = global_config.fee_recipient)]
+    pub fee_recipient: UncheckedAccount<'info>,
+
+    /// SPL Native mint (WSOL).
+    #[account(address = spl_token::native_mint::id())]
+    pub native_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Fee recipient's WSOL ATA. Created on demand.
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = native_mint,
+        associated_token::authority = fee_recipient,
+        associated_token::token_program = token_program,
+    )]
+    pub fee_recipient_wsol_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token program interface.
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Associated Token Program (for ATA creation).
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// System Program (payer/rent).
+    pub system_program: Program<'info, System>,
+}
+
+/// Transfers up to `amount` WSOL from `sol_treasury_vault` to the fee recipient's
+/// WSOL ATA and emits `TreasuryFeesWithdrawn` with the amount actually sent. The
+/// SOL reward reserve still owed to stakers (`sol_reserve_in_treasury`) is never
+/// paid out.
+///
+/// # Fails
+/// - `InvalidOwner` if the signer is not an admin.
+/// - `FeeRecipientNotSet` if `update_config` param `23` was never applied.
+pub fn withdraw_treasury_fees(ctx: Context<WithdrawTreasuryFees>, amount: u64) -> Result<()> {
+    require_gt!(amount, 0);
+    let withdrawable = ctx
+        .accounts
+        .sol_treasury_vault
+        .amount
+        .saturating_sub(ctx.accounts.stake_info.sol_reserve_in_treasury()?);
+    let amount = amount.min(withdrawable);
+
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.sol_treasury_vault.to_account_info(),
+        ctx.accounts.fee_recipient_wsol_token.to_account_info(),
+        ctx.accounts.native_mint.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        amount,
+        ctx.accounts.native_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]]],
+    )?;
+
+    emit!(TreasuryFeesWithdrawn {
+        recipient: ctx.accounts.fee_recipient.key(),
+        amount,
+    });
+
+    Ok(())
+}
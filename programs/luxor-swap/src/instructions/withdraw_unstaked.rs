@@ -0,0 +1,93 @@
+use crate::error::ErrorCode;
+use crate::states::UnstakeWithdrawn;
+use crate::{AUTH_SEED, USER_UNSTAKE_ACCOUNT_SEED};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::stake;
+use anchor_lang::solana_program::stake::instruction as stake_ix;
+use anchor_lang::solana_program::sysvar;
+
+/// Accounts context for `withdraw_unstaked`.
+///
+/// Second step of `unstake`: once `user_unstake_pda` has finished deactivating,
+/// all of its lamports (unstaked SOL plus the rent the user paid) are withdrawn
+/// back to the user, and the account can be reused for the next unstake.
+#[derive(Accounts)]
+pub struct WithdrawUnstaked<'info> {
+    /// User receiving the unstaked SOL.
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Program authority PDA (withdrawer of `user_unstake_pda`).
+    ///
+    /// CHECK: PDA derivation enforced by seeds; used only as signer for CPIs.
+    #[account(
+        seeds = [AUTH_SEED.as_bytes()],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The user's deactivated unstake account.
+    ///
+    /// CHECK: PDA seeds ensure derivation; must be owned by the Stake program.
+    #[account(
+        mut,
+        seeds = [
+            USER_UNSTAKE_ACCOUNT_SEED.as_bytes(),
+            owner.key().as_ref()
+        ],
+        bump,
+        owner = stake::program::ID @ ErrorCode::NoUnstakePending,
+    )]
+    pub user_unstake_pda: UncheckedAccount<'info>,
+
+    /// CHECK: Clock sysvar (required by Stake::Withdraw).
+    #[account(address = sysvar::clock::ID)]
+    pub clock: UncheckedAccount<'info>,
+
+    /// CHECK: Stake history sysvar (required by Stake::Withdraw).
+    #[account(address = sysvar::stake_history::ID)]
+    pub stake_history: UncheckedAccount<'info>,
+
+    /// CHECK: Stake program ID (CPI target).
+    #[account(address = stake::program::ID @ ErrorCode::InvalidStakeProgram)]
+    pub stake_program: UncheckedAccount<'info>,
+}
+
+/// Withdraws every lamport of `user_unstake_pda` to the user.
+///
+/// # Fails
+/// - `NoUnstakePending` if the user has no unstake account.
+/// - Stake program error while the account is still deactivating.
+pub fn withdraw_unstaked(ctx: Context<WithdrawUnstaked>) -> Result<()> {
+    let amount = ctx.accounts.user_unstake_pda.lamports();
+
+    let auth_bump = ctx.bumps.authority;
+    let seeds: &[&[u8]] = &[AUTH_SEED.as_bytes(), &[auth_bump]];
+
+    let ix = stake_ix::withdraw(
+        &ctx.accounts.user_unstake_pda.key(),
+        &ctx.accounts.authority.key(),
+        &ctx.accounts.owner.key(),
+        amount,
+        None,
+    );
+    invoke_signed(
+        &ix,
+        &[
+            ctx.accounts.user_unstake_pda.to_account_info(),
+            ctx.accounts.owner.to_account_info(),
+            ctx.accounts.clock.to_account_info(),
+            ctx.accounts.stake_history.to_account_info(),
+            ctx.accounts.authority.to_account_info(),
+        ],
+        &[seeds],
+    )?;
+
+    emit!(UnstakeWithdrawn {
+        user: ctx.accounts.owner.key(),
+        amount,
+    });
+
+    Ok(())
+}
@@ -37,22 +37,35 @@ pub mod luxor_mint {
     declare_id!("EBHC7XpycnQhCd3zq8iWmSuhvpGVyM6krjb6pvwgZ4zE");
 }
 
+/// Seed of the program authority PDA. This PDA is recorded as staker and
+/// withdrawer of the stake account at init, so the seed must stay stable across
+/// upgrades (use `rotate_stake_authority` before ever changing it).
 pub const AUTH_SEED: &str = "stake_and_treasury_auth";
 pub const LUXOR_VAULT_SEED: &str = "luxor_vault";
 pub const LUXOR_REWARD_VAULT_SEED: &str = "luxor_reward_vault";
 pub const SOL_TREASURY_VAULT_SEED: &str = "sol_treasury_vault";
 pub const STAKE_ACCOUNT_SEED: &str = "stake";
 pub const STAKE_SPLIT_ACCOUNT_SEED: &str = "stake_split";
+pub const USER_UNSTAKE_ACCOUNT_SEED: &str = "user_unstake";
 pub const PRECISION: u128 = 1_000_000_000;
+/// Most `remaining_accounts` a batch instruction (`migrate_accounts`) takes in one
+/// call, so a full batch stays inside the default compute budget.
+pub const MAX_BATCH: usize = 10;
+/// Decimals of the LUXOR mint. Pricing and every vault amount are in raw base
+/// units, so init and each handler touching the mint reject any other value.
+pub const LUXOR_DECIMALS: u8 = 9;
 
 pub mod curve;
 pub mod error;
 pub mod instructions;
+pub mod pricing;
 pub mod states;
+pub mod swap_venue;
 pub mod utils;
 
 use instructions::*;
 use solana_security_txt::security_txt;
+use states::FeeTier;
 
 #[program]
 pub mod luxor_swap {
@@ -71,24 +84,169 @@ pub mod luxor_swap {
         instructions::update_config(ctx, param, value)
     }
 
-    pub fn buyback(ctx: Context<Buyback>) -> Result<()> {
-        instructions::buyback(ctx)
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        instructions::accept_admin(ctx)
     }
 
-    pub fn redeem(ctx: Context<Redeem>) -> Result<()> {
-        instructions::redeem(ctx)
+    pub fn set_pool_fee_override(
+        ctx: Context<SetPoolFeeOverride>,
+        use_fee_override: bool,
+        trade_fee: u64,
+        protocol_fee: u64,
+        fund_fee: u64,
+        creator_fee: u64,
+    ) -> Result<()> {
+        instructions::set_pool_fee_override(
+            ctx,
+            use_fee_override,
+            trade_fee,
+            protocol_fee,
+            fund_fee,
+            creator_fee,
+        )
+    }
+
+    pub fn buyback(ctx: Context<Buyback>, min_lxr_out: u64) -> Result<()> {
+        instructions::buyback(ctx, min_lxr_out)
+    }
+
+    pub fn buyback_withdraw(ctx: Context<Buyback>) -> Result<()> {
+        instructions::buyback_withdraw(ctx)
+    }
+
+    pub fn buyback_settle(ctx: Context<Buyback>, min_lxr_out: u64) -> Result<()> {
+        instructions::buyback_settle(ctx, min_lxr_out)
+    }
+
+    pub fn redeem(ctx: Context<Redeem>, amount: Option<u64>) -> Result<()> {
+        instructions::redeem(ctx, amount)
+    }
+
+    pub fn claim_sol_rewards(ctx: Context<ClaimSolRewards>) -> Result<()> {
+        instructions::claim_sol_rewards(ctx)
+    }
+
+    pub fn unstake(ctx: Context<Unstake>, amount: u64) -> Result<()> {
+        instructions::unstake(ctx, amount)
+    }
+
+    pub fn withdraw_unstaked(ctx: Context<WithdrawUnstaked>) -> Result<()> {
+        instructions::withdraw_unstaked(ctx)
+    }
+
+    pub fn unstake_isolated(ctx: Context<UnstakeIsolated>) -> Result<()> {
+        instructions::unstake_isolated(ctx)
+    }
+
+    pub fn close_user_stake_info(ctx: Context<CloseUserStakeInfo>) -> Result<()> {
+        instructions::close_user_stake_info(ctx)
+    }
+
+    pub fn propose_blacklist(ctx: Context<ProposeBlacklist>) -> Result<()> {
+        instructions::propose_blacklist(ctx)
+    }
+
+    pub fn cancel_blacklist(ctx: Context<CancelBlacklist>) -> Result<()> {
+        instructions::cancel_blacklist(ctx)
+    }
+
+    pub fn finalize_blacklist(ctx: Context<FinalizeBlacklist>) -> Result<()> {
+        instructions::finalize_blacklist(ctx)
+    }
+
+    pub fn un_blacklist(ctx: Context<UnBlacklist>) -> Result<()> {
+        instructions::un_blacklist(ctx)
+    }
+
+    pub fn rotate_stake_authority(
+        ctx: Context<RotateStakeAuthority>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::rotate_stake_authority(ctx, new_authority)
+    }
+
+    pub fn migrate_reward_scale(
+        ctx: Context<MigrateRewardScale>,
+        numerator: u64,
+        denominator: u64,
+    ) -> Result<()> {
+        instructions::migrate_reward_scale(ctx, numerator, denominator)
+    }
+
+    pub fn migrate_accounts<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MigrateAccounts<'info>>,
+    ) -> Result<()> {
+        instructions::migrate_accounts(ctx)
+    }
+
+    pub fn reconcile_buyback_accounting(
+        ctx: Context<ReconcileBuybackAccounting>,
+        sol_used_for_buyback: u64,
+        sol_rewards_accrued: u64,
+    ) -> Result<()> {
+        instructions::reconcile_buyback_accounting(ctx, sol_used_for_buyback, sol_rewards_accrued)
+    }
+
+    pub fn finalize_stake_closure(ctx: Context<FinalizeStakeClosure>) -> Result<()> {
+        instructions::finalize_stake_closure(ctx)
+    }
+
+    pub fn redelegate(ctx: Context<Redelegate>) -> Result<()> {
+        instructions::redelegate(ctx)
+    }
+
+    pub fn get_vault_balances(ctx: Context<GetVaultBalances>) -> Result<()> {
+        instructions::get_vault_balances(ctx)
+    }
+
+    pub fn get_user_stake_status(ctx: Context<GetUserStakeStatus>) -> Result<()> {
+        instructions::get_user_stake_status(ctx)
+    }
+
+    pub fn get_blacklist_status(ctx: Context<GetBlacklistStatus>) -> Result<()> {
+        instructions::get_blacklist_status(ctx)
+    }
+
+    pub fn get_accrued_but_unbought_rewards(ctx: Context<GetAccruedButUnboughtRewards>) -> Result<()> {
+        instructions::get_accrued_but_unbought_rewards(ctx)
+    }
+
+    pub fn set_anchor_price(ctx: Context<SetAnchorPrice>, anchor_price_x32: u128) -> Result<()> {
+        instructions::set_anchor_price(ctx, anchor_price_x32)
+    }
+
+    pub fn get_claimable(ctx: Context<GetClaimable>) -> Result<()> {
+        instructions::get_claimable(ctx)
+    }
+
+    pub fn set_fee_tiers(ctx: Context<SetFeeTiers>, tiers: Vec<FeeTier>) -> Result<()> {
+        instructions::set_fee_tiers(ctx, tiers)
     }
 
-    pub fn blacklist(ctx: Context<Blacklist>) -> Result<()> {
-        instructions::blacklist(ctx)
+    pub fn queue_emergency(ctx: Context<QueueEmergency>, param: u8, value: u64) -> Result<()> {
+        instructions::queue_emergency(ctx, param, value)
+    }
+
+    pub fn rebalance_vaults(ctx: Context<RebalanceVaults>, from: u8, to: u8, amount: u64) -> Result<()> {
+        instructions::rebalance_vaults(ctx, from, to, amount)
     }
 
     pub fn purchase(
         ctx: Context<Purchase>,
         lxr_to_purchase: u64,
         max_sol_amount: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        instructions::purchase(ctx, lxr_to_purchase, max_sol_amount, deadline)
+    }
+
+    pub fn purchase_isolated(
+        ctx: Context<PurchaseIsolated>,
+        lxr_to_purchase: u64,
+        max_sol_amount: u64,
+        deadline: i64,
     ) -> Result<()> {
-        instructions::purchase(ctx, lxr_to_purchase, max_sol_amount)
+        instructions::purchase_isolated(ctx, lxr_to_purchase, max_sol_amount, deadline)
     }
 
     pub fn manual_purchase(
@@ -99,6 +257,22 @@ pub mod luxor_swap {
         instructions::manual_purchase(ctx, lxr_purchased, sol_spent)
     }
 
+    pub fn airdrop_lxr(ctx: Context<AirdropLxr>, amount: u64) -> Result<()> {
+        instructions::airdrop_lxr(ctx, amount)
+    }
+
+    pub fn deposit_to_treasury(
+        ctx: Context<DepositToTreasury>,
+        amount: u64,
+        bump_allocation: bool,
+    ) -> Result<()> {
+        instructions::deposit_to_treasury(ctx, amount, bump_allocation)
+    }
+
+    pub fn withdraw_treasury_fees(ctx: Context<WithdrawTreasuryFees>, amount: u64) -> Result<()> {
+        instructions::withdraw_treasury_fees(ctx, amount)
+    }
+
     pub fn initialise_configs(
         ctx: Context<InitialiseConfigs>,
         admin: Pubkey,
@@ -111,6 +285,7 @@ pub mod luxor_swap {
         purchase_enabled: bool,
         redeem_enabled: bool,
         initial_lxr_allocation_vault: u64,
+        emergency_delay: u64,
     ) -> Result<()> {
         instructions::initialise_configs(
             ctx,
@@ -124,6 +299,30 @@ pub mod luxor_swap {
             purchase_enabled,
             redeem_enabled,
             initial_lxr_allocation_vault,
+            emergency_delay,
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::pubkey;
+
+    /// The `authority` PDA is written into the protocol stake account as staker
+    /// and withdrawer at init. If this derivation ever changes, every stake
+    /// operation fails until `rotate_stake_authority` is run with the old seed.
+    #[test]
+    fn authority_pda_derivation_is_stable() {
+        let (authority, bump) = Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &ID);
+        assert_eq!(
+            authority,
+            pubkey!("HLECo3fM7GBk5XonVyZaTBcnKvtnBQdXy9v5x9XBz54m")
+        );
+        assert_eq!(bump, 255);
+        assert_eq!(
+            Pubkey::create_program_address(&[AUTH_SEED.as_bytes(), &[bump]], &ID).unwrap(),
+            authority
+        );
+    }
+}
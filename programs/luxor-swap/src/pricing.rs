@@ -0,0 +1,313 @@
+//! Purchase pricing shared by the program and off-chain clients
+
+use crate::curve::{CurveCalculator, FEE_RATE_DENOMINATOR_VALUE};
+use crate::error::ErrorCode;
+use crate::states::{GlobalConfig, PoolState, StakeInfo, SwapParams};
+use anchor_lang::prelude::*;
+
+/// The pool's LXR reserve must exceed the priced output by this factor, otherwise
+/// the exact-output curve is too steep to give a meaningful price.
+pub const POOL_LIQUIDITY_SAFETY_FACTOR: u128 = 10;
+
+/// Fee rates used when no admin fee override is set
+/// (`trade`, `protocol`, `fund`, `creator`).
+pub const DEFAULT_PURCHASE_FEE_RATES: (u64, u64, u64, u64) = (2500, 120000, 40000, 500);
+
+/// LXR amount `purchase` actually prices on the curve for `lxr_to_purchase`:
+/// minus `bonus_rate` for a new staker inside the early-bird window
+/// (`bonus_applies`), otherwise scaled by `initial_lxr_allocation_vault / luxor_vault_amount`.
+///
+/// The scaling inflates the priced amount as the treasury depletes, so the price
+/// rises with scarcity like a bonding curve: with `initial = 1_000_000` LXR, buying
+/// 100 LXR prices 100 on the curve at a full treasury, 200 at half and 1_000 at a
+/// tenth. A treasury refilled above `initial` discounts proportionally.
+pub fn priced_lxr_amount(
+    lxr_to_purchase: u64,
+    stake_info: &StakeInfo,
+    global_config: &GlobalConfig,
+    is_new_staker: bool,
+    luxor_vault_amount: u64,
+) -> Result<u64> {
+    if is_new_staker && global_config.bonus_applies(stake_info.total_stake_count) {
+        return Ok(lxr_to_purchase
+            .checked_sub(
+                lxr_to_purchase
+                    .checked_mul(global_config.bonus_rate)
+                    .ok_or(ErrorCode::MathOverflow)?
+                    .checked_div(FEE_RATE_DENOMINATOR_VALUE)
+                    .ok_or(ErrorCode::MathOverflow)?,
+            )
+            .ok_or(ErrorCode::UnderflowError)?);
+    }
+    // Without a reference allocation the scaling would price every purchase at zero.
+    require_gt!(global_config.initial_lxr_allocation_vault, 0, ErrorCode::InvalidParam);
+    Ok(u64::try_from(
+        u128::from(lxr_to_purchase)
+            .checked_mul(global_config.initial_lxr_allocation_vault as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(luxor_vault_amount as u128)
+            .ok_or(ErrorCode::MathOverflow)?,
+    )
+    .map_err(|_| ErrorCode::MathOverflow)?)
+}
+
+/// Lamports `purchase` charges for `lxr_to_purchase` LXR.
+///
+/// `token_0_amount` / `token_1_amount` are the pool's vault balances, and
+/// `luxor_vault_amount` is the treasury's LXR balance. `is_new_staker` is true
+/// when the buyer has never staked (no `UserStakeInfo`, or one without an `owner`).
+///
+/// 1. Priced amount from `priced_lxr_amount` (bonus or inventory scaling).
+/// 2. Pool checks: `check_pool_reserves`, `check_price_impact`, `check_price_band` on the
+///    pool-implied LXR price, and the LXR reserve must exceed `POOL_LIQUIDITY_SAFETY_FACTOR` times the priced amount.
+/// 3. Exact-output swap on the configured curve, using the fee override when set and
+///    `DEFAULT_PURCHASE_FEE_RATES` otherwise. The invariant must not decrease.
+///
+/// Does not check `min_swap_amount`/`max_swap_amount` or the treasury floors; those
+/// are enforced by `purchase` itself.
+#[allow(clippy::too_many_arguments)]
+pub fn quote_sol_for_lxr(
+    pool_state: &PoolState,
+    token_0_amount: u64,
+    token_1_amount: u64,
+    lxr_to_purchase: u64,
+    stake_info: &StakeInfo,
+    global_config: &GlobalConfig,
+    is_new_staker: bool,
+    luxor_vault_amount: u64,
+) -> Result<u64> {
+    // --- 1. Bonus for new stakers, otherwise scale against inventory depth ---
+    let amount_out = priced_lxr_amount(
+        lxr_to_purchase,
+        stake_info,
+        global_config,
+        is_new_staker,
+        luxor_vault_amount,
+    )?;
+
+    // --- 2. Pool reserves and liquidity guards ---
+    let SwapParams {
+        trade_direction: _,
+        total_input_token_amount,
+        total_output_token_amount,
+        token_0_price_x64: _,
+        token_1_price_x64: lxr_price_x32,
+        is_creator_fee_on_input,
+    } = pool_state.get_swap_params(
+        pool_state.token_0_vault,
+        pool_state.token_1_vault,
+        token_0_amount,
+        token_1_amount,
+    )?;
+    global_config.check_pool_reserves(total_input_token_amount, total_output_token_amount)?;
+    global_config.check_price_impact(lxr_to_purchase, total_output_token_amount)?;
+    global_config.check_price_band(lxr_price_x32)?;
+
+    // Refuse to price against a (near-)drained LXR reserve.
+    require_gt!(
+        u128::from(total_output_token_amount),
+        u128::from(amount_out)
+            .checked_mul(POOL_LIQUIDITY_SAFETY_FACTOR)
+            .ok_or(ErrorCode::MathOverflow)?,
+        ErrorCode::InsufficientPoolLiquidity
+    );
+
+    // --- 3. Exact-output swap on the configured curve ---
+    let curve_type = global_config.purchase_curve()?;
+    let constant_before = curve_type
+        .invariant(u128::from(total_input_token_amount), u128::from(total_output_token_amount))
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let (trade_fee_rate, protocol_fee_rate, fund_fee_rate, base_creator_fee_rate) =
+        match global_config.fee_override() {
+            Some(fees) => (fees.trade_fee_rate, fees.protocol_fee_rate, fees.fund_fee_rate, fees.creator_fee_rate),
+            None => DEFAULT_PURCHASE_FEE_RATES,
+        };
+    let creator_fee_rate = pool_state.adjust_creator_fee_rate(base_creator_fee_rate);
+    // A rate above the denominator means a malformed pool state; fee math would underflow.
+    require!(creator_fee_rate <= FEE_RATE_DENOMINATOR_VALUE, ErrorCode::InvalidFeeModel);
+
+    let result = CurveCalculator::swap_base_output(
+        u128::from(amount_out),
+        u128::from(total_input_token_amount),
+        u128::from(total_output_token_amount),
+        trade_fee_rate,
+        creator_fee_rate,
+        protocol_fee_rate,
+        fund_fee_rate,
+        is_creator_fee_on_input,
+        curve_type,
+    )
+    .ok_or(ErrorCode::ZeroTradingTokens)?;
+
+    let constant_after = curve_type
+        .invariant(result.new_input_vault_amount, result.new_output_vault_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    // Must price exactly the requested amount.
+    require_eq!(
+        u64::try_from(result.output_amount).map_err(|_| ErrorCode::MathOverflow)?,
+        amount_out
+    );
+    require_gte!(constant_after, constant_before);
+
+    Ok(u64::try_from(result.input_amount).map_err(|_| ErrorCode::MathOverflow)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BONUS_WINDOW: u64 = 3;
+
+    fn bonus_config() -> GlobalConfig {
+        GlobalConfig {
+            // 10% off the priced amount.
+            bonus_rate: FEE_RATE_DENOMINATOR_VALUE / 10,
+            max_stake_count_to_get_bonus: BONUS_WINDOW,
+            initial_lxr_allocation_vault: 1_000_000,
+            ..Default::default()
+        }
+    }
+
+    /// Runs `purchases` first-time purchases in order, incrementing
+    /// `total_stake_count` after each one the way `purchase` does, and returns
+    /// whether each got the bonus.
+    fn bonus_by_purchase(global_config: &GlobalConfig, purchases: u64) -> Vec<bool> {
+        let mut stake_info = StakeInfo::default();
+        (0..purchases)
+            .map(|_| {
+                let priced =
+                    priced_lxr_amount(1_000, &stake_info, global_config, true, 1_000_000).unwrap();
+                stake_info.total_stake_count += 1;
+                priced < 1_000
+            })
+            .collect()
+    }
+
+    #[test]
+    fn bonus_cliff_is_at_max_stake_count() {
+        // The Nth staker (count N - 1 before buying) still gets the bonus; the (N+1)th does not.
+        assert_eq!(
+            bonus_by_purchase(&bonus_config(), BONUS_WINDOW + 2),
+            vec![true, true, true, false, false]
+        );
+    }
+
+    #[test]
+    fn bonus_boundary_priced_amounts() {
+        let global_config = bonus_config();
+        let last_in_window = StakeInfo {
+            total_stake_count: BONUS_WINDOW - 1,
+            ..Default::default()
+        };
+        let first_after_window = StakeInfo {
+            total_stake_count: BONUS_WINDOW,
+            ..Default::default()
+        };
+        assert_eq!(
+            priced_lxr_amount(1_000, &last_in_window, &global_config, true, 1_000_000).unwrap(),
+            900
+        );
+        assert_eq!(
+            priced_lxr_amount(1_000, &first_after_window, &global_config, true, 1_000_000).unwrap(),
+            1_000
+        );
+    }
+
+    #[test]
+    fn bonus_needs_a_new_staker_and_an_open_window() {
+        // Returning stakers never get the bonus, even inside the window.
+        let in_window = StakeInfo::default();
+        assert_eq!(
+            priced_lxr_amount(1_000, &in_window, &bonus_config(), false, 1_000_000).unwrap(),
+            1_000
+        );
+        // `max_stake_count_to_get_bonus == 0` disables the bonus entirely.
+        let disabled = GlobalConfig {
+            max_stake_count_to_get_bonus: 0,
+            ..bonus_config()
+        };
+        assert_eq!(bonus_by_purchase(&disabled, 2), vec![false, false]);
+    }
+
+    /// SOL and LXR reserves of the test pool (1_000 SOL / 1_000_000 LXR).
+    const POOL_SOL: u64 = 1_000_000_000_000;
+    const POOL_LXR: u64 = 1_000_000_000_000_000;
+    /// Treasury allocation the inventory scaling is measured against.
+    const INITIAL_ALLOCATION: u64 = 500_000_000_000_000;
+    /// 1_000 LXR.
+    const LXR: u64 = 1_000_000_000_000;
+
+    fn quote_config() -> GlobalConfig {
+        GlobalConfig {
+            initial_lxr_allocation_vault: INITIAL_ALLOCATION,
+            ..bonus_config()
+        }
+    }
+
+    /// Lamports quoted for `lxr` at `total_stake_count`, against a fixed pool
+    /// whose token 0 is SOL and token 1 is LXR.
+    fn quote(
+        global_config: &GlobalConfig,
+        total_stake_count: u64,
+        is_new_staker: bool,
+        lxr: u64,
+        luxor_vault_amount: u64,
+    ) -> u64 {
+        let pool_state = PoolState {
+            token_0_vault: Pubkey::new_unique(),
+            token_1_vault: Pubkey::new_unique(),
+            ..Default::default()
+        };
+        let stake_info = StakeInfo {
+            total_stake_count,
+            ..Default::default()
+        };
+        quote_sol_for_lxr(
+            &pool_state,
+            POOL_SOL,
+            POOL_LXR,
+            lxr,
+            &stake_info,
+            global_config,
+            is_new_staker,
+            luxor_vault_amount,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn quote_in_bonus_phase_prices_the_discounted_amount() {
+        let global_config = quote_config();
+        let with_bonus = quote(&global_config, 0, true, LXR, INITIAL_ALLOCATION);
+
+        // A full treasury scales by 1, so the bonus quote is the quote for 90%.
+        assert_eq!(with_bonus, quote(&global_config, 0, false, LXR * 9 / 10, INITIAL_ALLOCATION));
+        assert!(with_bonus < quote(&global_config, 0, false, LXR, INITIAL_ALLOCATION));
+    }
+
+    #[test]
+    fn quote_after_bonus_phase_scales_with_inventory() {
+        let global_config = quote_config();
+        let full = quote(&global_config, BONUS_WINDOW, true, LXR, INITIAL_ALLOCATION);
+        let half = quote(&global_config, BONUS_WINDOW, true, LXR, INITIAL_ALLOCATION / 2);
+        let tenth = quote(&global_config, BONUS_WINDOW, true, LXR, INITIAL_ALLOCATION / 10);
+
+        // Half the treasury prices twice the amount, a tenth prices ten times.
+        assert_eq!(half, quote(&global_config, BONUS_WINDOW, true, 2 * LXR, INITIAL_ALLOCATION));
+        assert_eq!(tenth, quote(&global_config, BONUS_WINDOW, true, 10 * LXR, INITIAL_ALLOCATION));
+        assert!(full < half && half < tenth);
+    }
+
+    #[test]
+    fn quote_at_exact_max_stake_count_boundary() {
+        let global_config = quote_config();
+        let bonus = quote(&global_config, 0, true, LXR, INITIAL_ALLOCATION);
+        let no_bonus = quote(&global_config, 0, false, LXR, INITIAL_ALLOCATION);
+
+        assert_eq!(quote(&global_config, BONUS_WINDOW - 1, true, LXR, INITIAL_ALLOCATION), bonus);
+        assert_eq!(quote(&global_config, BONUS_WINDOW, true, LXR, INITIAL_ALLOCATION), no_bonus);
+        assert_eq!(quote(&global_config, BONUS_WINDOW + 1, true, LXR, INITIAL_ALLOCATION), no_bonus);
+    }
+}
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+/// Raydium CPMM fee configuration account, mirrored so buyback can price a
+/// swap with the same fee rates the Raydium CPI will apply.
+///
+/// The layout (and therefore the Anchor discriminator) must stay identical to
+/// Raydium's `AmmConfig`; the account is only ever deserialized, never written.
+#[account]
+#[derive(Default, Debug)]
+pub struct AmmConfig {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// Status to control if new pool can be created
+    pub disable_create_pool: bool,
+    /// Config index
+    pub index: u16,
+    /// The trade fee, denominated in hundredths of a bip (10^-6)
+    pub trade_fee_rate: u64,
+    /// The protocol fee
+    pub protocol_fee_rate: u64,
+    /// The fund fee, denominated in hundredths of a bip (10^-6)
+    pub fund_fee_rate: u64,
+    /// Fee for create a new pool
+    pub create_pool_fee: u64,
+    /// Address of the protocol fee owner
+    pub protocol_owner: Pubkey,
+    /// Address of the fund fee owner
+    pub fund_owner: Pubkey,
+    /// The pool creator fee, denominated in hundredths of a bip (10^-6)
+    pub creator_fee_rate: u64,
+    /// padding
+    pub padding: [u64; 15],
+}
@@ -1,3 +1,4 @@
+use crate::states::FeeTier;
 use anchor_lang::prelude::*;
 
 //
@@ -19,7 +20,7 @@ pub struct GlobalConfigInitialized {
     pub luxor_mint: Pubkey,
     /// Treasury LXR vault (protocol-owned).
     pub lxr_treasury_vault: Pubkey,
-    /// Rewards LXR vault (pays user redemptions).
+    /// Rewards vault (holds `reward_mint`, pays user redemptions).
     pub lxr_reward_vault: Pubkey,
     /// Stake account PDA (owned by Stake program).
     pub stake_account: Pubkey,
@@ -31,9 +32,9 @@ pub struct GlobalConfigInitialized {
     pub bonus_rate: u64,
     /// Maximum stake count threshold where bonus still applies.
     pub max_stake_count_to_get_bonus: u64,
-    /// Minimum LXR allowed per swap/purchase.
+    /// Minimum SOL (lamports) per purchase.
     pub min_swap_amount: u64,
-    /// Maximum LXR allowed per swap/purchase.
+    /// Maximum SOL (lamports) per purchase (`0` = no cap).
     pub max_swap_amount: u64,
     /// Treasury fee rate applied to certain flows (e.g., buybacks).
     pub fee_treasury_rate: u64,
@@ -43,26 +44,94 @@ pub struct GlobalConfigInitialized {
     pub redeem_enabled: bool,
     /// Initial LXR allocation reference used in pricing/scaling logic.
     pub initial_lxr_allocation_vault: u64,
+    /// Mint held by the reward vault (LUXOR unless a distinct reward token was chosen).
+    pub reward_mint: Pubkey,
+    /// Timelock (seconds) on `emergency_withdraw`; `0` = none.
+    pub emergency_delay: u64,
 }
 
 /// Emitted whenever configuration parameters are modified via `update_config`.
 ///
-/// Useful for tracking governance/admin actions that change economic parameters.
+/// Carries the `param` selector that changed plus a snapshot of **every** tunable
+/// field after the update, so indexers see the complete post-update state
+/// regardless of which param was touched.
 #[event]
 #[cfg_attr(feature = "client", derive(Debug))]
 pub struct ConfigUpdated {
+    /// `update_config` selector that was applied.
+    pub param: u8,
     /// Current admin (may be the same or newly set).
     pub admin: Pubkey,
-    /// New minimum swap/purchase amount.
+    /// Early-bird bonus rate.
+    pub bonus_rate: u64,
+    /// Stake count threshold where the bonus still applies.
+    pub max_stake_count_to_get_bonus: u64,
+    /// Minimum SOL (lamports) per purchase.
     pub min_swap_amount: u64,
-    /// New maximum swap/purchase amount.
+    /// Maximum SOL (lamports) per purchase (`0` = no cap).
     pub max_swap_amount: u64,
-    /// Updated treasury fee rate.
+    /// Treasury fee rate.
     pub fee_treasury_rate: u64,
     /// Whether purchasing is enabled after the update.
     pub purchase_enabled: bool,
     /// Whether redemption is enabled after the update.
     pub redeem_enabled: bool,
+    /// Inventory pricing reference.
+    pub initial_lxr_allocation_vault: u64,
+    /// Per-request buyback split cap (`0` = no cap).
+    pub max_sol_per_buyback: u64,
+    /// Propose → finalize blacklist delay (seconds).
+    pub blacklist_delay_secs: u64,
+    /// Cached stake account rent-exempt minimum (lamports).
+    pub stake_rent_exempt: u64,
+    /// Buyback Raydium `minimum_amount_out` tolerance (over the fee denominator).
+    pub buyback_out_tolerance_rate: u64,
+    /// Treasury LXR floor below which purchases pause.
+    pub min_treasury_for_purchase: u64,
+    /// Minimum pool reserve for purchase/buyback pricing (`0` = disabled).
+    pub min_pool_reserve: u64,
+    /// Purchase pricing curve (`0` = constant product, `1` = offset product).
+    pub curve_type: u8,
+    /// Offset curve virtual SOL reserve.
+    pub curve_offset_sol: u64,
+    /// Offset curve virtual LXR reserve.
+    pub curve_offset_lxr: u64,
+    /// Whether buyback takes a treasury fee.
+    pub buyback_fee_enabled: bool,
+    /// Redeem holdings grace (bps of base holdings).
+    pub forfeiture_grace_bps: u64,
+    /// Nominated admin awaiting `accept_admin` (`Pubkey::default()` = none).
+    pub pending_admin: Pubkey,
+    /// Per-purchase cap as a share of the pool's LXR reserve (bps, `0` = no cap).
+    pub max_pool_fraction_bps: u64,
+    /// Whether redeem forfeitures are redistributed to stakers.
+    pub redistribute_forfeitures: bool,
+    /// Reward vault LXR level buyback refills toward (`0` = disabled).
+    pub reward_vault_target: u64,
+    /// Minimum seconds between executed buybacks (`0` = no cooldown).
+    pub buyback_cooldown_secs: u64,
+    /// Recipient of `withdraw_treasury_fees` (`Pubkey::default()` = not set).
+    pub fee_recipient: Pubkey,
+    /// Share of base holdings (bps) required to redeem (`0` = off).
+    pub min_hold_ratio_bps: u64,
+    /// Pool buyback swaps into the reward mint through.
+    pub reward_pool_state: Pubkey,
+    /// DEX buyback swaps through (`DEX_TYPE_*`).
+    pub dex_type: u8,
+    /// Per-user stake cap in lamports (`0` = no cap).
+    pub max_stake_per_user: u64,
+    /// Global stake cap in lamports (`0` = unlimited).
+    pub max_total_staked_sol: u64,
+    /// Reference LXR price for the purchase price band (Q32 lamports per base unit).
+    pub anchor_price_x32: u128,
+    /// Allowed pool price deviation from the anchor in bps (`0` = band disabled).
+    pub max_price_deviation_bps: u64,
+    /// Share of accrued stake rewards reserved for SOL claims, in bps.
+    pub sol_reward_share_bps: u64,
+    /// Minimum LXR per purchase (`0` = no minimum).
+    pub min_lxr_per_purchase: u64,
+    /// Maximum LXR per purchase (`0` = no cap).
+    pub max_lxr_per_purchase: u64,
 }
 
 /// Emitted when a user buys LXR through the regular purchase path.
@@ -77,6 +146,9 @@ pub struct LxrPurchased {
     pub sol_amount: u64,
     /// LXR received (base units).
     pub lxr_amount: u64,
+    /// Lamports still accepted before `max_total_staked_sol` is reached
+    /// (`u64::MAX` when unlimited).
+    pub remaining_stake_capacity: u64,
 }
 
 /// Emitted when an admin records a manual purchase on behalf of a user.
@@ -93,18 +165,34 @@ pub struct ManualLxrPurchased {
     pub lxr_amount: u64,
 }
 
+/// Emitted when an admin airdrops LXR from the treasury without any SOL staked.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct LxrAirdropped {
+    /// Recipient of the airdrop.
+    pub user: Pubkey,
+    /// LXR transferred and added to the user's base holdings (base units).
+    pub amount: u64,
+}
+
 /// Emitted after executing a buyback using accrued SOL stake rewards.
 ///
 /// Shows SOL consumed, LXR acquired, and protocol fee routed to treasury.
 #[event]
 #[cfg_attr(feature = "client", derive(Debug))]
 pub struct BuybackExecuted {
-    /// Total SOL rewards used for this buyback (before fee), in lamports.
+    /// SOL rewards swapped in this buyback (before fee), in lamports.
     pub sol_amount: u64,
     /// LXR acquired from the market (base units).
     pub lxr_bought: u64,
     /// Fee portion of SOL sent to treasury (in lamports).
     pub fee_to_treasury: u64,
+    /// Split account rent returned to the admin who paid it (in lamports).
+    pub rent_refunded: u64,
+    /// Buyback sequence number; matches the `BuybackRequested` that split the SOL.
+    pub buyback_count: u64,
+    /// Reserved SOL rewards moved to the SOL treasury vault to back SOL claims (lamports).
+    pub sol_reserved: u64,
 }
 
 /// Emitted when a user redeems their LXR rewards.
@@ -118,8 +206,47 @@ pub struct RewardsCollected {
     pub collector: Pubkey,
     /// LXR paid out to the user (base units).
     pub lxr_collected: u64,
-    /// LXR forfeited to treasury due to shortfall vs base holdings (base units).
+    /// LXR forfeited due to shortfall vs base holdings (base units); sent to
+    /// treasury or redistributed (see `ForfeitureRedistributed`).
     pub lxr_forfeited: u64,
+    /// Claimable LXR left in `lxr_rewards_pending` by a partial redeem (`0` otherwise).
+    pub lxr_remaining: u64,
+}
+
+/// Emitted when a user claims their share of accrued SOL staking rewards.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct SolRewardsCollected {
+    /// The user who claimed rewards.
+    pub collector: Pubkey,
+    /// WSOL paid out to the user (lamports; may be `0`).
+    pub sol_collected: u64,
+    /// Rewards left in `sol_rewards_pending` because no buyback has funded them yet (lamports).
+    pub sol_deferred: u64,
+}
+
+/// Emitted when a user splits SOL off the protocol stake via `unstake`.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct Unstaked {
+    /// The user unstaking.
+    pub user: Pubkey,
+    /// Lamports split off the protocol stake account.
+    pub amount: u64,
+    /// Deactivating stake account holding the unstaked SOL.
+    pub destination: Pubkey,
+    /// LXR returned from the user's ATA to the treasury for the unstaked SOL.
+    pub lxr_returned: u64,
+}
+
+/// Emitted when a user withdraws a deactivated unstake account.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct UnstakeWithdrawn {
+    /// The user receiving the SOL.
+    pub user: Pubkey,
+    /// Lamports withdrawn (unstaked SOL plus the account's rent).
+    pub amount: u64,
 }
 
 /// Emitted when a user is blacklisted, forfeiting their pending rewards.
@@ -129,6 +256,379 @@ pub struct RewardsCollected {
 pub struct UserBlacklisted {
     /// The user who was blacklisted.
     pub user: Pubkey,
-    /// LXR forfeited to treasury due to blacklisting (base units).
+    /// Staked SOL (lamports) reassigned to the admin record.
     pub sol_blacklisted: u64,
+    /// Pending LXR rewards reassigned to the admin record (base units).
+    pub lxr_reassigned: u64,
+}
+
+/// Emitted when the stake PDA's staker/withdrawer authorities are re-assigned.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct StakeAuthorityRotated {
+    /// Protocol stake account whose authorities changed.
+    pub stake_account: Pubkey,
+    /// Previous staker/withdrawer (the program authority PDA).
+    pub old_authority: Pubkey,
+    /// New staker/withdrawer.
+    pub new_authority: Pubkey,
+}
+
+/// Emitted when the admin proposes blacklisting a user (opens the dispute window).
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct BlacklistProposed {
+    /// The user proposed for blacklisting.
+    pub user: Pubkey,
+    /// Earliest UNIX timestamp (seconds) at which `finalize_blacklist` succeeds.
+    pub finalize_after: u64,
+}
+
+/// Emitted when a pending blacklist proposal is cancelled.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct BlacklistCancelled {
+    /// The user whose proposal was withdrawn.
+    pub user: Pubkey,
+}
+
+/// Emitted when the reward indices are migrated to a new scale.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct RewardScaleMigrated {
+    /// Numerator of the correction factor.
+    pub numerator: u64,
+    /// Denominator of the correction factor.
+    pub denominator: u64,
+    /// New `StakeInfo::reward_scale_version`.
+    pub reward_scale_version: u8,
+}
+
+/// Emitted when the admin corrects the buyback accounting totals.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct BuybackAccountingReconciled {
+    /// `total_sol_used_for_buyback` before the correction.
+    pub old_used: u64,
+    /// `total_sol_used_for_buyback` after the correction.
+    pub new_used: u64,
+    /// `total_sol_rewards_accrued` before the correction.
+    pub old_accrued: u64,
+    /// `total_sol_rewards_accrued` after the correction.
+    pub new_accrued: u64,
+}
+
+/// Emitted by the read-only `get_vault_balances` view.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct VaultBalances {
+    /// LXR held by the treasury vault (`lxr_treasury_vault`).
+    pub treasury_lxr: u64,
+    /// LXR held by the reward vault (`lxr_reward_vault`).
+    pub reward_lxr: u64,
+    /// WSOL held by the SOL treasury vault (`sol_treasury_vault`).
+    pub treasury_wsol: u64,
+    /// Mint of both LXR vaults.
+    pub lxr_mint: Pubkey,
+    /// Mint of the SOL treasury vault (native mint).
+    pub wsol_mint: Pubkey,
+}
+
+/// Emitted when the admin nominates a successor (`update_config` param `0`).
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct AdminTransferInitiated {
+    /// Admin at the time of the nomination.
+    pub admin: Pubkey,
+    /// Nominated admin; must sign `accept_admin` to take over.
+    pub pending_admin: Pubkey,
+}
+
+/// Emitted when the nominated admin accepts and becomes `global_config.admin`.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct AdminTransferAccepted {
+    /// Admin being replaced.
+    pub old_admin: Pubkey,
+    /// New admin (previously `pending_admin`).
+    pub new_admin: Pubkey,
+}
+
+/// Emitted when a user closes their `UserStakeInfo` and reclaims its rent.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct UserStakeInfoClosed {
+    /// Owner of the closed record (rent recipient).
+    pub user: Pubkey,
+    /// Lifetime LXR claimed by the record.
+    pub total_lxr_claimed: u64,
+    /// Lifetime LXR forfeited by the record.
+    pub total_lxr_forfeited: u64,
+}
+
+/// Emitted when the admin sets the pricing fee override.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolFeeOverrideSet {
+    /// Whether `purchase`/`buyback` now price with the override rates.
+    pub use_fee_override: bool,
+    /// Override trade fee rate.
+    pub trade_fee: u64,
+    /// Override protocol fee rate.
+    pub protocol_fee: u64,
+    /// Override fund fee rate.
+    pub fund_fee: u64,
+    /// Override pool creator fee rate.
+    pub creator_fee: u64,
+}
+
+/// Emitted by the read-only `get_user_stake_status` view.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct UserStakeStatus {
+    /// User the record belongs to.
+    pub user: Pubkey,
+    /// SOL (lamports) still staked and earning rewards (`total_staked_sol`).
+    pub effective_stake: u64,
+    /// Cumulative SOL reassigned to the admin by past blacklists.
+    pub blacklisted_sol: u64,
+    /// Pending blacklist proposal time (`0` = none).
+    pub blacklist_pending_timestamp: u64,
+}
+
+/// Emitted when a blacklist is reversed and the stake returned to the user.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct UserUnblacklisted {
+    /// The restored user.
+    pub user: Pubkey,
+    /// Staked SOL (lamports) moved back from the admin record.
+    pub sol_restored: u64,
+}
+
+/// Emitted when LXR is purchased with SOL delegated from the buyer's own
+/// isolated stake account (outside the shared stake accounting).
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct IsolatedStakePurchased {
+    /// The user who performed the purchase.
+    pub purchaser: Pubkey,
+    /// The isolated stake account created for the purchase.
+    pub stake_account: Pubkey,
+    /// SOL delegated (in lamports, excluding rent).
+    pub sol_amount: u64,
+    /// LXR received (base units).
+    pub lxr_amount: u64,
+}
+
+/// Emitted when LXR forfeited on `redeem` is redistributed to the remaining
+/// stakers instead of going to treasury.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ForfeitureRedistributed {
+    /// LXR redistributed (base units); stays in the reward vault.
+    pub amount: u64,
+    /// `reward_per_token_lxr_stored` after the redistribution.
+    pub new_index: u128,
+}
+
+/// Emitted when the admin records that the protocol stake PDA has been closed.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct StakeClosureFinalized {
+    /// `total_staked_sol` still on the books at closure (settled off-chain).
+    pub total_staked_sol: u64,
+    /// Closure time (unix seconds).
+    pub timestamp: u64,
+}
+
+/// Emitted when the admin deposits LXR into the treasury vault.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct TreasuryFunded {
+    /// LXR deposited (base units).
+    pub amount: u64,
+    /// `initial_lxr_allocation_vault` after the deposit (unchanged unless bumped).
+    pub new_allocation: u64,
+}
+
+/// Emitted by the read-only `get_blacklist_status` view.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct BlacklistStatus {
+    /// User the record belongs to.
+    pub user: Pubkey,
+    /// `true` while a finalized blacklist is in effect (`blacklisted_sol > 0`).
+    pub is_blacklisted: bool,
+    /// Cumulative SOL reassigned to the admin by past blacklists.
+    pub blacklisted_sol: u64,
+}
+
+/// Emitted when buyback fees are paid out of the SOL treasury vault.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct TreasuryFeesWithdrawn {
+    /// Configured fee recipient (owner of the receiving WSOL ATA).
+    pub recipient: Pubkey,
+    /// WSOL transferred (lamports); capped at the vault balance.
+    pub amount: u64,
+}
+
+/// Emitted when the admin moves the protocol stake to a new validator.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ValidatorRedelegated {
+    /// Vote account the stake was delegated to before.
+    pub old_vote_account: Pubkey,
+    /// Vote account now recorded in `global_config.vote_account`.
+    pub new_vote_account: Pubkey,
+    /// `true` if the stake was delegated in this call; `false` while it still has to cool down.
+    pub delegated: bool,
+}
+
+/// Emitted by the request phase of `buyback`, once the reward SOL has been split
+/// off and the protocol stake deactivated; the matching `BuybackExecuted` carries
+/// the same `buyback_count`.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct BuybackRequested {
+    /// Buyback sequence number (seed of `split_pda`).
+    pub buyback_count: u64,
+    /// Lamports split off for the swap (`0` if no rewards were available).
+    pub sol_to_buyback: u64,
+    /// Reserved SOL rewards split off with it, to be moved to the SOL treasury vault.
+    pub sol_to_reserve: u64,
+    /// Split stake account holding the SOL until the execution phase.
+    pub split_pda: Pubkey,
+}
+
+/// Emitted by the read-only `get_accrued_but_unbought_rewards` view.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct AccruedButUnboughtRewards {
+    /// Accrued SOL rewards neither swapped nor moved to the SOL reward reserve (lamports).
+    pub accounted_rewards: u64,
+    /// Stake PDA lamports above `total_staked_sol` plus rent.
+    pub stake_rewards: u64,
+    /// Stake PDA lamports above `last_tracked_sol_balance`, not yet accrued.
+    pub unaccrued_rewards: u64,
+    /// Stake PDA balance the figures were read from.
+    pub stake_lamports: u64,
+}
+
+/// Emitted when the admin sets the purchase price-band anchor.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct AnchorPriceSet {
+    /// Previous anchor price (Q32 lamports per LXR base unit, `0` = none).
+    pub old_anchor_price_x32: u128,
+    /// New anchor price (Q32 lamports per LXR base unit, `0` = none).
+    pub anchor_price_x32: u128,
+}
+
+/// Emitted by the read-only `get_claimable` view.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ClaimableRewards {
+    /// User the figures belong to.
+    pub user: Pubkey,
+    /// Rewards `redeem` would pay now, carried-over `lxr_rewards_pending` included.
+    pub claimable: u64,
+    /// Rewards `redeem` would forfeit at the current holdings.
+    pub forfeitable: u64,
+    /// LXR holdings the forfeiture was computed against (`0` without an ATA).
+    pub lxr_holdings: u64,
+    /// Whether holdings reach `min_hold_ratio_bps`; `redeem` fails otherwise.
+    pub meets_min_hold: bool,
+}
+
+/// Emitted when the admin replaces the buyback fee schedule.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct FeeTiersSet {
+    /// New tiers (`min_sol` thresholds and rates over `FEE_RATE_DENOMINATOR_VALUE`).
+    pub tiers: Vec<FeeTier>,
+}
+
+/// Emitted by every `emergency_withdraw` operation (audit trail of admin power).
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct EmergencyAction {
+    /// Operation performed (`EmergencyOperation` as `u8`).
+    pub param: u8,
+    /// `value` argument as passed (only meaningful for `WithdrawStake`).
+    pub value: u64,
+    /// LXR, WSOL or lamports moved; for `DeactivateStake` the stake balance that entered cooldown.
+    pub amount: u64,
+    /// Vault or stake account the amount was taken from (or deactivated).
+    pub source: Pubkey,
+    /// Admin who executed the operation.
+    pub executor: Pubkey,
+}
+
+/// Emitted when the admin queues an `emergency_withdraw` behind the timelock.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct EmergencyQueued {
+    /// Queued operation (`EmergencyOperation` as `u8`).
+    pub param: u8,
+    /// Queued `value` argument.
+    pub value: u64,
+    /// Queued new stake authority (`RotateStakeAuthority` only, default otherwise).
+    pub target: Pubkey,
+    /// Unix timestamp from which the operation can be executed.
+    pub execute_after: u64,
+    /// Admin who queued it.
+    pub admin: Pubkey,
+}
+
+/// Emitted when `emergency_withdraw` executes a matured queued entry.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct EmergencyExecuted {
+    /// Executed operation (`EmergencyOperation` as `u8`).
+    pub param: u8,
+    /// Executed `value` argument.
+    pub value: u64,
+    /// Timestamp the entry had matured at.
+    pub execute_after: u64,
+}
+
+/// Emitted when the admin moves LXR between the treasury and reward vaults.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct VaultsRebalanced {
+    /// Source vault (`0` = treasury, `1` = reward).
+    pub from: u8,
+    /// Destination vault (`0` = treasury, `1` = reward).
+    pub to: u8,
+    /// LXR moved.
+    pub amount: u64,
+    /// LXR left in the source vault.
+    pub source_balance: u64,
+}
+
+/// Emitted by `buyback_withdraw` once the split rewards are wrapped and waiting for
+/// `buyback_settle`; the matching `BuybackExecuted` carries the same `buyback_count`.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct BuybackWithdrawn {
+    /// Buyback sequence number.
+    pub buyback_count: u64,
+    /// Rewards wrapped into the admin's WSOL account (lamports).
+    pub sol_amount: u64,
+    /// Split account rent returned to the admin who paid it (lamports).
+    pub rent_refunded: u64,
+}
+
+/// Emitted by `migrate_accounts`.
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct AccountsMigrated {
+    /// Whether `GlobalConfig` was grown from the legacy layout.
+    pub global_config_migrated: bool,
+    /// Whether `StakeInfo` was grown from the legacy layout.
+    pub stake_info_migrated: bool,
+    /// Number of `UserStakeInfo` records grown in this call.
+    pub user_records_migrated: u64,
 }
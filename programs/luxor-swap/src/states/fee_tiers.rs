@@ -0,0 +1,159 @@
+use crate::curve::FEE_RATE_DENOMINATOR_VALUE;
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+
+//
+// ──────────────────────────────────────────────────────────────────────────────
+// FeeTiers Account
+// ──────────────────────────────────────────────────────────────────────────────
+//
+
+/// PDA seed string used to derive the buyback fee tiers account.
+pub const FEE_TIERS_SEED: &str = "fee_tiers";
+
+/// Maximum number of tiers in the buyback fee schedule.
+pub const MAX_FEE_TIERS: usize = 8;
+
+/// One step of the buyback treasury fee schedule.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct FeeTier {
+    /// Smallest buyback (lamports withdrawn from the split) this tier applies to.
+    pub min_sol: u64,
+    /// Treasury fee rate for the tier, over `FEE_RATE_DENOMINATOR_VALUE`.
+    pub rate: u64,
+}
+
+/// Size-dependent treasury fee schedule for `buyback`, set by `set_fee_tiers`.
+///
+/// While the account does not exist, buyback charges the flat
+/// `global_config.fee_treasury_rate`, which is the same as a single tier at
+/// `min_sol = 0`. `buyback_fee_enabled = false` still skips the fee entirely.
+#[account]
+#[derive(Default, Debug)]
+pub struct FeeTiers {
+    /// PDA bump for this account.
+    pub bump: u8,
+
+    /// Tiers ordered by strictly increasing `min_sol`, the first starting at `0`.
+    pub tiers: Vec<FeeTier>,
+}
+
+impl FeeTiers {
+    /// Fixed serialized size of the account (for allocation at initialization).
+    ///
+    /// Breakdown:
+    /// - 8: account discriminator
+    /// - 1: bump
+    /// - 4 + (8 + 8) * MAX_FEE_TIERS: vector length prefix and entries
+    pub const LEN: usize = 8 + 1 + 4 + (8 + 8) * MAX_FEE_TIERS;
+
+    /// Checks a candidate schedule: `1..=MAX_FEE_TIERS` tiers, the first at
+    /// `min_sol = 0` so every size is covered, thresholds strictly increasing and
+    /// rates not above `FEE_RATE_DENOMINATOR_VALUE`.
+    pub fn validate(tiers: &[FeeTier]) -> Result<()> {
+        require!(
+            !tiers.is_empty() && tiers.len() <= MAX_FEE_TIERS,
+            ErrorCode::InvalidFeeTiers
+        );
+        require_eq!(tiers[0].min_sol, 0, ErrorCode::InvalidFeeTiers);
+        for (i, tier) in tiers.iter().enumerate() {
+            require_gte!(FEE_RATE_DENOMINATOR_VALUE, tier.rate, ErrorCode::InvalidFeeTiers);
+            require!(
+                i == 0 || tiers[i - 1].min_sol < tier.min_sol,
+                ErrorCode::InvalidFeeTiers
+            );
+        }
+        Ok(())
+    }
+
+    /// Reads the schedule from its PDA, or `None` while it has not been created.
+    /// The caller pins the address.
+    pub fn load(fee_tiers: &AccountInfo) -> Result<Option<Self>> {
+        if fee_tiers.owner != &crate::ID || fee_tiers.data_is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Self::try_deserialize(&mut &fee_tiers.data.borrow()[..])?))
+    }
+
+    /// Rate of the highest tier whose `min_sol` does not exceed `sol_amount`.
+    pub fn rate_for(&self, sol_amount: u64) -> u64 {
+        self.tiers
+            .iter()
+            .take_while(|tier| tier.min_sol <= sol_amount)
+            .last()
+            .map_or(0, |tier| tier.rate)
+    }
+
+    /// Highest rate in the schedule.
+    pub fn max_rate(&self) -> u64 {
+        self.tiers.iter().map(|tier| tier.rate).max().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `LEN` is hand-maintained; it must match a full schedule's serialized size.
+    #[test]
+    fn len_matches_serialized_size_of_a_full_schedule() {
+        let fee_tiers = FeeTiers {
+            bump: 255,
+            tiers: vec![FeeTier::default(); MAX_FEE_TIERS],
+        };
+        let mut data = Vec::new();
+        fee_tiers.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), FeeTiers::LEN);
+    }
+
+    fn schedule(tiers: &[(u64, u64)]) -> FeeTiers {
+        FeeTiers {
+            bump: 255,
+            tiers: tiers
+                .iter()
+                .map(|&(min_sol, rate)| FeeTier { min_sol, rate })
+                .collect(),
+        }
+    }
+
+    /// Each amount gets the rate of the highest tier it reaches; a threshold
+    /// itself belongs to its own tier.
+    #[test]
+    fn rate_for_selects_the_tier_reached() {
+        let fee_tiers = schedule(&[(0, 30_000), (1_000, 20_000), (50_000, 5_000)]);
+        FeeTiers::validate(&fee_tiers.tiers).unwrap();
+
+        assert_eq!(fee_tiers.rate_for(0), 30_000);
+        assert_eq!(fee_tiers.rate_for(999), 30_000);
+        assert_eq!(fee_tiers.rate_for(1_000), 20_000);
+        assert_eq!(fee_tiers.rate_for(49_999), 20_000);
+        assert_eq!(fee_tiers.rate_for(50_000), 5_000);
+        assert_eq!(fee_tiers.rate_for(u64::MAX), 5_000);
+        assert_eq!(fee_tiers.max_rate(), 30_000);
+    }
+
+    /// The default single flat tier charges the same rate at any size.
+    #[test]
+    fn single_tier_is_flat() {
+        let fee_tiers = schedule(&[(0, 25_000)]);
+        for sol_amount in [0, 1, 1_000_000_000, u64::MAX] {
+            assert_eq!(fee_tiers.rate_for(sol_amount), 25_000);
+        }
+    }
+
+    #[test]
+    fn validate_rejects_malformed_schedules() {
+        let too_many = (0..=MAX_FEE_TIERS as u64).map(|i| (i, 0)).collect();
+        for tiers in [
+            vec![],
+            too_many,
+            vec![(1, 10_000)],
+            vec![(0, 10_000), (0, 5_000)],
+            vec![(0, 10_000), (500, 5_000), (400, 1_000)],
+            vec![(0, FEE_RATE_DENOMINATOR_VALUE + 1)],
+        ] {
+            assert!(FeeTiers::validate(&schedule(&tiers).tiers).is_err(), "{tiers:?}");
+        }
+        FeeTiers::validate(&schedule(&[(0, FEE_RATE_DENOMINATOR_VALUE)]).tiers).unwrap();
+    }
+}
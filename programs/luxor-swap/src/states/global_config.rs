@@ -1,3 +1,4 @@
+use crate::{curve::CurveType, error::ErrorCode, states::AmmConfig};
 use anchor_lang::prelude::*;
 
 //
@@ -9,6 +10,18 @@ use anchor_lang::prelude::*;
 /// PDA seed string used to derive the global configuration account.
 pub const GLOBAL_CONFIG_SEED: &str = "global_config";
 
+/// `curve_type` value selecting the plain `x * y = k` purchase curve (default).
+pub const CURVE_TYPE_CONSTANT_PRODUCT: u8 = 0;
+
+/// `curve_type` value selecting the offset (virtual reserves) purchase curve.
+pub const CURVE_TYPE_OFFSET_PRODUCT: u8 = 1;
+
+/// Denominator for basis-point config values (`forfeiture_grace_bps`).
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Default `buyback_out_tolerance_rate` set at init (1% over `FEE_RATE_DENOMINATOR_VALUE`).
+pub const DEFAULT_BUYBACK_OUT_TOLERANCE_RATE: u64 = 10_000;
+
 /// Stores all protocol-wide configuration parameters and fixed vault addresses.
 ///
 /// This account is created once at initialization (`InitialiseConfigs`) and is
@@ -46,15 +59,17 @@ pub struct GlobalConfig {
     pub bonus_rate: u64,
 
     /// Maximum stake count threshold at which bonus rate still applies.
+    /// `0` disables the bonus: every purchase is priced with inventory scaling.
     pub max_stake_count_to_get_bonus: u64,
 
-    /// Minimum LXR amount permitted in a swap or purchase.
+    /// Minimum SOL (lamports) a purchase must pay.
     pub min_swap_amount: u64,
 
-    /// Maximum LXR amount permitted in a swap or purchase.
+    /// Maximum SOL (lamports) a purchase may pay (`0` = no cap).
     pub max_swap_amount: u64,
 
-    /// Fee rate applied to treasury (for buybacks and related flows).
+    /// Fee rate applied to treasury (for buybacks and related flows), over
+    /// `FEE_RATE_DENOMINATOR_VALUE`.
     pub fee_treasury_rate: u64,
 
     /// Global switch: if `false`, purchasing is disabled.
@@ -65,6 +80,143 @@ pub struct GlobalConfig {
 
     /// Initial LXR allocation used as a reference value for scaling purchase pricing.
     pub initial_lxr_allocation_vault: u64,
+
+    /// Maximum lamports split off the stake PDA by a single buyback request
+    /// (`0` = no cap, the full accrued reward is split).
+    pub max_sol_per_buyback: u64,
+
+    /// Decimals of the LUXOR mint recorded at init (always `LUXOR_DECIMALS`,
+    /// which init and the handlers check the live mint against).
+    pub luxor_decimals: u8,
+
+    /// Minimum delay (seconds) between `propose_blacklist` and `finalize_blacklist`
+    /// (`0` = finalize may follow the proposal immediately).
+    pub blacklist_delay_secs: u64,
+
+    /// Rent-exempt minimum (lamports) of a `StakeStateV2` account, cached at init so
+    /// buyback does not read the Rent sysvar. Refreshed via `update_config` param `10`.
+    pub stake_rent_exempt: u64,
+
+    /// Tolerance (over `FEE_RATE_DENOMINATOR_VALUE`) below the program-priced LXR output
+    /// that buyback passes to Raydium as `minimum_amount_out`.
+    pub buyback_out_tolerance_rate: u64,
+
+    /// Purchases are refused while `luxor_vault` holds less LXR than this
+    /// (`0` = only the per-purchase funding check applies).
+    pub min_treasury_for_purchase: u64,
+
+    /// Purchase and buyback refuse to price against the Raydium pool while either
+    /// reserve is below this absolute amount (`0` = disabled).
+    pub min_pool_reserve: u64,
+
+    /// Curve used to price purchases against the pool reserves
+    /// (`CURVE_TYPE_CONSTANT_PRODUCT` or `CURVE_TYPE_OFFSET_PRODUCT`).
+    pub curve_type: u8,
+
+    /// Virtual SOL reserve added to the pool's SOL side by the offset curve.
+    pub curve_offset_sol: u64,
+
+    /// Virtual LXR reserve added to the pool's LXR side by the offset curve.
+    pub curve_offset_lxr: u64,
+
+    /// When `false`, buyback takes no treasury fee and swaps the full withdrawn amount.
+    pub buyback_fee_enabled: bool,
+
+    /// Redeem treats LXR holdings within this many basis points of
+    /// `base_lxr_holdings` as full holdings (`0` = any dip forfeits).
+    pub forfeiture_grace_bps: u64,
+
+    /// Admin nominated by `update_config` param `0`; becomes `admin` once it
+    /// signs `accept_admin` (`Pubkey::default()` = no transfer pending).
+    pub pending_admin: Pubkey,
+
+    /// When `true`, `purchase` and `buyback` price with the `override_*_fee_rate`
+    /// values below instead of the Raydium `amm_config` (testing / fallback).
+    pub use_fee_override: bool,
+
+    /// Override trade fee rate (over `FEE_RATE_DENOMINATOR_VALUE`).
+    pub override_trade_fee_rate: u64,
+
+    /// Override protocol fee rate (over `FEE_RATE_DENOMINATOR_VALUE`).
+    pub override_protocol_fee_rate: u64,
+
+    /// Override fund fee rate (over `FEE_RATE_DENOMINATOR_VALUE`).
+    pub override_fund_fee_rate: u64,
+
+    /// Override pool creator fee rate (over `FEE_RATE_DENOMINATOR_VALUE`).
+    pub override_creator_fee_rate: u64,
+
+    /// Largest share of the pool's LXR reserve a single purchase may take, in
+    /// basis points (over `BPS_DENOMINATOR`, `0` = no cap).
+    pub max_pool_fraction_bps: u64,
+
+    /// When true, LXR forfeited on `redeem` is redistributed to the remaining
+    /// stakers through `reward_per_token_lxr_stored` instead of going to treasury.
+    pub redistribute_forfeitures: bool,
+
+    /// LXR balance (base units) buyback tries to keep in the reward vault. When
+    /// non-zero, a buyback request only splits the SOL needed to refill the vault
+    /// up to this level (`0` = always spend all available rewards).
+    pub reward_vault_target: u64,
+
+    /// Minimum seconds between executed buybacks, measured from
+    /// `StakeInfo::last_buyback_timestamp` (`0` = no cooldown). Only the execution
+    /// phase is gated; a split can still be requested at any time.
+    pub buyback_cooldown_secs: u64,
+
+    /// Owner of the WSOL ATA that `withdraw_treasury_fees` pays buyback fees to
+    /// (`Pubkey::default()` = not set, withdrawals disabled).
+    pub fee_recipient: Pubkey,
+
+    /// Minimum share (bps over `BPS_DENOMINATOR`) of `base_lxr_holdings` a user must
+    /// still hold to redeem at all (`0` = off). Below it `redeem` fails with
+    /// `NoRewardsToClaim` and rewards stay accrued; at or above it the pro-rata
+    /// forfeiture still applies.
+    pub min_hold_ratio_bps: u64,
+
+    /// Mint held by the reward vault: bought by `buyback` and paid out by `redeem`.
+    /// Set once at init (`migrate_accounts` sets LUXOR on a first-release config).
+    pub reward_mint: Pubkey,
+
+    /// Raydium pool `buyback` swaps WSOL into `reward_mint` through (`Pubkey::default()`
+    /// = the LUXOR pool); see `reward_pool_state()`.
+    pub reward_pool_state: Pubkey,
+
+    /// DEX buyback swaps through (`DEX_TYPE_*` in `crate::swap_venue`; default
+    /// `DEX_TYPE_RAYDIUM_CPMM`).
+    pub dex_type: u8,
+
+    /// Most SOL a single user may have staked through `purchase`/`manual_purchase`
+    /// (lamports, `0` = no cap).
+    pub max_stake_per_user: u64,
+
+    /// Most SOL `purchase`/`manual_purchase` accept into the shared stake in total,
+    /// compared against `StakeInfo::total_staked_sol` (lamports, `0` = unlimited).
+    pub max_total_staked_sol: u64,
+
+    /// Admin-set reference LXR price for the purchase price band: lamports per LXR
+    /// base unit as a Q32 fixed-point value, in the scale of `PoolState::token_price_x32`
+    /// (`0` = no reference; set via `set_anchor_price`).
+    pub anchor_price_x32: u128,
+
+    /// Purchases are refused while the pool-implied LXR price deviates from
+    /// `anchor_price_x32` by more than this many bps (`0` = band disabled).
+    pub max_price_deviation_bps: u64,
+
+    /// Seconds an `emergency_withdraw` must sit in `PendingEmergency` before it can
+    /// run (`0` = executes immediately). Set at init only.
+    pub emergency_delay: u64,
+
+    /// Share (bps over `BPS_DENOMINATOR`) of each accrued staking reward credited to
+    /// the SOL index for `claim_sol_rewards`; the rest funds LXR buybacks
+    /// (`0` = rewards are paid in LXR only).
+    pub sol_reward_share_bps: u64,
+
+    /// Minimum LXR a single purchase must buy (`0` = no minimum).
+    pub min_lxr_per_purchase: u64,
+
+    /// Maximum LXR a single purchase may buy (`0` = no cap).
+    pub max_lxr_per_purchase: u64,
 }
 
 impl GlobalConfig {
@@ -74,7 +226,271 @@ impl GlobalConfig {
     /// - 8: account discriminator
     /// - 1: bump
     /// - 32 * 7: seven Pubkeys
-    /// - 8 * 6: six u64 fields
+    /// - 8 * 7: seven u64 fields
     /// - 1 + 1: two booleans
-    pub const LEN: usize = 8 + 1 + 32 * 7 + 8 * 6 + 1 + 1;
+    /// - 1: LUXOR mint decimals
+    /// - 8: blacklist delay
+    /// - 8: cached stake account rent-exempt minimum
+    /// - 8: buyback output tolerance rate
+    /// - 8: minimum treasury balance for purchases
+    /// - 8: minimum pool reserve
+    /// - 1 + 8 * 2: purchase curve type and its SOL/LXR offsets
+    /// - 1: buyback fee flag
+    /// - 8: forfeiture grace (bps)
+    /// - 32: pending admin
+    /// - 1 + 8 * 4: fee override flag and trade/protocol/fund/creator rates
+    /// - 8: max pool fraction per purchase (bps)
+    /// - 1: forfeiture redistribution flag
+    /// - 8: reward vault refill target
+    /// - 8: buyback cooldown
+    /// - 32: treasury fee recipient
+    /// - 8: minimum hold ratio for redeem (bps)
+    /// - 32 * 2: reward mint and reward pool
+    /// - 1: buyback DEX type
+    /// - 8: per-user stake cap
+    /// - 8: global stake cap
+    /// - 16 + 8: anchor price and max price deviation
+    /// - 8: emergency timelock delay
+    /// - 8: SOL reward share (bps)
+    /// - 8 * 2: per-purchase LXR bounds
+    pub const LEN: usize = 8 + 1 + 32 * 7 + 8 * 7 + 1 + 1 + 1 + 8 + 8 + 8 + 8 + 8 + 1 + 8 * 2 + 1 + 8 + 32 + 1 + 8 * 4 + 8 + 1 + 8 + 8 + 32 + 8 + 32 * 2 + 1 + 8 + 8 + 16 + 8 + 8 + 8 + 8 * 2;
+
+    /// Size of configs created by the first release (every field up to
+    /// `initial_lxr_allocation_vault`). `migrate_accounts` grows them to `LEN`.
+    pub const LEGACY_LEN: usize = 8 + 1 + 32 * 7 + 8 * 6 + 1 + 1;
+
+    /// Gives the fields appended after `LEGACY_LEN` the values `initialise_configs`
+    /// sets, where a zero-filled field would not mean the same thing. Called by
+    /// `migrate_accounts` on a freshly grown legacy config only.
+    pub fn init_appended_fields(&mut self, stake_rent_exempt: u64) {
+        self.luxor_decimals = crate::LUXOR_DECIMALS;
+        self.stake_rent_exempt = stake_rent_exempt;
+        self.buyback_out_tolerance_rate = DEFAULT_BUYBACK_OUT_TOLERANCE_RATE;
+        // The first release always took the treasury fee on buybacks.
+        self.buyback_fee_enabled = true;
+        self.reward_mint = crate::luxor_mint::id();
+    }
+
+    /// Rejects dust-sized pools: both reserves returned by `get_swap_params`
+    /// must be at least `min_pool_reserve`.
+    pub fn check_pool_reserves(&self, total_input_token_amount: u64, total_output_token_amount: u64) -> Result<()> {
+        require_gte!(total_input_token_amount, self.min_pool_reserve, ErrorCode::InsufficientPoolLiquidity);
+        require_gte!(total_output_token_amount, self.min_pool_reserve, ErrorCode::InsufficientPoolLiquidity);
+        Ok(())
+    }
+
+    /// Bounds per-trade price impact: `lxr_amount` may be at most
+    /// `max_pool_fraction_bps` of the pool's LXR reserve (skipped when `0`).
+    pub fn check_price_impact(&self, lxr_amount: u64, total_output_token_amount: u64) -> Result<()> {
+        if self.max_pool_fraction_bps == 0 {
+            return Ok(());
+        }
+        let max_amount = (total_output_token_amount as u128)
+            .checked_mul(self.max_pool_fraction_bps as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(BPS_DENOMINATOR as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require_gte!(max_amount, lxr_amount as u128, ErrorCode::PriceImpactTooHigh);
+        Ok(())
+    }
+
+    /// Rejects a purchase of `lxr_amount` LXR for `sol_amount` lamports outside the
+    /// configured bounds: `min_swap_amount`/`max_swap_amount` on the SOL paid and
+    /// `min_lxr_per_purchase`/`max_lxr_per_purchase` on the LXR bought. A maximum
+    /// of `0` is no cap.
+    pub fn check_purchase_bounds(&self, lxr_amount: u64, sol_amount: u64) -> Result<()> {
+        require_gte!(sol_amount, self.min_swap_amount, ErrorCode::SwapAmountOutOfBounds);
+        if self.max_swap_amount > 0 {
+            require_gte!(self.max_swap_amount, sol_amount, ErrorCode::SwapAmountOutOfBounds);
+        }
+        require_gte!(lxr_amount, self.min_lxr_per_purchase, ErrorCode::SwapAmountOutOfBounds);
+        if self.max_lxr_per_purchase > 0 {
+            require_gte!(self.max_lxr_per_purchase, lxr_amount, ErrorCode::SwapAmountOutOfBounds);
+        }
+        Ok(())
+    }
+
+    /// Rejects stake that would take a user above `max_stake_per_user` (skipped when `0`).
+    pub fn check_user_stake_cap(&self, user_staked_sol: u64, new_sol: u64) -> Result<()> {
+        if self.max_stake_per_user == 0 {
+            return Ok(());
+        }
+        let staked_after = user_staked_sol.checked_add(new_sol).ok_or(ErrorCode::MathOverflow)?;
+        require_gte!(self.max_stake_per_user, staked_after, ErrorCode::UserStakeCapExceeded);
+        Ok(())
+    }
+
+    /// Rejects stake that would take the protocol above `max_total_staked_sol`
+    /// (skipped when `0`).
+    pub fn check_global_stake_cap(&self, total_staked_sol: u64, new_sol: u64) -> Result<()> {
+        if self.max_total_staked_sol == 0 {
+            return Ok(());
+        }
+        let staked_after = total_staked_sol.checked_add(new_sol).ok_or(ErrorCode::MathOverflow)?;
+        require_gte!(self.max_total_staked_sol, staked_after, ErrorCode::GlobalStakeCapReached);
+        Ok(())
+    }
+
+    /// Lamports still accepted before `max_total_staked_sol` is reached
+    /// (`u64::MAX` when unlimited).
+    pub fn remaining_stake_capacity(&self, total_staked_sol: u64) -> u64 {
+        match self.max_total_staked_sol {
+            0 => u64::MAX,
+            cap => cap.saturating_sub(total_staked_sol),
+        }
+    }
+
+    /// Rejects purchases while the pool-implied LXR price (`lxr_price_x32`, lamports per
+    /// LXR base unit in Q32) deviates from `anchor_price_x32` by more than
+    /// `max_price_deviation_bps` (skipped when either is `0`).
+    pub fn check_price_band(&self, lxr_price_x32: u128) -> Result<()> {
+        if self.max_price_deviation_bps == 0 || self.anchor_price_x32 == 0 {
+            return Ok(());
+        }
+        let deviation_bps = lxr_price_x32
+            .abs_diff(self.anchor_price_x32)
+            .checked_mul(BPS_DENOMINATOR as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(self.anchor_price_x32)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require_gte!(self.max_price_deviation_bps as u128, deviation_bps, ErrorCode::PriceOutOfBand);
+        Ok(())
+    }
+
+    /// Whether the early-bird bonus applies to the stake numbered
+    /// `total_stake_count + 1`. A zero `max_stake_count_to_get_bonus` means the
+    /// bonus is disabled rather than an empty bonus window.
+    pub fn bonus_applies(&self, total_stake_count: u64) -> bool {
+        self.max_stake_count_to_get_bonus > 0
+            && total_stake_count < self.max_stake_count_to_get_bonus
+    }
+
+    /// Admin-set fee rates to price with instead of the Raydium `amm_config`,
+    /// if `use_fee_override` is on. Only the rate fields are populated.
+    pub fn fee_override(&self) -> Option<AmmConfig> {
+        self.use_fee_override.then(|| AmmConfig {
+            trade_fee_rate: self.override_trade_fee_rate,
+            protocol_fee_rate: self.override_protocol_fee_rate,
+            fund_fee_rate: self.override_fund_fee_rate,
+            creator_fee_rate: self.override_creator_fee_rate,
+            ..Default::default()
+        })
+    }
+
+    /// Whether rewards are paid in LUXOR, i.e. the reward and treasury vaults share a mint.
+    pub fn rewards_in_lxr(&self) -> bool {
+        self.reward_mint == crate::luxor_mint::id()
+    }
+
+    /// Pool `buyback` swaps through; the LUXOR pool unless one was set with `update_config`.
+    pub fn reward_pool_state(&self) -> Pubkey {
+        match self.reward_pool_state {
+            key if key == Pubkey::default() => crate::luxor_pool_state::id(),
+            key => key,
+        }
+    }
+
+    /// Rent-exempt minimum of a stake account, as cached in `stake_rent_exempt`
+    /// (the rent every split or isolated stake account is funded with).
+    pub fn stake_split_rent(&self) -> Result<u64> {
+        require!(self.stake_rent_exempt > 0, ErrorCode::InsufficientRent);
+        Ok(self.stake_rent_exempt)
+    }
+
+    /// Curve used by `purchase` (SOL in, LXR out).
+    pub fn purchase_curve(&self) -> Result<CurveType> {
+        match self.curve_type {
+            CURVE_TYPE_CONSTANT_PRODUCT => Ok(CurveType::ConstantProduct),
+            CURVE_TYPE_OFFSET_PRODUCT => Ok(CurveType::OffsetProduct {
+                input_offset: u128::from(self.curve_offset_sol),
+                output_offset: u128::from(self.curve_offset_lxr),
+            }),
+            _ => err!(ErrorCode::InvalidParam),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `LEN` is hand-maintained; it must match the serialized size exactly.
+    #[test]
+    fn len_matches_serialized_size() {
+        let mut data = Vec::new();
+        GlobalConfig::default().try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), GlobalConfig::LEN);
+    }
+
+    /// A legacy config zero-extended to `LEN` must still read its original fields.
+    #[test]
+    fn legacy_config_grows_into_current_layout() {
+        let admin = Pubkey::new_unique();
+        let legacy = GlobalConfig {
+            bump: 254,
+            admin,
+            bonus_rate: 5,
+            initial_lxr_allocation_vault: 1_000,
+            purchase_enabled: true,
+            redeem_enabled: true,
+            ..Default::default()
+        };
+        let mut data = Vec::new();
+        legacy.try_serialize(&mut data).unwrap();
+        data.truncate(GlobalConfig::LEGACY_LEN);
+        data.resize(GlobalConfig::LEN, 0);
+
+        let mut grown = GlobalConfig::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!(grown.admin, admin);
+        assert_eq!(grown.bump, 254);
+        assert_eq!(grown.bonus_rate, 5);
+        assert_eq!(grown.initial_lxr_allocation_vault, 1_000);
+        assert!(grown.purchase_enabled && grown.redeem_enabled);
+        assert_eq!(grown.luxor_decimals, 0);
+
+        grown.init_appended_fields(2_282_880);
+        assert_eq!(grown.luxor_decimals, crate::LUXOR_DECIMALS);
+        assert_eq!(grown.stake_rent_exempt, 2_282_880);
+        assert_eq!(grown.buyback_out_tolerance_rate, DEFAULT_BUYBACK_OUT_TOLERANCE_RATE);
+        assert!(grown.buyback_fee_enabled);
+        assert_eq!(grown.reward_mint, crate::luxor_mint::id());
+        assert_eq!(grown.max_stake_per_user, 0);
+    }
+
+    #[test]
+    fn legacy_config_does_not_fit_current_layout() {
+        let mut data = vec![0u8; GlobalConfig::LEGACY_LEN];
+        data[..8].copy_from_slice(GlobalConfig::DISCRIMINATOR);
+        assert!(GlobalConfig::try_deserialize(&mut &data[..]).is_err());
+    }
+
+    #[test]
+    fn purchase_bounds_are_inclusive() {
+        let config = GlobalConfig {
+            min_swap_amount: 1_000,
+            max_swap_amount: 5_000,
+            min_lxr_per_purchase: 100,
+            max_lxr_per_purchase: 900,
+            ..Default::default()
+        };
+        // SOL floor: `min_swap_amount - 1` fails, exactly `min_swap_amount` passes.
+        assert!(config.check_purchase_bounds(100, 999).is_err());
+        assert!(config.check_purchase_bounds(100, 1_000).is_ok());
+        assert!(config.check_purchase_bounds(100, 5_000).is_ok());
+        assert!(config.check_purchase_bounds(100, 5_001).is_err());
+        // LXR bounds.
+        assert!(config.check_purchase_bounds(99, 1_000).is_err());
+        assert!(config.check_purchase_bounds(900, 1_000).is_ok());
+        assert!(config.check_purchase_bounds(901, 1_000).is_err());
+    }
+
+    #[test]
+    fn zero_maximums_do_not_cap_purchases() {
+        let config = GlobalConfig {
+            min_swap_amount: 1,
+            ..Default::default()
+        };
+        assert!(config.check_purchase_bounds(u64::MAX, u64::MAX).is_ok());
+        assert!(config.check_purchase_bounds(0, 0).is_err());
+    }
 }
@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+
+//
+// ──────────────────────────────────────────────────────────────────────────────
+// IsolatedStakeInfo Account
+// ──────────────────────────────────────────────────────────────────────────────
+//
+
+/// PDA seed string used to derive each user's isolated stake record.
+pub const ISOLATED_STAKE_INFO_SEED: &str = "isolated_stake_info";
+
+/// Record of an open isolated stake, created by `purchase_isolated` and closed
+/// by the `unstake_isolated` call that withdraws the stake.
+///
+/// Isolated stakes stay out of `UserStakeInfo`, so this is where the LXR sold
+/// against the stake is remembered: `unstake_isolated` takes it back into the
+/// treasury before the user gets their SOL back, like `unstake` does for the
+/// shared stake.
+#[account]
+#[derive(Default, Debug)]
+pub struct IsolatedStakeInfo {
+    /// PDA bump for this account.
+    pub bump: u8,
+
+    /// Owner of the isolated stake.
+    pub owner: Pubkey,
+
+    /// Lamports delegated by `purchase_isolated` (excluding rent).
+    pub sol_amount: u64,
+
+    /// LXR sold against the stake and not yet returned to the treasury.
+    pub lxr_amount: u64,
+}
+
+impl IsolatedStakeInfo {
+    /// Fixed serialized size of the account (for allocation at initialization).
+    ///
+    /// Breakdown:
+    /// - 8: account discriminator
+    /// - 1: bump
+    /// - 32: owner
+    /// - 8: sol_amount
+    /// - 8: lxr_amount
+    pub const LEN: usize = 8 + 1 + 32 + 8 + 8;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `LEN` is hand-maintained; it must match the serialized size exactly.
+    #[test]
+    fn len_matches_serialized_size() {
+        let mut data = Vec::new();
+        IsolatedStakeInfo::default()
+            .try_serialize(&mut data)
+            .unwrap();
+        assert_eq!(data.len(), IsolatedStakeInfo::LEN);
+    }
+}
@@ -1,6 +1,9 @@
 pub mod pool;
 pub use pool::*;
 
+pub mod amm_config;
+pub use amm_config::*;
+
 pub mod events;
 pub use events::*;
 
@@ -12,3 +15,12 @@ pub use stake_info::*;
 
 pub mod user_stake_info;
 pub use user_stake_info::*;
+
+pub mod fee_tiers;
+pub use fee_tiers::*;
+
+pub mod pending_emergency;
+pub use pending_emergency::*;
+
+pub mod isolated_stake_info;
+pub use isolated_stake_info::*;
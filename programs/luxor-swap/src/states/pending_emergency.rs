@@ -0,0 +1,96 @@
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+
+//
+// ──────────────────────────────────────────────────────────────────────────────
+// PendingEmergency Account
+// ──────────────────────────────────────────────────────────────────────────────
+//
+
+/// PDA seed string used to derive the queued emergency action account.
+pub const PENDING_EMERGENCY_SEED: &str = "pending_emergency";
+
+/// Emergency operation queued by `queue_emergency`, executable through
+/// `emergency_withdraw` (or `rotate_stake_authority`) once `execute_after` has passed.
+///
+/// Only consulted while `global_config.emergency_delay > 0`. A single entry is
+/// kept: queuing again replaces it and restarts the delay.
+#[account]
+#[derive(Default, Debug)]
+pub struct PendingEmergency {
+    /// PDA bump for this account.
+    pub bump: u8,
+
+    /// `emergency_withdraw` operation (`EmergencyOperation` as `u8`).
+    pub param: u8,
+
+    /// `emergency_withdraw` value argument.
+    pub value: u64,
+
+    /// Unix timestamp from which the entry can be executed (`0` = nothing queued).
+    pub execute_after: u64,
+
+    /// New stake authority for `RotateStakeAuthority`; default for every other operation.
+    pub target: Pubkey,
+}
+
+impl PendingEmergency {
+    /// Fixed serialized size of the account (for allocation at initialization).
+    ///
+    /// Breakdown:
+    /// - 8: account discriminator
+    /// - 1: bump
+    /// - 1: param
+    /// - 8: value
+    /// - 8: execute_after
+    /// - 32: target
+    pub const LEN: usize = 8 + 1 + 1 + 8 + 8 + 32;
+
+    /// Clears the entry stored in `pending_emergency` if it was queued for
+    /// `(param, value, target)` and has matured at `now`, returning its `execute_after`.
+    /// The caller pins the address.
+    ///
+    /// # Fails
+    /// - `EmergencyNotQueued` if the account does not exist, nothing is queued or the
+    ///   entry is for other arguments.
+    /// - `EmergencyTimelockActive` if `execute_after` is still in the future.
+    pub fn consume(
+        pending_emergency: &AccountInfo,
+        param: u8,
+        value: u64,
+        target: Pubkey,
+        now: u64,
+    ) -> Result<u64> {
+        require!(
+            pending_emergency.owner == &crate::ID && !pending_emergency.data_is_empty(),
+            ErrorCode::EmergencyNotQueued
+        );
+        let mut entry = Self::try_deserialize(&mut &pending_emergency.data.borrow()[..])?;
+        require!(
+            entry.execute_after != 0
+                && entry.param == param
+                && entry.value == value
+                && entry.target == target,
+            ErrorCode::EmergencyNotQueued
+        );
+        require_gte!(now, entry.execute_after, ErrorCode::EmergencyTimelockActive);
+
+        let execute_after = entry.execute_after;
+        entry.execute_after = 0;
+        entry.try_serialize(&mut &mut pending_emergency.data.borrow_mut()[..])?;
+        Ok(execute_after)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `LEN` is hand-maintained; it must match the serialized size exactly.
+    #[test]
+    fn len_matches_serialized_size() {
+        let mut data = Vec::new();
+        PendingEmergency::default().try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), PendingEmergency::LEN);
+    }
+}
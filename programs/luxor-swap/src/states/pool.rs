@@ -1,4 +1,4 @@
-use crate::{curve::TradeDirection, error::ErrorCode};
+use crate::{curve::TradeDirection, error::ErrorCode, states::AmmConfig};
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::Mint;
 use std::ops::{BitAnd, BitOr, BitXor};
@@ -315,6 +315,21 @@ impl PoolState {
         })
     }
 
+    /// Fee rates this pool's swaps are priced with: `fee_override` when set, otherwise
+    /// the Raydium `AmmConfig` in `amm_config`. Fails with `InvalidFeeModel` when
+    /// `amm_config` is not the pool's own config or does not parse as one.
+    ///
+    /// The creator fee still has to go through `adjust_creator_fee_rate`, since
+    /// `enable_creator_fee` lives on the pool, not on the config.
+    pub fn load_amm_config(&self, amm_config: &AccountInfo, fee_override: Option<AmmConfig>) -> Result<AmmConfig> {
+        require_keys_eq!(amm_config.key(), self.amm_config, ErrorCode::InvalidFeeModel);
+        match fee_override {
+            Some(fees) => Ok(fees),
+            None => AmmConfig::try_deserialize(&mut &amm_config.data.borrow()[..])
+                .map_err(|_| error!(ErrorCode::InvalidFeeModel)),
+        }
+    }
+
     pub fn adjust_creator_fee_rate(&self, creator_fee_rate: u64) -> u64 {
         if self.enable_creator_fee {
             creator_fee_rate
@@ -368,3 +383,122 @@ impl PoolState {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Raydium CPMM `AmmConfig` index 0, laid out byte for byte: discriminator,
+    /// bump, disable_create_pool, index, the four fee fields, both owners,
+    /// creator_fee_rate and padding. Fee values are mainnet's; the bump and owner
+    /// keys are placeholders (`0xff`, `[1; 32]`, `[2; 32]`).
+    const AMM_CONFIG_INDEX_0: &[u8] = include_bytes!("../../tests/fixtures/amm_config_index_0.bin");
+
+    fn load(pool_state: &PoolState, key: Pubkey, mut data: Vec<u8>) -> Result<AmmConfig> {
+        let owner = crate::raydium_cpmm::id();
+        let mut lamports = 0;
+        let account = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+        pool_state.load_amm_config(&account, None)
+    }
+
+    #[test]
+    fn load_amm_config_parses_raydium_layout() {
+        let key = Pubkey::new_unique();
+        let pool_state = PoolState { amm_config: key, ..Default::default() };
+
+        let config = load(&pool_state, key, AMM_CONFIG_INDEX_0.to_vec()).unwrap();
+        assert_eq!(config.bump, 0xff);
+        assert!(!config.disable_create_pool);
+        assert_eq!(config.index, 0);
+        assert_eq!(config.trade_fee_rate, 2_500);
+        assert_eq!(config.protocol_fee_rate, 120_000);
+        assert_eq!(config.fund_fee_rate, 40_000);
+        assert_eq!(config.create_pool_fee, 150_000_000);
+        assert_eq!(config.protocol_owner, Pubkey::new_from_array([1; 32]));
+        assert_eq!(config.fund_owner, Pubkey::new_from_array([2; 32]));
+        assert_eq!(config.creator_fee_rate, 0);
+    }
+
+    #[test]
+    fn load_amm_config_rejects_foreign_or_malformed_accounts() {
+        let key = Pubkey::new_unique();
+        let pool_state = PoolState { amm_config: key, ..Default::default() };
+        let invalid = || Err(ErrorCode::InvalidFeeModel.into());
+
+        // Not the pool's config.
+        let other = Pubkey::new_unique();
+        assert_eq!(load(&pool_state, other, AMM_CONFIG_INDEX_0.to_vec()).map(|_| ()), invalid());
+
+        // Wrong discriminator.
+        let mut data = AMM_CONFIG_INDEX_0.to_vec();
+        data[0] ^= 0xff;
+        assert_eq!(load(&pool_state, key, data).map(|_| ()), invalid());
+
+        // Truncated.
+        let data = AMM_CONFIG_INDEX_0[..AMM_CONFIG_INDEX_0.len() - 1].to_vec();
+        assert_eq!(load(&pool_state, key, data).map(|_| ()), invalid());
+    }
+
+    #[test]
+    fn load_amm_config_prefers_the_override() {
+        let key = Pubkey::new_unique();
+        let pool_state = PoolState { amm_config: key, ..Default::default() };
+        let owner = crate::raydium_cpmm::id();
+        let mut lamports = 0;
+        let mut data = Vec::new();
+        let account = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+
+        let fees = AmmConfig { trade_fee_rate: 10_000, ..Default::default() };
+        let config = pool_state.load_amm_config(&account, Some(fees)).unwrap();
+        assert_eq!(config.trade_fee_rate, 10_000);
+    }
+
+    /// The creator-fee side the curve is priced with comes from the same pool
+    /// fields the Raydium CPI reads, per `creator_fee_on` and trade direction.
+    #[test]
+    fn creator_fee_side_follows_pool_creator_fee_on() {
+        use TradeDirection::{OneForZero, ZeroForOne};
+        let cases = [
+            (CreatorFeeOn::BothToken, ZeroForOne, true),
+            (CreatorFeeOn::BothToken, OneForZero, true),
+            (CreatorFeeOn::OnlyToken0, ZeroForOne, true),
+            (CreatorFeeOn::OnlyToken0, OneForZero, false),
+            (CreatorFeeOn::OnlyToken1, ZeroForOne, false),
+            (CreatorFeeOn::OnlyToken1, OneForZero, true),
+        ];
+        for (fee_on, direction, expected) in cases {
+            let pool_state = PoolState {
+                creator_fee_on: fee_on.to_u8(),
+                token_0_vault: Pubkey::new_unique(),
+                token_1_vault: Pubkey::new_unique(),
+                ..Default::default()
+            };
+            assert_eq!(pool_state.is_creator_fee_on_input(direction).unwrap(), expected);
+
+            let (input_vault, output_vault) = match direction {
+                ZeroForOne => (pool_state.token_0_vault, pool_state.token_1_vault),
+                OneForZero => (pool_state.token_1_vault, pool_state.token_0_vault),
+            };
+            let params = pool_state.get_swap_params(input_vault, output_vault, 1_000, 1_000).unwrap();
+            assert_eq!(params.trade_direction, direction);
+            assert_eq!(params.is_creator_fee_on_input, expected);
+        }
+    }
+
+    #[test]
+    fn unknown_creator_fee_on_is_an_invalid_fee_model() {
+        let pool_state = PoolState { creator_fee_on: 3, ..Default::default() };
+        assert_eq!(
+            pool_state.is_creator_fee_on_input(TradeDirection::ZeroForOne).map(|_| ()),
+            Err(ErrorCode::InvalidFeeModel.into())
+        );
+    }
+
+    #[test]
+    fn creator_fee_rate_is_zero_unless_the_pool_enables_it() {
+        let disabled = PoolState::default();
+        assert_eq!(disabled.adjust_creator_fee_rate(5_000), 0);
+        let enabled = PoolState { enable_creator_fee: true, ..Default::default() };
+        assert_eq!(enabled.adjust_creator_fee_rate(5_000), 5_000);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::{error::ErrorCode, states::BPS_DENOMINATOR, PRECISION};
 use anchor_lang::prelude::*;
 
 //
@@ -36,10 +37,22 @@ pub struct StakeInfo {
     pub total_sol_rewards_accrued: u64,
 
     /// Last observed SOL balance of the stake PDA, used to detect newly accrued rewards.
+    ///
+    /// Epoch rewards are paid by the runtime by crediting the stake account's
+    /// lamports *and* compounding the same amount into `delegation.stake`, so a
+    /// lamport delta against this checkpoint captures them. Every instruction that
+    /// moves lamports in or out of the stake PDA must refresh this value afterwards,
+    /// otherwise deposits/withdrawals would be misread as rewards.
     pub last_tracked_sol_balance: u64,
 
     /// Global reward index for SOL-denominated rewards, scaled by PRECISION.
     /// Used to calculate each user's share of accrued SOL rewards.
+    ///
+    /// One index is exact because the whole stake PDA is delegated to a single
+    /// validator at a time: every staked lamport earns the same rate. After a
+    /// `redelegate` the lamport delta simply carries the new validator's rate.
+    /// Delegating to several validators at once would need per-validator indices
+    /// and a per-user delegation map.
     pub reward_per_token_sol_stored: u128,
 
     /// Cumulative amount of LXR bought back from rewards and accrued globally.
@@ -61,11 +74,63 @@ pub struct StakeInfo {
     /// Total LXR claimed by all users (sum of successful redemptions).
     pub total_lxr_claimed: u64,
 
-    /// Total LXR forfeited by users (sent to treasury due to under-holdings).
+    /// Total LXR forfeited by users and sent to treasury due to under-holdings.
     pub total_lxr_forfeited: u64,
 
     pub buyback_count: u64,
     pub buyback_requested: bool,
+
+    /// Number of `migrate_reward_scale` runs applied to the reward indices
+    /// (`0` = original scale). Users whose checkpoint carries an older version
+    /// are rescaled lazily the next time it is touched.
+    pub reward_scale_version: u8,
+
+    /// Numerator of the correction factor applied by the last migration.
+    pub reward_scale_numerator: u64,
+
+    /// Denominator of the correction factor applied by the last migration.
+    pub reward_scale_denominator: u64,
+
+    /// Total LXR forfeited on redeem and redistributed to stakers through the
+    /// reward index (not counted in `total_lxr_forfeited`).
+    pub total_forfeiture_redistributed: u64,
+
+    /// Set by `finalize_stake_closure` once the stake PDA has been deactivated and
+    /// drained; stake-dependent instructions refuse to run afterwards.
+    pub stake_closed: bool,
+
+    /// Set while a `buyback` phase runs (persisted before its CPIs) and cleared
+    /// when it returns. The runtime already forbids CPI re-entrancy into this
+    /// program, so this only catches a phase that returned without clearing it.
+    pub processing: bool,
+
+    /// Rewards `buyback_withdraw` wrapped into the admin's WSOL account that
+    /// `buyback_settle` has not swapped yet (lamports, `0` = none pending).
+    pub buyback_wsol_pending: u64,
+
+    /// Split account rent `buyback_withdraw` refunded to the admin alongside
+    /// `buyback_wsol_pending`; `buyback_settle` reports it in `BuybackExecuted`.
+    pub buyback_rent_refunded: u64,
+
+    /// Cumulative rewards (lamports) credited to `reward_per_token_sol_stored` for
+    /// `claim_sol_rewards`. They are excluded from the buyback budget.
+    pub total_sol_rewards_reserved: u64,
+
+    /// Cumulative reserved rewards (lamports) a buyback has moved out of the stake
+    /// PDA into `sol_treasury_vault` to back SOL claims.
+    pub total_sol_reserve_funded: u64,
+
+    /// Cumulative SOL rewards (lamports) paid by `claim_sol_rewards`.
+    pub total_sol_rewards_claimed: u64,
+
+    /// Reserved rewards (lamports) included in the pending buyback split; moved to
+    /// `sol_treasury_vault` instead of being swapped when it executes.
+    pub buyback_reserve_split: u64,
+
+    /// Bought reward tokens received while nothing was staked, and non-LXR forfeitures
+    /// made while no one else was staked; held in the reward vault and added to the
+    /// LXR index by the next buyback with stakers.
+    pub lxr_rewards_undistributed: u64,
 }
 
 impl StakeInfo {
@@ -74,7 +139,304 @@ impl StakeInfo {
     /// Breakdown:
     /// - 8: account discriminator
     /// - 1: bump
-    /// - 8 * 10: ten `u64` fields
+    /// - 8 * 11: eleven `u64` fields
     /// - 16 * 2: two `u128` fields
-    pub const LEN: usize = 8 + 1 + 8 * 11 + 16 * 2 + 1;
+    /// - 1: buyback_requested flag
+    /// - 1 + 8 * 2: reward scale version and correction factor
+    /// - 8: total forfeiture redistributed
+    /// - 1: stake closed flag
+    /// - 1: buyback processing flag
+    /// - 8 * 2: WSOL pending and rent refunded between split buyback phases
+    /// - 8 * 3: reserved, funded and claimed SOL reward totals
+    /// - 8: reserved SOL in the pending buyback split
+    /// - 8: LXR rewards held while nothing was staked
+    pub const LEN: usize = 8 + 1 + 8 * 11 + 16 * 2 + 1 + 1 + 8 * 2 + 8 + 1 + 1 + 8 * 2 + 8 * 3 + 8 + 8;
+
+    /// Size of the account as created by the first release (every field up to
+    /// `buyback_requested`). `migrate_accounts` grows it to `LEN`; all fields
+    /// appended since are valid when zero-filled.
+    pub const LEGACY_LEN: usize = 8 + 1 + 8 * 11 + 16 * 2 + 1;
+
+    /// Realizes rewards credited to the stake PDA since the last observation:
+    /// adds the lamport delta to `total_sol_rewards_accrued` and refreshes
+    /// `last_tracked_sol_balance`. Returns the delta.
+    ///
+    /// `sol_reward_share_bps` of the delta is reserved for SOL claims: it bumps
+    /// `reward_per_token_sol_stored` by `share * PRECISION / total_staked_sol` and
+    /// is added to `total_sol_rewards_reserved`, which keeps it out of the buyback
+    /// budget. The rest is left to buyback. While nothing is staked there is no
+    /// one to credit, so nothing is reserved.
+    pub fn accrue_sol_rewards(&mut self, stake_lamports: u64, sol_reward_share_bps: u64) -> Result<u64> {
+        if stake_lamports <= self.last_tracked_sol_balance {
+            return Ok(0);
+        }
+        let rewards_accrued = stake_lamports
+            .checked_sub(self.last_tracked_sol_balance)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.total_sol_rewards_accrued = self
+            .total_sol_rewards_accrued
+            .checked_add(rewards_accrued)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if self.total_staked_sol > 0 && sol_reward_share_bps > 0 {
+            let sol_share = (rewards_accrued as u128)
+                .checked_mul(sol_reward_share_bps as u128)
+                .ok_or(ErrorCode::MathOverflow)?
+                .checked_div(BPS_DENOMINATOR as u128)
+                .ok_or(ErrorCode::MathOverflow)?;
+            let reward_per_token = sol_share
+                .checked_mul(PRECISION)
+                .ok_or(ErrorCode::MathOverflow)?
+                .checked_div(self.total_staked_sol as u128)
+                .ok_or(ErrorCode::MathOverflow)?;
+            self.reward_per_token_sol_stored = self
+                .reward_per_token_sol_stored
+                .checked_add(reward_per_token)
+                .ok_or(ErrorCode::MathOverflow)?;
+            self.total_sol_rewards_reserved = self
+                .total_sol_rewards_reserved
+                .checked_add(sol_share as u64)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+        self.last_tracked_sol_balance = stake_lamports;
+        Ok(rewards_accrued)
+    }
+
+    /// Credits `lxr_amount` bought rewards to `reward_per_token_lxr_stored`, together
+    /// with any held in `lxr_rewards_undistributed`. While nothing is staked there is
+    /// no one to credit, so the amount is held there instead of dividing by zero.
+    pub fn credit_lxr_rewards(&mut self, lxr_amount: u64) -> Result<()> {
+        let to_credit = self
+            .lxr_rewards_undistributed
+            .checked_add(lxr_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if self.total_staked_sol == 0 {
+            self.lxr_rewards_undistributed = to_credit;
+            return Ok(());
+        }
+        self.reward_per_token_lxr_stored = self
+            .reward_per_token_lxr_stored
+            .checked_add(
+                (to_credit as u128)
+                    .checked_mul(PRECISION)
+                    .ok_or(ErrorCode::MathOverflow)?
+                    .checked_div(self.total_staked_sol as u128)
+                    .ok_or(ErrorCode::MathOverflow)?,
+            )
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.lxr_rewards_undistributed = 0;
+        Ok(())
+    }
+
+    /// Accrued rewards not yet spent on a buyback nor reserved for SOL claims:
+    /// `total_sol_rewards_accrued - total_sol_used_for_buyback - total_sol_rewards_reserved`.
+    pub fn sol_available_to_buyback(&self) -> Result<u64> {
+        Ok(self
+            .total_sol_rewards_accrued
+            .checked_sub(self.total_sol_used_for_buyback)
+            .ok_or(ErrorCode::UnderflowError)?
+            .checked_sub(self.total_sol_rewards_reserved)
+            .ok_or(ErrorCode::UnderflowError)?)
+    }
+
+    /// Reserved SOL rewards still held by the stake PDA (not yet moved to the
+    /// treasury vault by a buyback).
+    pub fn sol_reserve_unfunded(&self) -> Result<u64> {
+        Ok(self
+            .total_sol_rewards_reserved
+            .checked_sub(self.total_sol_reserve_funded)
+            .ok_or(ErrorCode::UnderflowError)?)
+    }
+
+    /// Reserved SOL rewards held by `sol_treasury_vault` and not yet claimed;
+    /// `claim_sol_rewards` pays from it and `withdraw_treasury_fees` leaves it alone.
+    pub fn sol_reserve_in_treasury(&self) -> Result<u64> {
+        Ok(self
+            .total_sol_reserve_funded
+            .checked_sub(self.total_sol_rewards_claimed)
+            .ok_or(ErrorCode::UnderflowError)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::states::UserStakeInfo;
+
+    /// `LEN` is hand-maintained; it must match the serialized size exactly.
+    #[test]
+    fn len_matches_serialized_size() {
+        let mut data = Vec::new();
+        StakeInfo::default().try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), StakeInfo::LEN);
+    }
+
+    #[test]
+    fn legacy_stake_info_grows_into_current_layout() {
+        let legacy = StakeInfo {
+            bump: 253,
+            total_staked_sol: 42,
+            reward_per_token_lxr_stored: 7,
+            buyback_count: 3,
+            buyback_requested: true,
+            ..Default::default()
+        };
+        let mut data = Vec::new();
+        legacy.try_serialize(&mut data).unwrap();
+        data.truncate(StakeInfo::LEGACY_LEN);
+        assert!(StakeInfo::try_deserialize(&mut &data[..]).is_err());
+        data.resize(StakeInfo::LEN, 0);
+
+        let grown = StakeInfo::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!(grown.total_staked_sol, 42);
+        assert_eq!(grown.reward_per_token_lxr_stored, 7);
+        assert_eq!(grown.buyback_count, 3);
+        assert!(grown.buyback_requested);
+        assert_eq!(grown.reward_scale_version, 0);
+        assert!(!grown.processing);
+        assert_eq!(grown.buyback_wsol_pending, 0);
+        assert_eq!(grown.buyback_rent_refunded, 0);
+    }
+
+    #[test]
+    fn accrued_rewards_split_between_buyback_and_sol_reserve() {
+        let mut stake_info = StakeInfo {
+            total_staked_sol: 1_000_000_000,
+            last_tracked_sol_balance: 1_000_000_000,
+            ..Default::default()
+        };
+        let delta = stake_info.accrue_sol_rewards(1_000_001_001, 2_500).unwrap();
+        assert_eq!(delta, 1_001);
+        // 25% of 1_001, rounded down, is owed to SOL claims; the rest is buyback budget.
+        assert_eq!(stake_info.total_sol_rewards_reserved, 250);
+        assert_eq!(stake_info.sol_available_to_buyback().unwrap(), 751);
+        assert_eq!(
+            stake_info.sol_available_to_buyback().unwrap() + stake_info.total_sol_rewards_reserved,
+            delta
+        );
+        assert_eq!(
+            stake_info.reward_per_token_sol_stored,
+            250 * PRECISION / 1_000_000_000
+        );
+        assert_eq!(stake_info.sol_reserve_unfunded().unwrap(), 250);
+        assert_eq!(stake_info.sol_reserve_in_treasury().unwrap(), 0);
+    }
+
+    #[test]
+    fn nothing_reserved_without_share_or_stakers() {
+        let mut stake_info = StakeInfo {
+            total_staked_sol: 1_000,
+            last_tracked_sol_balance: 1_000,
+            ..Default::default()
+        };
+        stake_info.accrue_sol_rewards(1_500, 0).unwrap();
+        assert_eq!(stake_info.total_sol_rewards_reserved, 0);
+        assert_eq!(stake_info.reward_per_token_sol_stored, 0);
+        assert_eq!(stake_info.sol_available_to_buyback().unwrap(), 500);
+
+        let mut unstaked = StakeInfo {
+            last_tracked_sol_balance: 1_000,
+            ..Default::default()
+        };
+        unstaked.accrue_sol_rewards(1_500, BPS_DENOMINATOR).unwrap();
+        assert_eq!(unstaked.total_sol_rewards_reserved, 0);
+        assert_eq!(unstaked.sol_available_to_buyback().unwrap(), 500);
+    }
+
+    #[test]
+    fn sol_reserve_is_paid_once() {
+        let mut stake_info = StakeInfo {
+            total_staked_sol: 1_000,
+            last_tracked_sol_balance: 1_000,
+            ..Default::default()
+        };
+        stake_info.accrue_sol_rewards(2_000, BPS_DENOMINATOR).unwrap();
+        // Everything is reserved, so nothing is left for a buyback to swap.
+        assert_eq!(stake_info.sol_available_to_buyback().unwrap(), 0);
+        assert_eq!(stake_info.sol_reserve_unfunded().unwrap(), 1_000);
+
+        // A buyback moves the reserve into the treasury vault...
+        stake_info.total_sol_reserve_funded += stake_info.sol_reserve_unfunded().unwrap();
+        assert_eq!(stake_info.sol_reserve_unfunded().unwrap(), 0);
+        assert_eq!(stake_info.sol_reserve_in_treasury().unwrap(), 1_000);
+
+        // ...and claims drain it; nothing is left to pay a second time.
+        stake_info.total_sol_rewards_claimed += 1_000;
+        assert_eq!(stake_info.sol_reserve_in_treasury().unwrap(), 0);
+        assert_eq!(stake_info.sol_available_to_buyback().unwrap(), 0);
+    }
+
+    #[test]
+    fn lxr_rewards_are_held_while_nothing_is_staked() {
+        let mut stake_info = StakeInfo::default();
+        // Must not divide by zero; the rewards are held instead.
+        stake_info.credit_lxr_rewards(500).unwrap();
+        stake_info.credit_lxr_rewards(250).unwrap();
+        assert_eq!(stake_info.reward_per_token_lxr_stored, 0);
+        assert_eq!(stake_info.lxr_rewards_undistributed, 750);
+
+        // The next credit with stakers distributes the held rewards as well.
+        stake_info.total_staked_sol = 1_000;
+        stake_info.credit_lxr_rewards(250).unwrap();
+        assert_eq!(stake_info.lxr_rewards_undistributed, 0);
+        assert_eq!(stake_info.reward_per_token_lxr_stored, 1_000 * PRECISION / 1_000);
+    }
+
+    /// Validator 1 pays 5% while only the first staker is in; the protocol then
+    /// redelegates to validator 2, which pays 2% after a second staker joins.
+    /// Each staker's share follows the rate of the validator they were staked with.
+    #[test]
+    fn rewards_across_a_redelegation_follow_each_validators_rate() {
+        let first_stake = 1_000_000_000;
+        let second_stake = 3_000_000_000;
+        let mut stake_info = StakeInfo {
+            total_staked_sol: first_stake,
+            last_tracked_sol_balance: first_stake,
+            ..Default::default()
+        };
+        let mut first = UserStakeInfo {
+            total_staked_sol: first_stake,
+            ..Default::default()
+        };
+
+        // Validator 1: 5% of the first stake.
+        let validator_1_rewards = first_stake / 20;
+        stake_info
+            .accrue_sol_rewards(first_stake + validator_1_rewards, BPS_DENOMINATOR)
+            .unwrap();
+
+        // The second staker joins at the current index, like `purchase` does.
+        let mut second = UserStakeInfo {
+            total_staked_sol: second_stake,
+            sol_reward_per_token_completed: stake_info.reward_per_token_sol_stored,
+            ..Default::default()
+        };
+        stake_info.total_staked_sol += second_stake;
+        stake_info.last_tracked_sol_balance += second_stake;
+
+        // Validator 2: 2% of both stakes.
+        let validator_2_rewards = (first_stake + second_stake) / 50;
+        let balance = stake_info.last_tracked_sol_balance + validator_2_rewards;
+        stake_info.accrue_sol_rewards(balance, BPS_DENOMINATOR).unwrap();
+
+        let first_rewards = first.pending_sol_rewards(&stake_info).unwrap();
+        let second_rewards = second.pending_sol_rewards(&stake_info).unwrap();
+        assert_eq!(first_rewards, first_stake / 20 + first_stake / 50);
+        assert_eq!(second_rewards, second_stake / 50);
+        assert_eq!(
+            first_rewards + second_rewards,
+            validator_1_rewards + validator_2_rewards
+        );
+    }
+
+    #[test]
+    fn zero_stake_accrual_does_not_abort() {
+        let mut stake_info = StakeInfo {
+            last_tracked_sol_balance: 1_000,
+            ..Default::default()
+        };
+        assert_eq!(stake_info.accrue_sol_rewards(1_400, BPS_DENOMINATOR).unwrap(), 400);
+        assert_eq!(stake_info.reward_per_token_sol_stored, 0);
+        stake_info.credit_lxr_rewards(0).unwrap();
+        assert_eq!(stake_info.reward_per_token_lxr_stored, 0);
+    }
 }
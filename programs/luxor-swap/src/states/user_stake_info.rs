@@ -1,3 +1,4 @@
+use crate::{error::ErrorCode, states::{StakeInfo, BPS_DENOMINATOR}, PRECISION};
 use anchor_lang::prelude::*;
 
 //
@@ -19,7 +20,7 @@ pub const ADMIN_STAKE_INFO_SEED: &str = "admin_stake_info";
 /// - The user’s SOL staked.
 /// - LXR rewards they’ve claimed or forfeited.
 /// - Their base LXR holdings at purchase time (used for pro-rata reward checks).
-/// - A reward index checkpoint for calculating pending LXR rewards.
+/// - Reward index checkpoints for calculating pending LXR and SOL rewards.
 /// - Any explicitly stored pending rewards not yet claimed.
 #[account]
 #[derive(Default, Debug)]
@@ -27,7 +28,9 @@ pub struct UserStakeInfo {
     /// PDA bump for this account.
     pub bump: u8,
 
-    /// Owner (user) to whom this record belongs.
+    /// Owner (user) to whom this record belongs. Set on the first stake; a record
+    /// created by `airdrop_lxr` alone keeps the default key, so its user still
+    /// counts as a new staker.
     pub owner: Pubkey,
 
     /// Total SOL (in lamports) the user has staked.
@@ -50,7 +53,28 @@ pub struct UserStakeInfo {
 
     /// LXR rewards that were calculated but not yet claimed by the user.
     pub lxr_rewards_pending: u64,
+
+    /// Cumulative SOL (lamports) reassigned to the admin record by
+    /// `finalize_blacklist`. Informational only: `total_staked_sol` is zeroed at
+    /// the same time, so this amount is already excluded from every reward and
+    /// unstake calculation. Any stake purchased afterwards is not affected.
+    /// `un_blacklist` moves it back into `total_staked_sol` and clears it.
     pub blacklisted_sol: u64,
+
+    /// UNIX timestamp (seconds) at which a blacklist was proposed for this user
+    /// (`0` = none pending). `finalize_blacklist` requires
+    /// `global_config.blacklist_delay_secs` to have elapsed since.
+    pub blacklist_pending_timestamp: u64,
+
+    /// `StakeInfo::reward_scale_version` the reward checkpoints are expressed in.
+    pub reward_scale_version: u8,
+
+    /// Reward index checkpoint (global `reward_per_token_sol_stored`)
+    /// at the time of the user's last update.
+    pub sol_reward_per_token_completed: u128,
+
+    /// SOL rewards (lamports) that were calculated but not yet claimed by the user.
+    pub sol_rewards_pending: u64,
 }
 
 impl UserStakeInfo {
@@ -60,7 +84,439 @@ impl UserStakeInfo {
     /// - 8: account discriminator
     /// - 1: bump
     /// - 32: owner pubkey
-    /// - 8 * 5: five `u64` fields
+    /// - 8 * 7: seven `u64` fields
     /// - 16: one `u128` field
-    pub const LEN: usize = 8 + 1 + 32 + 8 * 6 + 16;
+    /// - 1: reward scale version
+    /// - 16 + 8: SOL reward checkpoint and pending SOL rewards
+    pub const LEN: usize = 8 + 1 + 32 + 8 * 7 + 16 + 1 + 16 + 8;
+
+    /// Size of records created by the first release (every field up to
+    /// `blacklisted_sol`). `migrate_accounts` grows them to `LEN`.
+    pub const LEGACY_LEN: usize = 8 + 1 + 32 + 8 * 6 + 16;
+
+    /// Sets the SOL reward checkpoint of a freshly grown legacy record to the
+    /// current index. A zero-filled checkpoint would credit the record with the
+    /// entire SOL index accrued before the field existed.
+    pub fn init_appended_fields(&mut self, stake_info: &StakeInfo) -> Result<()> {
+        self.sync_reward_scale(stake_info)?;
+        self.sol_reward_per_token_completed = stake_info.reward_per_token_sol_stored;
+        Ok(())
+    }
+
+    /// Whether a finalized blacklist is in effect (`blacklisted_sol > 0`); cleared
+    /// by `un_blacklist`. A pending proposal alone does not count.
+    pub fn is_blacklisted(&self) -> bool {
+        self.blacklisted_sol > 0
+    }
+
+    /// Rescales both reward checkpoints into the current index scale if
+    /// `migrate_reward_scale` ran since they were written. Must run before any
+    /// delta against the global indices is taken.
+    pub fn sync_reward_scale(&mut self, stake_info: &StakeInfo) -> Result<()> {
+        if self.reward_scale_version < stake_info.reward_scale_version {
+            for checkpoint in [
+                &mut self.lxr_reward_per_token_completed,
+                &mut self.sol_reward_per_token_completed,
+            ] {
+                *checkpoint = checkpoint
+                    .checked_mul(stake_info.reward_scale_numerator as u128)
+                    .ok_or(ErrorCode::MathOverflow)?
+                    .checked_div(stake_info.reward_scale_denominator as u128)
+                    .ok_or(ErrorCode::MathOverflow)?;
+            }
+            self.reward_scale_version = stake_info.reward_scale_version;
+        }
+        Ok(())
+    }
+
+    /// LXR earned since the last checkpoint:
+    /// `total_staked_sol * (global_idx - user_idx) / PRECISION`.
+    ///
+    /// Fails with `UnderflowError` if the checkpoint is ahead of the global index
+    /// (corrupted checkpoint) instead of panicking, and with `MathOverflow` if the
+    /// amount does not fit a `u64`.
+    pub fn pending_lxr_rewards(&mut self, stake_info: &StakeInfo) -> Result<u64> {
+        self.sync_reward_scale(stake_info)?;
+        let reward_per_token_lxr_pending = stake_info
+            .reward_per_token_lxr_stored
+            .checked_sub(self.lxr_reward_per_token_completed)
+            .ok_or(ErrorCode::UnderflowError)?;
+        if reward_per_token_lxr_pending == 0 {
+            return Ok(0);
+        }
+        u64::try_from(
+            (self.total_staked_sol as u128)
+                .checked_mul(reward_per_token_lxr_pending)
+                .ok_or(ErrorCode::MathOverflow)?
+                .checked_div(PRECISION)
+                .ok_or(ErrorCode::MathOverflow)?,
+        )
+        .map_err(|_| error!(ErrorCode::MathOverflow))
+    }
+
+    /// Moves the LXR earned since the last checkpoint into `lxr_rewards_pending`
+    /// and advances the checkpoint. Returns the amount accrued.
+    pub fn checkpoint_lxr_rewards(&mut self, stake_info: &StakeInfo) -> Result<u64> {
+        let accrued = self.pending_lxr_rewards(stake_info)?;
+        self.lxr_rewards_pending = self
+            .lxr_rewards_pending
+            .checked_add(accrued)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.lxr_reward_per_token_completed = stake_info.reward_per_token_lxr_stored;
+        Ok(accrued)
+    }
+
+    /// SOL earned since the last checkpoint:
+    /// `total_staked_sol * (global_idx - user_idx) / PRECISION`.
+    ///
+    /// Fails with `UnderflowError` if the checkpoint is ahead of the global index
+    /// (corrupted checkpoint) instead of panicking, and with `MathOverflow` if the
+    /// amount does not fit a `u64`.
+    pub fn pending_sol_rewards(&mut self, stake_info: &StakeInfo) -> Result<u64> {
+        self.sync_reward_scale(stake_info)?;
+        let reward_per_token_sol_pending = stake_info
+            .reward_per_token_sol_stored
+            .checked_sub(self.sol_reward_per_token_completed)
+            .ok_or(ErrorCode::UnderflowError)?;
+        if reward_per_token_sol_pending == 0 {
+            return Ok(0);
+        }
+        u64::try_from(
+            (self.total_staked_sol as u128)
+                .checked_mul(reward_per_token_sol_pending)
+                .ok_or(ErrorCode::MathOverflow)?
+                .checked_div(PRECISION)
+                .ok_or(ErrorCode::MathOverflow)?,
+        )
+        .map_err(|_| error!(ErrorCode::MathOverflow))
+    }
+
+    /// Moves the SOL earned since the last checkpoint into `sol_rewards_pending`
+    /// and advances the checkpoint. Returns the amount accrued.
+    pub fn checkpoint_sol_rewards(&mut self, stake_info: &StakeInfo) -> Result<u64> {
+        let accrued = self.pending_sol_rewards(stake_info)?;
+        self.sol_rewards_pending = self
+            .sol_rewards_pending
+            .checked_add(accrued)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.sol_reward_per_token_completed = stake_info.reward_per_token_sol_stored;
+        Ok(accrued)
+    }
+
+    /// Splits freshly earned `rewards` into `(claimable, forfeited)`: when
+    /// `lxr_holdings` is below `base_lxr_holdings`, rewards are pro-rated by
+    /// `lxr_holdings / base_lxr_holdings` and the remainder is forfeited.
+    ///
+    /// Holdings within `grace_bps` (over `BPS_DENOMINATOR`) of the base count as
+    /// full, so rounding or transfer-fee dust does not trigger forfeiture.
+    ///
+    /// The pro-rated share is rounded down, so `claimable + forfeited == rewards`
+    /// always holds and truncation dust is forfeited rather than over-paid.
+    pub fn apply_holdings_forfeiture(&self, rewards: u64, lxr_holdings: u64, grace_bps: u64) -> Result<(u64, u64)> {
+        let grace = (self.base_lxr_holdings as u128)
+            .checked_mul(grace_bps as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(BPS_DENOMINATOR as u128)
+            .ok_or(ErrorCode::MathOverflow)? as u64;
+        if lxr_holdings >= self.base_lxr_holdings.saturating_sub(grace) {
+            return Ok((rewards, 0));
+        }
+        let claimable = (lxr_holdings as u128)
+            .checked_mul(rewards as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(self.base_lxr_holdings as u128)
+            .ok_or(ErrorCode::MathOverflow)? as u64;
+        let forfeited = rewards.checked_sub(claimable).ok_or(ErrorCode::MathOverflow)?;
+        Ok((claimable, forfeited))
+    }
+
+    /// Whether `lxr_holdings` reaches `min_hold_ratio_bps` (over `BPS_DENOMINATOR`)
+    /// of `base_lxr_holdings`. Always true when the ratio is `0` or there is no base.
+    /// Both sides are scaled by `BPS_DENOMINATOR` instead of dividing, so a fractional
+    /// share is not rounded in the holder's favour.
+    pub fn meets_min_hold(&self, lxr_holdings: u64, min_hold_ratio_bps: u64) -> Result<bool> {
+        let min_hold_scaled = (self.base_lxr_holdings as u128)
+            .checked_mul(min_hold_ratio_bps as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let holdings_scaled = (lxr_holdings as u128)
+            .checked_mul(BPS_DENOMINATOR as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(holdings_scaled >= min_hold_scaled)
+    }
+
+    /// LXR that must be handed back to unstake `amount` of `total_staked_sol`:
+    /// the same share of `base_lxr_holdings` (LXR bought with that SOL), rounded
+    /// up so repeated partial unstakes cannot keep any purchased LXR. Unstaking
+    /// everything returns the whole base.
+    pub fn lxr_to_return_for_unstake(&self, amount: u64) -> Result<u64> {
+        require_gte!(self.total_staked_sol, amount, ErrorCode::UnstakeExceedsStake);
+        if amount == self.total_staked_sol {
+            return Ok(self.base_lxr_holdings);
+        }
+        let numerator = (self.base_lxr_holdings as u128)
+            .checked_mul(amount as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let total = self.total_staked_sol as u128;
+        Ok(numerator
+            .checked_add(total - 1)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(total)
+            .ok_or(ErrorCode::MathOverflow)? as u64)
+    }
+
+    /// Sets both checkpoints to the current global indices (fresh records with
+    /// nothing accrued yet).
+    pub fn reset_reward_checkpoint(&mut self, stake_info: &StakeInfo) {
+        self.lxr_reward_per_token_completed = stake_info.reward_per_token_lxr_stored;
+        self.sol_reward_per_token_completed = stake_info.reward_per_token_sol_stored;
+        self.reward_scale_version = stake_info.reward_scale_version;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `LEN` is hand-maintained; it must match the serialized size exactly.
+    #[test]
+    fn len_matches_serialized_size() {
+        let mut data = Vec::new();
+        UserStakeInfo::default().try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), UserStakeInfo::LEN);
+    }
+
+    #[test]
+    fn legacy_record_grows_without_backdated_sol_rewards() {
+        let owner = Pubkey::new_unique();
+        let legacy = UserStakeInfo {
+            bump: 252,
+            owner,
+            total_staked_sol: 10 * PRECISION as u64,
+            lxr_reward_per_token_completed: 3,
+            blacklisted_sol: 9,
+            ..Default::default()
+        };
+        let mut data = Vec::new();
+        legacy.try_serialize(&mut data).unwrap();
+        data.truncate(UserStakeInfo::LEGACY_LEN);
+        assert!(UserStakeInfo::try_deserialize(&mut &data[..]).is_err());
+        data.resize(UserStakeInfo::LEN, 0);
+
+        let mut grown = UserStakeInfo::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!(grown.owner, owner);
+        assert_eq!(grown.lxr_reward_per_token_completed, 3);
+        assert_eq!(grown.blacklisted_sol, 9);
+
+        let stake_info = StakeInfo {
+            reward_per_token_sol_stored: 500,
+            reward_per_token_lxr_stored: 3,
+            ..Default::default()
+        };
+        grown.init_appended_fields(&stake_info).unwrap();
+        assert_eq!(grown.sol_reward_per_token_completed, 500);
+        assert_eq!(grown.pending_sol_rewards(&stake_info).unwrap(), 0);
+        assert_eq!(grown.pending_lxr_rewards(&stake_info).unwrap(), 0);
+    }
+
+    /// Global state after `migrate_reward_scale(numerator, denominator)` from the
+    /// given pre-migration indices.
+    fn migrated(lxr_index: u128, sol_index: u128, numerator: u64, denominator: u64) -> StakeInfo {
+        StakeInfo {
+            reward_per_token_lxr_stored: lxr_index * numerator as u128 / denominator as u128,
+            reward_per_token_sol_stored: sol_index * numerator as u128 / denominator as u128,
+            reward_scale_numerator: numerator,
+            reward_scale_denominator: denominator,
+            reward_scale_version: 1,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn version_zero_checkpoint_is_rescaled_once() {
+        let stake_info = migrated(4 * PRECISION, 6 * PRECISION, 1, 1_000);
+        let mut user = UserStakeInfo {
+            lxr_reward_per_token_completed: 3 * PRECISION,
+            sol_reward_per_token_completed: 5 * PRECISION,
+            ..Default::default()
+        };
+
+        user.sync_reward_scale(&stake_info).unwrap();
+        assert_eq!(user.reward_scale_version, 1);
+        assert_eq!(user.lxr_reward_per_token_completed, 3 * PRECISION / 1_000);
+        assert_eq!(user.sol_reward_per_token_completed, 5 * PRECISION / 1_000);
+
+        // Already on the current scale: a second sync leaves it alone.
+        user.sync_reward_scale(&stake_info).unwrap();
+        assert_eq!(user.lxr_reward_per_token_completed, 3 * PRECISION / 1_000);
+    }
+
+    /// A user still checkpointed at version 0 redeems exactly what a user whose
+    /// checkpoint was written after the migration does, for rewards on both sides
+    /// of it.
+    #[test]
+    fn post_migration_redemptions_match_for_stale_checkpoints() {
+        let (numerator, denominator) = (1, 1_000);
+        let staked = 10_000_000_000;
+        let checkpoint = 2 * PRECISION;
+        let mut stake_info = migrated(5 * PRECISION, 7 * PRECISION, numerator, denominator);
+        // Rewards credited after the migration, on the new scale.
+        stake_info.reward_per_token_lxr_stored += PRECISION / 1_000;
+        stake_info.reward_per_token_sol_stored += PRECISION / 2_000;
+
+        let mut stale = UserStakeInfo {
+            total_staked_sol: staked,
+            lxr_reward_per_token_completed: checkpoint,
+            sol_reward_per_token_completed: checkpoint,
+            ..Default::default()
+        };
+        let mut current = UserStakeInfo {
+            total_staked_sol: staked,
+            lxr_reward_per_token_completed: checkpoint * numerator as u128 / denominator as u128,
+            sol_reward_per_token_completed: checkpoint * numerator as u128 / denominator as u128,
+            reward_scale_version: 1,
+            ..Default::default()
+        };
+
+        let lxr = stale.pending_lxr_rewards(&stake_info).unwrap();
+        assert_eq!(lxr, current.pending_lxr_rewards(&stake_info).unwrap());
+        // (3 + 1) PRECISION before the rescale, 4 / 1_000 of it after.
+        assert_eq!(lxr, staked * 4 / 1_000);
+
+        let sol = stale.pending_sol_rewards(&stake_info).unwrap();
+        assert_eq!(sol, current.pending_sol_rewards(&stake_info).unwrap());
+        assert_eq!(sol, staked * 5 / 1_000 + staked / 2_000);
+    }
+
+    #[test]
+    fn pending_rewards_beyond_u64_fail_instead_of_truncating() {
+        let stake_info = StakeInfo {
+            reward_per_token_lxr_stored: 2 * PRECISION,
+            reward_per_token_sol_stored: 2 * PRECISION,
+            ..Default::default()
+        };
+        let mut user = UserStakeInfo {
+            total_staked_sol: u64::MAX,
+            ..Default::default()
+        };
+        assert!(user.pending_lxr_rewards(&stake_info).is_err());
+        assert!(user.pending_sol_rewards(&stake_info).is_err());
+    }
+
+    #[test]
+    fn unstake_returns_purchased_lxr_pro_rata() {
+        let user = UserStakeInfo {
+            total_staked_sol: 3_000,
+            base_lxr_holdings: 1_000,
+            ..Default::default()
+        };
+        // 1/3 of 1_000 rounds up to 334.
+        assert_eq!(user.lxr_to_return_for_unstake(1_000).unwrap(), 334);
+        assert_eq!(user.lxr_to_return_for_unstake(1).unwrap(), 1);
+        assert_eq!(user.lxr_to_return_for_unstake(3_000).unwrap(), 1_000);
+        assert!(user.lxr_to_return_for_unstake(3_001).is_err());
+
+        let no_base = UserStakeInfo {
+            total_staked_sol: 3_000,
+            ..Default::default()
+        };
+        assert_eq!(no_base.lxr_to_return_for_unstake(1_500).unwrap(), 0);
+    }
+
+    #[test]
+    fn partial_unstakes_return_at_least_the_whole_base() {
+        let mut user = UserStakeInfo {
+            total_staked_sol: 7,
+            base_lxr_holdings: 10,
+            ..Default::default()
+        };
+        let mut returned = 0;
+        while user.total_staked_sol > 0 {
+            let lxr = user.lxr_to_return_for_unstake(1).unwrap();
+            returned += lxr;
+            user.base_lxr_holdings -= lxr;
+            user.total_staked_sol -= 1;
+        }
+        assert_eq!(returned, 10);
+        assert_eq!(user.base_lxr_holdings, 0);
+    }
+
+    fn with_base(base_lxr_holdings: u64) -> UserStakeInfo {
+        UserStakeInfo {
+            base_lxr_holdings,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn forfeiture_conserves_rewards() {
+        let user = with_base(1_000);
+        for holdings in [0, 1, 333, 999, 1_000, 5_000] {
+            for rewards in [0, 1, 7, 1_001, u64::MAX] {
+                for grace_bps in [0, 100, BPS_DENOMINATOR] {
+                    let (claimable, forfeited) = user
+                        .apply_holdings_forfeiture(rewards, holdings, grace_bps)
+                        .unwrap();
+                    assert_eq!(claimable + forfeited, rewards);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn forfeiture_rounds_in_favour_of_forfeiting() {
+        // 1/3 of 10 is 3.33...: the user gets 3 and the dust is forfeited.
+        let user = with_base(3);
+        assert_eq!(user.apply_holdings_forfeiture(10, 1, 0).unwrap(), (3, 7));
+        // 2/3 of 10 is 6.66...: still rounded down.
+        assert_eq!(user.apply_holdings_forfeiture(10, 2, 0).unwrap(), (6, 4));
+    }
+
+    #[test]
+    fn forfeiture_edge_holdings() {
+        let user = with_base(1_000);
+        // Nothing held: everything is forfeited.
+        assert_eq!(user.apply_holdings_forfeiture(500, 0, 0).unwrap(), (0, 500));
+        // Holding more than the base forfeits nothing.
+        assert_eq!(user.apply_holdings_forfeiture(500, 2_000, 0).unwrap(), (500, 0));
+        // No base (nothing bought yet): nothing to pro-rate against, and no division by zero.
+        let no_base = with_base(0);
+        assert_eq!(no_base.apply_holdings_forfeiture(500, 0, 0).unwrap(), (500, 0));
+    }
+
+    #[test]
+    fn forfeiture_grace_counts_near_full_holdings_as_full() {
+        // 1% of 10_000 is 100: holdings down to 9_900 keep everything.
+        let user = with_base(10_000);
+        assert_eq!(user.apply_holdings_forfeiture(1_000, 9_900, 100).unwrap(), (1_000, 0));
+        // One below the grace band pro-rates against the full base.
+        assert_eq!(user.apply_holdings_forfeiture(1_000, 9_899, 100).unwrap(), (989, 11));
+    }
+
+    #[test]
+    fn min_hold_boundary() {
+        // 50% of 1_000 is 500: exactly the share passes, one unit below fails.
+        let user = with_base(1_000);
+        assert!(user.meets_min_hold(500, 5_000).unwrap());
+        assert!(!user.meets_min_hold(499, 5_000).unwrap());
+        // The whole base is required at 100%.
+        assert!(user.meets_min_hold(1_000, BPS_DENOMINATOR).unwrap());
+        assert!(!user.meets_min_hold(999, BPS_DENOMINATOR).unwrap());
+    }
+
+    #[test]
+    fn min_hold_fractional_share_is_not_rounded_down() {
+        // 50% of 1_001 is 500.5: 500 falls short, 501 reaches it.
+        let user = with_base(1_001);
+        assert!(!user.meets_min_hold(500, 5_000).unwrap());
+        assert!(user.meets_min_hold(501, 5_000).unwrap());
+    }
+
+    #[test]
+    fn min_hold_off_or_without_base() {
+        assert!(with_base(1_000).meets_min_hold(0, 0).unwrap());
+        assert!(with_base(0).meets_min_hold(0, BPS_DENOMINATOR).unwrap());
+        assert!(with_base(u64::MAX)
+            .meets_min_hold(u64::MAX, BPS_DENOMINATOR)
+            .unwrap());
+    }
 }
@@ -0,0 +1,137 @@
+//! DEX venues the buyback swap can be routed through
+
+use crate::error::ErrorCode;
+use anchor_lang::prelude::borsh::BorshSerialize;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::invoke;
+
+/// `dex_type` value routing buyback through Raydium CPMM (default).
+pub const DEX_TYPE_RAYDIUM_CPMM: u8 = 0;
+
+/// `dex_type` value reserved for Orca Whirlpools (not implemented yet).
+pub const DEX_TYPE_ORCA_WHIRLPOOL: u8 = 1;
+
+/// Anchor-encoded parameters for Raydium's `swap_base_input` CPI call.
+/// Represents an exact-input trade where `amount_in` is spent to receive
+/// at least `minimum_amount_out` of the output token.
+#[derive(BorshSerialize)]
+pub struct SwapBaseInput {
+    /// Exact amount of input tokens to spend.
+    amount_in: u64,
+    /// Minimum acceptable output (slippage guard).
+    minimum_amount_out: u64,
+}
+
+/// Venue the buyback's exact-input WSOL → reward token swap is sent to,
+/// selected by `GlobalConfig::dex_type`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SwapVenue {
+    /// Raydium constant-product AMM (`crate::raydium_cpmm`).
+    RaydiumCpmm,
+    /// Orca Whirlpools; selectable once its CPI builder is written.
+    OrcaWhirlpool,
+}
+
+impl SwapVenue {
+    /// Venue for a `dex_type` config value (`InvalidParam` if unknown).
+    pub fn from_dex_type(dex_type: u8) -> Result<Self> {
+        match dex_type {
+            DEX_TYPE_RAYDIUM_CPMM => Ok(SwapVenue::RaydiumCpmm),
+            DEX_TYPE_ORCA_WHIRLPOOL => Ok(SwapVenue::OrcaWhirlpool),
+            _ => err!(ErrorCode::InvalidParam),
+        }
+    }
+
+    /// Whether the venue has a CPI builder; others fail with `UnsupportedSwapVenue`.
+    pub fn is_supported(&self) -> bool {
+        matches!(self, SwapVenue::RaydiumCpmm)
+    }
+
+    /// Spends exactly `amount_in` of the input token for at least `minimum_amount_out`
+    /// of the output token.
+    ///
+    /// `accounts` are passed in the venue's own instruction order; for Raydium CPMM:
+    /// payer (signer), authority, amm_config, pool_state, input/output token accounts,
+    /// input/output vaults, input/output token programs, input/output mints and the
+    /// observation state.
+    pub fn swap_base_input<'info>(
+        &self,
+        accounts: &[AccountInfo<'info>],
+        amount_in: u64,
+        minimum_amount_out: u64,
+    ) -> Result<()> {
+        let ix = match self {
+            SwapVenue::RaydiumCpmm => raydium_swap_base_input_ix(accounts, amount_in, minimum_amount_out)?,
+            SwapVenue::OrcaWhirlpool => return err!(ErrorCode::UnsupportedSwapVenue),
+        };
+        invoke(&ix, accounts)?;
+        Ok(())
+    }
+}
+
+/// Builds Raydium CPMM's `swap_base_input` instruction over its 13 accounts.
+fn raydium_swap_base_input_ix(
+    accounts: &[AccountInfo],
+    amount_in: u64,
+    minimum_amount_out: u64,
+) -> Result<Instruction> {
+    require_eq!(accounts.len(), 13, ErrorCode::InvalidParam);
+
+    // Discriminator for `global:swap_base_input` (Raydium CPMM)
+    let discriminator =
+        anchor_lang::solana_program::hash::hash(b"global:swap_base_input").to_bytes()[..8].to_vec();
+    let mut data = discriminator;
+    data.extend(SwapBaseInput { amount_in, minimum_amount_out }.try_to_vec()?);
+
+    // Authority, amm_config, token programs and mints are read-only; the rest is written.
+    let readonly = |i: usize| matches!(i, 1 | 2 | 8 | 9 | 10 | 11);
+    let metas = accounts
+        .iter()
+        .enumerate()
+        .map(|(i, ai)| match (i, readonly(i)) {
+            (0, _) => AccountMeta::new(*ai.key, true),
+            (_, true) => AccountMeta::new_readonly(*ai.key, false),
+            (_, false) => AccountMeta::new(*ai.key, false),
+        })
+        .collect();
+
+    Ok(Instruction {
+        program_id: crate::raydium_cpmm::id(),
+        accounts: metas,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raydium_cpmm_is_supported() {
+        let venue = SwapVenue::from_dex_type(DEX_TYPE_RAYDIUM_CPMM).unwrap();
+        assert_eq!(venue, SwapVenue::RaydiumCpmm);
+        assert!(venue.is_supported());
+    }
+
+    #[test]
+    fn orca_whirlpool_is_known_but_unsupported() {
+        let venue = SwapVenue::from_dex_type(DEX_TYPE_ORCA_WHIRLPOOL).unwrap();
+        assert_eq!(venue, SwapVenue::OrcaWhirlpool);
+        assert!(!venue.is_supported());
+        assert_eq!(
+            venue.swap_base_input(&[], 1, 1).unwrap_err(),
+            ErrorCode::UnsupportedSwapVenue.into()
+        );
+    }
+
+    #[test]
+    fn unknown_dex_type_is_an_invalid_param() {
+        for dex_type in [2, 3, u8::MAX] {
+            assert_eq!(
+                SwapVenue::from_dex_type(dex_type).unwrap_err(),
+                ErrorCode::InvalidParam.into()
+            );
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Allocate, Assign, CreateAccount, Transfer};
+
+/// Creates the PDA `new_account` with `space` bytes owned by `owner`, funded to at
+/// least `lamports` by `payer`.
+///
+/// `create_account` fails once the address holds any lamports, so anyone could
+/// block a predictable PDA by sending it dust. An already funded address is
+/// instead topped up, allocated and assigned, which only needs the PDA signature.
+pub fn create_pda_account<'a>(
+    payer: &AccountInfo<'a>,
+    new_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    lamports: u64,
+    space: u64,
+    owner: &Pubkey,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let current_lamports = new_account.lamports();
+    if current_lamports == 0 {
+        return system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                CreateAccount {
+                    from: payer.clone(),
+                    to: new_account.clone(),
+                },
+                signer_seeds,
+            ),
+            lamports,
+            space,
+            owner,
+        );
+    }
+
+    let top_up = lamports.saturating_sub(current_lamports);
+    if top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                Transfer {
+                    from: payer.clone(),
+                    to: new_account.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            Allocate {
+                account_to_allocate: new_account.clone(),
+            },
+            signer_seeds,
+        ),
+        space,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            Assign {
+                account_to_assign: new_account.clone(),
+            },
+            signer_seeds,
+        ),
+        owner,
+    )
+}
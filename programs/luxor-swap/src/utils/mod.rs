@@ -1,4 +1,6 @@
+pub mod account;
 pub mod math;
 pub mod token;
+pub use account::*;
 pub use math::*;
 pub use token::*;
@@ -169,6 +169,12 @@ pub fn get_transfer_fee(mint_info: &AccountInfo, pre_fee_amount: u64) -> Result<
     Ok(fee)
 }
 
+/// Whether a LUXOR mint's `decimals` equal [`crate::LUXOR_DECIMALS`], the
+/// precision pricing and the vault amounts are expressed in.
+pub fn luxor_decimals_match(decimals: u8) -> bool {
+    decimals == crate::LUXOR_DECIMALS
+}
+
 pub fn is_supported_mint(mint_account: &InterfaceAccount<Mint>) -> Result<bool> {
     let mint_info = mint_account.to_account_info();
     if *mint_info.owner == Token::id() {
@@ -238,3 +244,16 @@ pub fn create_token_account<'a>(
         },
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn luxor_decimals_match_rejects_mismatch() {
+        assert!(luxor_decimals_match(crate::LUXOR_DECIMALS));
+        assert!(!luxor_decimals_match(crate::LUXOR_DECIMALS - 1));
+        assert!(!luxor_decimals_match(crate::LUXOR_DECIMALS + 1));
+        assert!(!luxor_decimals_match(0));
+    }
+}
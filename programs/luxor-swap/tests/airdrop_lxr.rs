@@ -0,0 +1,155 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::{stake, sysvar};
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use anchor_spl::token::spl_token;
+use common::*;
+use luxor_swap::pricing::quote_sol_for_lxr;
+use luxor_swap::states::*;
+use solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    signature::{Keypair, Signer},
+};
+
+const LXR: u64 = 1_000_000_000;
+const POOL_SOL: u64 = 1_000 * LAMPORTS_PER_SOL;
+const POOL_LXR: u64 = 1_000_000 * LXR;
+
+/// An airdrop recipient still buys as a new staker: the early-bird bonus prices
+/// the purchase and `total_stake_count` counts them.
+#[tokio::test]
+async fn airdrop_recipient_purchases_as_new_staker() {
+    let treasury = 1_000_000 * LXR;
+    let airdropped = 500 * LXR;
+    let purchased = 1_000 * LXR;
+
+    let mut fixture = ProtocolFixture::new().with_stake(10 * LAMPORTS_PER_SOL);
+    fixture.lxr_treasury_amount = treasury;
+    fixture.global_config.initial_lxr_allocation_vault = treasury;
+    fixture.global_config.max_stake_count_to_get_bonus = 100;
+    fixture.global_config.bonus_rate = 100_000;
+    fixture.stake_info.total_stake_count = 3;
+
+    let raydium_authority = luxor_swap::vault_and_lp_mint_auth::id();
+    let wsol = spl_token::native_mint::id();
+    let lxr_mint = luxor_swap::luxor_mint::id();
+    let pool_state = PoolState {
+        token_0_vault: Pubkey::new_unique(),
+        token_1_vault: Pubkey::new_unique(),
+        token_0_mint: wsol,
+        token_1_mint: lxr_mint,
+        ..Default::default()
+    };
+    let user = Keypair::new();
+    fixture.accounts.extend([
+        (
+            luxor_swap::luxor_pool_state::id(),
+            raydium_pool_account(&pool_state),
+        ),
+        (
+            pool_state.token_0_vault,
+            token_account(&wsol, &raydium_authority, POOL_SOL),
+        ),
+        (
+            pool_state.token_1_vault,
+            token_account(&lxr_mint, &raydium_authority, POOL_LXR),
+        ),
+        (user.pubkey(), system_account(SIGNER_LAMPORTS)),
+    ]);
+    let vote_account = fixture.global_config.vote_account;
+    let mut env = fixture.start().await;
+    let admin = env.admin.insecure_clone();
+
+    let lxr_vault = pda(luxor_swap::LUXOR_VAULT_SEED);
+    let user_lxr = get_associated_token_address(&user.pubkey(), &lxr_mint);
+    let record = user_stake_info_address(&user.pubkey());
+
+    // --- Airdrop: the record exists afterwards, but is not claimed as a staker's ---
+    let ix = TestEnv::instruction(
+        luxor_swap::accounts::AirdropLxr {
+            owner: admin.pubkey(),
+            global_config: pda(GLOBAL_CONFIG_SEED),
+            user: user.pubkey(),
+            user_stake_info: record,
+            stake_info: pda(STAKE_INFO_SEED),
+            authority: pda(luxor_swap::AUTH_SEED),
+            luxor_vault: lxr_vault,
+            luxor_mint: lxr_mint,
+            user_lxr_token: user_lxr,
+            token_program: spl_token::id(),
+            associated_token_program: associated_token::ID,
+            system_program: anchor_lang::system_program::ID,
+        },
+        luxor_swap::instruction::AirdropLxr { amount: airdropped },
+    );
+    env.send(&[ix], &[&admin]).await.unwrap();
+
+    let user_stake_info: UserStakeInfo = env.anchor_state(&record).await;
+    assert_eq!(user_stake_info.owner, Pubkey::default());
+    assert_eq!(user_stake_info.base_lxr_holdings, airdropped);
+
+    // --- First purchase: priced with the bonus and counted ---
+    let global_config: GlobalConfig = env.anchor_state(&pda(GLOBAL_CONFIG_SEED)).await;
+    let stake_info: StakeInfo = env.anchor_state(&pda(STAKE_INFO_SEED)).await;
+    let treasury_left = treasury - airdropped;
+    let quote = |is_new_staker| {
+        quote_sol_for_lxr(
+            &pool_state,
+            POOL_SOL,
+            POOL_LXR,
+            purchased,
+            &stake_info,
+            &global_config,
+            is_new_staker,
+            treasury_left,
+        )
+        .unwrap()
+    };
+    let bonus_price = quote(true);
+    assert!(bonus_price < quote(false));
+
+    let ix = TestEnv::instruction(
+        luxor_swap::accounts::Purchase {
+            owner: user.pubkey(),
+            global_config: pda(GLOBAL_CONFIG_SEED),
+            luxor_vault: lxr_vault,
+            user_stake_info: record,
+            stake_info: pda(STAKE_INFO_SEED),
+            authority: pda(luxor_swap::AUTH_SEED),
+            luxor_mint: lxr_mint,
+            owner_lxr_token: user_lxr,
+            stake_pda: pda(luxor_swap::STAKE_ACCOUNT_SEED),
+            vote_account,
+            stake_program: stake::program::id(),
+            clock: sysvar::clock::id(),
+            stake_history: sysvar::stake_history::id(),
+            stake_config: stake::config::ID,
+            pool_state: luxor_swap::luxor_pool_state::id(),
+            token_0_vault: pool_state.token_0_vault,
+            token_1_vault: pool_state.token_1_vault,
+            token_program: spl_token::id(),
+            associated_token_program: associated_token::ID,
+            system_program: anchor_lang::system_program::ID,
+        },
+        luxor_swap::instruction::Purchase {
+            lxr_to_purchase: purchased,
+            max_sol_amount: u64::MAX,
+            deadline: i64::MAX,
+        },
+    );
+    let logs = env.send(&[ix], &[&user]).await.unwrap();
+
+    let purchase = &events::<LxrPurchased>(&logs)[0];
+    assert_eq!(purchase.sol_amount, bonus_price);
+
+    let stake_info_after: StakeInfo = env.anchor_state(&pda(STAKE_INFO_SEED)).await;
+    assert_eq!(
+        stake_info_after.total_stake_count,
+        stake_info.total_stake_count + 1
+    );
+    let user_stake_info: UserStakeInfo = env.anchor_state(&record).await;
+    assert_eq!(user_stake_info.owner, user.pubkey());
+    assert_eq!(user_stake_info.total_staked_sol, bonus_price);
+    assert_eq!(user_stake_info.base_lxr_holdings, airdropped + purchased);
+}
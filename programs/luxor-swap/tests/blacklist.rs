@@ -0,0 +1,220 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use anchor_spl::token::spl_token;
+use common::*;
+use luxor_swap::error::ErrorCode;
+use luxor_swap::states::*;
+use solana_sdk::{
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    signature::{Keypair, Signer},
+};
+
+const LXR: u64 = 1_000_000_000;
+const STAKED: u64 = 5 * LAMPORTS_PER_SOL;
+const REWARDS: u64 = 50 * LXR;
+const HOLDINGS: u64 = 100 * LXR;
+const DELAY: u64 = 3_600;
+
+/// Protocol with `user` staked, holding their base LXR and owed `REWARDS`.
+async fn staked_user_env(user: &Keypair) -> TestEnv {
+    let mut fixture = ProtocolFixture::new().with_stake(10 * LAMPORTS_PER_SOL);
+    fixture.global_config.blacklist_delay_secs = DELAY;
+    fixture.stake_info.total_staked_sol = 10 * LAMPORTS_PER_SOL;
+    fixture.stake_info.reward_per_token_lxr_stored =
+        u128::from(REWARDS) * luxor_swap::PRECISION / u128::from(STAKED);
+    fixture.lxr_reward_amount = REWARDS;
+
+    let lxr_mint = luxor_swap::luxor_mint::id();
+    let (address, bump) = Pubkey::find_program_address(
+        &[USER_STAKE_INFO_SEED.as_bytes(), user.pubkey().as_ref()],
+        &luxor_swap::id(),
+    );
+    let record = UserStakeInfo {
+        bump,
+        owner: user.pubkey(),
+        total_staked_sol: STAKED,
+        base_lxr_holdings: HOLDINGS,
+        ..Default::default()
+    };
+    fixture.accounts.extend([
+        (user.pubkey(), system_account(SIGNER_LAMPORTS)),
+        (address, anchor_account(&record, UserStakeInfo::LEN)),
+        (
+            get_associated_token_address(&user.pubkey(), &lxr_mint),
+            token_account(&lxr_mint, &user.pubkey(), HOLDINGS),
+        ),
+    ]);
+    fixture.start().await
+}
+
+fn propose_ix(env: &TestEnv, user: &Pubkey) -> Instruction {
+    TestEnv::instruction(
+        luxor_swap::accounts::ProposeBlacklist {
+            owner: env.admin.pubkey(),
+            global_config: pda(GLOBAL_CONFIG_SEED),
+            user: *user,
+            user_stake_info: user_stake_info_address(user),
+        },
+        luxor_swap::instruction::ProposeBlacklist {},
+    )
+}
+
+fn cancel_ix(env: &TestEnv, user: &Pubkey) -> Instruction {
+    TestEnv::instruction(
+        luxor_swap::accounts::CancelBlacklist {
+            owner: env.admin.pubkey(),
+            global_config: pda(GLOBAL_CONFIG_SEED),
+            user: *user,
+            user_stake_info: user_stake_info_address(user),
+        },
+        luxor_swap::instruction::CancelBlacklist {},
+    )
+}
+
+fn finalize_ix(env: &TestEnv, user: &Pubkey) -> Instruction {
+    TestEnv::instruction(
+        luxor_swap::accounts::FinalizeBlacklist {
+            owner: env.admin.pubkey(),
+            global_config: pda(GLOBAL_CONFIG_SEED),
+            user: *user,
+            user_stake_info: user_stake_info_address(user),
+            admin_stake_info: pda(ADMIN_STAKE_INFO_SEED),
+            stake_info: pda(STAKE_INFO_SEED),
+            stake_pda: pda(luxor_swap::STAKE_ACCOUNT_SEED),
+            system_program: anchor_lang::system_program::ID,
+        },
+        luxor_swap::instruction::FinalizeBlacklist {},
+    )
+}
+
+fn redeem_ix(user: &Pubkey) -> Instruction {
+    let lxr_mint = luxor_swap::luxor_mint::id();
+    TestEnv::instruction(
+        luxor_swap::accounts::Redeem {
+            owner: *user,
+            global_config: pda(GLOBAL_CONFIG_SEED),
+            user_stake_info: user_stake_info_address(user),
+            authority: pda(luxor_swap::AUTH_SEED),
+            stake_info: pda(STAKE_INFO_SEED),
+            luxor_vault: pda(luxor_swap::LUXOR_VAULT_SEED),
+            luxor_reward_vault: pda(luxor_swap::LUXOR_REWARD_VAULT_SEED),
+            luxor_mint: lxr_mint,
+            reward_mint: lxr_mint,
+            owner_reward_token: get_associated_token_address(user, &lxr_mint),
+            owner_lxr_token: get_associated_token_address(user, &lxr_mint),
+            token_program: spl_token::id(),
+            associated_token_program: associated_token::ID,
+            system_program: anchor_lang::system_program::ID,
+        },
+        luxor_swap::instruction::Redeem { amount: None },
+    )
+}
+
+fn claim_sol_rewards_ix(user: &Pubkey) -> Instruction {
+    let native_mint = spl_token::native_mint::id();
+    TestEnv::instruction(
+        luxor_swap::accounts::ClaimSolRewards {
+            owner: *user,
+            global_config: pda(GLOBAL_CONFIG_SEED),
+            user_stake_info: user_stake_info_address(user),
+            authority: pda(luxor_swap::AUTH_SEED),
+            stake_info: pda(STAKE_INFO_SEED),
+            sol_treasury_vault: pda(luxor_swap::SOL_TREASURY_VAULT_SEED),
+            native_mint,
+            owner_wsol_token: get_associated_token_address(user, &native_mint),
+            token_program: spl_token::id(),
+            associated_token_program: associated_token::ID,
+            system_program: anchor_lang::system_program::ID,
+        },
+        luxor_swap::instruction::ClaimSolRewards {},
+    )
+}
+
+/// During the dispute window the user can neither redeem nor claim SOL, and the
+/// admin cannot finalize early; once it has passed, finalizing succeeds.
+#[tokio::test]
+async fn pending_blacklist_holds_payouts_until_finalized() {
+    let user = Keypair::new();
+    let mut env = staked_user_env(&user).await;
+    let admin = env.admin.insecure_clone();
+
+    let ix = propose_ix(&env, &user.pubkey());
+    env.send(&[ix], &[&admin]).await.unwrap();
+
+    assert_error(
+        env.send(&[redeem_ix(&user.pubkey())], &[&user]).await,
+        ErrorCode::BlacklistAlreadyProposed,
+    );
+    assert_error(
+        env.send(&[claim_sol_rewards_ix(&user.pubkey())], &[&user])
+            .await,
+        ErrorCode::BlacklistAlreadyProposed,
+    );
+
+    env.advance_clock(DELAY as i64 - 1).await;
+    let ix = finalize_ix(&env, &user.pubkey());
+    assert_error(
+        env.send(&[ix], &[&admin]).await,
+        ErrorCode::BlacklistDelayNotElapsed,
+    );
+
+    env.advance_clock(1).await;
+    let ix = finalize_ix(&env, &user.pubkey());
+    env.send(&[ix], &[&admin]).await.unwrap();
+
+    let record: UserStakeInfo = env
+        .anchor_state(&user_stake_info_address(&user.pubkey()))
+        .await;
+    assert_eq!(record.blacklist_pending_timestamp, 0);
+    assert_eq!(record.blacklisted_sol, STAKED);
+    assert_eq!(record.lxr_rewards_pending, 0);
+}
+
+/// A cancelled proposal cannot be finalized, even after the delay, and the user's
+/// payouts are open again.
+#[tokio::test]
+async fn cancelled_blacklist_cannot_be_finalized() {
+    let user = Keypair::new();
+    let mut env = staked_user_env(&user).await;
+    let admin = env.admin.insecure_clone();
+
+    let ix = propose_ix(&env, &user.pubkey());
+    env.send(&[ix], &[&admin]).await.unwrap();
+    let ix = cancel_ix(&env, &user.pubkey());
+    env.send(&[ix], &[&admin]).await.unwrap();
+
+    let record: UserStakeInfo = env
+        .anchor_state(&user_stake_info_address(&user.pubkey()))
+        .await;
+    assert_eq!(record.blacklist_pending_timestamp, 0);
+
+    // Nothing left to cancel.
+    let ix = cancel_ix(&env, &user.pubkey());
+    assert_error(
+        env.send(&[ix], &[&admin]).await,
+        ErrorCode::BlacklistNotProposed,
+    );
+
+    env.advance_clock(DELAY as i64).await;
+    let ix = finalize_ix(&env, &user.pubkey());
+    assert_error(
+        env.send(&[ix], &[&admin]).await,
+        ErrorCode::BlacklistNotProposed,
+    );
+
+    let logs = env
+        .send(&[redeem_ix(&user.pubkey())], &[&user])
+        .await
+        .unwrap();
+    let collected = &events::<RewardsCollected>(&logs)[0];
+    assert_eq!(collected.lxr_collected, REWARDS);
+
+    let record: UserStakeInfo = env
+        .anchor_state(&user_stake_info_address(&user.pubkey()))
+        .await;
+    assert_eq!(record.total_staked_sol, STAKED);
+    assert_eq!(record.blacklisted_sol, 0);
+}
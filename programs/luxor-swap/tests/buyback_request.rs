@@ -0,0 +1,79 @@
+//! Request phase of `buyback` sizing the split from the Raydium pool.
+
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::token::spl_token;
+use common::*;
+use luxor_swap::states::*;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+
+const LXR: u64 = 1_000_000_000;
+const PRINCIPAL: u64 = 10 * LAMPORTS_PER_SOL;
+const REWARDS: u64 = 2 * LAMPORTS_PER_SOL;
+
+/// With a `reward_vault_target` the split is priced from the pool vaults, so
+/// token accounts other than the pool's own are rejected and nothing is split.
+#[tokio::test]
+async fn request_rejects_vaults_outside_the_pool() {
+    let mut fixture = ProtocolFixture::new().with_stake(PRINCIPAL + REWARDS);
+    fixture.stake_info.total_staked_sol = PRINCIPAL;
+    fixture.stake_info.last_tracked_sol_balance =
+        fixture.global_config.stake_rent_exempt + PRINCIPAL;
+    fixture.global_config.reward_vault_target = 1_000 * LXR;
+
+    let raydium_authority = luxor_swap::vault_and_lp_mint_auth::id();
+    let wsol = spl_token::native_mint::id();
+    let lxr_mint = luxor_swap::luxor_mint::id();
+    let pool_state = PoolState {
+        token_0_vault: Pubkey::new_unique(),
+        token_1_vault: Pubkey::new_unique(),
+        token_0_mint: wsol,
+        token_1_mint: lxr_mint,
+        ..Default::default()
+    };
+    // Decoys of the right mints and owner, with a much cheaper LXR price.
+    let decoys = BuybackKeys {
+        vote_account: fixture.global_config.vote_account,
+        token_0_vault: Pubkey::new_unique(),
+        token_1_vault: Pubkey::new_unique(),
+    };
+    fixture.accounts.extend([
+        (
+            luxor_swap::luxor_pool_state::id(),
+            raydium_pool_account(&pool_state),
+        ),
+        (
+            pool_state.token_0_vault,
+            token_account(&wsol, &raydium_authority, 1_000 * LAMPORTS_PER_SOL),
+        ),
+        (
+            pool_state.token_1_vault,
+            token_account(&lxr_mint, &raydium_authority, 1_000_000 * LXR),
+        ),
+        (
+            decoys.token_0_vault,
+            token_account(&wsol, &raydium_authority, LAMPORTS_PER_SOL),
+        ),
+        (
+            decoys.token_1_vault,
+            token_account(&lxr_mint, &raydium_authority, 1_000_000_000 * LXR),
+        ),
+    ]);
+    let mut env = fixture.start().await;
+    let admin = env.admin.insecure_clone();
+
+    let ix = TestEnv::instruction(
+        buyback_accounts(&env, &decoys),
+        luxor_swap::instruction::Buyback { min_lxr_out: 0 },
+    );
+    let error = env.send(&[ix], &[&admin]).await.unwrap_err();
+    assert_eq!(
+        error_code(&error),
+        Some(anchor_lang::error::ErrorCode::RequireKeysEqViolated as u32)
+    );
+
+    let stake_info: StakeInfo = env.anchor_state(&pda(STAKE_INFO_SEED)).await;
+    assert!(!stake_info.buyback_requested);
+    assert!(env.account(&split_address(0)).await.is_none());
+}
@@ -0,0 +1,201 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::spl_token;
+use common::*;
+use luxor_swap::error::ErrorCode;
+use luxor_swap::states::*;
+use solana_sdk::{
+    instruction::Instruction, native_token::LAMPORTS_PER_SOL, rent::Rent, signature::Signer,
+    stake::state::StakeStateV2,
+};
+
+const PRINCIPAL: u64 = 10 * LAMPORTS_PER_SOL;
+const REWARDS: u64 = 2 * LAMPORTS_PER_SOL;
+/// Rent the split account was funded with on top of today's minimum: the cached
+/// `stake_rent_exempt` is refreshed between the two phases in the tests below.
+const RENT_SURPLUS: u64 = 1_000;
+
+/// Protocol with a requested buyback whose split account holds `REWARDS`, all of
+/// it reserved for SOL claims, so settling moves it to the treasury without a swap.
+fn requested_buyback() -> (ProtocolFixture, BuybackKeys) {
+    let mut fixture = ProtocolFixture::new().with_inactive_stake(PRINCIPAL);
+    let split_rent = Rent::default().minimum_balance(StakeStateV2::size_of()) + RENT_SURPLUS;
+    fixture.global_config.stake_rent_exempt = split_rent;
+    fixture.global_config.sol_reward_share_bps = BPS_DENOMINATOR;
+    fixture.stake_info.total_staked_sol = PRINCIPAL;
+    fixture.stake_info.total_sol_rewards_accrued = REWARDS;
+    fixture.stake_info.total_sol_rewards_reserved = REWARDS;
+    fixture.stake_info.buyback_reserve_split = REWARDS;
+    fixture.stake_info.buyback_requested = true;
+
+    let authority = pda(luxor_swap::AUTH_SEED);
+    fixture.accounts.push((
+        split_address(0),
+        initialized_stake_account(&authority, split_rent + REWARDS),
+    ));
+
+    // The Raydium pool vaults stay empty: the split paths below never swap.
+    let raydium_authority = luxor_swap::vault_and_lp_mint_auth::id();
+    let vaults = BuybackKeys {
+        vote_account: fixture.global_config.vote_account,
+        token_0_vault: Pubkey::new_unique(),
+        token_1_vault: Pubkey::new_unique(),
+    };
+    fixture.accounts.push((
+        vaults.token_0_vault,
+        token_account(&spl_token::native_mint::id(), &raydium_authority, 0),
+    ));
+    fixture.accounts.push((
+        vaults.token_1_vault,
+        token_account(&luxor_swap::luxor_mint::id(), &raydium_authority, 0),
+    ));
+    (fixture, vaults)
+}
+
+fn buyback_ix(env: &TestEnv, vaults: &BuybackKeys) -> Instruction {
+    TestEnv::instruction(
+        buyback_accounts(env, vaults),
+        luxor_swap::instruction::Buyback { min_lxr_out: 0 },
+    )
+}
+
+fn withdraw_ix(env: &TestEnv, vaults: &BuybackKeys) -> Instruction {
+    TestEnv::instruction(
+        buyback_accounts(env, vaults),
+        luxor_swap::instruction::BuybackWithdraw {},
+    )
+}
+
+fn settle_ix(env: &TestEnv, vaults: &BuybackKeys) -> Instruction {
+    TestEnv::instruction(
+        buyback_accounts(env, vaults),
+        luxor_swap::instruction::BuybackSettle { min_lxr_out: 0 },
+    )
+}
+
+/// Refreshes `stake_rent_exempt` from the Rent sysvar (`update_config` param 10),
+/// dropping the surplus the split account was funded with.
+fn refresh_stake_rent_ix(env: &TestEnv) -> Instruction {
+    TestEnv::instruction(
+        luxor_swap::accounts::UpdateConfig {
+            owner: env.admin.pubkey(),
+            global_config: pda(GLOBAL_CONFIG_SEED),
+            system_program: anchor_lang::system_program::ID,
+        },
+        luxor_swap::instruction::UpdateConfig {
+            param: 10,
+            value: 0,
+        },
+    )
+}
+
+/// `buyback_withdraw` records the WSOL and the rent it measured; `buyback_settle`
+/// reports that rent even after the cached `stake_rent_exempt` has changed.
+#[tokio::test]
+async fn withdraw_then_settle_reports_the_measured_rent() {
+    let (fixture, vaults) = requested_buyback();
+    let split_rent = fixture.global_config.stake_rent_exempt;
+    let mut env = fixture.start().await;
+    let admin = env.admin.insecure_clone();
+    let admin_wsol = get_associated_token_address(&admin.pubkey(), &spl_token::native_mint::id());
+    let sol_treasury_vault = pda(luxor_swap::SOL_TREASURY_VAULT_SEED);
+
+    // --- withdraw ---
+    let admin_before = env.lamports(&admin.pubkey()).await;
+    let ix = withdraw_ix(&env, &vaults);
+    let logs = env.send(&[ix], &[&admin]).await.unwrap();
+
+    let withdrawn = &events::<BuybackWithdrawn>(&logs)[0];
+    assert_eq!(withdrawn.buyback_count, 0);
+    assert_eq!(withdrawn.sol_amount, REWARDS);
+    assert_eq!(withdrawn.rent_refunded, split_rent);
+    let stake_info: StakeInfo = env.anchor_state(&pda(STAKE_INFO_SEED)).await;
+    assert_eq!(stake_info.buyback_wsol_pending, REWARDS);
+    assert_eq!(stake_info.buyback_rent_refunded, split_rent);
+    assert!(stake_info.buyback_requested);
+    assert!(env.account(&split_address(0)).await.is_none());
+    assert_eq!(env.token_amount(&admin_wsol).await, REWARDS);
+    // The rewards went into the WSOL account; only the rent stays with the admin.
+    assert_eq!(
+        env.lamports(&admin.pubkey()).await,
+        admin_before + split_rent
+    );
+
+    // --- the cached rent changes between the phases ---
+    let ix = refresh_stake_rent_ix(&env);
+    env.send(&[ix], &[&admin]).await.unwrap();
+    let config: GlobalConfig = env.anchor_state(&pda(GLOBAL_CONFIG_SEED)).await;
+    assert_eq!(config.stake_rent_exempt, split_rent - RENT_SURPLUS);
+
+    // --- settle ---
+    let treasury_before = env.token_amount(&sol_treasury_vault).await;
+    let ix = settle_ix(&env, &vaults);
+    let logs = env.send(&[ix], &[&admin]).await.unwrap();
+
+    let executed = &events::<BuybackExecuted>(&logs)[0];
+    assert_eq!(executed.buyback_count, 0);
+    assert_eq!(executed.sol_amount, 0);
+    assert_eq!(executed.sol_reserved, REWARDS);
+    assert_eq!(executed.lxr_bought, 0);
+    assert_eq!(executed.rent_refunded, split_rent);
+    assert_eq!(
+        env.token_amount(&sol_treasury_vault).await,
+        treasury_before + REWARDS
+    );
+
+    let stake_info: StakeInfo = env.anchor_state(&pda(STAKE_INFO_SEED)).await;
+    assert_eq!(stake_info.buyback_wsol_pending, 0);
+    assert_eq!(stake_info.buyback_rent_refunded, 0);
+    assert_eq!(stake_info.buyback_reserve_split, 0);
+    assert_eq!(stake_info.total_sol_reserve_funded, REWARDS);
+    assert!(!stake_info.buyback_requested);
+    assert_eq!(stake_info.buyback_count, 1);
+    assert!(!stake_info.processing);
+}
+
+#[tokio::test]
+async fn settle_without_withdraw_fails() {
+    let (fixture, vaults) = requested_buyback();
+    let mut env = fixture.start().await;
+    let admin = env.admin.insecure_clone();
+
+    let ix = settle_ix(&env, &vaults);
+    assert_error(
+        env.send(&[ix], &[&admin]).await,
+        ErrorCode::BuybackNotWithdrawn,
+    );
+    let stake_info: StakeInfo = env.anchor_state(&pda(STAKE_INFO_SEED)).await;
+    assert!(stake_info.buyback_requested);
+    assert_eq!(stake_info.buyback_count, 0);
+}
+
+/// While the withdrawn WSOL waits for `buyback_settle`, neither `buyback` nor a
+/// second `buyback_withdraw` may run the execution phase again.
+#[tokio::test]
+async fn buyback_and_second_withdraw_fail_while_wsol_is_pending() {
+    let (fixture, vaults) = requested_buyback();
+    let mut env = fixture.start().await;
+    let admin = env.admin.insecure_clone();
+
+    let ix = withdraw_ix(&env, &vaults);
+    env.send(&[ix], &[&admin]).await.unwrap();
+
+    let ix = buyback_ix(&env, &vaults);
+    assert_error(
+        env.send(&[ix], &[&admin]).await,
+        ErrorCode::BuybackInProgress,
+    );
+    let ix = withdraw_ix(&env, &vaults);
+    assert_error(
+        env.send(&[ix], &[&admin]).await,
+        ErrorCode::BuybackInProgress,
+    );
+
+    // The pending withdrawal is untouched and still settles.
+    let stake_info: StakeInfo = env.anchor_state(&pda(STAKE_INFO_SEED)).await;
+    assert_eq!(stake_info.buyback_wsol_pending, REWARDS);
+    let ix = settle_ix(&env, &vaults);
+    env.send(&[ix], &[&admin]).await.unwrap();
+}
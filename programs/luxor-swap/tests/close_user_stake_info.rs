@@ -0,0 +1,81 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use common::*;
+use luxor_swap::error::ErrorCode;
+use luxor_swap::states::*;
+use solana_sdk::signature::{Keypair, Signer};
+
+/// Fixture with one user record written at genesis.
+async fn start_with_user(user_stake_info: impl FnOnce(&mut UserStakeInfo)) -> (TestEnv, Keypair) {
+    let user = Keypair::new();
+    let mut record = UserStakeInfo {
+        bump: Pubkey::find_program_address(
+            &[USER_STAKE_INFO_SEED.as_bytes(), user.pubkey().as_ref()],
+            &luxor_swap::id(),
+        )
+        .1,
+        owner: user.pubkey(),
+        ..Default::default()
+    };
+    user_stake_info(&mut record);
+
+    let mut fixture = ProtocolFixture::new();
+    fixture
+        .accounts
+        .push((user.pubkey(), system_account(SIGNER_LAMPORTS)));
+    fixture.accounts.push((
+        user_stake_info_address(&user.pubkey()),
+        anchor_account(&record, UserStakeInfo::LEN),
+    ));
+    (fixture.start().await, user)
+}
+
+async fn close(
+    env: &mut TestEnv,
+    user: &Keypair,
+) -> Result<Vec<String>, solana_sdk::transaction::TransactionError> {
+    let ix = TestEnv::instruction(
+        luxor_swap::accounts::CloseUserStakeInfo {
+            owner: user.pubkey(),
+            global_config: pda(GLOBAL_CONFIG_SEED),
+            user_stake_info: user_stake_info_address(&user.pubkey()),
+            stake_info: pda(STAKE_INFO_SEED),
+        },
+        luxor_swap::instruction::CloseUserStakeInfo {},
+    );
+    env.send(&[ix], &[user]).await
+}
+
+#[tokio::test]
+async fn closes_an_empty_record() {
+    let (mut env, user) = start_with_user(|_| {}).await;
+    let logs = close(&mut env, &user).await.unwrap();
+
+    assert_eq!(events::<UserStakeInfoClosed>(&logs).len(), 1);
+    assert!(env
+        .account(&user_stake_info_address(&user.pubkey()))
+        .await
+        .is_none());
+}
+
+#[tokio::test]
+async fn blacklisted_record_cannot_be_closed() {
+    let (mut env, user) = start_with_user(|record| record.blacklisted_sol = 1_000_000_000).await;
+
+    assert_error(close(&mut env, &user).await, ErrorCode::UserBlacklisted);
+    assert!(env
+        .account(&user_stake_info_address(&user.pubkey()))
+        .await
+        .is_some());
+}
+
+#[tokio::test]
+async fn pending_blacklist_blocks_close() {
+    let (mut env, user) = start_with_user(|record| record.blacklist_pending_timestamp = 1).await;
+
+    assert_error(
+        close(&mut env, &user).await,
+        ErrorCode::BlacklistAlreadyProposed,
+    );
+}
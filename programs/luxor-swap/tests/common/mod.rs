@@ -0,0 +1,613 @@
+//! Shared `solana-program-test` harness for the instruction tests.
+//!
+//! The tests load the compiled program from `target/deploy/luxor_swap.so`, so
+//! run `anchor build` before `cargo test` (or point `SBF_OUT_DIR` at the build
+//! output).
+//!
+//! `ProtocolFixture` preseeds an already initialised protocol at genesis instead
+//! of calling `initialise_configs`, whose signer is the hardcoded program admin.
+//! Tests adjust the public fields before calling `start`.
+
+#![allow(dead_code)]
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::{
+    program_option::COption,
+    program_pack::Pack,
+    rent::Rent,
+    stake::{
+        self,
+        state::{Authorized, Delegation, Lockup, Meta, Stake, StakeFlags, StakeStateV2},
+    },
+    system_program, sysvar,
+    vote::{
+        self,
+        state::{VoteInit, VoteState, VoteStateVersions},
+    },
+};
+use anchor_lang::{
+    AccountDeserialize, AccountSerialize, AnchorDeserialize, Discriminator, Event, InstructionData,
+    ToAccountMetas,
+};
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use anchor_spl::token::spl_token;
+use base64::Engine;
+use luxor_swap::states::*;
+use solana_program_test::{ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    instruction::Instruction,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+/// Lamports given to every test signer.
+pub const SIGNER_LAMPORTS: u64 = 100_000_000_000;
+
+/// Program-derived address of `luxor_swap` for a single static seed.
+pub fn pda(seed: &str) -> Pubkey {
+    Pubkey::find_program_address(&[seed.as_bytes()], &luxor_swap::id()).0
+}
+
+/// `UserStakeInfo` address of `user`.
+pub fn user_stake_info_address(user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[USER_STAKE_INFO_SEED.as_bytes(), user.as_ref()],
+        &luxor_swap::id(),
+    )
+    .0
+}
+
+/// Rent-exempt program account holding `data` (discriminator included),
+/// zero-padded to `len` bytes.
+pub fn anchor_account<T: AccountSerialize>(data: &T, len: usize) -> Account {
+    let mut bytes = Vec::with_capacity(len);
+    data.try_serialize(&mut bytes).unwrap();
+    assert!(bytes.len() <= len, "serialized account exceeds its LEN");
+    bytes.resize(len, 0);
+    Account {
+        lamports: Rent::default().minimum_balance(len),
+        data: bytes,
+        owner: luxor_swap::id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// SPL Token mint with no mint authority.
+pub fn mint_account(decimals: u8, supply: u64) -> Account {
+    let mut data = vec![0; spl_token::state::Mint::LEN];
+    spl_token::state::Mint {
+        mint_authority: COption::None,
+        supply,
+        decimals,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    }
+    .pack_into_slice(&mut data);
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: spl_token::id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// SPL Token account holding `amount` of `mint`. WSOL accounts carry the
+/// amount as lamports on top of the rent reserve, like the token program does.
+pub fn token_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> Account {
+    let rent = Rent::default().minimum_balance(spl_token::state::Account::LEN);
+    let is_native = *mint == spl_token::native_mint::id();
+    let mut data = vec![0; spl_token::state::Account::LEN];
+    spl_token::state::Account {
+        mint: *mint,
+        owner: *owner,
+        amount,
+        delegate: COption::None,
+        state: spl_token::state::AccountState::Initialized,
+        is_native: if is_native {
+            COption::Some(rent)
+        } else {
+            COption::None
+        },
+        delegated_amount: 0,
+        close_authority: COption::None,
+    }
+    .pack_into_slice(&mut data);
+    Account {
+        lamports: if is_native { rent + amount } else { rent },
+        data,
+        owner: spl_token::id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// System account funded with `lamports`.
+pub fn system_account(lamports: u64) -> Account {
+    Account {
+        lamports,
+        data: vec![],
+        owner: system_program::id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Vote account whose node and authorities are all `node`.
+pub fn vote_account(node: &Pubkey, commission: u8) -> Account {
+    let vote_state = VoteState::new(
+        &VoteInit {
+            node_pubkey: *node,
+            authorized_voter: *node,
+            authorized_withdrawer: *node,
+            commission,
+        },
+        &Clock::default(),
+    );
+    let mut data = vec![0; VoteState::size_of()];
+    VoteState::serialize(&VoteStateVersions::new_current(vote_state), &mut data).unwrap();
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: vote::program::id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Stake account with `authority` as staker and withdrawer, holding the rent
+/// reserve plus `stake` lamports delegated to `voter`.
+///
+/// The delegation is a bootstrap one (`activation_epoch == u64::MAX`), so it is
+/// fully effective from genesis without a warmup.
+pub fn delegated_stake_account(authority: &Pubkey, voter: &Pubkey, stake: u64) -> Account {
+    let rent_exempt_reserve = Rent::default().minimum_balance(StakeStateV2::size_of());
+    let state = StakeStateV2::Stake(
+        Meta {
+            rent_exempt_reserve,
+            authorized: Authorized {
+                staker: *authority,
+                withdrawer: *authority,
+            },
+            lockup: Lockup::default(),
+        },
+        Stake {
+            delegation: Delegation::new(voter, stake, u64::MAX),
+            credits_observed: 0,
+        },
+        StakeFlags::empty(),
+    );
+    let mut data = vec![0; StakeStateV2::size_of()];
+    bincode::serialize_into(&mut data[..], &state).unwrap();
+    Account {
+        lamports: rent_exempt_reserve + stake,
+        data,
+        owner: stake::program::id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Undelegated stake account (`Initialized`) with `authority` as staker and
+/// withdrawer, holding `lamports` in total.
+pub fn initialized_stake_account(authority: &Pubkey, lamports: u64) -> Account {
+    let state = StakeStateV2::Initialized(Meta {
+        rent_exempt_reserve: Rent::default().minimum_balance(StakeStateV2::size_of()),
+        authorized: Authorized {
+            staker: *authority,
+            withdrawer: *authority,
+        },
+        lockup: Lockup::default(),
+    });
+    let mut data = vec![0; StakeStateV2::size_of()];
+    bincode::serialize_into(&mut data[..], &state).unwrap();
+    Account {
+        lamports,
+        data,
+        owner: stake::program::id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Raydium CPMM pool account holding `pool_state`.
+pub fn raydium_pool_account(pool_state: &PoolState) -> Account {
+    let mut data = PoolState::DISCRIMINATOR.to_vec();
+    data.extend_from_slice(bytemuck::bytes_of(pool_state));
+    Account {
+        lamports: 1_000_000_000,
+        data,
+        owner: luxor_swap::raydium_cpmm::id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Split stake account created by the request phase of buyback `buyback_count`.
+pub fn split_address(buyback_count: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            luxor_swap::STAKE_SPLIT_ACCOUNT_SEED.as_bytes(),
+            &buyback_count.to_le_bytes(),
+        ],
+        &luxor_swap::id(),
+    )
+    .0
+}
+
+/// Fixture addresses the buyback accounts need besides the protocol PDAs.
+pub struct BuybackKeys {
+    pub vote_account: Pubkey,
+    pub token_0_vault: Pubkey,
+    pub token_1_vault: Pubkey,
+}
+
+/// Accounts of `buyback`, `buyback_withdraw` and `buyback_settle` for the first
+/// buyback, signed by the fixture admin.
+pub fn buyback_accounts(env: &TestEnv, keys: &BuybackKeys) -> luxor_swap::accounts::Buyback {
+    let admin = env.admin.pubkey();
+    let native_mint = spl_token::native_mint::id();
+    let lxr_mint = luxor_swap::luxor_mint::id();
+    luxor_swap::accounts::Buyback {
+        owner: admin,
+        global_config: pda(GLOBAL_CONFIG_SEED),
+        stake_info: pda(STAKE_INFO_SEED),
+        vote_account: keys.vote_account,
+        stake_pda: pda(luxor_swap::STAKE_ACCOUNT_SEED),
+        stake_split_pda: split_address(0),
+        authority: pda(luxor_swap::AUTH_SEED),
+        luxor_reward_vault: pda(luxor_swap::LUXOR_REWARD_VAULT_SEED),
+        sol_treasury_vault: pda(luxor_swap::SOL_TREASURY_VAULT_SEED),
+        token_0_account: get_associated_token_address(&admin, &native_mint),
+        token_1_account: get_associated_token_address(&admin, &lxr_mint),
+        token_0_vault: keys.token_0_vault,
+        token_1_vault: keys.token_1_vault,
+        vault_0_mint: native_mint,
+        vault_1_mint: lxr_mint,
+        pool_state: luxor_swap::luxor_pool_state::id(),
+        raydium_authority: luxor_swap::vault_and_lp_mint_auth::id(),
+        amm_config: Pubkey::new_unique(),
+        fee_tiers: pda(FEE_TIERS_SEED),
+        observation_state: Pubkey::new_unique(),
+        raydium_cpmm_program: luxor_swap::raydium_cpmm::id(),
+        stake_program: stake::program::id(),
+        clock: sysvar::clock::id(),
+        stake_history: sysvar::stake_history::id(),
+        stake_config: stake::config::ID,
+        token_program: spl_token::id(),
+        associated_token_program: associated_token::ID,
+        system_program: system_program::ID,
+    }
+}
+
+/// Protocol state written at genesis. Every address in `global_config` is
+/// filled in by `new`; tests tweak parameters and balances before `start`.
+pub struct ProtocolFixture {
+    pub admin: Keypair,
+    pub validator: Keypair,
+    pub global_config: GlobalConfig,
+    pub stake_info: StakeInfo,
+    pub admin_stake_info: UserStakeInfo,
+    /// Delegated lamports on the protocol stake PDA (on top of its rent reserve).
+    pub stake_lamports: u64,
+    /// Whether the stake PDA is delegated at genesis; otherwise it is left
+    /// `Initialized` with the same balance.
+    pub stake_delegated: bool,
+    pub lxr_treasury_amount: u64,
+    pub lxr_reward_amount: u64,
+    pub sol_treasury_amount: u64,
+    /// Extra accounts written at genesis (pools, users, second validators...).
+    pub accounts: Vec<(Pubkey, Account)>,
+}
+
+impl ProtocolFixture {
+    pub fn new() -> Self {
+        let admin = Keypair::new();
+        let validator = Keypair::new();
+        let stake_rent_exempt = Rent::default().minimum_balance(StakeStateV2::size_of());
+
+        let global_config = GlobalConfig {
+            bump: Pubkey::find_program_address(&[GLOBAL_CONFIG_SEED.as_bytes()], &luxor_swap::id())
+                .1,
+            admin: admin.pubkey(),
+            lxr_treasury_vault: pda(luxor_swap::LUXOR_VAULT_SEED),
+            lxr_reward_vault: pda(luxor_swap::LUXOR_REWARD_VAULT_SEED),
+            sol_treasury_vault: pda(luxor_swap::SOL_TREASURY_VAULT_SEED),
+            stake_account: pda(luxor_swap::STAKE_ACCOUNT_SEED),
+            vote_account: Pubkey::new_unique(),
+            stake_info: pda(STAKE_INFO_SEED),
+            luxor_decimals: luxor_swap::LUXOR_DECIMALS,
+            stake_rent_exempt,
+            buyback_out_tolerance_rate: DEFAULT_BUYBACK_OUT_TOLERANCE_RATE,
+            buyback_fee_enabled: true,
+            reward_mint: luxor_swap::luxor_mint::id(),
+            purchase_enabled: true,
+            redeem_enabled: true,
+            ..Default::default()
+        };
+        let stake_info = StakeInfo {
+            bump: Pubkey::find_program_address(&[STAKE_INFO_SEED.as_bytes()], &luxor_swap::id()).1,
+            last_tracked_sol_balance: stake_rent_exempt,
+            ..Default::default()
+        };
+        let admin_stake_info = UserStakeInfo {
+            bump: Pubkey::find_program_address(
+                &[ADMIN_STAKE_INFO_SEED.as_bytes()],
+                &luxor_swap::id(),
+            )
+            .1,
+            ..Default::default()
+        };
+
+        Self {
+            admin,
+            validator,
+            global_config,
+            stake_info,
+            admin_stake_info,
+            stake_lamports: 0,
+            stake_delegated: true,
+            lxr_treasury_amount: 0,
+            lxr_reward_amount: 0,
+            sol_treasury_amount: 0,
+            accounts: vec![],
+        }
+    }
+
+    /// Delegates `lamports` from the protocol stake PDA at genesis and records
+    /// the PDA balance the way `purchase` would have.
+    pub fn with_stake(mut self, lamports: u64) -> Self {
+        self.stake_lamports = lamports;
+        self.stake_info.last_tracked_sol_balance = self.global_config.stake_rent_exempt + lamports;
+        self
+    }
+
+    /// Like `with_stake`, but the PDA holds the lamports undelegated, as after a
+    /// completed deactivation; instructions that re-delegate it can then run.
+    pub fn with_inactive_stake(mut self, lamports: u64) -> Self {
+        self = self.with_stake(lamports);
+        self.stake_delegated = false;
+        self
+    }
+
+    /// Writes the fixture into a fresh `ProgramTest` and starts the bank.
+    pub async fn start(self) -> TestEnv {
+        let mut program_test = ProgramTest::new("luxor_swap", luxor_swap::id(), None);
+        program_test.prefer_bpf(true);
+
+        let authority = pda(luxor_swap::AUTH_SEED);
+        let lxr_mint = luxor_swap::luxor_mint::id();
+        let native_mint = spl_token::native_mint::id();
+        let config = &self.global_config;
+
+        program_test.add_account(
+            pda(GLOBAL_CONFIG_SEED),
+            anchor_account(config, GlobalConfig::LEN),
+        );
+        program_test.add_account(
+            config.stake_info,
+            anchor_account(&self.stake_info, StakeInfo::LEN),
+        );
+        program_test.add_account(
+            pda(ADMIN_STAKE_INFO_SEED),
+            anchor_account(&self.admin_stake_info, UserStakeInfo::LEN),
+        );
+        program_test.add_account(
+            lxr_mint,
+            mint_account(luxor_swap::LUXOR_DECIMALS, 1_000_000_000_000_000_000),
+        );
+        program_test.add_account(native_mint, mint_account(9, 0));
+        program_test.add_account(
+            config.lxr_treasury_vault,
+            token_account(&lxr_mint, &authority, self.lxr_treasury_amount),
+        );
+        // The reward vault holds `reward_mint`, which may differ from LXR.
+        if config.reward_mint != lxr_mint {
+            program_test.add_account(config.reward_mint, mint_account(6, 1_000_000_000_000_000));
+        }
+        program_test.add_account(
+            config.lxr_reward_vault,
+            token_account(&config.reward_mint, &authority, self.lxr_reward_amount),
+        );
+        program_test.add_account(
+            config.sol_treasury_vault,
+            token_account(&native_mint, &authority, self.sol_treasury_amount),
+        );
+        program_test.add_account(
+            config.vote_account,
+            vote_account(&self.validator.pubkey(), 0),
+        );
+        let stake_account = if self.stake_delegated {
+            delegated_stake_account(&authority, &config.vote_account, self.stake_lamports)
+        } else {
+            let rent = Rent::default().minimum_balance(StakeStateV2::size_of());
+            initialized_stake_account(&authority, rent + self.stake_lamports)
+        };
+        program_test.add_account(config.stake_account, stake_account);
+
+        // The admin's ATAs exist up front so `init_if_needed` never charges the
+        // admin rent and balance assertions stay exact.
+        let admin = self.admin.pubkey();
+        program_test.add_account(admin, system_account(SIGNER_LAMPORTS));
+        program_test.add_account(self.validator.pubkey(), system_account(SIGNER_LAMPORTS));
+        program_test.add_account(
+            get_associated_token_address(&admin, &lxr_mint),
+            token_account(&lxr_mint, &admin, 0),
+        );
+        program_test.add_account(
+            get_associated_token_address(&admin, &native_mint),
+            token_account(&native_mint, &admin, 0),
+        );
+
+        for (address, account) in self.accounts {
+            program_test.add_account(address, account);
+        }
+
+        let context = program_test.start_with_context().await;
+        TestEnv {
+            context,
+            admin: self.admin,
+            validator: self.validator,
+        }
+    }
+}
+
+/// A started bank plus the fixture's signers.
+pub struct TestEnv {
+    pub context: ProgramTestContext,
+    pub admin: Keypair,
+    pub validator: Keypair,
+}
+
+impl TestEnv {
+    /// Sends `instructions` in one transaction paid by the context payer and
+    /// signed by `signers`; returns the program logs.
+    pub async fn send(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<Vec<String>, TransactionError> {
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let mut all_signers: Vec<&Keypair> = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        let outcome = self
+            .context
+            .banks_client
+            .process_transaction_with_metadata(tx)
+            .await
+            .unwrap();
+        let logs = outcome.metadata.map(|m| m.log_messages).unwrap_or_default();
+        outcome.result.map(|_| logs)
+    }
+
+    /// Builds an Anchor instruction for `luxor_swap`.
+    pub fn instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+        Instruction {
+            program_id: luxor_swap::id(),
+            accounts: accounts.to_account_metas(None),
+            data: data.data(),
+        }
+    }
+
+    pub async fn lamports(&mut self, address: &Pubkey) -> u64 {
+        self.context
+            .banks_client
+            .get_balance(*address)
+            .await
+            .unwrap()
+    }
+
+    pub async fn account(&mut self, address: &Pubkey) -> Option<Account> {
+        self.context
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+    }
+
+    /// Deserializes the Anchor account at `address`.
+    pub async fn anchor_state<T: AccountDeserialize>(&mut self, address: &Pubkey) -> T {
+        let account = self.account(address).await.expect("account missing");
+        T::try_deserialize(&mut &account.data[..]).unwrap()
+    }
+
+    pub async fn token_amount(&mut self, address: &Pubkey) -> u64 {
+        let account = self.account(address).await.expect("token account missing");
+        spl_token::state::Account::unpack(&account.data)
+            .unwrap()
+            .amount
+    }
+
+    pub async fn stake_state(&mut self, address: &Pubkey) -> StakeStateV2 {
+        let account = self.account(address).await.expect("stake account missing");
+        bincode::deserialize(&account.data).unwrap()
+    }
+
+    pub async fn current_epoch(&mut self) -> u64 {
+        self.context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .epoch
+    }
+
+    /// Warps to the first slot of `epoch`, running the epoch boundaries
+    /// (stake history, activation/cooldown) in between.
+    pub async fn warp_to_epoch(&mut self, epoch: u64) {
+        let slot = self
+            .context
+            .genesis_config()
+            .epoch_schedule
+            .get_first_slot_in_epoch(epoch);
+        self.context.warp_to_slot(slot).unwrap();
+    }
+
+    /// Gives `vote_account` vote credits in the current epoch and warps into the
+    /// next one, so the runtime pays that epoch's inflation rewards to the stake
+    /// delegated to it.
+    pub async fn land_epoch_rewards(&mut self, vote_account: &Pubkey) {
+        let epoch = self.current_epoch().await;
+        self.context
+            .increment_vote_account_credits(vote_account, 100);
+        self.warp_to_epoch(epoch + 1).await;
+        self.context
+            .warp_forward_force_reward_interval_end()
+            .unwrap();
+    }
+
+    /// Moves the bank clock `secs` seconds forward (timelocks, cooldowns).
+    pub async fn advance_clock(&mut self, secs: i64) {
+        let mut clock = self
+            .context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap();
+        clock.unix_timestamp += secs;
+        self.context.set_sysvar(&clock);
+    }
+}
+
+/// Anchor events of type `T` emitted in `logs` (`Program data:` entries).
+pub fn events<T: Event>(logs: &[String]) -> Vec<T> {
+    logs.iter()
+        .filter_map(|log| log.strip_prefix("Program data: "))
+        .filter_map(|data| base64::engine::general_purpose::STANDARD.decode(data).ok())
+        .filter(|data| data.starts_with(T::DISCRIMINATOR))
+        .map(|data| T::try_from_slice(&data[T::DISCRIMINATOR.len()..]).unwrap())
+        .collect()
+}
+
+/// Anchor error code carried by a failed transaction, if any.
+pub fn error_code(error: &TransactionError) -> Option<u32> {
+    match error {
+        TransactionError::InstructionError(
+            _,
+            solana_sdk::instruction::InstructionError::Custom(code),
+        ) => Some(*code),
+        _ => None,
+    }
+}
+
+/// Asserts that `result` failed with the program error `expected`.
+pub fn assert_error<T: std::fmt::Debug>(
+    result: Result<T, TransactionError>,
+    expected: luxor_swap::error::ErrorCode,
+) {
+    let error = result.expect_err("transaction unexpectedly succeeded");
+    assert_eq!(
+        error_code(&error),
+        Some(anchor_lang::error::ERROR_CODE_OFFSET + expected as u32),
+        "unexpected error {error:?}"
+    );
+}
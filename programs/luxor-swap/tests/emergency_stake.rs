@@ -0,0 +1,263 @@
+mod common;
+
+use anchor_lang::solana_program::{stake, sysvar};
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use anchor_spl::token::spl_token;
+use common::*;
+use luxor_swap::error::ErrorCode;
+use luxor_swap::instructions::EmergencyOperation;
+use luxor_swap::states::*;
+use solana_sdk::{
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+fn emergency_withdraw_accounts(env: &TestEnv) -> luxor_swap::accounts::EmergencyWithdraw {
+    let admin = env.admin.pubkey();
+    luxor_swap::accounts::EmergencyWithdraw {
+        owner: admin,
+        global_config: pda(GLOBAL_CONFIG_SEED),
+        authority: pda(luxor_swap::AUTH_SEED),
+        luxor_vault_any: pda(luxor_swap::LUXOR_VAULT_SEED),
+        luxor_reward_vault: pda(luxor_swap::LUXOR_REWARD_VAULT_SEED),
+        sol_treasury_vault: pda(luxor_swap::SOL_TREASURY_VAULT_SEED),
+        admin_stake_info: pda(ADMIN_STAKE_INFO_SEED),
+        stake_info: pda(STAKE_INFO_SEED),
+        luxor_mint: luxor_swap::luxor_mint::id(),
+        native_mint: spl_token::native_mint::id(),
+        owner_lxr_token: get_associated_token_address(&admin, &luxor_swap::luxor_mint::id()),
+        owner_wsol_token: get_associated_token_address(&admin, &spl_token::native_mint::id()),
+        stake_pda: pda(luxor_swap::STAKE_ACCOUNT_SEED),
+        pending_emergency: pda(PENDING_EMERGENCY_SEED),
+        token_program: spl_token::id(),
+        clock: sysvar::clock::id(),
+        stake_program: stake::program::id(),
+        stake_history: sysvar::stake_history::id(),
+        associated_token_program: associated_token::ID,
+        system_program: anchor_lang::system_program::ID,
+    }
+}
+
+fn emergency_withdraw_ix(env: &TestEnv, operation: EmergencyOperation, value: u64) -> Instruction {
+    TestEnv::instruction(
+        emergency_withdraw_accounts(env),
+        luxor_swap::instruction::EmergencyWithdraw {
+            param: operation as u8,
+            value,
+        },
+    )
+}
+
+/// `DeactivateStake` followed by a partial `WithdrawStake` once the cooldown has
+/// passed: the lamports leave the stake PDA and land on the admin.
+#[tokio::test]
+async fn deactivate_then_partial_withdraw_across_epochs() {
+    let delegated = 10 * LAMPORTS_PER_SOL;
+    let withdrawn = 4 * LAMPORTS_PER_SOL;
+    let mut env = ProtocolFixture::new().with_stake(delegated).start().await;
+    let admin = env.admin.insecure_clone();
+    let stake_pda = pda(luxor_swap::STAKE_ACCOUNT_SEED);
+
+    // --- 3: deactivate in the current epoch ---
+    let deactivation_epoch = env.current_epoch().await;
+    let stake_balance = env.lamports(&stake_pda).await;
+    let ix = emergency_withdraw_ix(&env, EmergencyOperation::DeactivateStake, 0);
+    let logs = env.send(&[ix], &[&admin]).await.unwrap();
+
+    let action = &events::<EmergencyAction>(&logs)[0];
+    assert_eq!(action.param, EmergencyOperation::DeactivateStake as u8);
+    assert_eq!(action.amount, stake_balance);
+    assert_eq!(action.source, stake_pda);
+    let delegation = env.stake_state(&stake_pda).await.delegation().unwrap();
+    assert_eq!(delegation.deactivation_epoch, deactivation_epoch);
+
+    // Still cooling down: the stake program refuses to release delegated lamports.
+    let ix = emergency_withdraw_ix(&env, EmergencyOperation::WithdrawStake, withdrawn);
+    assert!(env.send(&[ix], &[&admin]).await.is_err());
+
+    // --- 4: withdraw part of the balance once the stake is inactive ---
+    env.warp_to_epoch(deactivation_epoch + 2).await;
+    let admin_before = env.lamports(&admin.pubkey()).await;
+    let stake_before = env.lamports(&stake_pda).await;
+
+    let ix = emergency_withdraw_ix(&env, EmergencyOperation::WithdrawStake, withdrawn);
+    let logs = env.send(&[ix], &[&admin]).await.unwrap();
+
+    let action = &events::<EmergencyAction>(&logs)[0];
+    assert_eq!(action.param, EmergencyOperation::WithdrawStake as u8);
+    assert_eq!(action.amount, withdrawn);
+    // The context payer covers the fee, so the deltas are exact.
+    assert_eq!(
+        env.lamports(&admin.pubkey()).await,
+        admin_before + withdrawn
+    );
+    assert_eq!(env.lamports(&stake_pda).await, stake_before - withdrawn);
+}
+
+/// With `emergency_delay > 0`, stake operations need a matured queue entry.
+#[tokio::test]
+async fn deactivate_requires_queue_while_timelocked() {
+    let mut fixture = ProtocolFixture::new().with_stake(10 * LAMPORTS_PER_SOL);
+    fixture.global_config.emergency_delay = 3_600;
+    let mut env = fixture.start().await;
+    let admin = env.admin.insecure_clone();
+
+    let ix = emergency_withdraw_ix(&env, EmergencyOperation::DeactivateStake, 0);
+    assert_error(
+        env.send(&[ix], &[&admin]).await,
+        ErrorCode::EmergencyNotQueued,
+    );
+}
+
+/// Full wind-down: deactivate, withdraw everything, record the closure. Afterwards
+/// neither `purchase` nor `buyback` can touch the stake PDA again.
+#[tokio::test]
+async fn finalized_closure_rejects_purchase_and_buyback() {
+    let mut fixture = ProtocolFixture::new().with_stake(10 * LAMPORTS_PER_SOL);
+    let raydium_authority = luxor_swap::vault_and_lp_mint_auth::id();
+    let wsol = spl_token::native_mint::id();
+    let lxr_mint = luxor_swap::luxor_mint::id();
+    let pool_state = PoolState {
+        token_0_vault: Pubkey::new_unique(),
+        token_1_vault: Pubkey::new_unique(),
+        token_0_mint: wsol,
+        token_1_mint: lxr_mint,
+        ..Default::default()
+    };
+    let user = Keypair::new();
+    fixture.accounts.extend([
+        (
+            luxor_swap::luxor_pool_state::id(),
+            raydium_pool_account(&pool_state),
+        ),
+        (
+            pool_state.token_0_vault,
+            token_account(&wsol, &raydium_authority, 1_000 * LAMPORTS_PER_SOL),
+        ),
+        (
+            pool_state.token_1_vault,
+            token_account(&lxr_mint, &raydium_authority, 1_000_000_000_000_000),
+        ),
+        (user.pubkey(), system_account(SIGNER_LAMPORTS)),
+    ]);
+    let keys = BuybackKeys {
+        vote_account: fixture.global_config.vote_account,
+        token_0_vault: pool_state.token_0_vault,
+        token_1_vault: pool_state.token_1_vault,
+    };
+    let mut env = fixture.start().await;
+    let admin = env.admin.insecure_clone();
+    let stake_pda = pda(luxor_swap::STAKE_ACCOUNT_SEED);
+
+    // --- Wind down the stake PDA ---
+    let deactivation_epoch = env.current_epoch().await;
+    let ix = emergency_withdraw_ix(&env, EmergencyOperation::DeactivateStake, 0);
+    env.send(&[ix], &[&admin]).await.unwrap();
+    env.warp_to_epoch(deactivation_epoch + 2).await;
+    let balance = env.lamports(&stake_pda).await;
+    let ix = emergency_withdraw_ix(&env, EmergencyOperation::WithdrawStake, balance);
+    env.send(&[ix], &[&admin]).await.unwrap();
+    assert!(env.account(&stake_pda).await.is_none());
+
+    let ix = TestEnv::instruction(
+        luxor_swap::accounts::FinalizeStakeClosure {
+            owner: admin.pubkey(),
+            global_config: pda(GLOBAL_CONFIG_SEED),
+            stake_info: pda(STAKE_INFO_SEED),
+            stake_pda,
+        },
+        luxor_swap::instruction::FinalizeStakeClosure {},
+    );
+    let logs = env.send(&[ix], &[&admin]).await.unwrap();
+    assert_eq!(events::<StakeClosureFinalized>(&logs).len(), 1);
+    let stake_info: StakeInfo = env.anchor_state(&pda(STAKE_INFO_SEED)).await;
+    assert!(stake_info.stake_closed);
+
+    // --- Nothing stakes into or splits from it any more ---
+    let ix = TestEnv::instruction(
+        luxor_swap::accounts::Purchase {
+            owner: user.pubkey(),
+            global_config: pda(GLOBAL_CONFIG_SEED),
+            luxor_vault: pda(luxor_swap::LUXOR_VAULT_SEED),
+            user_stake_info: user_stake_info_address(&user.pubkey()),
+            stake_info: pda(STAKE_INFO_SEED),
+            authority: pda(luxor_swap::AUTH_SEED),
+            luxor_mint: lxr_mint,
+            owner_lxr_token: get_associated_token_address(&user.pubkey(), &lxr_mint),
+            stake_pda,
+            vote_account: keys.vote_account,
+            stake_program: stake::program::id(),
+            clock: sysvar::clock::id(),
+            stake_history: sysvar::stake_history::id(),
+            stake_config: stake::config::ID,
+            pool_state: luxor_swap::luxor_pool_state::id(),
+            token_0_vault: pool_state.token_0_vault,
+            token_1_vault: pool_state.token_1_vault,
+            token_program: spl_token::id(),
+            associated_token_program: associated_token::ID,
+            system_program: anchor_lang::system_program::ID,
+        },
+        luxor_swap::instruction::Purchase {
+            lxr_to_purchase: 1_000_000_000,
+            max_sol_amount: u64::MAX,
+            deadline: i64::MAX,
+        },
+    );
+    assert_error(env.send(&[ix], &[&user]).await, ErrorCode::StakeClosed);
+
+    let ix = TestEnv::instruction(
+        buyback_accounts(&env, &keys),
+        luxor_swap::instruction::Buyback { min_lxr_out: 0 },
+    );
+    assert_error(env.send(&[ix], &[&admin]).await, ErrorCode::StakeClosed);
+}
+
+/// `ClaimAdminRewards` moves the admin record's LXR rewards from the reward vault
+/// into the treasury, and refuses a destination aliasing the reward vault.
+#[tokio::test]
+async fn claim_admin_rewards_moves_lxr_to_treasury() {
+    let admin_staked = 4 * LAMPORTS_PER_SOL;
+    let rewards = 80_000_000_000;
+    let mut fixture = ProtocolFixture::new().with_stake(admin_staked);
+    fixture.stake_info.total_staked_sol = admin_staked;
+    fixture.stake_info.reward_per_token_lxr_stored =
+        u128::from(rewards) * luxor_swap::PRECISION / u128::from(admin_staked);
+    fixture.admin_stake_info.total_staked_sol = admin_staked;
+    fixture.lxr_reward_amount = rewards;
+    let mut env = fixture.start().await;
+    let admin = env.admin.insecure_clone();
+    let treasury = pda(luxor_swap::LUXOR_VAULT_SEED);
+    let reward_vault = pda(luxor_swap::LUXOR_REWARD_VAULT_SEED);
+    let treasury_before = env.token_amount(&treasury).await;
+
+    // Source and destination would be the same vault.
+    let ix = TestEnv::instruction(
+        luxor_swap::accounts::EmergencyWithdraw {
+            luxor_vault_any: reward_vault,
+            ..emergency_withdraw_accounts(&env)
+        },
+        luxor_swap::instruction::EmergencyWithdraw {
+            param: EmergencyOperation::ClaimAdminRewards as u8,
+            value: 0,
+        },
+    );
+    assert_error(env.send(&[ix], &[&admin]).await, ErrorCode::InvalidVault);
+
+    let ix = emergency_withdraw_ix(&env, EmergencyOperation::ClaimAdminRewards, 0);
+    let logs = env.send(&[ix], &[&admin]).await.unwrap();
+
+    let action = &events::<EmergencyAction>(&logs)[0];
+    assert_eq!(action.param, EmergencyOperation::ClaimAdminRewards as u8);
+    assert_eq!(action.amount, rewards);
+    assert_eq!(action.source, reward_vault);
+    assert_eq!(env.token_amount(&reward_vault).await, 0);
+    assert_eq!(env.token_amount(&treasury).await, treasury_before + rewards);
+    let admin_record: UserStakeInfo = env.anchor_state(&pda(ADMIN_STAKE_INFO_SEED)).await;
+    assert_eq!(admin_record.lxr_rewards_pending, 0);
+    assert_eq!(
+        admin_record.lxr_reward_per_token_completed,
+        u128::from(rewards) * luxor_swap::PRECISION / u128::from(admin_staked)
+    );
+}
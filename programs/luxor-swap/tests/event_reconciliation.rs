@@ -0,0 +1,445 @@
+//! Event payloads checked against the state changes they report, so indexers can
+//! rely on the events alone.
+
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::{stake, sysvar};
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use anchor_spl::token::spl_token;
+use common::*;
+use luxor_swap::states::*;
+use solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    rent::Rent,
+    signature::{Keypair, Signer},
+};
+
+const PRINCIPAL: u64 = 10 * LAMPORTS_PER_SOL;
+const LXR: u64 = 1_000_000_000;
+
+fn lxr_ata(owner: &Pubkey) -> Pubkey {
+    get_associated_token_address(owner, &luxor_swap::luxor_mint::id())
+}
+
+/// `UserStakeInfo` of `user` written at genesis, after `record` tweaked it.
+fn push_user(
+    fixture: &mut ProtocolFixture,
+    user: &Keypair,
+    record: impl FnOnce(&mut UserStakeInfo),
+) {
+    let (address, bump) = Pubkey::find_program_address(
+        &[USER_STAKE_INFO_SEED.as_bytes(), user.pubkey().as_ref()],
+        &luxor_swap::id(),
+    );
+    let mut user_stake_info = UserStakeInfo {
+        bump,
+        owner: user.pubkey(),
+        ..Default::default()
+    };
+    record(&mut user_stake_info);
+    fixture.accounts.extend([
+        (user.pubkey(), system_account(SIGNER_LAMPORTS)),
+        (
+            address,
+            anchor_account(&user_stake_info, UserStakeInfo::LEN),
+        ),
+    ]);
+}
+
+/// `LxrPurchased`: the SOL lands on the stake PDA and in both stake tallies, the
+/// LXR leaves the treasury for the buyer's ATA.
+#[tokio::test]
+async fn purchase_event_matches_stake_and_token_deltas() {
+    let treasury = 1_000_000 * LXR;
+    let purchased = 1_000 * LXR;
+    let max_total_staked_sol = 1_000 * LAMPORTS_PER_SOL;
+
+    let mut fixture = ProtocolFixture::new().with_stake(PRINCIPAL);
+    fixture.stake_info.total_staked_sol = PRINCIPAL;
+    fixture.lxr_treasury_amount = treasury;
+    // A full treasury prices the requested amount as is.
+    fixture.global_config.initial_lxr_allocation_vault = treasury;
+    fixture.global_config.max_total_staked_sol = max_total_staked_sol;
+
+    let raydium_authority = luxor_swap::vault_and_lp_mint_auth::id();
+    let wsol = spl_token::native_mint::id();
+    let lxr_mint = luxor_swap::luxor_mint::id();
+    let pool_state = PoolState {
+        token_0_vault: Pubkey::new_unique(),
+        token_1_vault: Pubkey::new_unique(),
+        token_0_mint: wsol,
+        token_1_mint: lxr_mint,
+        ..Default::default()
+    };
+    let user = Keypair::new();
+    fixture.accounts.extend([
+        (
+            luxor_swap::luxor_pool_state::id(),
+            raydium_pool_account(&pool_state),
+        ),
+        (
+            pool_state.token_0_vault,
+            token_account(&wsol, &raydium_authority, 1_000 * LAMPORTS_PER_SOL),
+        ),
+        (
+            pool_state.token_1_vault,
+            token_account(&lxr_mint, &raydium_authority, 1_000_000 * LXR),
+        ),
+        (user.pubkey(), system_account(SIGNER_LAMPORTS)),
+    ]);
+    let vote_account = fixture.global_config.vote_account;
+    let mut env = fixture.start().await;
+
+    let stake_pda = pda(luxor_swap::STAKE_ACCOUNT_SEED);
+    let lxr_vault = pda(luxor_swap::LUXOR_VAULT_SEED);
+    let stake_before = env.lamports(&stake_pda).await;
+    let user_before = env.lamports(&user.pubkey()).await;
+
+    let ix = TestEnv::instruction(
+        luxor_swap::accounts::Purchase {
+            owner: user.pubkey(),
+            global_config: pda(GLOBAL_CONFIG_SEED),
+            luxor_vault: lxr_vault,
+            user_stake_info: user_stake_info_address(&user.pubkey()),
+            stake_info: pda(STAKE_INFO_SEED),
+            authority: pda(luxor_swap::AUTH_SEED),
+            luxor_mint: lxr_mint,
+            owner_lxr_token: lxr_ata(&user.pubkey()),
+            stake_pda,
+            vote_account,
+            stake_program: stake::program::id(),
+            clock: sysvar::clock::id(),
+            stake_history: sysvar::stake_history::id(),
+            stake_config: stake::config::ID,
+            pool_state: luxor_swap::luxor_pool_state::id(),
+            token_0_vault: pool_state.token_0_vault,
+            token_1_vault: pool_state.token_1_vault,
+            token_program: spl_token::id(),
+            associated_token_program: associated_token::ID,
+            system_program: anchor_lang::system_program::ID,
+        },
+        luxor_swap::instruction::Purchase {
+            lxr_to_purchase: purchased,
+            max_sol_amount: u64::MAX,
+            deadline: i64::MAX,
+        },
+    );
+    let logs = env.send(&[ix], &[&user]).await.unwrap();
+
+    let purchase = &events::<LxrPurchased>(&logs)[0];
+    assert_eq!(purchase.purchaser, user.pubkey());
+    assert!(purchase.sol_amount > 0);
+    assert_eq!(purchase.lxr_amount, purchased);
+
+    // SOL side.
+    assert_eq!(
+        env.lamports(&stake_pda).await,
+        stake_before + purchase.sol_amount
+    );
+    let stake_info: StakeInfo = env.anchor_state(&pda(STAKE_INFO_SEED)).await;
+    assert_eq!(stake_info.total_staked_sol, PRINCIPAL + purchase.sol_amount);
+    assert_eq!(
+        stake_info.last_tracked_sol_balance,
+        stake_before + purchase.sol_amount
+    );
+    assert_eq!(
+        purchase.remaining_stake_capacity,
+        max_total_staked_sol - stake_info.total_staked_sol
+    );
+    let user_stake_info: UserStakeInfo = env
+        .anchor_state(&user_stake_info_address(&user.pubkey()))
+        .await;
+    assert_eq!(user_stake_info.total_staked_sol, purchase.sol_amount);
+    // The buyer also paid rent for their stake record and LXR ATA.
+    let rent = Rent::default();
+    let account_rent = rent.minimum_balance(UserStakeInfo::LEN)
+        + rent.minimum_balance(spl_token::state::Account::LEN);
+    assert_eq!(
+        env.lamports(&user.pubkey()).await,
+        user_before - purchase.sol_amount - account_rent
+    );
+
+    // LXR side.
+    assert_eq!(
+        env.token_amount(&lxr_vault).await,
+        treasury - purchase.lxr_amount
+    );
+    assert_eq!(
+        env.token_amount(&lxr_ata(&user.pubkey())).await,
+        purchase.lxr_amount
+    );
+    assert_eq!(user_stake_info.base_lxr_holdings, purchase.lxr_amount);
+}
+
+/// `BuybackRequested` and `BuybackExecuted` across both phases. The rewards are all
+/// reserved for SOL claims, so no Raydium swap runs and the bought side is zero;
+/// the reserved side carries the amounts.
+#[tokio::test]
+async fn buyback_events_match_split_and_treasury_deltas() {
+    let rewards = 2 * LAMPORTS_PER_SOL;
+    // The rewards sit on top of the delegated principal but are not tracked yet.
+    let mut fixture = ProtocolFixture::new().with_stake(PRINCIPAL + rewards);
+    fixture.stake_info.last_tracked_sol_balance -= rewards;
+    fixture.stake_info.total_staked_sol = PRINCIPAL;
+    fixture.global_config.sol_reward_share_bps = BPS_DENOMINATOR;
+    let split_rent = fixture.global_config.stake_rent_exempt;
+
+    let raydium_authority = luxor_swap::vault_and_lp_mint_auth::id();
+    let keys = BuybackKeys {
+        vote_account: fixture.global_config.vote_account,
+        token_0_vault: Pubkey::new_unique(),
+        token_1_vault: Pubkey::new_unique(),
+    };
+    fixture.accounts.extend([
+        (
+            keys.token_0_vault,
+            token_account(&spl_token::native_mint::id(), &raydium_authority, 0),
+        ),
+        (
+            keys.token_1_vault,
+            token_account(&luxor_swap::luxor_mint::id(), &raydium_authority, 0),
+        ),
+    ]);
+    let mut env = fixture.start().await;
+    let admin = env.admin.insecure_clone();
+    let stake_pda = pda(luxor_swap::STAKE_ACCOUNT_SEED);
+    let reward_vault = pda(luxor_swap::LUXOR_REWARD_VAULT_SEED);
+    let sol_treasury_vault = pda(luxor_swap::SOL_TREASURY_VAULT_SEED);
+
+    // --- request: split the rewards off the stake PDA ---
+    let request_epoch = env.current_epoch().await;
+    let stake_before = env.lamports(&stake_pda).await;
+    let ix = TestEnv::instruction(
+        buyback_accounts(&env, &keys),
+        luxor_swap::instruction::Buyback { min_lxr_out: 0 },
+    );
+    let logs = env.send(&[ix], &[&admin]).await.unwrap();
+
+    let requested = &events::<BuybackRequested>(&logs)[0];
+    assert_eq!(requested.buyback_count, 0);
+    assert_eq!(requested.split_pda, split_address(0));
+    assert_eq!(requested.sol_to_buyback, 0);
+    assert_eq!(requested.sol_to_reserve, rewards);
+    let split = requested.sol_to_buyback + requested.sol_to_reserve;
+    assert_eq!(env.lamports(&stake_pda).await, stake_before - split);
+    assert_eq!(env.lamports(&requested.split_pda).await, split_rent + split);
+    let stake_info: StakeInfo = env.anchor_state(&pda(STAKE_INFO_SEED)).await;
+    assert_eq!(stake_info.last_tracked_sol_balance, stake_before - split);
+    assert_eq!(stake_info.buyback_reserve_split, requested.sol_to_reserve);
+    let used_before = stake_info.total_sol_used_for_buyback;
+    let funded_before = stake_info.total_sol_reserve_funded;
+
+    // --- execute once the split stake has cooled down ---
+    env.warp_to_epoch(request_epoch + 2).await;
+    let treasury_before = env.token_amount(&sol_treasury_vault).await;
+    let reward_vault_before = env.token_amount(&reward_vault).await;
+    let admin_before = env.lamports(&admin.pubkey()).await;
+    let ix = TestEnv::instruction(
+        buyback_accounts(&env, &keys),
+        luxor_swap::instruction::Buyback { min_lxr_out: 0 },
+    );
+    let logs = env.send(&[ix], &[&admin]).await.unwrap();
+
+    let executed = &events::<BuybackExecuted>(&logs)[0];
+    assert_eq!(executed.buyback_count, requested.buyback_count);
+    assert_eq!(executed.sol_amount, requested.sol_to_buyback);
+    assert_eq!(executed.sol_reserved, requested.sol_to_reserve);
+    assert_eq!(executed.fee_to_treasury, 0);
+    assert_eq!(
+        env.token_amount(&sol_treasury_vault).await,
+        treasury_before + executed.sol_reserved + executed.fee_to_treasury
+    );
+    assert_eq!(
+        env.token_amount(&reward_vault).await,
+        reward_vault_before + executed.lxr_bought
+    );
+    assert_eq!(
+        env.lamports(&admin.pubkey()).await,
+        admin_before + executed.rent_refunded
+    );
+    let stake_info: StakeInfo = env.anchor_state(&pda(STAKE_INFO_SEED)).await;
+    assert_eq!(
+        stake_info.total_sol_used_for_buyback,
+        used_before + executed.sol_amount
+    );
+    assert_eq!(
+        stake_info.total_sol_reserve_funded,
+        funded_before + executed.sol_reserved
+    );
+    assert!(env.account(&requested.split_pda).await.is_none());
+}
+
+/// `RewardsCollected` on a partial redeem by a user holding half their base LXR:
+/// half the entitlement is forfeited to the treasury, the requested amount is paid
+/// and the rest stays pending.
+#[tokio::test]
+async fn redeem_event_matches_vault_and_record_deltas() {
+    let staked = 5 * LAMPORTS_PER_SOL;
+    let base_holdings = 1_000 * LXR;
+    let entitlement = 1_000 * LXR;
+    let requested = 100 * LXR;
+
+    let mut fixture = ProtocolFixture::new().with_stake(PRINCIPAL);
+    fixture.stake_info.total_staked_sol = PRINCIPAL;
+    fixture.stake_info.reward_per_token_lxr_stored =
+        u128::from(entitlement) * luxor_swap::PRECISION / u128::from(staked);
+    fixture.lxr_reward_amount = entitlement;
+    let user = Keypair::new();
+    push_user(&mut fixture, &user, |record| {
+        record.total_staked_sol = staked;
+        record.base_lxr_holdings = base_holdings;
+    });
+    let lxr_mint = luxor_swap::luxor_mint::id();
+    fixture.accounts.push((
+        lxr_ata(&user.pubkey()),
+        token_account(&lxr_mint, &user.pubkey(), base_holdings / 2),
+    ));
+    let mut env = fixture.start().await;
+
+    let lxr_vault = pda(luxor_swap::LUXOR_VAULT_SEED);
+    let reward_vault = pda(luxor_swap::LUXOR_REWARD_VAULT_SEED);
+    let treasury_before = env.token_amount(&lxr_vault).await;
+    let reward_vault_before = env.token_amount(&reward_vault).await;
+    let holdings_before = env.token_amount(&lxr_ata(&user.pubkey())).await;
+
+    let ix = TestEnv::instruction(
+        luxor_swap::accounts::Redeem {
+            owner: user.pubkey(),
+            global_config: pda(GLOBAL_CONFIG_SEED),
+            user_stake_info: user_stake_info_address(&user.pubkey()),
+            authority: pda(luxor_swap::AUTH_SEED),
+            stake_info: pda(STAKE_INFO_SEED),
+            luxor_vault: lxr_vault,
+            luxor_reward_vault: reward_vault,
+            luxor_mint: lxr_mint,
+            reward_mint: lxr_mint,
+            owner_reward_token: lxr_ata(&user.pubkey()),
+            owner_lxr_token: lxr_ata(&user.pubkey()),
+            token_program: spl_token::id(),
+            associated_token_program: associated_token::ID,
+            system_program: anchor_lang::system_program::ID,
+        },
+        luxor_swap::instruction::Redeem {
+            amount: Some(requested),
+        },
+    );
+    let logs = env.send(&[ix], &[&user]).await.unwrap();
+
+    let collected = &events::<RewardsCollected>(&logs)[0];
+    assert_eq!(collected.collector, user.pubkey());
+    assert_eq!(collected.lxr_collected, requested);
+    assert_eq!(collected.lxr_forfeited, entitlement / 2);
+    assert_eq!(
+        collected.lxr_remaining,
+        entitlement - collected.lxr_forfeited - collected.lxr_collected
+    );
+
+    assert_eq!(
+        env.token_amount(&lxr_ata(&user.pubkey())).await,
+        holdings_before + collected.lxr_collected
+    );
+    assert_eq!(
+        env.token_amount(&lxr_vault).await,
+        treasury_before + collected.lxr_forfeited
+    );
+    assert_eq!(
+        env.token_amount(&reward_vault).await,
+        reward_vault_before - collected.lxr_collected - collected.lxr_forfeited
+    );
+    let record: UserStakeInfo = env
+        .anchor_state(&user_stake_info_address(&user.pubkey()))
+        .await;
+    assert_eq!(record.total_lxr_claimed, collected.lxr_collected);
+    assert_eq!(record.total_lxr_forfeited, collected.lxr_forfeited);
+    assert_eq!(record.lxr_rewards_pending, collected.lxr_remaining);
+    let stake_info: StakeInfo = env.anchor_state(&pda(STAKE_INFO_SEED)).await;
+    assert_eq!(stake_info.total_lxr_claimed, collected.lxr_collected);
+    assert_eq!(stake_info.total_lxr_forfeited, collected.lxr_forfeited);
+}
+
+/// `BlacklistProposed` carries the stored dispute window and `UserBlacklisted` the
+/// stake and rewards that move to the admin record.
+#[tokio::test]
+async fn blacklist_events_match_reassigned_stake_and_rewards() {
+    let staked = 5 * LAMPORTS_PER_SOL;
+    let carried_over = 7 * LXR;
+    let fresh_rewards = 50 * LXR;
+    let delay = 3_600;
+
+    let mut fixture = ProtocolFixture::new().with_stake(PRINCIPAL);
+    fixture.global_config.blacklist_delay_secs = delay;
+    fixture.stake_info.total_staked_sol = PRINCIPAL;
+    fixture.stake_info.reward_per_token_lxr_stored =
+        u128::from(fresh_rewards) * luxor_swap::PRECISION / u128::from(staked);
+    let user = Keypair::new();
+    push_user(&mut fixture, &user, |record| {
+        record.total_staked_sol = staked;
+        record.lxr_rewards_pending = carried_over;
+        record.base_lxr_holdings = 100 * LXR;
+    });
+    let mut env = fixture.start().await;
+    let admin = env.admin.insecure_clone();
+    let user_stake_info = user_stake_info_address(&user.pubkey());
+
+    // --- propose ---
+    let ix = TestEnv::instruction(
+        luxor_swap::accounts::ProposeBlacklist {
+            owner: admin.pubkey(),
+            global_config: pda(GLOBAL_CONFIG_SEED),
+            user: user.pubkey(),
+            user_stake_info,
+        },
+        luxor_swap::instruction::ProposeBlacklist {},
+    );
+    let logs = env.send(&[ix], &[&admin]).await.unwrap();
+
+    let proposed = &events::<BlacklistProposed>(&logs)[0];
+    assert_eq!(proposed.user, user.pubkey());
+    let record: UserStakeInfo = env.anchor_state(&user_stake_info).await;
+    assert_eq!(
+        proposed.finalize_after,
+        record.blacklist_pending_timestamp + delay
+    );
+
+    // --- finalize after the dispute window ---
+    env.advance_clock(delay as i64).await;
+    let admin_record_before: UserStakeInfo = env.anchor_state(&pda(ADMIN_STAKE_INFO_SEED)).await;
+    let ix = TestEnv::instruction(
+        luxor_swap::accounts::FinalizeBlacklist {
+            owner: admin.pubkey(),
+            global_config: pda(GLOBAL_CONFIG_SEED),
+            user: user.pubkey(),
+            user_stake_info,
+            admin_stake_info: pda(ADMIN_STAKE_INFO_SEED),
+            stake_info: pda(STAKE_INFO_SEED),
+            stake_pda: pda(luxor_swap::STAKE_ACCOUNT_SEED),
+            system_program: anchor_lang::system_program::ID,
+        },
+        luxor_swap::instruction::FinalizeBlacklist {},
+    );
+    let logs = env.send(&[ix], &[&admin]).await.unwrap();
+
+    let blacklisted = &events::<UserBlacklisted>(&logs)[0];
+    assert_eq!(blacklisted.user, user.pubkey());
+    assert_eq!(blacklisted.sol_blacklisted, staked);
+    assert_eq!(blacklisted.lxr_reassigned, carried_over + fresh_rewards);
+
+    let record: UserStakeInfo = env.anchor_state(&user_stake_info).await;
+    assert_eq!(record.blacklisted_sol, blacklisted.sol_blacklisted);
+    assert_eq!(record.total_staked_sol, 0);
+    assert_eq!(record.lxr_rewards_pending, 0);
+    assert_eq!(record.base_lxr_holdings, 0);
+    let admin_record: UserStakeInfo = env.anchor_state(&pda(ADMIN_STAKE_INFO_SEED)).await;
+    assert_eq!(
+        admin_record.total_staked_sol,
+        admin_record_before.total_staked_sol + blacklisted.sol_blacklisted
+    );
+    assert_eq!(
+        admin_record.lxr_rewards_pending,
+        admin_record_before.lxr_rewards_pending + blacklisted.lxr_reassigned
+    );
+    // Reassigning the stake leaves the protocol total untouched.
+    let stake_info: StakeInfo = env.anchor_state(&pda(STAKE_INFO_SEED)).await;
+    assert_eq!(stake_info.total_staked_sol, PRINCIPAL);
+}
@@ -0,0 +1,145 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::{stake, sysvar};
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::spl_token;
+use common::*;
+use luxor_swap::states::*;
+use solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+const STAKED: u64 = 5 * LAMPORTS_PER_SOL;
+const LXR_BOUGHT: u64 = 1_000_000_000_000;
+
+fn isolated_stake_address(user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[luxor_swap::STAKE_ACCOUNT_SEED.as_bytes(), user.as_ref()],
+        &luxor_swap::id(),
+    )
+    .0
+}
+
+fn isolated_stake_info_address(user: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[ISOLATED_STAKE_INFO_SEED.as_bytes(), user.as_ref()],
+        &luxor_swap::id(),
+    )
+}
+
+/// A user holding `lxr_held` LXR with an isolated stake opened by
+/// `purchase_isolated` for `LXR_BOUGHT`.
+async fn start_with_isolated_stake(lxr_held: u64) -> (TestEnv, Keypair) {
+    let user = Keypair::new();
+    let mut fixture = ProtocolFixture::new();
+    let (record_address, bump) = isolated_stake_info_address(&user.pubkey());
+    let record = IsolatedStakeInfo {
+        bump,
+        owner: user.pubkey(),
+        sol_amount: STAKED,
+        lxr_amount: LXR_BOUGHT,
+    };
+    let vote_account = fixture.global_config.vote_account;
+    fixture.accounts.extend([
+        (user.pubkey(), system_account(SIGNER_LAMPORTS)),
+        (
+            get_associated_token_address(&user.pubkey(), &luxor_swap::luxor_mint::id()),
+            token_account(&luxor_swap::luxor_mint::id(), &user.pubkey(), lxr_held),
+        ),
+        (
+            isolated_stake_address(&user.pubkey()),
+            delegated_stake_account(&pda(luxor_swap::AUTH_SEED), &vote_account, STAKED),
+        ),
+        (
+            record_address,
+            anchor_account(&record, IsolatedStakeInfo::LEN),
+        ),
+    ]);
+    (fixture.start().await, user)
+}
+
+async fn unstake_isolated(
+    env: &mut TestEnv,
+    user: &Keypair,
+) -> Result<Vec<String>, TransactionError> {
+    let ix = TestEnv::instruction(
+        luxor_swap::accounts::UnstakeIsolated {
+            owner: user.pubkey(),
+            global_config: pda(GLOBAL_CONFIG_SEED),
+            authority: pda(luxor_swap::AUTH_SEED),
+            user_stake_pda: isolated_stake_address(&user.pubkey()),
+            isolated_stake_info: isolated_stake_info_address(&user.pubkey()).0,
+            luxor_vault: pda(luxor_swap::LUXOR_VAULT_SEED),
+            luxor_mint: luxor_swap::luxor_mint::id(),
+            owner_lxr_token: get_associated_token_address(
+                &user.pubkey(),
+                &luxor_swap::luxor_mint::id(),
+            ),
+            token_program: spl_token::id(),
+            clock: sysvar::clock::id(),
+            stake_history: sysvar::stake_history::id(),
+            stake_program: stake::program::id(),
+        },
+        luxor_swap::instruction::UnstakeIsolated {},
+    );
+    env.send(&[ix], &[user]).await
+}
+
+/// The LXR bought against the stake goes back to the treasury before the stake
+/// starts cooling down, and the SOL is only released afterwards.
+#[tokio::test]
+async fn unstake_isolated_returns_the_purchased_lxr() {
+    let (mut env, user) = start_with_isolated_stake(LXR_BOUGHT).await;
+    let user_lxr = get_associated_token_address(&user.pubkey(), &luxor_swap::luxor_mint::id());
+    let treasury = pda(luxor_swap::LUXOR_VAULT_SEED);
+    let stake_account = isolated_stake_address(&user.pubkey());
+
+    let logs = unstake_isolated(&mut env, &user).await.unwrap();
+    let unstaked = &events::<Unstaked>(&logs)[0];
+    assert_eq!(unstaked.lxr_returned, LXR_BOUGHT);
+    assert_eq!(unstaked.amount, STAKED);
+    assert_eq!(env.token_amount(&user_lxr).await, 0);
+    assert_eq!(env.token_amount(&treasury).await, LXR_BOUGHT);
+    let record: IsolatedStakeInfo = env
+        .anchor_state(&isolated_stake_info_address(&user.pubkey()).0)
+        .await;
+    assert_eq!(record.lxr_amount, 0);
+
+    // After the cooldown the whole stake comes back and the record is closed.
+    let epoch = env.current_epoch().await;
+    env.warp_to_epoch(epoch + 2).await;
+    let user_before = env.lamports(&user.pubkey()).await;
+    let stake_lamports = env.lamports(&stake_account).await;
+    let record_rent = env
+        .lamports(&isolated_stake_info_address(&user.pubkey()).0)
+        .await;
+
+    let logs = unstake_isolated(&mut env, &user).await.unwrap();
+    assert_eq!(events::<UnstakeWithdrawn>(&logs)[0].amount, stake_lamports);
+    assert_eq!(
+        env.lamports(&user.pubkey()).await,
+        user_before + stake_lamports + record_rent
+    );
+    assert!(env
+        .account(&isolated_stake_info_address(&user.pubkey()).0)
+        .await
+        .is_none());
+    // The LXR stays with the treasury.
+    assert_eq!(env.token_amount(&treasury).await, LXR_BOUGHT);
+}
+
+/// Selling the LXR and keeping the SOL is not possible: without the LXR the
+/// stake cannot be unstaked.
+#[tokio::test]
+async fn unstake_isolated_requires_the_purchased_lxr() {
+    let (mut env, user) = start_with_isolated_stake(LXR_BOUGHT - 1).await;
+
+    assert!(unstake_isolated(&mut env, &user).await.is_err());
+    let state = env
+        .stake_state(&isolated_stake_address(&user.pubkey()))
+        .await;
+    assert_eq!(state.delegation().unwrap().deactivation_epoch, u64::MAX);
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::AccountSerialize;
+use common::*;
+use luxor_swap::error::ErrorCode;
+use luxor_swap::states::*;
+use luxor_swap::MAX_BATCH;
+use solana_sdk::{account::Account, rent::Rent, signature::Signer};
+
+/// A first-release `UserStakeInfo` of a fresh user: the current layout cut
+/// back to `LEGACY_LEN`.
+fn legacy_user_record() -> (Pubkey, Account) {
+    let user = Pubkey::new_unique();
+    let (address, bump) = Pubkey::find_program_address(
+        &[USER_STAKE_INFO_SEED.as_bytes(), user.as_ref()],
+        &luxor_swap::id(),
+    );
+    let record = UserStakeInfo {
+        bump,
+        owner: user,
+        total_staked_sol: 1_000_000_000,
+        ..Default::default()
+    };
+    let mut data = Vec::new();
+    record.try_serialize(&mut data).unwrap();
+    data.truncate(UserStakeInfo::LEGACY_LEN);
+    let account = Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: luxor_swap::id(),
+        executable: false,
+        rent_epoch: 0,
+    };
+    (address, account)
+}
+
+async fn start_with_legacy_records(count: usize) -> (TestEnv, Vec<Pubkey>) {
+    let mut fixture = ProtocolFixture::new();
+    let records: Vec<_> = (0..count).map(|_| legacy_user_record()).collect();
+    let addresses = records.iter().map(|(address, _)| *address).collect();
+    fixture.accounts.extend(records);
+    (fixture.start().await, addresses)
+}
+
+async fn migrate(
+    env: &mut TestEnv,
+    records: &[Pubkey],
+) -> Result<Vec<String>, solana_sdk::transaction::TransactionError> {
+    let admin = env.admin.insecure_clone();
+    let mut ix = TestEnv::instruction(
+        luxor_swap::accounts::MigrateAccounts {
+            owner: admin.pubkey(),
+            global_config: pda(GLOBAL_CONFIG_SEED),
+            stake_info: pda(STAKE_INFO_SEED),
+            system_program: anchor_lang::system_program::ID,
+        },
+        luxor_swap::instruction::MigrateAccounts {},
+    );
+    ix.accounts.extend(
+        records
+            .iter()
+            .map(|record| AccountMeta::new(*record, false)),
+    );
+    env.send(&[ix], &[&admin]).await
+}
+
+/// A full batch fits in the default compute budget and grows every record.
+#[tokio::test]
+async fn migrates_a_full_batch() {
+    let (mut env, records) = start_with_legacy_records(MAX_BATCH).await;
+
+    let logs = migrate(&mut env, &records).await.unwrap();
+
+    let migrated = &events::<AccountsMigrated>(&logs)[0];
+    assert_eq!(migrated.user_records_migrated, MAX_BATCH as u64);
+    for record in &records {
+        let account = env.account(record).await.unwrap();
+        assert_eq!(account.data.len(), UserStakeInfo::LEN);
+        let user_stake_info: UserStakeInfo = env.anchor_state(record).await;
+        assert_eq!(user_stake_info.total_staked_sol, 1_000_000_000);
+    }
+}
+
+/// One record over the cap is rejected before anything is touched.
+#[tokio::test]
+async fn rejects_a_batch_over_max_batch() {
+    let (mut env, records) = start_with_legacy_records(MAX_BATCH + 1).await;
+
+    assert_error(migrate(&mut env, &records).await, ErrorCode::BatchTooLarge);
+    for record in &records {
+        let account = env.account(record).await.unwrap();
+        assert_eq!(account.data.len(), UserStakeInfo::LEGACY_LEN);
+    }
+}
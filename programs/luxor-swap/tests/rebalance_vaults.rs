@@ -0,0 +1,100 @@
+mod common;
+
+use anchor_spl::token::spl_token;
+use common::*;
+use luxor_swap::error::ErrorCode;
+use luxor_swap::instructions::{REBALANCE_VAULT_REWARD, REBALANCE_VAULT_TREASURY};
+use luxor_swap::states::*;
+use solana_sdk::{instruction::Instruction, signature::Signer};
+
+const LXR: u64 = 1_000_000_000;
+const TREASURY: u64 = 1_000 * LXR;
+const REWARDS: u64 = 300 * LXR;
+
+fn rebalance_ix(env: &TestEnv, from: u8, to: u8, amount: u64) -> Instruction {
+    TestEnv::instruction(
+        luxor_swap::accounts::RebalanceVaults {
+            owner: env.admin.pubkey(),
+            global_config: pda(GLOBAL_CONFIG_SEED),
+            authority: pda(luxor_swap::AUTH_SEED),
+            luxor_vault: pda(luxor_swap::LUXOR_VAULT_SEED),
+            luxor_reward_vault: pda(luxor_swap::LUXOR_REWARD_VAULT_SEED),
+            luxor_mint: luxor_swap::luxor_mint::id(),
+            token_program: spl_token::id(),
+        },
+        luxor_swap::instruction::RebalanceVaults { from, to, amount },
+    )
+}
+
+async fn funded_env() -> TestEnv {
+    let mut fixture = ProtocolFixture::new();
+    fixture.lxr_treasury_amount = TREASURY;
+    fixture.lxr_reward_amount = REWARDS;
+    fixture.start().await
+}
+
+/// Moving a vault's whole balance is allowed and lands exactly on the other one.
+#[tokio::test]
+async fn moves_the_exact_amount() {
+    let mut env = funded_env().await;
+    let admin = env.admin.insecure_clone();
+    let treasury = pda(luxor_swap::LUXOR_VAULT_SEED);
+    let reward = pda(luxor_swap::LUXOR_REWARD_VAULT_SEED);
+
+    let ix = rebalance_ix(
+        &env,
+        REBALANCE_VAULT_REWARD,
+        REBALANCE_VAULT_TREASURY,
+        REWARDS,
+    );
+    let logs = env.send(&[ix], &[&admin]).await.unwrap();
+
+    let rebalanced = &events::<VaultsRebalanced>(&logs)[0];
+    assert_eq!(rebalanced.from, REBALANCE_VAULT_REWARD);
+    assert_eq!(rebalanced.to, REBALANCE_VAULT_TREASURY);
+    assert_eq!(rebalanced.amount, REWARDS);
+    assert_eq!(rebalanced.source_balance, 0);
+    assert_eq!(env.token_amount(&reward).await, 0);
+    assert_eq!(env.token_amount(&treasury).await, TREASURY + REWARDS);
+}
+
+#[tokio::test]
+async fn same_vault_fails() {
+    let mut env = funded_env().await;
+    let admin = env.admin.insecure_clone();
+
+    let ix = rebalance_ix(
+        &env,
+        REBALANCE_VAULT_TREASURY,
+        REBALANCE_VAULT_TREASURY,
+        LXR,
+    );
+    assert_error(env.send(&[ix], &[&admin]).await, ErrorCode::InvalidVault);
+}
+
+/// One unit more than the source holds fails and moves nothing.
+#[tokio::test]
+async fn oversized_amount_fails() {
+    let mut env = funded_env().await;
+    let admin = env.admin.insecure_clone();
+
+    let ix = rebalance_ix(
+        &env,
+        REBALANCE_VAULT_TREASURY,
+        REBALANCE_VAULT_REWARD,
+        TREASURY + 1,
+    );
+    assert_error(
+        env.send(&[ix], &[&admin]).await,
+        ErrorCode::InsufficientVault,
+    );
+    assert_eq!(
+        env.token_amount(&pda(luxor_swap::LUXOR_VAULT_SEED)).await,
+        TREASURY
+    );
+    assert_eq!(
+        env.token_amount(&pda(luxor_swap::LUXOR_REWARD_VAULT_SEED))
+            .await,
+        REWARDS
+    );
+}
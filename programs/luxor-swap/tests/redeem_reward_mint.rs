@@ -0,0 +1,154 @@
+//! `redeem` with a `reward_mint` other than LXR: forfeitures cannot go to the LXR
+//! treasury, so they are redistributed or held for the next buyback.
+
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use anchor_spl::token::spl_token;
+use common::*;
+use luxor_swap::states::*;
+use solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    signature::{Keypair, Signer},
+};
+
+const UNIT: u64 = 1_000_000;
+const STAKED: u64 = 5 * LAMPORTS_PER_SOL;
+const ENTITLEMENT: u64 = 1_000 * UNIT;
+const BASE_HOLDINGS: u64 = 1_000 * UNIT;
+
+/// Protocol paying rewards in a fresh mint, with `user` owed `ENTITLEMENT` while
+/// holding half their base LXR (so half of it is forfeited).
+async fn reward_mint_env(user: &Keypair, total_staked_sol: u64) -> (TestEnv, Pubkey) {
+    let reward_mint = Pubkey::new_unique();
+    let mut fixture = ProtocolFixture::new().with_stake(total_staked_sol);
+    fixture.global_config.reward_mint = reward_mint;
+    fixture.stake_info.total_staked_sol = total_staked_sol;
+    fixture.stake_info.reward_per_token_lxr_stored =
+        u128::from(ENTITLEMENT) * luxor_swap::PRECISION / u128::from(STAKED);
+    fixture.lxr_reward_amount = ENTITLEMENT;
+
+    let lxr_mint = luxor_swap::luxor_mint::id();
+    let (address, bump) = Pubkey::find_program_address(
+        &[USER_STAKE_INFO_SEED.as_bytes(), user.pubkey().as_ref()],
+        &luxor_swap::id(),
+    );
+    let record = UserStakeInfo {
+        bump,
+        owner: user.pubkey(),
+        total_staked_sol: STAKED,
+        base_lxr_holdings: BASE_HOLDINGS,
+        ..Default::default()
+    };
+    fixture.accounts.extend([
+        (user.pubkey(), system_account(SIGNER_LAMPORTS)),
+        (address, anchor_account(&record, UserStakeInfo::LEN)),
+        (
+            get_associated_token_address(&user.pubkey(), &lxr_mint),
+            token_account(&lxr_mint, &user.pubkey(), BASE_HOLDINGS / 2),
+        ),
+    ]);
+    (fixture.start().await, reward_mint)
+}
+
+async fn redeem(env: &mut TestEnv, user: &Keypair, reward_mint: Pubkey) -> RewardsCollected {
+    let lxr_mint = luxor_swap::luxor_mint::id();
+    let ix = TestEnv::instruction(
+        luxor_swap::accounts::Redeem {
+            owner: user.pubkey(),
+            global_config: pda(GLOBAL_CONFIG_SEED),
+            user_stake_info: user_stake_info_address(&user.pubkey()),
+            authority: pda(luxor_swap::AUTH_SEED),
+            stake_info: pda(STAKE_INFO_SEED),
+            luxor_vault: pda(luxor_swap::LUXOR_VAULT_SEED),
+            luxor_reward_vault: pda(luxor_swap::LUXOR_REWARD_VAULT_SEED),
+            luxor_mint: lxr_mint,
+            reward_mint,
+            owner_reward_token: get_associated_token_address(&user.pubkey(), &reward_mint),
+            owner_lxr_token: get_associated_token_address(&user.pubkey(), &lxr_mint),
+            token_program: spl_token::id(),
+            associated_token_program: associated_token::ID,
+            system_program: anchor_lang::system_program::ID,
+        },
+        luxor_swap::instruction::Redeem { amount: None },
+    );
+    let logs = env.send(&[ix], &[user]).await.unwrap();
+    events::<RewardsCollected>(&logs).remove(0)
+}
+
+/// With other stakers, the forfeited half is credited to their index and stays in
+/// the reward vault; the LXR treasury is untouched.
+#[tokio::test]
+async fn forfeiture_is_redistributed_to_other_stakers() {
+    let user = Keypair::new();
+    let others = 3 * STAKED;
+    let (mut env, reward_mint) = reward_mint_env(&user, STAKED + others).await;
+    let lxr_vault = pda(luxor_swap::LUXOR_VAULT_SEED);
+    let reward_vault = pda(luxor_swap::LUXOR_REWARD_VAULT_SEED);
+    let treasury_before = env.token_amount(&lxr_vault).await;
+    let index_before = env
+        .anchor_state::<StakeInfo>(&pda(STAKE_INFO_SEED))
+        .await
+        .reward_per_token_lxr_stored;
+
+    let collected = redeem(&mut env, &user, reward_mint).await;
+    assert_eq!(collected.lxr_forfeited, ENTITLEMENT / 2);
+    assert_eq!(collected.lxr_collected, ENTITLEMENT / 2);
+
+    assert_eq!(
+        env.token_amount(&get_associated_token_address(&user.pubkey(), &reward_mint))
+            .await,
+        collected.lxr_collected
+    );
+    assert_eq!(
+        env.token_amount(&reward_vault).await,
+        ENTITLEMENT - collected.lxr_collected
+    );
+    assert_eq!(env.token_amount(&lxr_vault).await, treasury_before);
+
+    let stake_info: StakeInfo = env.anchor_state(&pda(STAKE_INFO_SEED)).await;
+    assert_eq!(
+        stake_info.reward_per_token_lxr_stored,
+        index_before
+            + u128::from(collected.lxr_forfeited) * luxor_swap::PRECISION / u128::from(others)
+    );
+    assert_eq!(
+        stake_info.total_forfeiture_redistributed,
+        collected.lxr_forfeited
+    );
+    assert_eq!(stake_info.lxr_rewards_undistributed, 0);
+    assert_eq!(stake_info.total_lxr_forfeited, 0);
+}
+
+/// As the only staker there is no one to redistribute to: the forfeited half is
+/// held in `lxr_rewards_undistributed` instead of sitting unaccounted.
+#[tokio::test]
+async fn forfeiture_is_held_without_other_stakers() {
+    let user = Keypair::new();
+    let (mut env, reward_mint) = reward_mint_env(&user, STAKED).await;
+    let lxr_vault = pda(luxor_swap::LUXOR_VAULT_SEED);
+    let reward_vault = pda(luxor_swap::LUXOR_REWARD_VAULT_SEED);
+    let treasury_before = env.token_amount(&lxr_vault).await;
+    let index_before = env
+        .anchor_state::<StakeInfo>(&pda(STAKE_INFO_SEED))
+        .await
+        .reward_per_token_lxr_stored;
+
+    let collected = redeem(&mut env, &user, reward_mint).await;
+    assert_eq!(collected.lxr_forfeited, ENTITLEMENT / 2);
+
+    assert_eq!(
+        env.token_amount(&reward_vault).await,
+        ENTITLEMENT - collected.lxr_collected
+    );
+    assert_eq!(env.token_amount(&lxr_vault).await, treasury_before);
+
+    let stake_info: StakeInfo = env.anchor_state(&pda(STAKE_INFO_SEED)).await;
+    assert_eq!(stake_info.reward_per_token_lxr_stored, index_before);
+    assert_eq!(
+        stake_info.lxr_rewards_undistributed,
+        collected.lxr_forfeited
+    );
+    assert_eq!(stake_info.total_forfeiture_redistributed, 0);
+}
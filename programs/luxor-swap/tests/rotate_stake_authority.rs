@@ -0,0 +1,130 @@
+mod common;
+
+use anchor_lang::solana_program::{stake, sysvar};
+use common::*;
+use luxor_swap::error::ErrorCode;
+use luxor_swap::instructions::EmergencyOperation;
+use luxor_swap::states::*;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::Signer,
+};
+
+const DELAY: u64 = 3_600;
+
+fn rotate_ix(env: &TestEnv, new_authority: Pubkey) -> Instruction {
+    TestEnv::instruction(
+        luxor_swap::accounts::RotateStakeAuthority {
+            owner: env.admin.pubkey(),
+            global_config: pda(GLOBAL_CONFIG_SEED),
+            authority: pda(luxor_swap::AUTH_SEED),
+            stake_pda: pda(luxor_swap::STAKE_ACCOUNT_SEED),
+            clock: sysvar::clock::id(),
+            stake_program: stake::program::id(),
+            pending_emergency: pda(PENDING_EMERGENCY_SEED),
+        },
+        luxor_swap::instruction::RotateStakeAuthority { new_authority },
+    )
+}
+
+fn queue_rotation_ix(env: &TestEnv, target: Pubkey) -> Instruction {
+    let mut ix = TestEnv::instruction(
+        luxor_swap::accounts::QueueEmergency {
+            owner: env.admin.pubkey(),
+            global_config: pda(GLOBAL_CONFIG_SEED),
+            pending_emergency: pda(PENDING_EMERGENCY_SEED),
+            system_program: anchor_lang::system_program::ID,
+        },
+        luxor_swap::instruction::QueueEmergency {
+            param: EmergencyOperation::RotateStakeAuthority as u8,
+            value: 0,
+        },
+    );
+    ix.accounts.push(AccountMeta::new_readonly(target, false));
+    ix
+}
+
+async fn timelocked_env() -> TestEnv {
+    let mut fixture = ProtocolFixture::new().with_stake(10 * LAMPORTS_PER_SOL);
+    fixture.global_config.emergency_delay = DELAY;
+    fixture.start().await
+}
+
+/// With a delay set and nothing queued, the rotation is refused and the stake
+/// authorities stay on the program PDA.
+#[tokio::test]
+async fn rotation_requires_queue_while_timelocked() {
+    let mut env = timelocked_env().await;
+    let admin = env.admin.insecure_clone();
+    let stake_pda = pda(luxor_swap::STAKE_ACCOUNT_SEED);
+
+    let ix = rotate_ix(&env, Pubkey::new_unique());
+    assert_error(
+        env.send(&[ix], &[&admin]).await,
+        ErrorCode::EmergencyNotQueued,
+    );
+
+    let authorized = env.stake_state(&stake_pda).await.authorized().unwrap();
+    assert_eq!(authorized.staker, pda(luxor_swap::AUTH_SEED));
+    assert_eq!(authorized.withdrawer, pda(luxor_swap::AUTH_SEED));
+}
+
+/// A queued rotation only executes for its own target, after the delay.
+#[tokio::test]
+async fn queued_rotation_executes_after_delay() {
+    let mut env = timelocked_env().await;
+    let admin = env.admin.insecure_clone();
+    let stake_pda = pda(luxor_swap::STAKE_ACCOUNT_SEED);
+    let new_authority = Pubkey::new_unique();
+
+    let ix = queue_rotation_ix(&env, new_authority);
+    let logs = env.send(&[ix], &[&admin]).await.unwrap();
+    let queued = &events::<EmergencyQueued>(&logs)[0];
+    assert_eq!(queued.param, EmergencyOperation::RotateStakeAuthority as u8);
+    assert_eq!(queued.target, new_authority);
+
+    // Not matured yet.
+    let ix = rotate_ix(&env, new_authority);
+    assert_error(
+        env.send(&[ix], &[&admin]).await,
+        ErrorCode::EmergencyTimelockActive,
+    );
+
+    env.advance_clock(DELAY as i64).await;
+
+    // Another key than the queued one.
+    let ix = rotate_ix(&env, Pubkey::new_unique());
+    assert_error(
+        env.send(&[ix], &[&admin]).await,
+        ErrorCode::EmergencyNotQueued,
+    );
+
+    let ix = rotate_ix(&env, new_authority);
+    let logs = env.send(&[ix], &[&admin]).await.unwrap();
+    let executed = &events::<EmergencyExecuted>(&logs)[0];
+    assert_eq!(
+        executed.param,
+        EmergencyOperation::RotateStakeAuthority as u8
+    );
+    assert_eq!(executed.execute_after, queued.execute_after);
+
+    let authorized = env.stake_state(&stake_pda).await.authorized().unwrap();
+    assert_eq!(authorized.staker, new_authority);
+    assert_eq!(authorized.withdrawer, new_authority);
+}
+
+/// Queuing a rotation without its target account fails.
+#[tokio::test]
+async fn queue_rotation_requires_target() {
+    let mut env = timelocked_env().await;
+    let admin = env.admin.insecure_clone();
+
+    let mut ix = queue_rotation_ix(&env, Pubkey::new_unique());
+    ix.accounts.pop();
+    assert_error(
+        env.send(&[ix], &[&admin]).await,
+        ErrorCode::MissingRemainingAccount,
+    );
+}
@@ -0,0 +1,94 @@
+//! Native epoch rewards on the delegated stake PDA and their accrual.
+
+mod common;
+
+use anchor_lang::solana_program::{stake, sysvar};
+use common::*;
+use luxor_swap::states::*;
+use solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    signature::{Keypair, Signer},
+};
+
+const STAKED: u64 = 1_000 * LAMPORTS_PER_SOL;
+
+/// An epoch reward compounds into `delegation.stake` and raises the PDA's lamports
+/// by the same amount, and the next accrual books exactly that delta.
+#[tokio::test]
+async fn epoch_reward_on_stake_pda_is_accrued() {
+    let mut fixture = ProtocolFixture::new().with_stake(STAKED);
+    fixture.global_config.sol_reward_share_bps = 5_000;
+    fixture.stake_info.total_staked_sol = STAKED;
+    let user = Keypair::new();
+    fixture
+        .accounts
+        .push((user.pubkey(), system_account(SIGNER_LAMPORTS)));
+    let vote_account = fixture.global_config.vote_account;
+    let mut env = fixture.start().await;
+    let admin = env.admin.insecure_clone();
+    let stake_pda = pda(luxor_swap::STAKE_ACCOUNT_SEED);
+
+    let lamports_before = env.lamports(&stake_pda).await;
+    let delegated_before = env
+        .stake_state(&stake_pda)
+        .await
+        .stake()
+        .unwrap()
+        .delegation
+        .stake;
+
+    env.land_epoch_rewards(&vote_account).await;
+
+    let reward = env.lamports(&stake_pda).await - lamports_before;
+    assert!(reward > 0);
+    let delegated_after = env
+        .stake_state(&stake_pda)
+        .await
+        .stake()
+        .unwrap()
+        .delegation
+        .stake;
+    assert_eq!(delegated_after - delegated_before, reward);
+
+    // Nothing is booked until an instruction accrues.
+    let stake_info: StakeInfo = env.anchor_state(&pda(STAKE_INFO_SEED)).await;
+    assert_eq!(stake_info.total_sol_rewards_accrued, 0);
+    assert_eq!(stake_info.last_tracked_sol_balance, lamports_before);
+
+    let sol_spent = LAMPORTS_PER_SOL;
+    let ix = TestEnv::instruction(
+        luxor_swap::accounts::ManualPurchase {
+            owner: admin.pubkey(),
+            global_config: pda(GLOBAL_CONFIG_SEED),
+            user: user.pubkey(),
+            user_stake_info: user_stake_info_address(&user.pubkey()),
+            stake_info: pda(STAKE_INFO_SEED),
+            authority: pda(luxor_swap::AUTH_SEED),
+            stake_pda,
+            vote_account,
+            stake_program: stake::program::id(),
+            clock: sysvar::clock::id(),
+            stake_history: sysvar::stake_history::id(),
+            stake_config: stake::config::ID,
+            system_program: anchor_lang::system_program::ID,
+        },
+        luxor_swap::instruction::ManualPurchase {
+            lxr_purchased: 1_000_000_000,
+            sol_spent,
+        },
+    );
+    env.send(&[ix], &[&admin]).await.unwrap();
+
+    let stake_info: StakeInfo = env.anchor_state(&pda(STAKE_INFO_SEED)).await;
+    assert_eq!(stake_info.total_sol_rewards_accrued, reward);
+    assert_eq!(stake_info.total_sol_rewards_reserved, reward / 2);
+    assert_eq!(
+        stake_info.reward_per_token_sol_stored,
+        u128::from(reward / 2) * luxor_swap::PRECISION / u128::from(STAKED)
+    );
+    // The purchase itself is stake, not reward.
+    assert_eq!(
+        stake_info.last_tracked_sol_balance,
+        lamports_before + reward + sol_spent
+    );
+}
@@ -0,0 +1,301 @@
+mod common;
+
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_spl::token::spl_token;
+use common::*;
+use luxor_swap::curve::FEE_RATE_DENOMINATOR_VALUE;
+use luxor_swap::error::ErrorCode;
+use luxor_swap::states::*;
+use luxor_swap::swap_venue::{DEX_TYPE_ORCA_WHIRLPOOL, DEX_TYPE_RAYDIUM_CPMM};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    stake::state::StakeStateV2,
+    transaction::TransactionError,
+};
+
+/// Reads a config field as `u64`, from the account and from the event snapshot.
+type Field = (fn(&GlobalConfig) -> u64, fn(&ConfigUpdated) -> u64);
+
+macro_rules! field {
+    ($name:ident) => {
+        (
+            (|c: &GlobalConfig| c.$name as u64) as fn(&GlobalConfig) -> u64,
+            (|e: &ConfigUpdated| e.$name as u64) as fn(&ConfigUpdated) -> u64,
+        )
+    };
+}
+
+async fn update_as(
+    env: &mut TestEnv,
+    signer: &Keypair,
+    param: u8,
+    value: u64,
+    remaining: &[Pubkey],
+) -> Result<Vec<String>, TransactionError> {
+    let mut ix = TestEnv::instruction(
+        luxor_swap::accounts::UpdateConfig {
+            owner: signer.pubkey(),
+            global_config: pda(GLOBAL_CONFIG_SEED),
+            system_program: anchor_lang::system_program::ID,
+        },
+        luxor_swap::instruction::UpdateConfig { param, value },
+    );
+    ix.accounts.extend(
+        remaining
+            .iter()
+            .map(|key| AccountMeta::new_readonly(*key, false)),
+    );
+    env.send(&[ix], &[signer]).await
+}
+
+async fn update(
+    env: &mut TestEnv,
+    param: u8,
+    value: u64,
+    remaining: &[Pubkey],
+) -> Result<Vec<String>, TransactionError> {
+    let admin = env.admin.insecure_clone();
+    update_as(env, &admin, param, value, remaining).await
+}
+
+async fn config(env: &mut TestEnv) -> GlobalConfig {
+    env.anchor_state(&pda(GLOBAL_CONFIG_SEED)).await
+}
+
+/// Sets `param` to `value` and checks both the stored field and the
+/// `ConfigUpdated` snapshot.
+async fn assert_update(env: &mut TestEnv, param: u8, value: u64, (stored, emitted): Field) {
+    let logs = update(env, param, value, &[]).await.unwrap();
+    let event = &events::<ConfigUpdated>(&logs)[0];
+    assert_eq!(event.param, param);
+    assert_eq!(emitted(event), value, "ConfigUpdated for param {param}");
+    assert_eq!(
+        stored(&config(env).await),
+        value,
+        "stored value for param {param}"
+    );
+}
+
+#[tokio::test]
+async fn plain_u64_params() {
+    let mut env = ProtocolFixture::new().start().await;
+    let params: [(u8, Field); 16] = [
+        (1, field!(min_swap_amount)),
+        (2, field!(max_swap_amount)),
+        (6, field!(max_stake_count_to_get_bonus)),
+        (7, field!(initial_lxr_allocation_vault)),
+        (8, field!(max_sol_per_buyback)),
+        (9, field!(blacklist_delay_secs)),
+        (12, field!(min_treasury_for_purchase)),
+        (13, field!(min_pool_reserve)),
+        (15, field!(curve_offset_sol)),
+        (16, field!(curve_offset_lxr)),
+        (21, field!(reward_vault_target)),
+        (22, field!(buyback_cooldown_secs)),
+        (27, field!(max_stake_per_user)),
+        (28, field!(max_total_staked_sol)),
+        (32, field!(min_lxr_per_purchase)),
+        (33, field!(max_lxr_per_purchase)),
+    ];
+    for (param, field) in params {
+        assert_update(&mut env, param, 1_000 + u64::from(param), field).await;
+    }
+}
+
+#[tokio::test]
+async fn toggles_treat_any_nonzero_value_as_true() {
+    let mut env = ProtocolFixture::new().start().await;
+    let toggles: [(u8, Field); 4] = [
+        (4, field!(purchase_enabled)),
+        (5, field!(redeem_enabled)),
+        (17, field!(buyback_fee_enabled)),
+        (20, field!(redistribute_forfeitures)),
+    ];
+    for (param, (stored, emitted)) in toggles {
+        assert_update(&mut env, param, 0, (stored, emitted)).await;
+
+        let logs = update(&mut env, param, 42, &[]).await.unwrap();
+        assert_eq!(emitted(&events::<ConfigUpdated>(&logs)[0]), 1);
+        assert_eq!(stored(&config(&mut env).await), 1);
+    }
+}
+
+#[tokio::test]
+async fn bounded_params_accept_the_bound_and_reject_above_it() {
+    let mut env = ProtocolFixture::new().start().await;
+    let bps = BPS_DENOMINATOR;
+    let fee = FEE_RATE_DENOMINATOR_VALUE;
+    let params: [(u8, u64, Field); 8] = [
+        (3, fee, field!(fee_treasury_rate)),
+        (11, fee, field!(buyback_out_tolerance_rate)),
+        (18, bps, field!(forfeiture_grace_bps)),
+        (19, bps, field!(max_pool_fraction_bps)),
+        (24, bps, field!(min_hold_ratio_bps)),
+        (29, bps, field!(max_price_deviation_bps)),
+        (30, fee, field!(bonus_rate)),
+        (31, bps, field!(sol_reward_share_bps)),
+    ];
+    for (param, bound, (stored, emitted)) in params {
+        assert_update(&mut env, param, bound, (stored, emitted)).await;
+
+        assert_error(
+            update(&mut env, param, bound + 1, &[]).await,
+            ErrorCode::InvalidParam,
+        );
+        assert_eq!(stored(&config(&mut env).await), bound);
+    }
+}
+
+#[tokio::test]
+async fn curve_type_and_dex_type_accept_only_known_values() {
+    let mut env = ProtocolFixture::new().start().await;
+
+    assert_update(
+        &mut env,
+        14,
+        u64::from(CURVE_TYPE_OFFSET_PRODUCT),
+        field!(curve_type),
+    )
+    .await;
+    assert_update(
+        &mut env,
+        14,
+        u64::from(CURVE_TYPE_CONSTANT_PRODUCT),
+        field!(curve_type),
+    )
+    .await;
+    assert_error(update(&mut env, 14, 2, &[]).await, ErrorCode::InvalidParam);
+
+    assert_update(
+        &mut env,
+        26,
+        u64::from(DEX_TYPE_RAYDIUM_CPMM),
+        field!(dex_type),
+    )
+    .await;
+    assert_error(
+        update(&mut env, 26, u64::from(DEX_TYPE_ORCA_WHIRLPOOL), &[]).await,
+        ErrorCode::UnsupportedSwapVenue,
+    );
+    assert_error(update(&mut env, 26, 2, &[]).await, ErrorCode::InvalidParam);
+    assert_error(
+        update(&mut env, 26, 256, &[]).await,
+        ErrorCode::InvalidParam,
+    );
+    assert_eq!(config(&mut env).await.dex_type, DEX_TYPE_RAYDIUM_CPMM);
+}
+
+#[tokio::test]
+async fn stake_rent_exempt_is_read_from_the_rent_sysvar() {
+    let mut fixture = ProtocolFixture::new();
+    fixture.global_config.stake_rent_exempt = 1;
+    let mut env = fixture.start().await;
+    let rent = env.context.banks_client.get_rent().await.unwrap();
+    let expected = rent.minimum_balance(StakeStateV2::size_of());
+
+    // `value` is ignored.
+    let logs = update(&mut env, 10, 7, &[]).await.unwrap();
+    assert_eq!(
+        events::<ConfigUpdated>(&logs)[0].stake_rent_exempt,
+        expected
+    );
+    assert_eq!(config(&mut env).await.stake_rent_exempt, expected);
+}
+
+#[tokio::test]
+async fn admin_transfer_needs_the_nominee_account() {
+    let mut env = ProtocolFixture::new().start().await;
+    let admin = env.admin.pubkey();
+    let nominee = Pubkey::new_unique();
+
+    assert_error(
+        update(&mut env, 0, 0, &[]).await,
+        ErrorCode::MissingRemainingAccount,
+    );
+    assert_eq!(config(&mut env).await.pending_admin, Pubkey::default());
+
+    let logs = update(&mut env, 0, 0, &[nominee]).await.unwrap();
+    let initiated = &events::<AdminTransferInitiated>(&logs)[0];
+    assert_eq!((initiated.admin, initiated.pending_admin), (admin, nominee));
+    let event = &events::<ConfigUpdated>(&logs)[0];
+    assert_eq!((event.admin, event.pending_admin), (admin, nominee));
+    // Nominating does not hand over the admin role.
+    let stored = config(&mut env).await;
+    assert_eq!((stored.admin, stored.pending_admin), (admin, nominee));
+}
+
+#[tokio::test]
+async fn fee_recipient_needs_the_recipient_account() {
+    let mut env = ProtocolFixture::new().start().await;
+    let recipient = Pubkey::new_unique();
+
+    assert_error(
+        update(&mut env, 23, 0, &[]).await,
+        ErrorCode::MissingRemainingAccount,
+    );
+
+    let logs = update(&mut env, 23, 0, &[recipient]).await.unwrap();
+    assert_eq!(events::<ConfigUpdated>(&logs)[0].fee_recipient, recipient);
+    assert_eq!(config(&mut env).await.fee_recipient, recipient);
+}
+
+/// Raydium pool account pairing `token_0_mint` with `token_1_mint`.
+fn raydium_pool(token_0_mint: Pubkey, token_1_mint: Pubkey) -> Account {
+    raydium_pool_account(&PoolState {
+        token_0_mint,
+        token_1_mint,
+        ..Default::default()
+    })
+}
+
+#[tokio::test]
+async fn reward_pool_must_pair_wsol_with_the_reward_mint() {
+    let good_pool = Pubkey::new_unique();
+    let reversed_pool = Pubkey::new_unique();
+    let mut fixture = ProtocolFixture::new();
+    let reward_mint = fixture.global_config.reward_mint;
+    let wsol = spl_token::native_mint::id();
+    fixture
+        .accounts
+        .push((good_pool, raydium_pool(wsol, reward_mint)));
+    fixture
+        .accounts
+        .push((reversed_pool, raydium_pool(reward_mint, wsol)));
+    let mut env = fixture.start().await;
+
+    assert_error(
+        update(&mut env, 25, 0, &[]).await,
+        ErrorCode::MissingRemainingAccount,
+    );
+    assert_error(
+        update(&mut env, 25, 0, &[reversed_pool]).await,
+        ErrorCode::InvalidRewardMint,
+    );
+
+    let logs = update(&mut env, 25, 0, &[good_pool]).await.unwrap();
+    assert_eq!(
+        events::<ConfigUpdated>(&logs)[0].reward_pool_state,
+        good_pool
+    );
+    assert_eq!(config(&mut env).await.reward_pool_state, good_pool);
+}
+
+#[tokio::test]
+async fn rejects_unknown_params_and_non_admins() {
+    let mut env = ProtocolFixture::new().start().await;
+
+    assert_error(update(&mut env, 34, 1, &[]).await, ErrorCode::InvalidParam);
+    assert_error(
+        update(&mut env, u8::MAX, 1, &[]).await,
+        ErrorCode::InvalidParam,
+    );
+
+    // The outsider only signs; the context payer funds the transaction.
+    let outsider = Keypair::new();
+    assert_error(
+        update_as(&mut env, &outsider, 1, 1, &[]).await,
+        ErrorCode::InvalidOwner,
+    );
+    assert_eq!(config(&mut env).await.min_swap_amount, 0);
+}